@@ -19,7 +19,7 @@ use gpui_platform;
 use gpuikit::elements::input::{input, text_area};
 use gpuikit::elements::select::{select, SelectChanged, SelectState};
 use gpuikit::elements::slider::{Slider, SliderChanged};
-use gpuikit::input::{bind_input_keys, InputState};
+use gpuikit::input::InputState;
 
 use fixtures::SampleText;
 
@@ -591,7 +591,6 @@ impl Render for InputSandbox {
 fn main() {
     Application::with_platform(gpui_platform::current_platform(false)).run(|cx: &mut App| {
         gpuikit::init(cx);
-        bind_input_keys(cx, None);
 
         cx.bind_keys([KeyBinding::new("ctrl-t", ToggleMode, None)]);
 
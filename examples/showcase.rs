@@ -9,9 +9,9 @@ use gpui::{
 use gpui_platform;
 use gpuikit::a11y::FocusNavigation;
 use gpuikit::date::{Date, Weekday};
-use gpuikit::input::InputState;
+use gpuikit::input::{InputState, PrefixCompletions};
 use gpuikit::markdown::{preprocessing_available, Markdown, MarkdownElement};
-use gpuikit::theme::{ActiveTheme, GlobalTheme, Theme, Themeable};
+use gpuikit::theme::{ActiveTheme, ThemeRegistry, Themeable};
 use gpuikit::{
     elements::{
         accordion::{accordion, accordion_item, AccordionState},
@@ -38,6 +38,8 @@ use gpuikit::{
         label::label,
         list::{List, ListEntry},
         loading_indicator::loading_indicator,
+        mentions::{mentions, Mention, MentionsState},
+        number_field::{number_field, NumberFieldState},
         popover::{popover, PopoverState},
         progress::{progress, ProgressVariant},
         radio_group::{radio_group, radio_option, RadioGroup},
@@ -69,7 +71,6 @@ use gpuikit::{
 use std::cell::RefCell;
 use std::collections::HashSet;
 use std::rc::Rc;
-use std::sync::Arc;
 use std::time::Duration;
 
 /// The Markdown page's document. It doubles as a regression surface: every
@@ -212,6 +213,8 @@ const ELEMENT_COVERAGE: &[(&str, &str)] = &[
     ("label", "badge"),
     ("list", "list"),
     ("loading_indicator", "loading"),
+    ("mentions", "text"),
+    ("number_field", "slider"),
     ("popover", "popover"),
     ("progress", "loading"),
     ("radio_group", "selection"),
@@ -418,16 +421,6 @@ enum TextStyle {
     Underline,
 }
 
-#[derive(Clone, PartialEq, Debug)]
-enum ThemeChoice {
-    GruvboxDark,
-    GruvboxLight,
-    CatppuccinLatte,
-    CatppuccinFrappe,
-    CatppuccinMacchiato,
-    CatppuccinMocha,
-}
-
 #[derive(Clone, PartialEq, Debug)]
 enum Country {
     US,
@@ -573,7 +566,7 @@ struct Showcase {
     toggled_count: usize,
     size_select: Entity<SelectState<Size>>,
     priority_select: Entity<SelectState<Priority>>,
-    theme_select: Entity<SelectState<ThemeChoice>>,
+    theme_select: Entity<SelectState<SharedString>>,
     country_select: Entity<SelectState<Country>>,
     /// The six states `docs/issues/combobox.md` asked a page to show, plus the
     /// two blur modes that are not the default.
@@ -603,6 +596,10 @@ struct Showcase {
     slider_volume: Entity<Slider>,
     slider_brightness: Entity<Slider>,
     slider_disabled: Entity<Slider>,
+    /// The slider page's number fields: a bounded integer with a scrub label,
+    /// and an unbounded float with its own format.
+    number_width: Entity<NumberFieldState>,
+    number_price: Entity<NumberFieldState>,
     toggle_bold: Entity<Toggle>,
     toggle_pinned: Entity<Toggle>,
     toggle_disabled: Entity<Toggle>,
@@ -636,6 +633,7 @@ struct Showcase {
     text_field_composed: Entity<InputState>,
     text_field_disabled: Entity<InputState>,
     text_field_read_only: Entity<InputState>,
+    text_field_password: Entity<InputState>,
     /// One of each stateful control per rung, for the Control Sizes page.
     /// Indexed by `ControlSize::ALL`.
     control_row_checkboxes: [Entity<Checkbox>; 3],
@@ -649,6 +647,10 @@ struct Showcase {
     /// hazard.
     textarea_disabled: Entity<InputState>,
     textarea_read_only: Entity<InputState>,
+    /// Grows from one row to six as it is typed into.
+    textarea_growing: Entity<InputState>,
+    /// A chat composer: `@` suggests people and `#` channels.
+    mentions_example: Entity<MentionsState>,
     popover_example: Entity<PopoverState>,
     dialog_example: Entity<DialogState>,
     /// The destructive confirmation: same element, confirm mode.
@@ -701,34 +703,18 @@ impl Showcase {
             )
         });
 
-        let theme_select = cx.new(|_cx| {
+        let theme_select = cx.new(|cx| {
+            let themes = ThemeRegistry::global(cx)
+                .names()
+                .map(|name| (name.clone(), name.clone()))
+                .collect();
             SelectState::new(
-                select(
-                    "theme-select",
-                    "Theme",
-                    vec![
-                        (ThemeChoice::GruvboxDark, "Gruvbox Dark"),
-                        (ThemeChoice::GruvboxLight, "Gruvbox Light"),
-                        (ThemeChoice::CatppuccinLatte, "Catppuccin Latte"),
-                        (ThemeChoice::CatppuccinFrappe, "Catppuccin Frappé"),
-                        (ThemeChoice::CatppuccinMacchiato, "Catppuccin Macchiato"),
-                        (ThemeChoice::CatppuccinMocha, "Catppuccin Mocha"),
-                    ],
-                )
-                .selected(ThemeChoice::GruvboxDark)
-                .full_width(true)
-                .on_change(|choice, window, cx| {
-                    let theme = match choice {
-                        ThemeChoice::GruvboxDark => Theme::gruvbox_dark(),
-                        ThemeChoice::GruvboxLight => Theme::gruvbox_light(),
-                        ThemeChoice::CatppuccinLatte => Theme::catppuccin_latte(),
-                        ThemeChoice::CatppuccinFrappe => Theme::catppuccin_frappe(),
-                        ThemeChoice::CatppuccinMacchiato => Theme::catppuccin_macchiato(),
-                        ThemeChoice::CatppuccinMocha => Theme::catppuccin_mocha(),
-                    };
-                    cx.set_global(GlobalTheme(Arc::new(theme)));
-                    window.refresh();
-                }),
+                select("theme-select", "Theme", themes)
+                    .selected(cx.theme().name.clone())
+                    .full_width(true)
+                    .on_change(|name: SharedString, _window, cx| {
+                        ThemeRegistry::set_active(&name, cx).ok();
+                    }),
             )
         });
 
@@ -918,13 +904,24 @@ impl Showcase {
         let text_field_icon = cx.new(|cx| InputState::new_singleline(cx));
         let text_field_affixes = cx.new(|cx| InputState::new_singleline(cx));
         let text_field_action = cx.new(|cx| InputState::new_singleline(cx));
-        let text_field_composed = cx.new(|cx| InputState::new_singleline(cx));
+        let text_field_composed = cx.new(|cx| {
+            InputState::new_singleline(cx).completions(PrefixCompletions::new([
+                "git status",
+                "git stash",
+                "git switch",
+                "git commit --amend",
+            ]))
+        });
         let text_field_disabled = cx.new(|cx| InputState::new_singleline(cx));
         let text_field_read_only = cx.new(|cx| {
             let mut state = InputState::new_singleline(cx);
             state.set_content("gpuikit-0.8.0", cx);
+            // Not shown — the field has content — but it is the field's
+            // accessible name.
+            state.set_placeholder("Version", cx);
             state
         });
+        let text_field_password = cx.new(|cx| InputState::new_singleline(cx).secure(true));
         // One of each stateful control per rung. Built here rather than in
         // `render` because `render` runs every frame.
         let control_row_checkboxes = ControlSize::ALL.map(|size| {
@@ -980,6 +977,44 @@ impl Showcase {
             );
             state
         });
+        let textarea_growing = cx.new(InputState::new_multiline);
+
+        let composer = cx.new(InputState::new_multiline);
+        let mentions_example = cx.new(|cx| {
+            let suggest = |names: &'static [(&'static str, &'static str)], trigger: char| {
+                move |query: &str| {
+                    names
+                        .iter()
+                        .filter(|(handle, _)| handle.starts_with(&query.to_lowercase()))
+                        .map(|(handle, name)| {
+                            Mention::new(format!("{trigger}{handle}"), *handle)
+                                .label(format!("{trigger}{handle} — {name}"))
+                        })
+                        .collect()
+                }
+            };
+            MentionsState::new(
+                mentions("mentions-composer", "Message", &composer)
+                    .source(
+                        '@',
+                        suggest(
+                            &[
+                                ("alice", "Alice Liddell"),
+                                ("albert", "Albert Hofmann"),
+                                ("bob", "Bob Kahn"),
+                            ],
+                            '@',
+                        ),
+                    )
+                    .source(
+                        '#',
+                        suggest(&[("general", "Everyone"), ("random", "Off topic")], '#'),
+                    )
+                    .rows(3),
+                _window,
+                cx,
+            )
+        });
 
         let popover_example = cx.new(|_cx| {
             PopoverState::new(
@@ -1069,6 +1104,27 @@ impl Showcase {
             })
         };
 
+        let number_width = cx.new(|cx| {
+            NumberFieldState::new(
+                number_field("number-width", "Width", 320.)
+                    .range(0.0..=4096.)
+                    .integer(true)
+                    .label("W"),
+                _window,
+                cx,
+            )
+        });
+        let number_price = cx.new(|cx| {
+            NumberFieldState::new(
+                number_field("number-price", "Price", 9.5)
+                    .step(0.25)
+                    .format(|value| format!("{value:.2}").replace('.', ","))
+                    .parse(|text| text.replace(',', ".").parse().ok()),
+                _window,
+                cx,
+            )
+        });
+
         let combobox_default = new_combobox("combobox-default", &|b| b, _window, cx);
         let combobox_selected =
             new_combobox("combobox-selected", &|b| b.selected("banana"), _window, cx);
@@ -1179,6 +1235,8 @@ impl Showcase {
             slider_volume,
             slider_brightness,
             slider_disabled,
+            number_width,
+            number_price,
             toggle_bold,
             toggle_pinned,
             toggle_disabled,
@@ -1206,6 +1264,7 @@ impl Showcase {
             text_field_composed,
             text_field_disabled,
             text_field_read_only,
+            text_field_password,
             control_row_checkboxes,
             control_row_switches,
             control_row_toggles,
@@ -1214,6 +1273,8 @@ impl Showcase {
             textarea_example,
             textarea_disabled,
             textarea_read_only,
+            textarea_growing,
+            mentions_example,
             popover_example,
             dialog_example,
             destructive_dialog,
@@ -1559,6 +1620,30 @@ impl Showcase {
                     .child(self.slider_brightness.clone())
                     .child(self.slider_disabled.clone()),
             )
+            .child(
+                div()
+                    .text_lg()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(theme.fg_muted())
+                    .child("Number field"),
+            )
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(theme.fg_muted())
+                    .child(
+                        "Type a value and press Enter, step with the arrows, PageUp / PageDown \
+                         or the buttons, or drag the \"W\" label to scrub. The price uses a \
+                         decimal comma.",
+                    ),
+            )
+            .child(
+                v_stack()
+                    .gap_4()
+                    .items_start()
+                    .child(self.number_width.clone())
+                    .child(self.number_price.clone()),
+            )
     }
 
     fn render_tabs_page(&self, cx: &Context<Self>) -> impl IntoElement {
@@ -2061,7 +2146,7 @@ impl Showcase {
                         h_stack()
                             .gap_2()
                             .items_center()
-                            .child(text_field(&self.text_field_composed, cx).placeholder("Query"))
+                            .child(text_field(&self.text_field_composed, cx).placeholder("Try git s…"))
                             .child(button("text-field-go", "Go")),
                         theme,
                     ))
@@ -2080,6 +2165,15 @@ impl Showcase {
                         // refused by `InputState`.
                         text_field(&self.text_field_read_only, cx).read_only(true),
                         theme,
+                    ))
+                    .child(row(
+                        "Password:",
+                        // Bullets, no copy or cut, no undo history; the button
+                        // shows the text without lifting the rest.
+                        text_field(&self.text_field_password, cx)
+                            .placeholder("Password")
+                            .reveal_toggle(true),
+                        theme,
                     )),
             )
     }
@@ -2127,10 +2221,33 @@ impl Showcase {
                                     .rows(2)
                                     .read_only(true),
                             ),
+                    )
+                    .child(
+                        field("growing-message")
+                            .label("Growing")
+                            .description("One row, up to six as you type; then it scrolls")
+                            .child(
+                                textarea(&self.textarea_growing, cx)
+                                    .placeholder("Write a reply...")
+                                    .min_rows(1)
+                                    .max_rows(6),
+                            ),
                     ),
             )
     }
 
+    fn render_mentions_page(&self) -> impl IntoElement {
+        card()
+            .title("Mentions")
+            .description("Type @ for people or # for channels; each choice becomes a chip")
+            .body(
+                field("mentions-message")
+                    .label("Message")
+                    .description("Backspace removes a chip whole")
+                    .child(self.mentions_example.clone()),
+            )
+    }
+
     fn render_avatar_page(&self, cx: &Context<Self>) -> impl IntoElement {
         let theme = cx.theme();
         v_stack()
@@ -4207,6 +4324,7 @@ impl Render for Showcase {
                 .child(self.render_field_page(cx))
                 .child(self.render_text_field_page(cx))
                 .child(self.render_textarea_page(cx))
+                .child(self.render_mentions_page())
                 .into_any_element(),
             "form" => self.render_form_page(cx).into_any_element(),
             "slider" => self.render_slider_page(cx).into_any_element(),
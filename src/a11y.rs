@@ -891,15 +891,11 @@ pub const ELEMENTS_WITHOUT_A_ROLE: &[(&str, &str)] = &[
         "would be Role::TabList over Role::Tab, composite-item roles that need the roving-focus \
          convention before a per-item rule can be right",
     ),
-    (
-        "text_field",
-        "would be Role::TextInput with a `value` and a required name; it owns a FocusHandle \
-         already, so adopting it is a move onto `A11y::focus_handle`",
-    ),
     (
         "textarea",
-        "would be Role::MultilineTextInput; same shape as `text_field`, and the two should be \
-         adopted in one change so their focus handling matches",
+        "would be Role::MultilineTextInput; same shape as `text_field`, which announces only \
+         when it has a name — adopting either fully takes the other in the same change, so \
+         their focus handling matches",
     ),
     (
         "toast",
@@ -1,5 +1,7 @@
 use gpui::{px, rgb, ElementId, Pixels, Rgba, SharedString, TextRun};
 
+use crate::theme::TextMetrics;
+
 use super::buffer::{GapBuffer, TextBuffer};
 use super::syntax_highlighter::SyntaxHighlighter;
 
@@ -58,6 +60,8 @@ pub struct Editor {
     language: String,
     current_theme: String,
     scroll_row: usize,
+    /// Whether the font fields of `config` track the theme's code rung.
+    follow_type_scale: bool,
 }
 
 impl Editor {
@@ -82,6 +86,7 @@ impl Editor {
             language,
             current_theme: String::new(),
             scroll_row: 0,
+            follow_type_scale: true,
         }
     }
 
@@ -101,6 +106,30 @@ impl Editor {
         self.config = config;
     }
 
+    /// Whether `font_size`, `line_height` and `font_family` in the config are
+    /// taken from the theme's [`TextRole::Code`](crate::theme::TextRole::Code)
+    /// rung each time the editor paints. On by default; a caller that sets
+    /// its own font through [`config_mut`](Self::config_mut) or
+    /// [`set_config`](Self::set_config) turns it off, or the next paint
+    /// overwrites that font.
+    pub fn set_follow_type_scale(&mut self, follow: bool) {
+        self.follow_type_scale = follow;
+    }
+
+    /// Take the font settings from a rung of the type scale, at the window's
+    /// rem size. A no-op once [`set_follow_type_scale`](Self::set_follow_type_scale)
+    /// has turned following off.
+    pub fn apply_type_scale(&mut self, metrics: &TextMetrics, rem_size: Pixels) {
+        if !self.follow_type_scale {
+            return;
+        }
+        self.config.font_size = rem_size * metrics.size.0;
+        self.config.line_height = rem_size * metrics.line_height.0;
+        if let Some(family) = &metrics.family {
+            self.config.font_family = family.clone();
+        }
+    }
+
     pub fn cursor_position(&self) -> CursorPosition {
         self.cursor_position
     }
@@ -2,6 +2,7 @@
 
 use super::buffer::TextBuffer;
 use super::editor::Editor;
+use crate::theme::{ActiveTheme, TextRole, Themeable};
 use gpui::{canvas, Stateful, *};
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -384,6 +385,10 @@ impl IntoElement for EditorElement {
                         // Prepaint - nothing needed here
                     },
                     move |bounds, _, window, cx| {
+                        editor_for_render
+                            .borrow_mut()
+                            .apply_type_scale(&cx.theme().text(TextRole::Code), window.rem_size());
+
                         // Create a temporary EditorElement for rendering
                         let mut temp_element = EditorElement {
                             editor: editor_for_render.clone(),
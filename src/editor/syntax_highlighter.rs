@@ -632,6 +632,45 @@ impl SyntaxHighlighter {
         Ok(())
     }
 
+    /// Highlight with an imported editor theme's own syntax colours, or, when
+    /// it has none, with the bundled theme whose background is nearest its
+    /// `bg` — so a dark import does not get a light code theme.
+    ///
+    /// Returns the name of the theme now in use.
+    pub fn use_imported_theme(&mut self, imported: &crate::theme::ImportedTheme) -> String {
+        let mut inner = self.inner.borrow_mut();
+        let name = match &imported.syntax {
+            Some(theme) => {
+                let name = imported.theme.name.to_string();
+                inner.theme_set.themes.insert(name.clone(), theme.clone());
+                name
+            }
+            None => {
+                let target = gpui::Rgba::from(imported.theme.bg_color);
+                let distance = |color: syntect::highlighting::Color| {
+                    let channel = |a: u8, b: f32| (a as f32 / 255.0 - b).powi(2);
+                    channel(color.r, target.r)
+                        + channel(color.g, target.g)
+                        + channel(color.b, target.b)
+                };
+                let nearest = inner
+                    .theme_set
+                    .themes
+                    .iter()
+                    .filter_map(|(name, theme)| Some((name, distance(theme.settings.background?))))
+                    .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                    .map(|(name, _)| name.clone());
+                match nearest {
+                    Some(name) => name,
+                    None => return inner.current_theme.clone(),
+                }
+            }
+        };
+        inner.current_theme = name.clone();
+        inner.highlight_states.clear();
+        name
+    }
+
     // Load custom syntax definitions
     // Example: highlighter.load_syntax_from_file("./syntaxes/mylang.sublime-syntax")
     #[allow(dead_code)]
@@ -918,6 +957,29 @@ mod tests {
         assert_eq!(runs[0].color, gpui::rgb(0xcccccc).into());
         assert!(highlighter.inner.borrow().parse_states.is_empty());
     }
+
+    /// An import with its own syntax colours uses them; one without gets the
+    /// bundled theme nearest its background.
+    #[test]
+    fn an_imported_theme_picks_its_syntax_colours() {
+        let mut highlighter = SyntaxHighlighter::new();
+
+        let own = crate::theme::from_vscode(
+            r##"{"name": "Own", "colors": {"editor.background": "#101010"},
+                "tokenColors": [{"scope": "storage, keyword", "settings": {"foreground": "#ff0000"}}]}"##,
+        )
+        .unwrap();
+        assert_eq!(highlighter.use_imported_theme(&own), "Own");
+        let runs = highlighter.highlight_line("fn main() {}\n", "Rust", 0, "test".into(), 14.0);
+        assert_eq!(runs[0].color, gpui::rgb(0xff0000).into());
+
+        let bare = crate::theme::from_vscode(
+            r##"{"name": "Bare", "colors": {"editor.background": "#ffffff"}}"##,
+        )
+        .unwrap();
+        highlighter.use_imported_theme(&bare);
+        assert!(highlighter.get_theme_background().l > 0.9);
+    }
 }
 
 // HOW TO ADD CUSTOM GRAMMARS AND THEMES:
@@ -1090,3 +1090,26 @@ fn test_last_line_is_not_given_a_separator_it_does_not_have() {
     );
     assert_eq!(empty_line_runs[0].len, 0);
 }
+
+#[test]
+fn test_font_follows_the_type_scale_until_told_not_to() {
+    use crate::theme::{TextRole, TypeScale};
+    use gpui::px;
+
+    let mut editor = Editor::new("test_type_scale", vec![]);
+    let scale = TypeScale {
+        body: gpui::Rems(1.25),
+        code_font_family: "Iosevka".into(),
+        ..Default::default()
+    };
+
+    editor.apply_type_scale(&scale.metrics(TextRole::Code), px(16.0));
+    assert_eq!(editor.config().font_size, px(17.5));
+    assert_eq!(editor.config().line_height, px(26.25));
+    assert_eq!(editor.config().font_family.as_ref(), "Iosevka");
+
+    editor.set_follow_type_scale(false);
+    editor.config_mut().font_size = px(12.0);
+    editor.apply_type_scale(&scale.metrics(TextRole::Code), px(16.0));
+    assert_eq!(editor.config().font_size, px(12.0));
+}
@@ -22,12 +22,14 @@ pub mod input;
 pub mod kbd;
 pub mod label;
 pub mod list;
-/// The listbox popup `select` and `combobox` are both built out of. The one
-/// `pub(crate)` in this list, and deliberately so: it is not a component, it
-/// is the thing two components are made of. See `docs/menus-and-listboxes.md`
-/// §2 and the module docs.
+/// The listbox popup `select`, `combobox` and `mentions` are built out of.
+/// The one `pub(crate)` in this list, and deliberately so: it is not a
+/// component, it is the thing components are made of. See
+/// `docs/menus-and-listboxes.md` §2 and the module docs.
 pub(crate) mod listbox;
 pub mod loading_indicator;
+pub mod mentions;
+pub mod number_field;
 pub mod popover;
 pub mod progress;
 pub mod radio_group;
@@ -23,10 +23,10 @@
 //! ```
 
 use crate::icons::Icons;
-use crate::theme::{ActiveTheme, Themeable};
+use crate::theme::{ActiveTheme, Radius, Themeable};
 use gpui::{
-    div, prelude::*, px, rems, Context, ElementId, EventEmitter, IntoElement, ParentElement,
-    Render, SharedString, Styled, Window,
+    div, prelude::*, px, Context, ElementId, EventEmitter, IntoElement, ParentElement, Render,
+    SharedString, Styled, Window,
 };
 use std::collections::HashSet;
 
@@ -250,7 +250,7 @@ impl Render for AccordionState {
             .w_full()
             .border_1()
             .border_color(theme.border())
-            .rounded(rems(0.5))
+            .rounded(theme.radius(Radius::Large))
             .overflow_hidden()
             .children(self.items.iter().enumerate().map(|(index, item)| {
                 let is_expanded = self.expanded.contains(&item.id);
@@ -275,8 +275,8 @@ impl Render for AccordionState {
                             .flex()
                             .items_center()
                             .justify_between()
-                            .px(rems(0.75))
-                            .py(rems(0.5))
+                            .px(theme.space(3.))
+                            .py(theme.space(2.))
                             .bg(theme.surface())
                             .when(!disabled, |this| {
                                 this.cursor_pointer()
@@ -313,8 +313,8 @@ impl Render for AccordionState {
                         this.child(
                             // Content
                             div()
-                                .px(rems(0.75))
-                                .py(rems(0.5))
+                                .px(theme.space(3.))
+                                .py(theme.space(2.))
                                 .bg(theme.surface())
                                 .border_t_1()
                                 .border_color(theme.border_subtle())
@@ -4,9 +4,9 @@
 
 use crate::element_id::scoped;
 use crate::icons::Icons;
-use crate::theme::{ActiveTheme, Themeable};
+use crate::theme::{ActiveTheme, Radius, Themeable};
 use gpui::{
-    div, prelude::FluentBuilder, px, rems, App, ClickEvent, Context, ElementId, Entity, Hsla,
+    div, prelude::FluentBuilder, px, App, ClickEvent, Context, ElementId, Entity, Hsla,
     InteractiveElement, IntoElement, MouseButton, ParentElement, RenderOnce, SharedString,
     StatefulInteractiveElement, Styled, Svg, Window,
 };
@@ -203,6 +203,7 @@ impl RenderOnce for Alert {
             variant_color,
             bg_color,
             border_color,
+            shape,
         ) = {
             let theme = cx.theme();
             (
@@ -212,6 +213,7 @@ impl RenderOnce for Alert {
                 self.variant.color(theme.as_ref()),
                 self.variant.bg_color(theme.as_ref()),
                 self.variant.border_color(theme.as_ref()),
+                theme.shape(),
             )
         };
 
@@ -250,12 +252,12 @@ impl RenderOnce for Alert {
         div()
             .w_full()
             .flex()
-            .gap(rems(0.75))
-            .p(rems(0.75))
+            .gap(shape.space(3.))
+            .p(shape.space(3.))
             .bg(bg_color)
             .border_1()
             .border_color(border_color)
-            .rounded(rems(0.375))
+            .rounded(shape.radius(Radius::Medium))
             // Icon
             .when_some(icon, |alert, icon| {
                 alert.child(
@@ -271,7 +273,7 @@ impl RenderOnce for Alert {
                     .flex_1()
                     .flex()
                     .flex_col()
-                    .gap(rems(0.25))
+                    .gap(shape.space(1.))
                     // Title
                     .when_some(self.title, |content, title| {
                         content.child(
@@ -310,7 +312,7 @@ impl RenderOnce for Alert {
                         .flex()
                         .items_center()
                         .justify_center()
-                        .rounded(px(4.0))
+                        .rounded(shape.radius(Radius::Small))
                         .cursor_pointer()
                         .hover(|div| div.bg(surface_secondary_color.opacity(0.5)))
                         .on_mouse_down(MouseButton::Left, |_, window, _| window.prevent_default())
@@ -3,9 +3,8 @@
 use crate::layout::h_stack;
 use crate::theme::{ActiveTheme, Themeable};
 use gpui::{
-    div, prelude::*, rems, App, ClickEvent, ElementId, InteractiveElement, IntoElement,
-    MouseButton, ParentElement, RenderOnce, SharedString, StatefulInteractiveElement, Styled,
-    Window,
+    div, prelude::*, App, ClickEvent, ElementId, InteractiveElement, IntoElement, MouseButton,
+    ParentElement, RenderOnce, SharedString, StatefulInteractiveElement, Styled, Window,
 };
 
 /// A single breadcrumb item
@@ -93,7 +92,7 @@ impl RenderOnce for Breadcrumb {
 
         h_stack()
             .id(self.id)
-            .gap(rems(0.375))
+            .gap(theme.space(1.5))
             .items_center()
             .text_sm()
             .children(
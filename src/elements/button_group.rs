@@ -2,11 +2,11 @@
 //!
 //! A component for grouping related buttons together with connected borders.
 
-use crate::theme::{ActiveTheme, Themeable};
+use crate::theme::{ActiveTheme, Radius, Themeable};
 use crate::traits::disableable::Disableable;
 use crate::traits::orientable::{Orientable, Orientation};
 use gpui::{
-    div, rems, AnyElement, App, ElementId, InteractiveElement, IntoElement, ParentElement,
+    div, AnyElement, App, ElementId, InteractiveElement, IntoElement, ParentElement, Rems,
     RenderOnce, Styled, Window,
 };
 
@@ -57,6 +57,10 @@ impl RenderOnce for ButtonGroup {
         let theme = cx.theme();
         let child_count = self.children.len();
         let is_horizontal = self.orientation == Orientation::Horizontal;
+        let radius = theme.radius(Radius::Small);
+        // The buttons sit inside the group's 1px border, so their corners
+        // follow a curve 1px tighter than the group's.
+        let inner_radius = Rems((radius.0 - 1. / 16.).max(0.));
 
         let mut container = div()
             .id(self.id)
@@ -65,7 +69,7 @@ impl RenderOnce for ButtonGroup {
             .overflow_hidden()
             .border_1()
             .border_color(theme.button_border())
-            .rounded(rems(0.25));
+            .rounded(radius);
 
         container = if is_horizontal {
             container.flex_row()
@@ -100,17 +104,17 @@ impl RenderOnce for ButtonGroup {
             // Rounded corners for first/last items
             if is_horizontal {
                 if is_first {
-                    wrapper = wrapper.rounded_l(rems(0.2));
+                    wrapper = wrapper.rounded_l(inner_radius);
                 }
                 if is_last {
-                    wrapper = wrapper.rounded_r(rems(0.2));
+                    wrapper = wrapper.rounded_r(inner_radius);
                 }
             } else {
                 if is_first {
-                    wrapper = wrapper.rounded_t(rems(0.2));
+                    wrapper = wrapper.rounded_t(inner_radius);
                 }
                 if is_last {
-                    wrapper = wrapper.rounded_b(rems(0.2));
+                    wrapper = wrapper.rounded_b(inner_radius);
                 }
             }
 
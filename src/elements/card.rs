@@ -1,6 +1,6 @@
-use crate::theme::{ActiveTheme, Themeable};
+use crate::theme::{ActiveTheme, Radius, Themeable};
 use gpui::{
-    div, prelude::FluentBuilder, rems, AnyElement, App, IntoElement, ParentElement, RenderOnce,
+    div, prelude::FluentBuilder, AnyElement, App, IntoElement, ParentElement, RenderOnce,
     SharedString, Styled, Window,
 };
 
@@ -72,12 +72,12 @@ impl RenderOnce for Card {
             .bg(theme.surface())
             .border_1()
             .border_color(theme.border())
-            .rounded(rems(0.5))
+            .rounded(theme.radius(Radius::Large))
             .overflow_hidden()
             .when_some(self.header, |card, header| {
                 card.child(
                     div()
-                        .p(rems(0.75))
+                        .p(theme.space(3.))
                         .border_b_1()
                         .border_color(theme.border_subtle())
                         .child(header),
@@ -86,10 +86,10 @@ impl RenderOnce for Card {
             .when(has_title_section, |card| {
                 card.child(
                     div()
-                        .p(rems(0.75))
+                        .p(theme.space(3.))
                         .flex()
                         .flex_col()
-                        .gap(rems(0.25))
+                        .gap(theme.space(1.))
                         .when_some(self.title, |section, title| {
                             section.child(
                                 div()
@@ -110,12 +110,12 @@ impl RenderOnce for Card {
                 )
             })
             .when_some(self.body, |card, body| {
-                card.child(div().p(rems(0.75)).child(body))
+                card.child(div().p(theme.space(3.)).child(body))
             })
             .when_some(self.footer, |card, footer| {
                 card.child(
                     div()
-                        .p(rems(0.75))
+                        .p(theme.space(3.))
                         .border_t_1()
                         .border_color(theme.border_subtle())
                         .child(footer),
@@ -2,12 +2,12 @@
 
 use crate::icons::Icons;
 use crate::layout::h_stack;
-use crate::theme::{ActiveTheme, Themeable};
+use crate::theme::{ActiveTheme, Radius, Themeable};
 use crate::traits::disableable::Disableable;
 use gpui::{
-    div, prelude::*, px, rems, AnyElement, App, Context, ElementId, EventEmitter,
-    InteractiveElement, IntoElement, MouseButton, ParentElement, Render, SharedString,
-    StatefulInteractiveElement, Styled, Window,
+    div, prelude::*, px, AnyElement, App, Context, ElementId, EventEmitter, InteractiveElement,
+    IntoElement, MouseButton, ParentElement, Render, SharedString, StatefulInteractiveElement,
+    Styled, Window,
 };
 use std::rc::Rc;
 
@@ -127,6 +127,7 @@ impl Render for Collapsible {
         let fg_color = theme.fg();
         let fg_muted = theme.fg_muted();
         let surface_secondary = theme.surface_secondary();
+        let shape = theme.shape();
 
         // Build trigger element
         let trigger_element = if let Some(ref render) = self.trigger_render {
@@ -163,9 +164,9 @@ impl Render for Collapsible {
                 h_stack()
                     .id(self.id.clone())
                     .w_full()
-                    .gap(rems(0.5))
+                    .gap(shape.space(2.))
                     .items_center()
-                    .py(rems(0.5))
+                    .py(shape.space(2.))
                     .when(!disabled, |this| {
                         this.cursor_pointer()
                             .on_mouse_down(MouseButton::Left, |_, window, _| {
@@ -177,8 +178,8 @@ impl Render for Collapsible {
                             .hover(move |style| style.bg(surface_secondary))
                     })
                     .when(disabled, |this| this.cursor_not_allowed().opacity(0.65))
-                    .rounded(rems(0.25))
-                    .px(rems(0.5))
+                    .rounded(shape.radius(Radius::Small))
+                    .px(shape.space(2.))
                     .when(show_indicator, |this| {
                         this.child(
                             div()
@@ -198,9 +199,9 @@ impl Render for Collapsible {
             .when_some(content_element, |this, content| {
                 this.child(
                     div()
-                        .pl(if show_indicator { rems(1.5) } else { rems(0.5) })
-                        .pr(rems(0.5))
-                        .pb(rems(0.5))
+                        .pl(shape.space(if show_indicator { 6. } else { 2. }))
+                        .pr(shape.space(2.))
+                        .pb(shape.space(2.))
                         .child(content),
                 )
             })
@@ -103,7 +103,7 @@ use crate::elements::listbox::{matches_query, Listbox, ListboxFocus, LISTBOX_GAP
 use crate::elements::text_field::{text_field, Adornment};
 use crate::icons::Icons;
 use crate::input::{InputState, InputStateEvent};
-use crate::theme::{ActiveTheme, ControlSize, Themeable};
+use crate::theme::{with_theme, ActiveTheme, ControlSize, Themeable};
 use crate::traits::accessible::Accessible;
 use crate::traits::control_sized::ControlSized;
 use crate::traits::disableable::Disableable;
@@ -694,7 +694,13 @@ impl<T: Clone + PartialEq + 'static> Render for ComboboxState<T> {
             // Rung 1 of `docs/overlays.md`'s ladder, the same one select,
             // popover and context menu use. A chooser's popup is not a new
             // layer.
-            this.child(deferred(anchored().offset(point(px(0.), gap)).child(popup)).with_priority(1))
+            this.child(
+                deferred(with_theme(
+                    cx.theme().clone(),
+                    anchored().offset(point(px(0.), gap)).child(popup),
+                ))
+                .with_priority(1),
+            )
         })
     }
 }
@@ -702,7 +708,7 @@ impl<T: Clone + PartialEq + 'static> Render for ComboboxState<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use gpui::{px, size, Entity, TestAppContext, VisualTestContext};
+    use gpui::{px, size, Entity, Focusable, TestAppContext, VisualTestContext};
     use std::cell::RefCell;
     use std::ops::Deref;
 
@@ -728,7 +734,16 @@ mod tests {
         cx: &mut TestAppContext,
         build: impl FnOnce(Combobox<usize>) -> Combobox<usize>,
     ) -> (Entity<ComboboxState<usize>>, &'static mut VisualTestContext) {
-        cx.update(crate::init);
+        open_after(crate::init, cx, build)
+    }
+
+    /// [`open`], initialized by `init` instead.
+    fn open_after(
+        init: impl FnOnce(&mut App),
+        cx: &mut TestAppContext,
+        build: impl FnOnce(Combobox<usize>) -> Combobox<usize>,
+    ) -> (Entity<ComboboxState<usize>>, &'static mut VisualTestContext) {
+        cx.update(init);
         let window = cx.open_window(size(px(400.), px(300.)), |window, cx| {
             let combobox = cx.new(|cx| {
                 ComboboxState::new(build(combobox("test", "Fruit", options())), window, cx)
@@ -911,4 +926,48 @@ mod tests {
             });
         });
     }
+
+    /// A field with a recall history still hands Down to the combobox: the
+    /// `"Combobox > Input"` binding wins the tie, so the popup opens and no
+    /// submission is recalled.
+    #[gpui::test]
+    fn down_opens_the_popup_even_with_a_recall_history(cx: &mut TestAppContext) {
+        let (state, cx) = open(cx, |builder| builder);
+        cx.update(|window, cx| {
+            let input = state.read(cx).input.clone();
+            input.update(cx, |input, _cx| {
+                let mut history = crate::input::RecallHistory::new();
+                history.push("Banana");
+                input.set_recall(Some(history));
+            });
+            window.focus(&input.focus_handle(cx), cx);
+        });
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes("down");
+        cx.update(|_window, cx| {
+            assert!(state.read(cx).is_open());
+            assert_eq!(state.read(cx).input.read(cx).content(), "");
+        });
+    }
+
+    /// An app that starts with a keybinding preset still gets the combobox's
+    /// Down: `init_with_input_bindings` registers the preset where `init`
+    /// registers the defaults, before `bind_combobox_keys`.
+    #[gpui::test]
+    fn down_opens_the_popup_under_the_readline_preset(cx: &mut TestAppContext) {
+        let (state, cx) = open_after(
+            |cx| crate::init_with_input_bindings(cx, crate::input::InputBindings::readline()),
+            cx,
+            |builder| builder,
+        );
+        cx.update(|window, cx| {
+            let input = state.read(cx).input.clone();
+            window.focus(&input.focus_handle(cx), cx);
+        });
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes("down");
+        cx.update(|_window, cx| assert!(state.read(cx).is_open()));
+    }
 }
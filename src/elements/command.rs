@@ -70,7 +70,7 @@ use crate::element_id::for_entity;
 use crate::elements::listbox::wrapped_index;
 use crate::elements::text_field::text_field;
 use crate::input::{InputState, InputStateEvent};
-use crate::theme::{ActiveTheme, ControlSize, Themeable};
+use crate::theme::{with_theme, ActiveTheme, ControlSize, Elevation, Themeable};
 use crate::traits::accessible::Accessible;
 use crate::traits::control_sized::ControlSized;
 use gpui::{
@@ -542,7 +542,7 @@ impl Render for CommandState {
             .border_1()
             .border_color(border)
             .rounded(metrics.radius)
-            .shadow_lg()
+            .shadow(theme.elevation(Elevation::Overlay))
             .flex()
             .flex_col()
             .child(
@@ -605,7 +605,7 @@ impl Render for CommandState {
         // and its distance from the top is padding on the scrim rather than an
         // offset.
         div().when(self.open, |this| {
-            this.child(deferred(scrimmed).with_priority(10))
+            this.child(deferred(with_theme(cx.theme().clone(), scrimmed)).with_priority(10))
         })
     }
 }
@@ -37,7 +37,7 @@ use gpui::{
 use crate::element_id::scoped;
 use crate::elements::kbd::kbd;
 use crate::icons::Icons;
-use crate::theme::{ActiveTheme, Themeable};
+use crate::theme::{with_theme, ActiveTheme, Elevation, Radius, Themeable};
 use crate::traits::control_sized::ControlSized;
 
 /// The id of the open popup of the menu attached to `menu_id`.
@@ -543,11 +543,13 @@ impl RenderOnce for ContextMenu {
             })
             .child(trigger);
 
+        let overlay_theme = cx.theme().clone();
         div()
             .child(trigger)
             .when_some(open, |el, (position, rows, scroll, focus_handle)| {
                 el.child(
-                    deferred(
+                    deferred(with_theme(
+                        overlay_theme,
                         anchored()
                             .position(position)
                             // Keep the whole menu on screen near a window edge.
@@ -562,7 +564,7 @@ impl RenderOnce for ContextMenu {
                                 max_height,
                                 cx,
                             ))),
-                    )
+                    ))
                     .with_priority(1),
                 )
             })
@@ -642,9 +644,9 @@ fn menu_popup(
         .bg(theme.surface())
         .border_1()
         .border_color(theme.border())
-        .rounded_md()
-        .shadow_lg()
-        .py_1()
+        .rounded(theme.radius(Radius::Medium))
+        .shadow(theme.elevation(Elevation::Overlay))
+        .py(theme.space(1.))
         .flex()
         .flex_col()
         .children(
@@ -658,14 +660,14 @@ fn menu_row(row: Row, state: Entity<MenuState>, cx: &App) -> impl IntoElement {
 
     match row {
         Row::Separator => div()
-            .my_1()
+            .my(theme.space(1.))
             .h(px(1.))
             .bg(theme.border_subtle())
             .into_any_element(),
         Row::Header(label) => div()
-            .px_3()
-            .pt_2()
-            .pb_1()
+            .px(theme.space(3.))
+            .pt(theme.space(2.))
+            .pb(theme.space(1.))
             .text_xs()
             .text_color(theme.fg_muted())
             .child(label)
@@ -697,14 +699,14 @@ fn menu_row(row: Row, state: Entity<MenuState>, cx: &App) -> impl IntoElement {
 
             let mut row = div()
                 .id(("gpuikit-context-menu-item", index))
-                .px_3()
-                .py_1()
-                .mx_1()
-                .rounded_sm()
+                .px(theme.space(3.))
+                .py(theme.space(1.))
+                .mx(theme.space(1.))
+                .rounded(theme.radius(Radius::Small))
                 .text_xs()
                 .flex()
                 .items_center()
-                .gap_2()
+                .gap(theme.space(2.))
                 .text_color(text_color);
 
             if disabled {
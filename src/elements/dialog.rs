@@ -36,7 +36,7 @@ use crate::elements::button::button;
 use crate::elements::icon_button::icon_button;
 use crate::icons::Icons;
 use crate::layout::h_stack;
-use crate::theme::{ActiveTheme, ControlSize, Themeable};
+use crate::theme::{with_theme, ActiveTheme, ControlSize, Elevation, Radius, Themeable};
 use crate::traits::accessible::Accessible;
 use crate::traits::control_sized::ControlSized;
 use gpui::{
@@ -548,9 +548,14 @@ impl Render for DialogState {
         let border_color = theme.border();
         let fg_color = theme.fg();
         let fg_muted_color = theme.fg_muted();
+        let shape = theme.shape();
+        // Deferred content paints after the enclosing theme scope has closed;
+        // this reopens it. See `theme::scope`.
+        let overlay_theme = theme.clone();
 
         // Render the dialog overlay using deferred() for proper layering
-        deferred(
+        deferred(with_theme(
+            overlay_theme,
             div()
                 .id(self.id.clone())
                 .key_context(DIALOG_CONTEXT)
@@ -599,8 +604,8 @@ impl Render for DialogState {
                         .bg(surface_color)
                         .border_1()
                         .border_color(border_color)
-                        .rounded_lg()
-                        .shadow_xl()
+                        .rounded(shape.radius(Radius::Large))
+                        .shadow(shape.elevation(Elevation::Modal))
                         .flex()
                         .flex_col()
                         // Header
@@ -610,7 +615,7 @@ impl Render for DialogState {
                                     .flex()
                                     .items_start()
                                     .justify_between()
-                                    .p_4()
+                                    .p(shape.space(4.))
                                     .when(
                                         description.is_some()
                                             || content.is_some()
@@ -648,13 +653,13 @@ impl Render for DialogState {
                         .when_some(description, |this, desc| {
                             this.child(
                                 div()
-                                    .px_4()
-                                    .pt_2()
+                                    .px(shape.space(4.))
+                                    .pt(shape.space(2.))
                                     .when(
                                         content.is_none()
                                             && footer.is_none()
                                             && confirmation.is_none(),
-                                        |this| this.pb_4(),
+                                        |this| this.pb(shape.space(4.)),
                                     )
                                     .text_sm()
                                     .text_color(fg_muted_color)
@@ -665,10 +670,10 @@ impl Render for DialogState {
                         .when_some(content, |this, content| {
                             this.child(
                                 div()
-                                    .px_4()
-                                    .pt_2()
+                                    .px(shape.space(4.))
+                                    .pt(shape.space(2.))
                                     .when(footer.is_none() && confirmation.is_none(), |this| {
-                                        this.pb_4()
+                                        this.pb(shape.space(4.))
                                     })
                                     .child(content(window, cx)),
                             )
@@ -679,8 +684,8 @@ impl Render for DialogState {
                                 div()
                                     .flex()
                                     .justify_end()
-                                    .gap_2()
-                                    .p_4()
+                                    .gap(shape.space(2.))
+                                    .p(shape.space(4.))
                                     .child(footer(window, cx)),
                             )
                         })
@@ -692,7 +697,7 @@ impl Render for DialogState {
                                 h_stack()
                                     .justify_end()
                                     .gap(footer_gap)
-                                    .p_4()
+                                    .p(shape.space(4.))
                                     .child(
                                         button(
                                             scoped(&self.id, "cancel"),
@@ -726,7 +731,7 @@ impl Render for DialogState {
                             )
                         }),
                 ),
-        )
+        ))
         .with_priority(10)
         .into_any_element()
     }
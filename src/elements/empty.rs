@@ -1,6 +1,6 @@
 use crate::theme::{ActiveTheme, Themeable};
 use gpui::{
-    div, prelude::FluentBuilder, px, rems, AnyElement, App, IntoElement, ParentElement, RenderOnce,
+    div, prelude::FluentBuilder, px, AnyElement, App, IntoElement, ParentElement, RenderOnce,
     SharedString, Styled, Svg, Window,
 };
 
@@ -66,9 +66,9 @@ impl RenderOnce for Empty {
             .flex_col()
             .items_center()
             .justify_center()
-            .py(rems(2.0))
-            .px(rems(1.5))
-            .gap(rems(0.75))
+            .py(theme.space(8.))
+            .px(theme.space(6.))
+            .gap(theme.space(3.))
             .when_some(self.icon, |container, icon| {
                 container.child(
                     icon.size(px(48.0))
@@ -96,7 +96,7 @@ impl RenderOnce for Empty {
                 )
             })
             .when_some(self.action, |container, action| {
-                container.child(div().pt(rems(0.5)).child(action))
+                container.child(div().pt(theme.space(2.)).child(action))
             })
     }
 }
@@ -14,7 +14,7 @@ use crate::traits::control_sized::ControlSized;
 use crate::traits::disableable::Disableable;
 use crate::traits::labelable::Labelable;
 use gpui::{
-    div, prelude::FluentBuilder, rems, AnyElement, App, ElementId, InteractiveElement, IntoElement,
+    div, prelude::FluentBuilder, AnyElement, App, ElementId, InteractiveElement, IntoElement,
     ParentElement, Rems, RenderOnce, Role, SharedString, StatefulInteractiveElement, Styled, Window,
 };
 
@@ -198,7 +198,7 @@ impl RenderOnce for Field {
                 // the control" assertable at all.
                 .debug_selector(|| "gpuikit-field-label".into())
                 .flex()
-                .gap(rems(0.25))
+                .gap(theme.space(1.))
                 // Clicking a label focuses the control it names. A control
                 // that has not adopted `form::focus_handle_here` tracks no
                 // such handle, and the click is inert rather than wrong.
@@ -254,7 +254,7 @@ impl RenderOnce for Field {
                     .announce(a11y)
                     .flex()
                     .flex_col()
-                    .gap(rems(0.375))
+                    .gap(theme.space(1.5))
                     .when(disabled, |el| el.cursor_not_allowed())
                     .when_some(label_element, |container, label| container.child(label))
                     .when_some(description_element, |container, desc| container.child(desc))
@@ -268,7 +268,7 @@ impl RenderOnce for Field {
                     .announce(a11y)
                     .flex()
                     .flex_col()
-                    .gap(rems(0.375))
+                    .gap(theme.space(1.5))
                     .when(disabled, |el| el.cursor_not_allowed())
                     .child(
                         div()
@@ -281,7 +281,7 @@ impl RenderOnce for Field {
                                         .flex()
                                         .flex_col()
                                         .justify_center()
-                                        .gap(rems(0.25))
+                                        .gap(theme.space(1.))
                                         // The label's box is exactly the
                                         // input's box, so the two lines of
                                         // text centre against each other —
@@ -81,7 +81,7 @@ use crate::traits::accessible::Accessible;
 use crate::traits::control_sized::ControlSized;
 use crate::traits::disableable::Disableable;
 use gpui::{
-    div, prelude::FluentBuilder, rems, AnyElement, App, Bounds, ElementId, FocusHandle,
+    div, prelude::FluentBuilder, AnyElement, App, Bounds, ElementId, FocusHandle,
     GlobalElementId, InspectorElementId, IntoElement, LayoutId, ParentElement, Pixels, RenderOnce,
     InteractiveElement, Role, SharedString, Styled, Window,
 };
@@ -534,12 +534,12 @@ impl RenderOnce for Fieldset {
         v_stack()
             .id(self.id)
             .announce(a11y)
-            .gap(rems(0.5))
+            .gap(theme.space(2.))
             .when_some(legend, |this, legend| this.child(legend))
             .when_some(description, |this, description| this.child(description))
             .child(WithFormContext::new(
                 FormContext::new().disabled(disabled),
-                v_stack().gap(rems(0.75)).children(self.children),
+                v_stack().gap(theme.space(3.)).children(self.children),
             ))
             .when_some(error, |this, error| this.child(error))
     }
@@ -7,27 +7,39 @@
 //!
 //! Use `input()` for single-line text fields and `text_area()` for multi-line text editing.
 
+use std::rc::Rc;
 use std::sync::Arc;
 
 use gpui::{
-    fill, point, px, relative, size, Action, App, Bounds, ContentMask, Context, CursorStyle,
-    DispatchPhase, Element, ElementId, Entity, FocusHandle, Focusable, GlobalElementId, Hitbox,
-    HitboxBehavior, Hsla, InspectorElementId, InteractiveElement, Interactivity, IntoElement,
-    LayoutId, Length, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, Point,
-    ScrollWheelEvent, SharedString, StyleRefinement, Styled, TextAlign, TextRun, TextStyle,
-    TextStyleRefinement, Window, WrappedLine,
+    anchored, deferred, fill, point, px, relative, size, Action, AnyElement, App, AvailableSpace,
+    Bounds, ContentMask, Context, CursorStyle, DispatchPhase, Element, ElementId, Entity,
+    ExternalPaths, FocusHandle, Focusable, GlobalElementId, Hitbox, HitboxBehavior, Hsla,
+    InspectorElementId, InteractiveElement, Interactivity, IntoElement, KeyContext, LayoutId,
+    Length, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement, Pixels,
+    Point, ScrollWheelEvent, SharedString, StyleRefinement, Styled, TextAlign, TextRun, TextStyle,
+    TextStyleRefinement, UnderlineStyle, Window, WrappedLine,
 };
 
+use crate::elements::tooltip::tooltip_panel;
+use crate::markdown::InlinePalette;
 use crate::theme::{ActiveTheme, ControlSize, Themeable};
 use crate::traits::control_sized::ControlSized;
 
 use crate::input::{
-    bindings::Escape, ElementInputHandler, InputLineLayout, InputState, TextDirection,
-    INPUT_CONTEXT,
+    bindings::Escape, drag::TextDrag, Decoration, DecorationStyle, DraggedText,
+    ElementInputHandler, InputLineLayout, InputState, TextDirection, VimMode, INPUT_CONTEXT,
 };
 
+/// What `TextField` and `Textarea` keep to hand their `Input` in
+/// [`Input::on_file_drop`].
+pub(crate) type FileDropHandler = Rc<dyn Fn(&ExternalPaths, &mut Window, &mut App)>;
+
 const CURSOR_WIDTH: f32 = 2.0;
 const MARKED_TEXT_UNDERLINE_THICKNESS: f32 = 2.0;
+/// How thick a decoration's line is; a squiggle is three times as tall.
+const DECORATION_THICKNESS: f32 = 1.0;
+/// The side of each dot of a dotted decoration, and the gap after it.
+const DECORATION_DOT: f32 = 1.5;
 
 /// Creates a new single-line `Input` element powered by the given `InputState`.
 ///
@@ -51,7 +63,12 @@ pub struct Input {
     interactivity: Interactivity,
     placeholder: Option<SharedString>,
     selection_color: Option<Hsla>,
+    search_match_color: Option<Hsla>,
+    token_color: Option<Hsla>,
+    code_color: Option<Hsla>,
     cursor_color: Option<Hsla>,
+    /// The fewest and most rows an auto-growing multiline input shows.
+    grow_rows: Option<(u32, u32)>,
     multiline: bool,
     size: ControlSize,
 }
@@ -65,12 +82,32 @@ impl Input {
             interactivity: Interactivity::new(),
             placeholder: None,
             selection_color: None,
+            search_match_color: None,
+            token_color: None,
+            code_color: None,
             cursor_color: None,
+            grow_rows: None,
             multiline,
             size: ControlSize::default(),
         };
         input.register_actions();
-        input.key_context(INPUT_CONTEXT).track_focus(&focus_handle)
+        let state = input_state.clone();
+        input
+            .interactivity
+            .on_drop::<DraggedText>(move |text, window, cx| {
+                let position = window.mouse_position();
+                state.update(cx, |state, cx| {
+                    if let Some(position) = drop_position(state, position, multiline) {
+                        state.drop_text(&text.0, position, window, cx);
+                    }
+                });
+            });
+        // `vim_mode` is always set, so the vim preset's insert-mode keys can
+        // name it without catching inputs in normal mode.
+        let mut context = KeyContext::default();
+        context.add(INPUT_CONTEXT);
+        context.set("vim_mode", input_state.read(cx).vim_mode().context_value());
+        input.key_context(context).track_focus(&focus_handle)
     }
 
     /// Sets the placeholder text shown when the input is empty.
@@ -85,12 +122,65 @@ impl Input {
         self
     }
 
+    /// Sets the color painted behind each match of the input's search.
+    pub fn search_match_color(mut self, color: impl Into<Hsla>) -> Self {
+        self.search_match_color = Some(color.into());
+        self
+    }
+
+    /// Sets the color of the pill painted behind each of the input's tokens.
+    pub fn token_color(mut self, color: impl Into<Hsla>) -> Self {
+        self.token_color = Some(color.into());
+        self
+    }
+
+    /// Sets the color of the wash painted behind inline code in an
+    /// [attributed](InputState::attributed) input.
+    pub fn code_color(mut self, color: impl Into<Hsla>) -> Self {
+        self.code_color = Some(color.into());
+        self
+    }
+
     /// Sets the color of the text cursor.
     pub fn cursor_color(mut self, color: impl Into<Hsla>) -> Self {
         self.cursor_color = Some(color.into());
         self
     }
 
+    /// Sizes a multiline input to its wrapped content: at least `min_rows`
+    /// lines tall and at most `max_rows`, past which it scrolls. Only while
+    /// the element's own height is left unset.
+    ///
+    /// The content is wrapped at the width layout gives the input while that
+    /// layout is being worked out, so a change of width never shows a frame
+    /// at the height of the old wrap.
+    pub fn auto_grow(mut self, min_rows: u32, max_rows: u32) -> Self {
+        let min_rows = min_rows.max(1);
+        self.grow_rows = Some((min_rows, max_rows.max(min_rows)));
+        self
+    }
+
+    /// Calls `handler` with files dropped on the input, once the caret has
+    /// moved to where they were dropped. See [`drag`](crate::input::drag).
+    pub fn on_file_drop(
+        mut self,
+        handler: impl Fn(&ExternalPaths, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        let state = self.input.clone();
+        let multiline = self.multiline;
+        self.interactivity
+            .on_drop::<ExternalPaths>(move |paths, window, cx| {
+                let position = window.mouse_position();
+                state.update(cx, |state, cx| {
+                    if let Some(position) = drop_position(state, position, multiline) {
+                        state.drop_files(position, window, cx);
+                    }
+                });
+                handler(paths, window, cx);
+            });
+        self
+    }
+
     fn color(&self, cx: &App) -> PaintColors {
         let theme = cx.theme();
 
@@ -98,7 +188,13 @@ impl Input {
             selection: self
                 .selection_color
                 .unwrap_or_else(|| theme.input_selection()),
+            search_match: self
+                .search_match_color
+                .unwrap_or_else(|| theme.warning().opacity(0.3)),
+            token: self.token_color.unwrap_or_else(|| theme.accent_bg()),
+            code: self.code_color.unwrap_or_else(|| theme.fg().opacity(0.1)),
             cursor: self.cursor_color.unwrap_or_else(|| theme.input_cursor()),
+            decoration: theme.danger(),
         }
     }
 
@@ -193,10 +289,90 @@ impl Input {
         register_action(&mut self.interactivity, &self.input, InputState::cut);
         register_action(&mut self.interactivity, &self.input, InputState::undo);
         register_action(&mut self.interactivity, &self.input, InputState::redo);
+        register_action(
+            &mut self.interactivity,
+            &self.input,
+            InputState::next_completion,
+        );
+        register_action(
+            &mut self.interactivity,
+            &self.input,
+            InputState::previous_completion,
+        );
+        register_action(
+            &mut self.interactivity,
+            &self.input,
+            InputState::select_next_occurrence,
+        );
+        register_action(&mut self.interactivity, &self.input, InputState::next_match);
+        register_action(
+            &mut self.interactivity,
+            &self.input,
+            InputState::previous_match,
+        );
+        register_action(
+            &mut self.interactivity,
+            &self.input,
+            InputState::toggle_bold,
+        );
+        register_action(
+            &mut self.interactivity,
+            &self.input,
+            InputState::toggle_italic,
+        );
+        register_action(
+            &mut self.interactivity,
+            &self.input,
+            InputState::toggle_code,
+        );
+        register_action(
+            &mut self.interactivity,
+            &self.input,
+            InputState::kill_to_end_of_line,
+        );
+        register_action(
+            &mut self.interactivity,
+            &self.input,
+            InputState::kill_to_beginning_of_line,
+        );
+        register_action(
+            &mut self.interactivity,
+            &self.input,
+            InputState::kill_word_left,
+        );
+        register_action(
+            &mut self.interactivity,
+            &self.input,
+            InputState::kill_word_right,
+        );
+        register_action(&mut self.interactivity, &self.input, InputState::yank);
+        register_action(&mut self.interactivity, &self.input, InputState::yank_pop);
+        register_action(&mut self.interactivity, &self.input, InputState::transpose);
+        register_action(
+            &mut self.interactivity,
+            &self.input,
+            InputState::enter_normal_mode,
+        );
+        register_action(
+            &mut self.interactivity,
+            &self.input,
+            InputState::enter_insert_mode,
+        );
+        register_action(
+            &mut self.interactivity,
+            &self.input,
+            InputState::enter_insert_mode_after,
+        );
 
+        // Escape first goes back to one selection, and only then gives up
+        // focus.
+        let input = self.input.clone();
         self.interactivity
-            .on_action::<Escape>(|_action, window, _cx| {
-                window.blur();
+            .on_action::<Escape>(move |_action, window, cx| {
+                if input.update(cx, |input, cx| input.collapse_selections(cx)) {
+                    return;
+                }
+                window.blur(cx);
             });
     }
 }
@@ -241,6 +417,8 @@ pub struct InputLayoutState {
 /// Prepaint state passed from prepaint to paint.
 pub struct InputPrepaintState {
     hitbox: Option<Hitbox>,
+    /// The message of the decoration under the pointer.
+    tooltip: Option<AnyElement>,
 }
 
 impl Element for Input {
@@ -264,6 +442,8 @@ impl Element for Input {
     ) -> (LayoutId, Self::RequestLayoutState) {
         let mut resolved_text_style = None;
         let multiline = self.multiline;
+        let grow_rows = self.grow_rows.filter(|_| multiline);
+        let input = self.input.clone();
         let metrics = cx.theme().control(self.size);
 
         // Content text defaults to the theme foreground — the window's
@@ -303,6 +483,30 @@ impl Element for Input {
                                 layout_style.size.width = relative(1.).into();
                             }
                             if let Length::Auto = layout_style.size.height {
+                                if let Some(rows) = grow_rows {
+                                    let text_style = window.text_style();
+                                    return window.request_measured_layout(
+                                        layout_style,
+                                        move |known, available, window, cx| {
+                                            let width = known.width.or(match available.width {
+                                                AvailableSpace::Definite(width) => Some(width),
+                                                _ => None,
+                                            });
+                                            let line_height =
+                                                text_style.line_height_in_pixels(window.rem_size());
+                                            let height = input.update(cx, |input, _cx| {
+                                                input.fit_rows(
+                                                    width,
+                                                    line_height,
+                                                    &text_style,
+                                                    rows,
+                                                    window,
+                                                )
+                                            });
+                                            size(width.unwrap_or_default(), height)
+                                        },
+                                    );
+                                }
                                 layout_style.size.height = relative(1.).into();
                             }
                         } else if let Length::Auto = layout_style.size.height {
@@ -346,7 +550,13 @@ impl Element for Input {
             px(100000.)
         };
 
+        let link_color = cx.theme().accent();
         self.input.update(cx, |input, _cx| {
+            input.set_inline_palette(InlinePalette {
+                code_background: None,
+                link_color: Some(link_color),
+            });
+            input.last_bounds = Some(bounds);
             input.available_height = bounds.size.height;
             input.available_width = bounds.size.width;
             input.update_line_layouts(wrap_width, line_height, &layout_state.text_style, window);
@@ -364,7 +574,29 @@ impl Element for Input {
             },
         );
 
-        InputPrepaintState { hitbox }
+        let hovered = hitbox
+            .as_ref()
+            .is_some_and(|hitbox| hitbox.is_hovered(window));
+        let message = self
+            .input
+            .read(cx)
+            .hovered_decoration()
+            .and_then(|decoration| decoration.message.clone())
+            .filter(|_| hovered);
+        let tooltip = message.map(|message| {
+            let mut tooltip = deferred(
+                anchored()
+                    .position(window.mouse_position())
+                    .snap_to_window_with_margin(px(8.))
+                    .child(tooltip_panel(message, cx)),
+            )
+            .with_priority(1)
+            .into_any_element();
+            tooltip.prepaint_as_root(bounds.origin, AvailableSpace::min_size(), window, cx);
+            tooltip
+        });
+
+        InputPrepaintState { hitbox, tooltip }
     }
 
     fn paint(
@@ -384,11 +616,15 @@ impl Element for Input {
             window.set_cursor_style(CursorStyle::IBeam, hitbox);
         }
 
-        window.handle_input(
-            &focus_handle,
-            ElementInputHandler::new(bounds, self.input.clone()),
-            cx,
-        );
+        // In vim's normal mode keys are commands, and what they would have
+        // typed goes nowhere.
+        if self.input.read(cx).vim_mode() == VimMode::Insert {
+            window.handle_input(
+                &focus_handle,
+                ElementInputHandler::new(bounds, self.input.clone()),
+                cx,
+            );
+        }
 
         let input = self.input.clone();
         let placeholder = self.placeholder.clone();
@@ -442,9 +678,14 @@ impl Element for Input {
                             cx,
                         );
                     }
+                    paint_drop_caret(&input, colors.cursor, window, cx);
                 });
             },
         );
+
+        if let Some(tooltip) = &mut prepaint_state.tooltip {
+            tooltip.paint(window, cx);
+        }
     }
 }
 
@@ -452,7 +693,12 @@ impl Element for Input {
 #[derive(Clone)]
 struct PaintColors {
     pub selection: Hsla,
+    pub search_match: Hsla,
+    pub token: Hsla,
+    pub code: Hsla,
     pub cursor: Hsla,
+    /// For a decoration that names no colour of its own.
+    pub decoration: Hsla,
 }
 
 /// Registers all mouse event handlers for the input.
@@ -464,7 +710,7 @@ fn handle_mouse(
     cx: &App,
 ) {
     mouse_down(input.clone(), bounds, multiline, window);
-    mouse_up(input.clone(), window);
+    mouse_up(input.clone(), bounds, multiline, window);
     mouse_move(input.clone(), bounds, multiline, window);
     handle_scroll(input.clone(), bounds, multiline, window, cx);
 }
@@ -493,6 +739,7 @@ fn mouse_down(
                 text_position,
                 event.click_count,
                 event.modifiers.shift,
+                event.modifiers.alt,
                 window,
                 cx,
             );
@@ -500,8 +747,13 @@ fn mouse_down(
     });
 }
 
-fn mouse_up(input: Entity<InputState>, window: &mut Window) {
-    window.on_mouse_event(move |event: &MouseUpEvent, phase, _window, cx| {
+fn mouse_up(
+    input: Entity<InputState>,
+    bounds: Bounds<Pixels>,
+    multiline: bool,
+    window: &mut Window,
+) {
+    window.on_mouse_event(move |event: &MouseUpEvent, phase, window, cx| {
         if phase != DispatchPhase::Bubble {
             return;
         }
@@ -509,6 +761,26 @@ fn mouse_up(input: Entity<InputState>, window: &mut Window) {
             return;
         }
 
+        // Text dragged out of an input lands in whichever input it is
+        // released over, and once every input has seen the release it is
+        // gone either way.
+        if bounds.contains(&event.position) && cx.has_global::<TextDrag>() {
+            let drag = cx.remove_global::<TextDrag>();
+            let remove = input.update(cx, |input, cx| {
+                let text_position =
+                    screen_to_text_position(event.position, bounds, input.scroll_offset, multiline);
+                input.drop_text_drag(&drag, text_position, event.modifiers.alt, window, cx)
+            });
+            if let Some(source) = drag.source.upgrade().filter(|_| remove) {
+                source.update(cx, |source, cx| source.remove_dragged(&drag, window, cx));
+            }
+        }
+        cx.defer(|cx| {
+            if cx.has_global::<TextDrag>() {
+                cx.remove_global::<TextDrag>();
+            }
+        });
+
         input.update(cx, |input, cx| {
             input.on_mouse_up(cx);
         });
@@ -530,6 +802,9 @@ fn mouse_move(
             let text_position =
                 screen_to_text_position(event.position, bounds, input.scroll_offset, multiline);
             input.on_mouse_move(text_position, cx);
+            let hovered = bounds.contains(&event.position).then_some(text_position);
+            input.drag_over(hovered, cx);
+            input.hover(hovered, cx);
         });
     });
 }
@@ -583,6 +858,21 @@ fn handle_scroll(
 
 /// Converts a screen position to a position relative to the text area origin,
 /// adjusted for scroll offset.
+/// Where a drop at `position` falls in the text of `input` as last painted.
+fn drop_position(
+    input: &InputState,
+    position: Point<Pixels>,
+    multiline: bool,
+) -> Option<Point<Pixels>> {
+    let bounds = input.last_bounds?;
+    Some(screen_to_text_position(
+        position,
+        bounds,
+        input.scroll_offset,
+        multiline,
+    ))
+}
+
 fn screen_to_text_position(
     screen_position: Point<Pixels>,
     bounds: Bounds<Pixels>,
@@ -602,6 +892,21 @@ fn screen_to_text_position(
     }
 }
 
+/// Paints where text dragged over the input would land.
+fn paint_drop_caret(input: &Entity<InputState>, color: Hsla, window: &mut Window, cx: &App) {
+    let state = input.read(cx);
+    let Some(caret) = state
+        .drop_caret
+        .and_then(|offset| state.caret_bounds_at(offset))
+    else {
+        return;
+    };
+    window.paint_quad(fill(
+        Bounds::new(caret.origin, size(px(CURSOR_WIDTH), caret.size.height)),
+        color,
+    ));
+}
+
 fn paint_multiline(
     input: &Entity<InputState>,
     focus_handle: &FocusHandle,
@@ -615,18 +920,53 @@ fn paint_multiline(
 ) {
     let input_state = input.read(cx);
     let content = input_state.content().to_string();
-    let selected_range = input_state.selected_range().clone();
+    let selections = input_state.selections();
+    let search_matches = input_state.search_matches().to_vec();
+    let decorations: Vec<Decoration> = input_state.decorations().cloned().collect();
+    let tokens: Vec<std::ops::Range<usize>> = input_state
+        .tokens()
+        .iter()
+        .map(|token| token.range.clone())
+        .collect();
+    let code = input_state.code_ranges();
     let marked_range = input_state.marked_range().cloned();
-    let cursor_offset = input_state.cursor_offset();
     let line_layouts = input_state.line_layouts.clone();
     let scroll_offset = input_state.scroll_offset;
     let line_height = input_state.line_height;
     let is_focused = focus_handle.is_focused(window);
 
-    if !selected_range.is_empty() {
+    for token in &tokens {
+        for (left, right, bottom) in
+            multiline_range_rows(&line_layouts, token, bounds, scroll_offset, line_height)
+        {
+            paint_token(left, right, bottom, line_height, colors.token, window);
+        }
+    }
+    for code in &code {
+        for (left, right, bottom) in
+            multiline_range_rows(&line_layouts, code, bounds, scroll_offset, line_height)
+        {
+            paint_code(left, right, bottom, line_height, colors.code, window);
+        }
+    }
+
+    // Under the selection, which marks the match that is selected.
+    for found in &search_matches {
+        paint_multiline_selection(
+            &line_layouts,
+            found,
+            bounds,
+            scroll_offset,
+            line_height,
+            colors.search_match,
+            window,
+        );
+    }
+
+    for selection in selections.iter().filter(|s| !s.range.is_empty()) {
         paint_multiline_selection(
             &line_layouts,
-            &selected_range,
+            &selection.range,
             bounds,
             scroll_offset,
             line_height,
@@ -652,6 +992,19 @@ fn paint_multiline(
         );
     }
 
+    for decoration in &decorations {
+        let color = decoration.color.unwrap_or(colors.decoration);
+        for (left, right, bottom) in multiline_range_rows(
+            &line_layouts,
+            &decoration.range,
+            bounds,
+            scroll_offset,
+            line_height,
+        ) {
+            paint_decoration(left, right, bottom, decoration.style, color, window);
+        }
+    }
+
     if let Some(marked_range) = &marked_range {
         if !marked_range.is_empty() {
             paint_multiline_marked_underline(
@@ -666,17 +1019,19 @@ fn paint_multiline(
         }
     }
 
-    if is_focused && selected_range.is_empty() && cursor_visible {
-        paint_multiline_cursor(
-            &line_layouts,
-            cursor_offset,
-            &content,
-            bounds,
-            scroll_offset,
-            line_height,
-            colors.cursor,
-            window,
-        );
+    if is_focused && cursor_visible {
+        for selection in selections.iter().filter(|s| s.range.is_empty()) {
+            paint_multiline_cursor(
+                &line_layouts,
+                selection.cursor(),
+                &content,
+                bounds,
+                scroll_offset,
+                line_height,
+                colors.cursor,
+                window,
+            );
+        }
     }
 }
 
@@ -765,10 +1120,10 @@ fn paint_multiline_selection(
             let sel_start = selected_range.start.max(line_start) - line_start;
             let sel_end = selected_range.end.min(line_end) - line_start;
 
-            let start_pos = wrapped
+            let start_pos = line
                 .position_for_index(sel_start, line_height)
                 .unwrap_or(point(px(0.), px(0.)));
-            let end_pos = wrapped
+            let end_pos = line
                 .position_for_index(sel_end, line_height)
                 .unwrap_or_else(|| {
                     let last_line_y = line_height * (line.visual_line_count - 1) as f32;
@@ -908,6 +1263,154 @@ fn paint_multiline_text(
     }
 }
 
+/// Where `range` runs on each visual line it is on, as the left and right
+/// edges and the bottom of the line, in window coordinates.
+fn multiline_range_rows(
+    line_layouts: &[InputLineLayout],
+    range: &std::ops::Range<usize>,
+    bounds: Bounds<Pixels>,
+    scroll_offset: Pixels,
+    line_height: Pixels,
+) -> Vec<(Pixels, Pixels, Pixels)> {
+    let mut rows = Vec::new();
+    for line in line_layouts {
+        let line_y = line.y_offset - scroll_offset;
+        if line.text_range.is_empty()
+            || !line_intersects_range(&line.text_range, range)
+            || !is_line_visible(
+                line_y,
+                line_height,
+                line.visual_line_count,
+                bounds.size.height,
+            )
+        {
+            continue;
+        }
+        let Some(wrapped) = &line.wrapped_line else {
+            continue;
+        };
+
+        let left = bounds.left() + compute_alignment_offset(line, bounds.size.width);
+        let top = bounds.top() + line_y;
+        let line_start = line.text_range.start;
+        let start = range.start.max(line_start) - line_start;
+        let end = range.end.min(line.text_range.end) - line_start;
+        let start_pos = line
+            .position_for_index(start, line_height)
+            .unwrap_or(point(px(0.), px(0.)));
+        let end_pos = line
+            .position_for_index(end, line_height)
+            .unwrap_or_else(|| {
+                let last_line_y = line_height * (line.visual_line_count - 1) as f32;
+                point(wrapped.width(), last_line_y)
+            });
+
+        let first = compute_visual_line_index(start_pos.y, line_height);
+        let last = compute_visual_line_index(end_pos.y, line_height);
+        for visual_line in first..=last {
+            let from = if visual_line == first {
+                start_pos.x
+            } else {
+                px(0.)
+            };
+            let to = if visual_line == last {
+                end_pos.x
+            } else {
+                wrapped.width()
+            };
+            let bottom = top + line_height * (visual_line + 1) as f32;
+            rows.push((left + from, left + to, bottom));
+        }
+    }
+    rows
+}
+
+/// The pill behind a token, from `left` to `right` on the line above `bottom`.
+fn paint_token(
+    left: Pixels,
+    right: Pixels,
+    bottom: Pixels,
+    line_height: Pixels,
+    color: Hsla,
+    window: &mut Window,
+) {
+    if right <= left {
+        return;
+    }
+    window.paint_quad(
+        fill(
+            Bounds::from_corners(point(left, bottom - line_height), point(right, bottom)),
+            color,
+        )
+        .corner_radii(line_height / 2.),
+    );
+}
+
+/// Paints the wash behind a run of inline code, from `left` to `right` on
+/// the line whose `bottom` is given.
+fn paint_code(
+    left: Pixels,
+    right: Pixels,
+    bottom: Pixels,
+    line_height: Pixels,
+    color: Hsla,
+    window: &mut Window,
+) {
+    if right <= left {
+        return;
+    }
+    window.paint_quad(
+        fill(
+            Bounds::from_corners(point(left, bottom - line_height), point(right, bottom)),
+            color,
+        )
+        .corner_radii(px(3.)),
+    );
+}
+
+/// Underlines from `left` to `right` along the `bottom` of a line, in a
+/// decoration's style.
+fn paint_decoration(
+    left: Pixels,
+    right: Pixels,
+    bottom: Pixels,
+    style: DecorationStyle,
+    color: Hsla,
+    window: &mut Window,
+) {
+    let width = right - left;
+    if width <= px(0.) {
+        return;
+    }
+    let thickness = px(DECORATION_THICKNESS);
+    match style {
+        DecorationStyle::Squiggly | DecorationStyle::Straight => {
+            let wavy = style == DecorationStyle::Squiggly;
+            let height = if wavy { thickness * 3. } else { thickness };
+            window.paint_underline(
+                point(left, bottom - height),
+                width,
+                &UnderlineStyle {
+                    thickness,
+                    color: Some(color),
+                    wavy,
+                },
+            );
+        }
+        DecorationStyle::Dotted => {
+            let dot = px(DECORATION_DOT);
+            let mut x = left;
+            while x + dot <= right {
+                window.paint_quad(fill(
+                    Bounds::new(point(x, bottom - dot), size(dot, dot)),
+                    color,
+                ));
+                x += dot * 2.;
+            }
+        }
+    }
+}
+
 fn paint_multiline_marked_underline(
     line_layouts: &[InputLineLayout],
     marked_range: &std::ops::Range<usize>,
@@ -948,10 +1451,10 @@ fn paint_multiline_marked_underline(
             let mark_start = marked_range.start.max(line_start) - line_start;
             let mark_end = marked_range.end.min(line_end) - line_start;
 
-            let start_pos = wrapped
+            let start_pos = line
                 .position_for_index(mark_start, line_height)
                 .unwrap_or(point(px(0.), px(0.)));
-            let end_pos = wrapped
+            let end_pos = line
                 .position_for_index(mark_end, line_height)
                 .unwrap_or_else(|| {
                     let last_line_y = line_height * (line.visual_line_count - 1) as f32;
@@ -1061,10 +1564,9 @@ fn paint_multiline_cursor(
             continue;
         }
 
-        let cursor_position = if let Some(wrapped) = &line.wrapped_line {
+        let cursor_position = if line.wrapped_line.is_some() {
             let local_offset = cursor_offset.saturating_sub(line.text_range.start);
-            wrapped
-                .position_for_index(local_offset, line_height)
+            line.position_for_index(local_offset, line_height)
                 .unwrap_or(point(px(0.), px(0.)))
         } else {
             point(px(0.), px(0.))
@@ -1090,6 +1592,10 @@ fn paint_multiline_cursor(
 struct SingleLinePaintState {
     content: String,
     selected_range: std::ops::Range<usize>,
+    search_matches: Vec<std::ops::Range<usize>>,
+    decorations: Vec<Decoration>,
+    tokens: Vec<std::ops::Range<usize>>,
+    code: Vec<std::ops::Range<usize>>,
     marked_range: Option<std::ops::Range<usize>>,
     cursor_offset: usize,
     scroll_offset: Pixels,
@@ -1099,6 +1605,11 @@ struct SingleLinePaintState {
     char_positions: Vec<Pixels>,
     wrapped_line: Option<Arc<WrappedLine>>,
     direction: TextDirection,
+    /// A mask's unfilled slots, drawn after the content.
+    slots: Option<SharedString>,
+    /// A suggested continuation, drawn after the content while focused. Never
+    /// alongside `slots`: a mask already says what comes next.
+    completion: Option<SharedString>,
 }
 
 impl SingleLinePaintState {
@@ -1109,6 +1620,15 @@ impl SingleLinePaintState {
         cx: &App,
     ) -> Self {
         let input_state = input.read(cx);
+        let slots = input_state
+            .masked()
+            .map(|masked| masked.slots)
+            .filter(|slots| !slots.is_empty())
+            .map(SharedString::from);
+        let completion = input_state
+            .completion()
+            .filter(|_| slots.is_none())
+            .map(|completion| SharedString::from(completion.to_string()));
 
         let mut char_positions = Vec::new();
         let mut text_width = px(0.);
@@ -1119,7 +1639,7 @@ impl SingleLinePaintState {
                 let content = input_state.content();
                 let mut idx = 0;
                 for ch in content.chars() {
-                    if let Some(pos) = wrapped.position_for_index(idx, input_state.line_height) {
+                    if let Some(pos) = line.position_for_index(idx, input_state.line_height) {
                         char_positions.push(pos.x);
                     } else {
                         char_positions.push(text_width);
@@ -1144,6 +1664,14 @@ impl SingleLinePaintState {
         Self {
             content: input_state.content().to_string(),
             selected_range: input_state.selected_range().clone(),
+            search_matches: input_state.search_matches().to_vec(),
+            decorations: input_state.decorations().cloned().collect(),
+            tokens: input_state
+                .tokens()
+                .iter()
+                .map(|token| token.range.clone())
+                .collect(),
+            code: input_state.code_ranges(),
             marked_range: input_state.marked_range().cloned(),
             cursor_offset: input_state.cursor_offset(),
             scroll_offset: input_state.scroll_offset,
@@ -1153,6 +1681,8 @@ impl SingleLinePaintState {
             char_positions,
             wrapped_line,
             direction,
+            slots,
+            completion,
         }
     }
 
@@ -1187,18 +1717,87 @@ fn paint_singleline(
 ) {
     let state = SingleLinePaintState::from_input(input, focus_handle, window, cx);
 
+    let alignment_offset = state.alignment_offset(bounds.size.width);
+    let y_offset = (bounds.size.height - state.line_height).max(px(0.)) / 2.0;
+    let bottom = bounds.top() + y_offset + state.line_height;
+    let x =
+        |index| bounds.left() + state.x_for_index(index) - state.scroll_offset + alignment_offset;
+
+    for token in &state.tokens {
+        paint_token(
+            x(token.start),
+            x(token.end),
+            bottom,
+            state.line_height,
+            colors.token,
+            window,
+        );
+    }
+    for code in &state.code {
+        paint_code(
+            x(code.start),
+            x(code.end),
+            bottom,
+            state.line_height,
+            colors.code,
+            window,
+        );
+    }
+    for found in &state.search_matches {
+        paint_singleline_range(&state, found, bounds, colors.search_match, window);
+    }
     if !state.selected_range.is_empty() {
-        paint_singleline_selection(&state, bounds, colors.selection, window);
+        paint_singleline_range(
+            &state,
+            &state.selected_range,
+            bounds,
+            colors.selection,
+            window,
+        );
     }
 
+    let placeholder = placeholder.filter(|placeholder| !placeholder.is_empty());
+    // Ghost text follows the end of left-to-right text only: after
+    // right-to-left text the end is on the left, where the text already is.
+    let completion = state
+        .completion
+        .as_ref()
+        .filter(|_| state.is_focused && state.direction == TextDirection::Ltr);
     if state.content.is_empty() {
-        if let Some(placeholder_str) = placeholder {
-            if !placeholder_str.is_empty() {
-                paint_singleline_placeholder(placeholder_str, bounds, text_style, window, cx);
+        // A mask's template stands in for the placeholder once the field is
+        // focused, and when there is no placeholder to show; so does a
+        // suggestion.
+        match (completion, &state.slots, placeholder) {
+            (Some(completion), _, _) => {
+                paint_singleline_placeholder(completion, px(0.), bounds, text_style, window, cx);
             }
+            (None, Some(slots), _) if state.is_focused || placeholder.is_none() => {
+                paint_singleline_placeholder(slots, px(0.), bounds, text_style, window, cx);
+            }
+            (None, _, Some(placeholder)) => {
+                paint_singleline_placeholder(placeholder, px(0.), bounds, text_style, window, cx);
+            }
+            _ => {}
         }
     } else {
         paint_singleline_text(&state, bounds, window, cx);
+        let x = state.text_width - state.scroll_offset;
+        if let Some(slots) = &state.slots {
+            paint_singleline_placeholder(slots, x, bounds, text_style, window, cx);
+        } else if let Some(completion) = completion {
+            paint_singleline_placeholder(completion, x, bounds, text_style, window, cx);
+        }
+    }
+
+    for decoration in &state.decorations {
+        paint_decoration(
+            x(decoration.range.start),
+            x(decoration.range.end),
+            bottom,
+            decoration.style,
+            decoration.color.unwrap_or(colors.decoration),
+            window,
+        );
     }
 
     if let Some(marked_range) = &state.marked_range {
@@ -1212,17 +1811,18 @@ fn paint_singleline(
     }
 }
 
-fn paint_singleline_selection(
+/// Fills `range` of the line, as a selection is: the selection itself, or a
+/// search match.
+fn paint_singleline_range(
     state: &SingleLinePaintState,
+    range: &std::ops::Range<usize>,
     bounds: Bounds<Pixels>,
     selection_color: Hsla,
     window: &mut Window,
 ) {
     let alignment_offset = state.alignment_offset(bounds.size.width);
-    let start_x =
-        state.x_for_index(state.selected_range.start) - state.scroll_offset + alignment_offset;
-    let end_x =
-        state.x_for_index(state.selected_range.end) - state.scroll_offset + alignment_offset;
+    let start_x = state.x_for_index(range.start) - state.scroll_offset + alignment_offset;
+    let end_x = state.x_for_index(range.end) - state.scroll_offset + alignment_offset;
 
     let y_offset = (bounds.size.height - state.line_height).max(px(0.)) / 2.0;
 
@@ -1238,8 +1838,10 @@ fn paint_singleline_selection(
     ));
 }
 
+/// Draws `placeholder` in the placeholder colour, `x` from the left edge.
 fn paint_singleline_placeholder(
     placeholder: &SharedString,
+    x: Pixels,
     bounds: Bounds<Pixels>,
     text_style: &TextStyle,
     window: &mut Window,
@@ -1263,7 +1865,7 @@ fn paint_singleline_placeholder(
     let line_height = text_style.line_height_in_pixels(window.rem_size());
 
     let y_offset = (bounds.size.height - line_height).max(px(0.)) / 2.0;
-    let paint_origin = point(bounds.origin.x, bounds.origin.y + y_offset);
+    let paint_origin = point(bounds.origin.x + x, bounds.origin.y + y_offset);
 
     let _ = shaped_line.paint(paint_origin, line_height, TextAlign::Left, None, window, cx);
 }
@@ -1386,7 +1988,7 @@ pub(crate) fn disabled_display(
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
-    use gpui::{Render, TestAppContext, VisualTestContext};
+    use gpui::{AppContext, Render, TestAppContext, VisualTestContext};
 
     /// A window holding one focused control, drawn and parked — the harness
     /// both wrapper test modules use to ask what a keystroke actually does.
@@ -1430,4 +2032,153 @@ pub(crate) mod tests {
         cx.run_until_parked();
         cx
     }
+
+    /// An alt-drag over three rows selects the same columns of each, and
+    /// Escape goes back to one selection before it gives up focus.
+    #[gpui::test]
+    fn alt_drag_selects_a_column(cx: &mut TestAppContext) {
+        cx.update(crate::init);
+        let state = cx.update(|cx| cx.new(InputState::new_multiline));
+        state.update(cx, |state, cx| state.set_content("abcd\nefgh\nijkl", cx));
+
+        let for_render = state.clone();
+        let cx = focused_input_window(cx, &state, move |_window, cx| {
+            text_area(&for_render, cx).into_any_element()
+        });
+
+        cx.update(|window, cx| {
+            state.update(cx, |state, cx| {
+                let line_height = state.line_height;
+                let x = |index| {
+                    state.line_layouts[0]
+                        .position_for_index(index, line_height)
+                        .unwrap()
+                        .x
+                };
+                let from = point(x(1), line_height * 0.5);
+                let to = point(x(3), line_height * 2.5);
+                state.on_mouse_down(from, 1, false, true, window, cx);
+                state.on_mouse_move(to, cx);
+                state.on_mouse_up(cx);
+            })
+        });
+        let ranges = |cx: &mut VisualTestContext| {
+            state.read_with(cx, |state, _| {
+                state
+                    .selections()
+                    .into_iter()
+                    .map(|selection| selection.range)
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(ranges(cx), [1..3, 6..8, 11..13]);
+
+        let focused = cx.update(|window, cx| window.focused(cx));
+        cx.simulate_keystrokes("escape");
+        cx.run_until_parked();
+        let (multiple, primary) = state.read_with(cx, |state, _| {
+            (
+                state.has_multiple_selections(),
+                state.selected_range().clone(),
+            )
+        });
+        assert!(!multiple);
+        assert_eq!(primary, 11..13);
+        assert_eq!(cx.update(|window, cx| window.focused(cx)), focused);
+    }
+
+    /// Moving the pointer over a decorated word makes it the hovered one —
+    /// what a context menu over the input reads — and moving off clears it.
+    #[gpui::test]
+    fn hovering_a_decoration(cx: &mut TestAppContext) {
+        cx.update(crate::init);
+        let state = cx.update(|cx| cx.new(InputState::new_multiline));
+        state.update(cx, |state, cx| {
+            state.set_content("fine wrold", cx);
+            state.set_decorations(vec![Decoration::new(5..10).message("Unknown word")], cx);
+        });
+
+        let for_render = state.clone();
+        let cx = focused_input_window(cx, &state, move |_window, cx| {
+            text_area(&for_render, cx).into_any_element()
+        });
+
+        let at = |index, cx: &mut VisualTestContext| {
+            state.read_with(cx, |state, _| {
+                let line_height = state.line_height;
+                let x = state.line_layouts[0]
+                    .position_for_index(index, line_height)
+                    .unwrap()
+                    .x;
+                point(x, line_height * 0.5)
+            })
+        };
+        let hovered = |cx: &mut VisualTestContext| {
+            state.read_with(cx, |state, _| {
+                state
+                    .hovered_decoration()
+                    .map(|decoration| decoration.range.clone())
+            })
+        };
+
+        let over = at(7, cx);
+        cx.simulate_mouse_move(over, None, gpui::Modifiers::none());
+        cx.run_until_parked();
+        assert_eq!(hovered(cx), Some(5..10));
+
+        let off = at(1, cx);
+        cx.simulate_mouse_move(off, None, gpui::Modifiers::none());
+        cx.run_until_parked();
+        assert_eq!(hovered(cx), None);
+    }
+
+    /// A selection dragged out of one input and released over another moves
+    /// across, and one let go of anywhere else stays where it was.
+    #[gpui::test]
+    fn dragging_a_selection_between_inputs(cx: &mut TestAppContext) {
+        cx.update(crate::init);
+        let from = cx.update(|cx| cx.new(InputState::new_singleline));
+        let to = cx.update(|cx| cx.new(InputState::new_singleline));
+        from.update(cx, |state, cx| {
+            state.set_content("hello world", cx);
+            state.set_selected_range(0..6);
+        });
+
+        let (from_render, to_render) = (from.clone(), to.clone());
+        let cx = focused_input_window(cx, &from, move |_window, cx| {
+            gpui::div()
+                .flex()
+                .flex_col()
+                .child(input(&from_render, cx).w(px(300.)).h(px(24.)))
+                .child(input(&to_render, cx).w(px(300.)).h(px(24.)))
+                .into_any_element()
+        });
+
+        let middle = |state: &Entity<InputState>, cx: &mut VisualTestContext| {
+            let bounds = state.read_with(cx, |state, _| state.last_bounds.unwrap());
+            point(bounds.left() + px(2.), bounds.center().y)
+        };
+        let drag = |start: Point<Pixels>, end: Point<Pixels>, cx: &mut VisualTestContext| {
+            let (left, none) = (MouseButton::Left, gpui::Modifiers::none());
+            cx.simulate_mouse_down(start, left, none);
+            cx.simulate_mouse_move(start + point(px(30.), px(0.)), Some(left), none);
+            cx.simulate_mouse_move(end, Some(left), none);
+            cx.simulate_mouse_up(end, left, none);
+            cx.run_until_parked();
+        };
+        let content = |state: &Entity<InputState>, cx: &mut VisualTestContext| {
+            state.read_with(cx, |state, _| state.content().to_string())
+        };
+
+        let (start, end) = (middle(&from, cx), middle(&to, cx));
+        drag(start, end, cx);
+        assert_eq!(content(&to, cx), "hello ");
+        assert_eq!(content(&from, cx), "world");
+
+        to.update(cx, |state, _| state.set_selected_range(0..6));
+        let nowhere = end + point(px(0.), px(200.));
+        drag(end, nowhere, cx);
+        assert_eq!(content(&to, cx), "hello ");
+        assert!(!cx.update(|_, cx| cx.has_global::<TextDrag>()));
+    }
 }
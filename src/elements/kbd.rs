@@ -1,4 +1,4 @@
-use crate::theme::{ActiveTheme, ControlSize, Themeable};
+use crate::theme::{ActiveTheme, ControlSize, Elevation, Themeable};
 use crate::traits::control_sized::ControlSized;
 use gpui::{
     div, App, FontWeight, IntoElement, ParentElement, RenderOnce, SharedString, Styled, Window,
@@ -59,7 +59,7 @@ impl RenderOnce for Kbd {
             // border this used to draw does not fit a declared height: gpui
             // lays out border-box, so on the Small rung a 14px line box plus
             // 1px + 2px of border overflows its 16px box.
-            .shadow_sm()
+            .shadow(theme.elevation(Elevation::Raised))
             .whitespace_nowrap()
             .flex()
             .flex_none()
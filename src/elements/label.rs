@@ -2,7 +2,7 @@
 
 use crate::theme::{ActiveTheme, Themeable};
 use gpui::{
-    div, prelude::FluentBuilder, rems, App, Div, ElementId, FontWeight, IntoElement, ParentElement,
+    div, prelude::FluentBuilder, App, Div, ElementId, FontWeight, IntoElement, ParentElement,
     RenderOnce, SharedString, Styled, Window,
 };
 
@@ -63,7 +63,7 @@ impl RenderOnce for Label {
         div()
             .flex()
             .items_center()
-            .gap(rems(0.25))
+            .gap(theme.space(1.))
             .text_sm()
             .font_weight(FontWeight::MEDIUM)
             .text_color(text_color)
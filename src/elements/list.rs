@@ -20,7 +20,7 @@
 //! List::new("my-list", entries).render(window, cx)
 //! ```
 
-use crate::theme::{ActiveTheme, Themeable};
+use crate::theme::{ActiveTheme, Radius, Themeable};
 use gpui::{
     div, prelude::FluentBuilder, px, uniform_list, AnyElement, App, ClickEvent, ElementId,
     InteractiveElement, IntoElement, ParentElement, Pixels, SharedString,
@@ -148,6 +148,8 @@ impl List {
         let fg = theme.fg();
         let accent = theme.accent();
         let accent_bg = theme.accent_bg();
+        let header_padding = theme.space(2.);
+        let item_radius = theme.radius(Radius::Small);
 
         let list = uniform_list(self.id, entry_count, move |range, window, cx| {
             range
@@ -159,7 +161,7 @@ impl List {
                             .w_full()
                             .flex()
                             .items_end()
-                            .px_2()
+                            .px(header_padding)
                             .pb(px(2.))
                             .child(
                                 div()
@@ -182,7 +184,7 @@ impl List {
                                 .flex()
                                 .items_center()
                                 .text_size(font_size)
-                                .rounded_sm()
+                                .rounded(item_radius)
                                 .when(*selected, |el| el.bg(accent_bg).text_color(accent))
                                 .when(!*selected, |el| {
                                     el.text_color(fg).hover(|s| s.bg(accent_bg.opacity(0.5)))
@@ -35,7 +35,7 @@
 use crate::a11y::{A11y, Announce, FocusNext, FocusPrevious};
 use crate::element_id::for_entity;
 use crate::icons::Icons;
-use crate::theme::{ActiveTheme, ControlSize, Themeable};
+use crate::theme::{ActiveTheme, ControlSize, Elevation, Themeable};
 use crate::traits::accessible::Accessible;
 use gpui::{
     actions, div, prelude::*, px, App, Context, DismissEvent, ElementId, Entity, EventEmitter,
@@ -519,7 +519,7 @@ impl Render for Listbox {
             .border_1()
             .border_color(theme.border())
             .rounded(metrics.radius)
-            .shadow_lg()
+            .shadow(theme.elevation(Elevation::Overlay))
             .py(metrics.padding_y())
             .flex()
             .flex_col()
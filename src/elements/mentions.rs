@@ -0,0 +1,645 @@
+//! Mentions — `@user` and `#channel` suggestions in a text field or textarea.
+//!
+//! A mentions control wraps an `Entity<InputState>` the caller owns — a chat
+//! composer, usually multiline — and gives it a suggestion popup. Each
+//! trigger character has a **source**: typing the trigger at the start of a
+//! word opens a listbox of what the source suggests for the word typed after
+//! it, and choosing a row replaces the word with a **token**, which the caret
+//! steps over and Backspace removes whole. See `crate::input::tokens` for how
+//! tokens behave once they are in the text.
+//!
+//! It is in the listbox family, not the menu family: a row is a value that
+//! ends up in the text and stays there. See `docs/menus-and-listboxes.md`.
+//!
+//! # The keyboard
+//!
+//! The same four keys as `elements::combobox`, bound the same way and for the
+//! same reason: focus stays in the field, so Down, Up, Enter and Escape are
+//! bound in the `"Mentions > Input"` predicate, registered after
+//! `bind_input_keys` so that the tie on depth goes to them. See
+//! `elements::combobox`'s `# The keyboard` for the whole argument, which is
+//! not repeated here.
+//!
+//! Each calls `cx.propagate()` with the popup closed, so Enter still sends or
+//! breaks the line and Escape still closes an enclosing dialog. Escape closes
+//! the popup for the rest of the word it was opened for: typing on does not
+//! bring it back, and a new trigger does.
+//!
+//! # Where the popup goes
+//!
+//! Under the trigger, not under the field: the suggestions are about the word
+//! being typed, and a composer can be many lines tall. It is placed with
+//! [`InputState::caret_bounds_at`] at the query's start, which is already laid
+//! out in the frame after the trigger is typed and does not move while the
+//! query grows. A query pasted in whole waits one more frame for its popup.
+//! Rung 1 of `docs/overlays.md`'s ladder, like every other chooser's popup.
+
+use crate::a11y::{A11y, Announce};
+use crate::elements::listbox::{Listbox, ListboxFocus, LISTBOX_GAP};
+use crate::elements::text_field::text_field;
+use crate::elements::textarea::textarea;
+use crate::input::{InputState, InputStateEvent, TriggerQuery};
+use crate::theme::{with_theme, ActiveTheme, ControlSize};
+use crate::traits::accessible::Accessible;
+use crate::traits::control_sized::ControlSized;
+use crate::traits::disableable::Disableable;
+use gpui::{
+    actions, anchored, deferred, div, point, prelude::*, px, App, Context, DismissEvent, ElementId,
+    Entity, IntoElement, KeyBinding, ParentElement, Render, Role, SharedString, Styled, Window,
+};
+use std::rc::Rc;
+
+actions!(
+    mentions,
+    [
+        /// Move the highlight to the next suggestion.
+        MentionsHighlightNext,
+        /// Move the highlight to the previous suggestion.
+        MentionsHighlightPrevious,
+        /// Put the highlighted suggestion in the text as a token.
+        MentionsChoose,
+        /// Close the popup for the rest of the word.
+        MentionsDismiss,
+    ]
+);
+
+/// The key context the mentions wrapper declares.
+pub const MENTIONS_CONTEXT: &str = "Mentions";
+
+/// Bind the mentions popup's four keys: Down, Up, Enter and Escape.
+///
+/// **Call this after `input::bind_input_keys`**, for the reason
+/// [`bind_combobox_keys`](crate::elements::combobox::bind_combobox_keys)
+/// gives: the `"Mentions > Input"` predicate ties with the plain `Input`
+/// bindings on depth, and the later registration wins the tie.
+pub fn bind_mentions_keys(cx: &mut App) {
+    let under_input = Some("Mentions > Input");
+    cx.bind_keys([
+        KeyBinding::new("down", MentionsHighlightNext, under_input),
+        KeyBinding::new("up", MentionsHighlightPrevious, under_input),
+        KeyBinding::new("enter", MentionsChoose, under_input),
+        KeyBinding::new("escape", MentionsDismiss, under_input),
+    ]);
+}
+
+/// One suggestion: the text that goes in the field, what it stands for, and
+/// how its row reads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mention {
+    /// Put in the field as the token's text — `@alice`.
+    pub text: SharedString,
+    /// The token's payload — a user id, a channel id.
+    pub payload: String,
+    /// The popup row. The text, unless set.
+    pub label: SharedString,
+}
+
+impl Mention {
+    /// A suggestion of `text`, standing for `payload`.
+    pub fn new(text: impl Into<SharedString>, payload: impl Into<String>) -> Self {
+        let text = text.into();
+        Self {
+            label: text.clone(),
+            text,
+            payload: payload.into(),
+        }
+    }
+
+    /// Set how its row reads — a display name beside the handle, say.
+    pub fn label(mut self, label: impl Into<SharedString>) -> Self {
+        self.label = label.into();
+        self
+    }
+}
+
+/// What a trigger suggests for the word typed after it.
+type Source = Rc<dyn Fn(&str) -> Vec<Mention>>;
+
+/// Builder for a mentions control. Use [`mentions`] to create one.
+pub struct Mentions {
+    id: ElementId,
+    label: SharedString,
+    input: Entity<InputState>,
+    sources: Vec<(char, Source)>,
+    rows: Option<u32>,
+    full_width: bool,
+    disabled: bool,
+    size: ControlSize,
+}
+
+/// Creates a new mentions builder.
+///
+/// # Arguments
+///
+/// * `id` — unique identifier for the control
+/// * `name` — the accessible name: what the field is *for*, as a combobox's
+///   is. [`Role::ComboBox`] requires one
+/// * `input` — the field's state, which the caller keeps: it is the draft
+///   being written, and reads and clears it on send
+pub fn mentions(
+    id: impl Into<ElementId>,
+    name: impl Into<SharedString>,
+    input: &Entity<InputState>,
+) -> Mentions {
+    Mentions {
+        id: id.into(),
+        label: name.into(),
+        input: input.clone(),
+        sources: Vec::new(),
+        rows: None,
+        full_width: false,
+        disabled: false,
+        size: ControlSize::default(),
+    }
+}
+
+impl Mentions {
+    /// Suggest what `source` returns for the word typed after `trigger`, best
+    /// first. The word is passed without the trigger, and is empty right after
+    /// it is typed. No suggestions, no popup.
+    pub fn source(
+        mut self,
+        trigger: char,
+        source: impl Fn(&str) -> Vec<Mention> + 'static,
+    ) -> Self {
+        self.sources.retain(|(existing, _)| *existing != trigger);
+        self.sources.push((trigger, Rc::new(source)));
+        self
+    }
+
+    /// Set the visible rows of a multiline field.
+    pub fn rows(mut self, rows: u32) -> Self {
+        self.rows = Some(rows);
+        self
+    }
+
+    /// Make the field expand to fill available width.
+    pub fn full_width(mut self, full_width: bool) -> Self {
+        self.full_width = full_width;
+        self
+    }
+}
+
+impl Disableable for Mentions {
+    fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl ControlSized for Mentions {
+    fn control_size(mut self, size: ControlSize) -> Self {
+        self.size = size;
+        self
+    }
+}
+
+/// The stateful mentions control. Build one with [`MentionsState::new`]
+/// inside `cx.new(…)`; it has to outlive a frame because it owns the popup.
+pub struct MentionsState {
+    id: ElementId,
+    label: SharedString,
+    input: Entity<InputState>,
+    sources: Vec<(char, Source)>,
+    listbox: Option<Entity<Listbox>>,
+    /// The word the popup is open for.
+    query: Option<TriggerQuery>,
+    /// What the popup shows, row for row.
+    suggestions: Vec<Mention>,
+    /// Where the word Escape closed the popup for starts, until a new word.
+    dismissed_at: Option<usize>,
+    rows: Option<u32>,
+    full_width: bool,
+    disabled: bool,
+    size: ControlSize,
+}
+
+impl MentionsState {
+    /// Build the state, give the input its triggers, and subscribe to it.
+    pub fn new(mentions: Mentions, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let triggers: Vec<char> = mentions
+            .sources
+            .iter()
+            .map(|(trigger, _)| *trigger)
+            .collect();
+        mentions
+            .input
+            .update(cx, |input, _cx| input.set_token_triggers(triggers));
+
+        cx.subscribe_in(
+            &mentions.input,
+            window,
+            |this, _input, event, window, cx| match event {
+                InputStateEvent::TextChanged => this.text_changed(window, cx),
+                InputStateEvent::Blur => this.close(cx),
+                _ => {}
+            },
+        )
+        .detach();
+
+        Self {
+            id: mentions.id,
+            label: mentions.label,
+            input: mentions.input,
+            sources: mentions.sources,
+            listbox: None,
+            query: None,
+            suggestions: Vec::new(),
+            dismissed_at: None,
+            rows: mentions.rows,
+            full_width: mentions.full_width,
+            disabled: mentions.disabled,
+            size: mentions.size,
+        }
+    }
+
+    /// The field's state.
+    pub fn input(&self) -> &Entity<InputState> {
+        &self.input
+    }
+
+    /// Whether the popup is open.
+    pub fn is_open(&self) -> bool {
+        self.listbox.is_some()
+    }
+
+    /// What the popup is showing, row for row.
+    pub fn suggestions(&self) -> &[Mention] {
+        &self.suggestions
+    }
+
+    /// Ask the trigger's source again for the word now at the caret, and
+    /// open, refill or close the popup to match.
+    fn text_changed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let query = if self.disabled {
+            None
+        } else {
+            self.input.read(cx).trigger_query()
+        };
+        let Some(query) = query else {
+            self.dismissed_at = None;
+            self.close(cx);
+            return;
+        };
+        if self.dismissed_at == Some(query.range.start) {
+            return;
+        }
+        self.dismissed_at = None;
+
+        let suggestions = self
+            .sources
+            .iter()
+            .find(|(trigger, _)| *trigger == query.trigger)
+            .map(|(_, source)| source(&query.query))
+            .unwrap_or_default();
+        self.query = Some(query);
+        self.suggestions = suggestions;
+        if self.suggestions.is_empty() {
+            self.close(cx);
+            return;
+        }
+
+        let labels: Vec<SharedString> = self
+            .suggestions
+            .iter()
+            .map(|mention| mention.label.clone())
+            .collect();
+        match self.listbox.clone() {
+            Some(listbox) => {
+                listbox.update(cx, |listbox, cx| listbox.set_options(labels, None, cx));
+            }
+            None => self.open(labels, window, cx),
+        }
+        cx.notify();
+    }
+
+    fn open(&mut self, labels: Vec<SharedString>, window: &mut Window, cx: &mut Context<Self>) {
+        let entity = cx.entity().downgrade();
+        let listbox = Listbox::build(
+            self.label.clone(),
+            labels,
+            None,
+            self.size,
+            // Focus stays in the field, where the user is still typing.
+            ListboxFocus::Caller,
+            move |row, window, cx| {
+                if let Some(entity) = entity.upgrade() {
+                    entity.update(cx, |this, cx| this.choose_row(row, window, cx));
+                }
+            },
+            window,
+            cx,
+        );
+
+        cx.subscribe_in(
+            &listbox,
+            window,
+            |this, _, _event: &DismissEvent, _window, cx| {
+                this.listbox = None;
+                cx.notify();
+            },
+        )
+        .detach();
+
+        self.listbox = Some(listbox);
+    }
+
+    fn close(&mut self, cx: &mut Context<Self>) {
+        if self.listbox.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    /// Replace the word with the suggestion at popup row `row`.
+    fn choose_row(&mut self, row: usize, _window: &mut Window, cx: &mut Context<Self>) {
+        let (Some(mention), Some(query)) = (self.suggestions.get(row).cloned(), self.query.take())
+        else {
+            return;
+        };
+        self.listbox = None;
+        self.input.update(cx, |input, cx| {
+            input.insert_token(query.range, &mention.text, mention.payload, cx);
+        });
+        cx.notify();
+    }
+
+    fn move_highlight(&mut self, delta: isize, cx: &mut Context<Self>) {
+        match self.listbox.clone() {
+            Some(listbox) => listbox.update(cx, |listbox, cx| listbox.move_highlight(delta, cx)),
+            // Closed, so the arrow moves the caret.
+            None => cx.propagate(),
+        }
+    }
+
+    fn choose_highlighted(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let row = self
+            .listbox
+            .as_ref()
+            .and_then(|listbox| listbox.read(cx).highlighted);
+        match row {
+            Some(row) => self.choose_row(row, window, cx),
+            // Enter sends, or breaks the line, as it would without us.
+            None => cx.propagate(),
+        }
+    }
+
+    fn dismiss(&mut self, cx: &mut Context<Self>) {
+        if self.listbox.is_none() {
+            // Not ours: an enclosing dialog's, most often.
+            cx.propagate();
+            return;
+        }
+        self.dismissed_at = self.query.as_ref().map(|query| query.range.start);
+        self.close(cx);
+    }
+}
+
+/// The wrapper carries the role, as a combobox's does: a field with a popup
+/// of values beside it that focus never enters.
+impl Accessible for MentionsState {
+    fn a11y(&self) -> A11y {
+        let a11y = A11y::new(Role::ComboBox)
+            .name(self.label.clone())
+            .expanded(self.listbox.is_some());
+
+        if self.disabled {
+            a11y.not_focusable("a disabled field takes no text to suggest for")
+        } else {
+            // The field's own focus, as in `combobox`.
+            a11y.focusable()
+        }
+    }
+}
+
+impl Render for MentionsState {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let a11y = self.a11y();
+        let gap = LISTBOX_GAP.to_pixels(window.rem_size());
+        let full_width = self.full_width;
+
+        let field = if self.input.read(cx).is_multiline() {
+            let field = textarea(&self.input, cx)
+                .control_size(self.size)
+                .disabled(self.disabled);
+            match self.rows {
+                Some(rows) => field.rows(rows).into_any_element(),
+                None => field.into_any_element(),
+            }
+        } else {
+            text_field(&self.input, cx)
+                .control_size(self.size)
+                .disabled(self.disabled)
+                .full_width(full_width)
+                .into_any_element()
+        };
+
+        let wrapper = div()
+            .id(self.id.clone())
+            .announce(a11y)
+            .key_context(MENTIONS_CONTEXT)
+            .on_action(cx.listener(|this, _: &MentionsHighlightNext, _window, cx| {
+                this.move_highlight(1, cx);
+            }))
+            .on_action(
+                cx.listener(|this, _: &MentionsHighlightPrevious, _window, cx| {
+                    this.move_highlight(-1, cx);
+                }),
+            )
+            .on_action(cx.listener(|this, _: &MentionsChoose, window, cx| {
+                this.choose_highlighted(window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &MentionsDismiss, _window, cx| {
+                this.dismiss(cx);
+            }))
+            .when(full_width, |this| this.w_full())
+            .child(field);
+
+        let anchor = self
+            .query
+            .as_ref()
+            .and_then(|query| self.input.read(cx).caret_bounds_at(query.range.start));
+        // Text pasted in whole, trigger and all, was not laid out last frame.
+        // It will have been by the next.
+        if self.listbox.is_some() && anchor.is_none() {
+            window.request_animation_frame();
+        }
+
+        wrapper.when_some(
+            self.listbox.clone().zip(anchor),
+            |this, (listbox, anchor)| {
+                let popup = div().occlude().child(listbox);
+
+                #[cfg(test)]
+                let popup = popup.debug_selector(|| "gpuikit-mentions-popup".into());
+
+                this.child(
+                    deferred(with_theme(
+                        cx.theme().clone(),
+                        anchored()
+                            .position(point(anchor.left(), anchor.bottom() + gap))
+                            .snap_to_window_with_margin(px(8.))
+                            .child(popup),
+                    ))
+                    .with_priority(1),
+                )
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{size, Focusable, TestAppContext, VisualTestContext};
+    use std::ops::Deref;
+
+    fn people(query: &str) -> Vec<Mention> {
+        [("@alice", "u1"), ("@albert", "u2"), ("@bob", "u3")]
+            .into_iter()
+            .filter(|(text, _)| text[1..].starts_with(query))
+            .map(|(text, id)| Mention::new(text, id))
+            .collect()
+    }
+
+    struct TestView {
+        mentions: Entity<MentionsState>,
+    }
+
+    impl Render for TestView {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            div().size_full().child(self.mentions.clone())
+        }
+    }
+
+    fn open(cx: &mut TestAppContext) -> (Entity<MentionsState>, &'static mut VisualTestContext) {
+        cx.update(crate::init);
+        let window = cx.open_window(size(px(400.), px(300.)), |window, cx| {
+            let input = cx.new(InputState::new_multiline);
+            let mentions = cx.new(|cx| {
+                MentionsState::new(
+                    mentions("composer", "Message", &input).source('@', people),
+                    window,
+                    cx,
+                )
+            });
+            TestView { mentions }
+        });
+        let state = window
+            .read_with(cx, |view, _cx| view.mentions.clone())
+            .expect("the window's root view is the test view");
+        let cx = VisualTestContext::from_window(*window.deref(), cx).into_mut();
+        cx.run_until_parked();
+        (state, cx)
+    }
+
+    fn type_text(state: &Entity<MentionsState>, text: &str, cx: &mut VisualTestContext) {
+        let text = text.to_string();
+        cx.update(|_window, cx| {
+            let input = state.read(cx).input.clone();
+            input.update(cx, |input, cx| input.insert_text(&text, cx));
+        });
+        cx.run_until_parked();
+    }
+
+    #[gpui::test]
+    fn a_trigger_opens_suggestions_that_the_word_filters(cx: &mut TestAppContext) {
+        let (state, cx) = open(cx);
+        type_text(&state, "hi @", cx);
+        cx.update(|_window, cx| {
+            assert!(state.read(cx).is_open());
+            assert_eq!(state.read(cx).suggestions().len(), 3);
+        });
+
+        type_text(&state, "al", cx);
+        cx.update(|_window, cx| {
+            let texts: Vec<_> = state
+                .read(cx)
+                .suggestions()
+                .iter()
+                .map(|mention| mention.text.clone())
+                .collect();
+            assert_eq!(texts, ["@alice", "@albert"]);
+        });
+
+        type_text(&state, " ", cx);
+        cx.update(|_window, cx| assert!(!state.read(cx).is_open()));
+    }
+
+    #[gpui::test]
+    fn choosing_puts_a_token_in_place_of_the_word(cx: &mut TestAppContext) {
+        let (state, cx) = open(cx);
+        type_text(&state, "hi @alb", cx);
+        cx.update(|window, cx| {
+            state.update(cx, |this, cx| this.choose_row(0, window, cx));
+            let input = state.read(cx).input.read(cx);
+            assert_eq!(input.content(), "hi @albert ");
+            assert_eq!(input.tokens().len(), 1);
+            assert_eq!(input.tokens()[0].range, 3..10);
+            assert_eq!(input.tokens()[0].payload, "u2");
+            assert_eq!(input.cursor_offset(), 11);
+        });
+        cx.run_until_parked();
+        cx.update(|_window, cx| assert!(!state.read(cx).is_open()));
+    }
+
+    /// The bindings, end to end: Down and Enter reach the popup only because
+    /// `bind_mentions_keys` is registered after `bind_input_keys`.
+    #[gpui::test]
+    fn the_keyboard_chooses_from_a_popup_under_the_trigger(cx: &mut TestAppContext) {
+        let (state, cx) = open(cx);
+        cx.update(|window, cx| {
+            let input = state.read(cx).input.clone();
+            window.focus(&input.focus_handle(cx), cx);
+        });
+        cx.simulate_input("hi @al");
+
+        let popup = cx
+            .debug_bounds("gpuikit-mentions-popup")
+            .expect("the popup should have been laid out");
+        let trigger = cx.update(|_window, cx| {
+            state
+                .read(cx)
+                .input
+                .read(cx)
+                .caret_bounds_at(3)
+                .expect("the field has been drawn")
+        });
+        assert!(popup.top() >= trigger.bottom(), "{popup:?} {trigger:?}");
+        assert!(
+            (popup.left() - trigger.left()).abs() < px(1.),
+            "{popup:?} {trigger:?}"
+        );
+
+        cx.simulate_keystrokes("down enter");
+        cx.update(|_window, cx| {
+            let input = state.read(cx).input.read(cx);
+            assert_eq!(input.content(), "hi @albert ");
+            assert!(!state.read(cx).is_open());
+        });
+
+        // Closed, Enter is the field's again.
+        cx.simulate_keystrokes("enter");
+        cx.update(|_window, cx| {
+            assert_eq!(state.read(cx).input.read(cx).content(), "hi @albert \n");
+        });
+    }
+
+    #[gpui::test]
+    fn escape_closes_the_popup_for_the_rest_of_the_word(cx: &mut TestAppContext) {
+        let (state, cx) = open(cx);
+        type_text(&state, "@a", cx);
+        cx.update(|_window, cx| {
+            state.update(cx, |this, cx| this.dismiss(cx));
+            assert!(!state.read(cx).is_open());
+        });
+        type_text(&state, "l", cx);
+        cx.update(|_window, cx| assert!(!state.read(cx).is_open()));
+
+        type_text(&state, " @", cx);
+        cx.update(|_window, cx| assert!(state.read(cx).is_open()));
+    }
+}
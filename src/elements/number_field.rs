@@ -0,0 +1,959 @@
+//! Number field — a text field that holds a number.
+//!
+//! A [`Slider`](crate::elements::slider) is the control for "roughly this much
+//! of a range". A parameter panel also needs the other thing: a value typed to
+//! the digit, nudged one step at a time, with no range at all if it has none.
+//! This is that, built the way [`combobox`](crate::elements::combobox) is — a
+//! stateful wrapper that owns an `Entity<InputState>` and draws it through
+//! [`TextField`](crate::elements::text_field::TextField).
+//!
+//! ```ignore
+//! let width = cx.new(|cx| {
+//!     NumberFieldState::new(
+//!         number_field("width", "Width", 120.)
+//!             .range(0.0..=4096.)
+//!             .integer(true)
+//!             .label("W"),
+//!         window,
+//!         cx,
+//!     )
+//! });
+//! cx.subscribe(&width, |this, _, event: &NumberFieldChanged, cx| { … });
+//! ```
+//!
+//! # The value and the text
+//!
+//! As in a combobox, the two are held apart: the **value** is an `f64` here,
+//! and the **text** is the `InputState`'s. Typing changes only the text. The
+//! text is read back into the value when it is **committed** — Enter, focus
+//! leaving the field, or any step — through the field's parse closure, and
+//! then clamped to the range and, for an [`integer`](NumberField::integer)
+//! field, rounded. Text that does not parse is replaced by the value it was
+//! meant to change, so the field never shows a number it does not hold.
+//!
+//! Typing is filtered to what the default parser can read — digits, a sign, a
+//! point and an exponent — with the input's
+//! [`char_filter`](crate::input::InputState::char_filter). An integer field
+//! takes no exponent but keeps the point, so `4.6` commits as 5 rather than
+//! losing its point and reading as 46. A field given its own
+//! [`parse`](NumberField::parse) is not filtered: it decides what it reads.
+//!
+//! # Formatting
+//!
+//! The crate assumes no locale. The default shows an integer field's value as
+//! a plain integer, and anything else with up to six decimals and no trailing
+//! zeros, so `0.1 + 0.2` shows as `0.3`. A caller that wants a decimal comma,
+//! grouping or a unit passes [`format`](NumberField::format) — and, since the
+//! committed text is read back with the parser, a [`parse`](NumberField::parse)
+//! that reads what the formatter writes.
+//!
+//! # Stepping
+//!
+//! Up and Down move the value by one [`step`](NumberField::step), PageUp and
+//! PageDown by one [`page_step`](NumberField::page_step) — ten steps unless
+//! set. A stepped value lands on the step grid, counted from the minimum (or
+//! from zero without one), so a field at `2.37` with a step of `0.1` steps to
+//! `2.4`, not `2.47`. A typed value is not snapped: precise entry is what the
+//! field is for.
+//!
+//! The keys are bound as `combobox`'s are, in `"NumberField > Input"`, and for
+//! the same reason: `up` and `down` are already `Input` bindings on the
+//! focused field, so [`bind_number_field_keys`] has to be registered after
+//! `bind_input_keys` to win the tie. [`crate::init`] does.
+//!
+//! The minus and plus buttons after the text do the same as the arrow keys.
+//! [`steppers(false)`](NumberField::steppers) leaves them out.
+//!
+//! # Scrubbing
+//!
+//! A field with a [`label`](Labelable::label) draws it before the box, with a
+//! horizontal resize cursor: dragging it left or right moves the value one step
+//! for every [`SCRUB_STEP_WIDTH`] of travel, one page with shift held. The drag
+//! is followed on the window, not on the label, exactly as `Slider`'s is — see
+//! its module docs for why — so it carries on past the label's edge.
+//!
+//! # Accessibility
+//!
+//! The wrapper announces `Role::SpinButton` with the constructor's name. A
+//! field with both bounds reports its value through
+//! [`A11y::number_value`], with the range and the step; a field missing either
+//! bound has no range to report, and `A11yValue` says such a control reports
+//! its value as text rather than a number announced as unbounded — so it does.
+//!
+//! The spin button's focus is the text field's, announced through
+//! [`A11y::focus_handle`], so the box, the text and the buttons are one tab
+//! stop.
+
+use crate::a11y::{A11y, Announce};
+use crate::element_id::scoped;
+use crate::elements::icon_button::icon_button;
+use crate::elements::text_field::{text_field, Adornment};
+use crate::icons::Icons;
+use crate::input::{InputState, InputStateEvent, SubmitOn};
+use crate::layout::h_stack;
+use crate::theme::{ActiveTheme, ControlSize, Themeable};
+use crate::traits::accessible::Accessible;
+use crate::traits::control_sized::ControlSized;
+use crate::traits::disableable::Disableable;
+use crate::traits::labelable::Labelable;
+use gpui::{
+    actions, canvas, div, prelude::*, px, App, Context, CursorStyle, DispatchPhase, ElementId,
+    Entity, EventEmitter, FocusHandle, Focusable, IntoElement, KeyBinding, MouseButton,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement, Pixels, Render, Role,
+    SharedString, Styled, Window,
+};
+use std::ops::RangeInclusive;
+use std::rc::Rc;
+
+actions!(
+    number_field,
+    [
+        /// Add one step to the value.
+        NumberFieldIncrement,
+        /// Take one step off the value.
+        NumberFieldDecrement,
+        /// Add one page — ten steps unless set — to the value.
+        NumberFieldPageUp,
+        /// Take one page off the value.
+        NumberFieldPageDown,
+    ]
+);
+
+/// The key context the number field's wrapper declares.
+pub const NUMBER_FIELD_CONTEXT: &str = "NumberField";
+
+/// How far a scrub travels for one step.
+pub const SCRUB_STEP_WIDTH: Pixels = px(4.);
+
+/// The most decimals the default format shows.
+const DEFAULT_DECIMALS: usize = 6;
+
+/// Bind the number field's four keys: Up, Down, PageUp and PageDown.
+///
+/// **Call this after `input::bind_input_keys`**, for the reason
+/// [`bind_combobox_keys`](crate::elements::combobox::bind_combobox_keys)
+/// gives: `up` and `down` are also bound in the plain `Input` context on the
+/// field that holds focus, and the later registration wins the tie.
+pub fn bind_number_field_keys(cx: &mut App) {
+    let under_input = Some("NumberField > Input");
+    cx.bind_keys([
+        KeyBinding::new("up", NumberFieldIncrement, under_input),
+        KeyBinding::new("down", NumberFieldDecrement, under_input),
+        KeyBinding::new("pageup", NumberFieldPageUp, under_input),
+        KeyBinding::new("pagedown", NumberFieldPageDown, under_input),
+        KeyBinding::new("up", NumberFieldIncrement, Some(NUMBER_FIELD_CONTEXT)),
+        KeyBinding::new("down", NumberFieldDecrement, Some(NUMBER_FIELD_CONTEXT)),
+        KeyBinding::new("pageup", NumberFieldPageUp, Some(NUMBER_FIELD_CONTEXT)),
+        KeyBinding::new("pagedown", NumberFieldPageDown, Some(NUMBER_FIELD_CONTEXT)),
+    ]);
+}
+
+/// Emitted when the number field's value changes.
+pub struct NumberFieldChanged {
+    pub value: f64,
+}
+
+/// How a value is shown.
+type Formatter = Rc<dyn Fn(f64) -> String>;
+/// How committed text is read back; `None` for text that is not a number.
+type Parser = Rc<dyn Fn(&str) -> Option<f64>>;
+
+/// Builder for a number field. Use [`number_field`] to create one.
+pub struct NumberField {
+    id: ElementId,
+    name: SharedString,
+    value: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+    step: f64,
+    page_step: Option<f64>,
+    integer: bool,
+    format: Option<Formatter>,
+    parse: Option<Parser>,
+    label: Option<SharedString>,
+    steppers: bool,
+    full_width: bool,
+    disabled: bool,
+    size: ControlSize,
+}
+
+/// Creates a new number field builder.
+///
+/// # Arguments
+///
+/// * `id` — unique identifier for the field
+/// * `name` — the accessible name. Required for the reason
+///   [`combobox`](crate::elements::combobox::combobox)'s is: the visible text
+///   is the value, not what the value is of
+/// * `value` — the starting value
+pub fn number_field(
+    id: impl Into<ElementId>,
+    name: impl Into<SharedString>,
+    value: f64,
+) -> NumberField {
+    NumberField::new(id, name, value)
+}
+
+impl NumberField {
+    /// See [`number_field`].
+    pub fn new(id: impl Into<ElementId>, name: impl Into<SharedString>, value: f64) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            value,
+            min: None,
+            max: None,
+            step: 1.,
+            page_step: None,
+            integer: false,
+            format: None,
+            parse: None,
+            label: None,
+            steppers: true,
+            full_width: false,
+            disabled: false,
+            size: ControlSize::default(),
+        }
+    }
+
+    /// The lowest value the field takes.
+    pub fn min(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// The highest value the field takes.
+    pub fn max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Both bounds at once.
+    pub fn range(self, range: RangeInclusive<f64>) -> Self {
+        let (min, max) = range.into_inner();
+        self.min(min).max(max)
+    }
+
+    /// How far Up, Down and the stepper buttons move the value. `1` by
+    /// default; a step that is not positive is ignored.
+    pub fn step(mut self, step: f64) -> Self {
+        if step > 0. {
+            self.step = step;
+        }
+        self
+    }
+
+    /// How far PageUp and PageDown move the value. Ten steps by default.
+    pub fn page_step(mut self, page_step: f64) -> Self {
+        if page_step > 0. {
+            self.page_step = Some(page_step);
+        }
+        self
+    }
+
+    /// Hold whole numbers only: a committed value is rounded, and the default
+    /// format shows no decimals.
+    pub fn integer(mut self, integer: bool) -> Self {
+        self.integer = integer;
+        self
+    }
+
+    /// Show the value with `format` rather than the default. See the module
+    /// docs' `# Formatting`.
+    pub fn format(mut self, format: impl Fn(f64) -> String + 'static) -> Self {
+        self.format = Some(Rc::new(format));
+        self
+    }
+
+    /// Read committed text with `parse` rather than `str::parse`. `None` means
+    /// the text is not a number, and the field goes back to its value.
+    pub fn parse(mut self, parse: impl Fn(&str) -> Option<f64> + 'static) -> Self {
+        self.parse = Some(Rc::new(parse));
+        self
+    }
+
+    /// Draw the minus and plus buttons after the text. On by default.
+    pub fn steppers(mut self, steppers: bool) -> Self {
+        self.steppers = steppers;
+        self
+    }
+
+    /// Make the field expand to fill available width.
+    pub fn full_width(mut self, full_width: bool) -> Self {
+        self.full_width = full_width;
+        self
+    }
+}
+
+impl Labelable for NumberField {
+    /// A label drawn before the box, which scrubs the value when dragged.
+    fn label(mut self, label: impl Into<SharedString>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+impl Disableable for NumberField {
+    fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl ControlSized for NumberField {
+    fn control_size(mut self, size: ControlSize) -> Self {
+        self.size = size;
+        self
+    }
+}
+
+/// Where a scrub started.
+struct Scrub {
+    x: Pixels,
+    value: f64,
+}
+
+/// The stateful number field. Build one with [`NumberFieldState::new`] inside
+/// `cx.new(…)`; it has to outlive a frame because it owns the text's entity.
+pub struct NumberFieldState {
+    id: ElementId,
+    name: SharedString,
+    value: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+    step: f64,
+    page_step: f64,
+    integer: bool,
+    format: Formatter,
+    parse: Parser,
+    label: Option<SharedString>,
+    steppers: bool,
+    full_width: bool,
+    disabled: bool,
+    size: ControlSize,
+    input: Entity<InputState>,
+    focus_handle: FocusHandle,
+    scrub: Option<Scrub>,
+}
+
+impl EventEmitter<NumberFieldChanged> for NumberFieldState {}
+
+impl NumberFieldState {
+    /// Build the state, its `InputState` and the subscription that commits
+    /// the text.
+    pub fn new(field: NumberField, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let integer = field.integer;
+        let filtered = field.parse.is_none();
+        let name = field.name.clone();
+        let input = cx.new(|cx| {
+            let mut state = InputState::new_singleline(cx).submit_on(SubmitOn::Enter);
+            if filtered {
+                state = state.char_filter(move |c| {
+                    c.is_ascii_digit()
+                        || c == '-'
+                        || c == '+'
+                        || c == '.'
+                        || (!integer && matches!(c, 'e' | 'E'))
+                });
+            }
+            // The text field's own name, inside the spin button.
+            state.set_placeholder(name, cx);
+            state
+        });
+
+        cx.subscribe_in(
+            &input,
+            window,
+            |this, _input, event, _window, cx| match event {
+                InputStateEvent::Submit | InputStateEvent::Blur => this.commit(cx),
+                _ => {}
+            },
+        )
+        .detach();
+
+        let mut state = Self {
+            id: field.id,
+            name: field.name,
+            value: 0.,
+            min: field.min,
+            max: field.max,
+            step: field.step,
+            page_step: field.page_step.unwrap_or(field.step * 10.),
+            integer,
+            format: field
+                .format
+                .unwrap_or_else(|| Rc::new(move |value| default_format(value, integer))),
+            parse: field.parse.unwrap_or_else(|| Rc::new(default_parse)),
+            label: field.label,
+            steppers: field.steppers,
+            full_width: field.full_width,
+            disabled: field.disabled,
+            size: field.size,
+            focus_handle: input.focus_handle(cx),
+            input,
+            scrub: None,
+        };
+        state.value = state.settle(field.value);
+        state.show_value(cx);
+        state
+    }
+
+    /// The value.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Set the value, clamped to the range and rounded for an integer field,
+    /// and show it. Emits [`NumberFieldChanged`] if that changed it.
+    pub fn set_value(&mut self, value: f64, cx: &mut Context<Self>) {
+        let value = self.settle(value);
+        let changed = value != self.value;
+        self.value = value;
+        self.show_value(cx);
+        if changed {
+            cx.emit(NumberFieldChanged { value });
+            cx.notify();
+        }
+    }
+
+    /// The text in the field, which is the value as shown unless it is being
+    /// typed over.
+    pub fn text(&self, cx: &App) -> SharedString {
+        self.input.read(cx).content().to_string().into()
+    }
+
+    /// Move the value by `steps` steps — negative for down — landing on the
+    /// step grid. See the module docs' `# Stepping`.
+    pub fn step_by(&mut self, steps: f64, cx: &mut Context<Self>) {
+        self.commit(cx);
+        let value = self.snap(self.value + steps * self.step, steps);
+        self.set_value(value, cx);
+    }
+
+    /// Move the value by `pages` pages.
+    pub fn page_by(&mut self, pages: f64, cx: &mut Context<Self>) {
+        self.step_by(pages * self.page_step / self.step, cx);
+    }
+
+    /// Read the text back into the value, or put the value back if the text
+    /// is not a number.
+    fn commit(&mut self, cx: &mut Context<Self>) {
+        let text = self.input.read(cx).content().to_string();
+        match (self.parse)(text.trim()).filter(|value| value.is_finite()) {
+            Some(value) => self.set_value(value, cx),
+            None => self.show_value(cx),
+        }
+    }
+
+    /// Put the value, formatted, in the field — unless it is there already,
+    /// which would cost the cursor its place and the user their undo.
+    fn show_value(&mut self, cx: &mut Context<Self>) {
+        let text = (self.format)(self.value);
+        if self.input.read(cx).content() != text {
+            self.input
+                .update(cx, |input, cx| input.set_content(text, cx));
+        }
+    }
+
+    /// `value` clamped, and rounded for an integer field.
+    fn settle(&self, value: f64) -> f64 {
+        let mut value = if self.integer { value.round() } else { value };
+        if let Some(max) = self.max {
+            value = value.min(max);
+        }
+        if let Some(min) = self.min {
+            value = value.max(min);
+        }
+        // `-0` is a value no one typed.
+        if value == 0. {
+            0.
+        } else {
+            value
+        }
+    }
+
+    /// `value` on the step grid, with the float noise of getting there
+    /// rounded off at the step's own precision.
+    ///
+    /// A step up lands on the grid line at or below where it ended, and a
+    /// step down on the one at or above, so a step from between two lines
+    /// goes to the next line rather than past it. `direction` is `0.` for the
+    /// nearest line.
+    fn snap(&self, value: f64, direction: f64) -> f64 {
+        // Slack for the noise in the division, so a value on a line is not
+        // read as just short of it.
+        const SLACK: f64 = 1e-9;
+        let origin = self.min.unwrap_or(0.);
+        let steps = (value - origin) / self.step;
+        let steps = if direction > 0. {
+            (steps + SLACK).floor()
+        } else if direction < 0. {
+            (steps - SLACK).ceil()
+        } else {
+            steps.round()
+        };
+        let snapped = origin + steps * self.step;
+        let scale = 10f64.powi(decimals(self.step).max(decimals(origin)) as i32);
+        (snapped * scale).round() / scale
+    }
+
+    fn begin_scrub(&mut self, event: &MouseDownEvent, cx: &mut Context<Self>) {
+        if self.disabled {
+            return;
+        }
+        self.commit(cx);
+        self.scrub = Some(Scrub {
+            x: event.position.x,
+            value: self.value,
+        });
+        cx.notify();
+    }
+
+    /// The movement, from the window rather than the label.
+    fn scrub_to(&mut self, event: &MouseMoveEvent, cx: &mut Context<Self>) {
+        let Some(scrub) = &self.scrub else {
+            return;
+        };
+        // The release the window never saw — as in `Slider::on_drag_move`.
+        if !event.dragging() || self.disabled {
+            self.end_scrub(cx);
+            return;
+        }
+        let steps = ((event.position.x - scrub.x) / SCRUB_STEP_WIDTH).trunc() as f64;
+        let distance = if event.modifiers.shift {
+            steps * self.page_step
+        } else {
+            steps * self.step
+        };
+        let value = self.snap(scrub.value + distance, 0.);
+        self.set_value(value, cx);
+    }
+
+    fn end_scrub(&mut self, cx: &mut Context<Self>) {
+        if self.scrub.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    fn at_min(&self) -> bool {
+        self.min.is_some_and(|min| self.value <= min)
+    }
+
+    fn at_max(&self) -> bool {
+        self.max.is_some_and(|max| self.value >= max)
+    }
+}
+
+/// The default format: see the module docs' `# Formatting`.
+fn default_format(value: f64, integer: bool) -> String {
+    if integer {
+        return format!("{}", value.round() as i64);
+    }
+    let text = format!("{value:.DEFAULT_DECIMALS$}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// The default parser: `str::parse`, of text already trimmed.
+fn default_parse(text: &str) -> Option<f64> {
+    text.parse().ok()
+}
+
+/// How many decimals `value` is written with, up to the default format's.
+fn decimals(value: f64) -> usize {
+    default_format(value, false)
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len())
+}
+
+impl Focusable for NumberFieldState {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Accessible for NumberFieldState {
+    /// `Role::SpinButton` with the constructor's name, and the value as the
+    /// module docs' `# Accessibility` describes.
+    fn a11y(&self) -> A11y {
+        let a11y = A11y::new(Role::SpinButton).name(self.name.clone());
+        let a11y = match (self.min, self.max) {
+            (Some(min), Some(max)) => a11y.number_value(self.value, min, max, self.step),
+            _ => a11y.text_value((self.format)(self.value)),
+        };
+
+        if self.disabled {
+            a11y.not_focusable("a disabled number field shows its value as static text")
+        } else {
+            a11y.focus_handle(self.focus_handle.clone())
+        }
+    }
+}
+
+impl Render for NumberFieldState {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let a11y = self.a11y();
+        let theme = cx.theme();
+        let metrics = theme.control(self.size);
+        let disabled = self.disabled;
+        let entity = cx.entity();
+
+        let steppers = (self.steppers && !disabled).then(|| {
+            let stepper = |suffix: &str, icon, steps: f64, stopped: bool| {
+                let entity = entity.clone();
+                icon_button(scoped(&self.id, suffix), icon)
+                    .box_size(metrics.ink)
+                    .icon_size(metrics.ink)
+                    .disabled(stopped)
+                    .on_click(move |_, _, cx| {
+                        entity.update(cx, |this, cx| this.step_by(steps, cx));
+                    })
+            };
+            h_stack()
+                .gap(metrics.gap / 2.)
+                .child(stepper("decrement", Icons::minus(), -1., self.at_min()))
+                .child(stepper("increment", Icons::plus(), 1., self.at_max()))
+        });
+
+        let mut field = text_field(&self.input, cx)
+            .control_size(self.size)
+            .disabled(disabled)
+            .full_width(self.full_width);
+        if let Some(steppers) = steppers {
+            field = field.suffix(Adornment::element(steppers));
+        }
+
+        let label = self.label.clone().map(|label| {
+            let scrubbing = self.scrub.is_some();
+            div()
+                .id(scoped(&self.id, "label"))
+                .relative()
+                .flex_none()
+                .text_size(metrics.text_size)
+                .text_color(if disabled {
+                    theme.fg_disabled()
+                } else if scrubbing {
+                    theme.fg()
+                } else {
+                    theme.fg_muted()
+                })
+                .child(label)
+                .when(!disabled, |this| {
+                    this.cursor(CursorStyle::ResizeLeftRight)
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, event, _window, cx| this.begin_scrub(event, cx)),
+                        )
+                        .child(
+                            canvas(|_, _, _| (), {
+                                let entity = entity.clone();
+                                move |_, _, window, _cx| {
+                                    // Registered on every paint, live scrub or
+                                    // not — see `Slider`'s module docs.
+                                    window.on_mouse_event({
+                                        let entity = entity.clone();
+                                        move |event: &MouseMoveEvent, phase, _window, cx| {
+                                            if phase == DispatchPhase::Bubble {
+                                                entity.update(cx, |this, cx| {
+                                                    this.scrub_to(event, cx)
+                                                });
+                                            }
+                                        }
+                                    });
+                                    window.on_mouse_event({
+                                        let entity = entity.clone();
+                                        move |event: &MouseUpEvent, phase, _window, cx| {
+                                            if phase == DispatchPhase::Bubble
+                                                && event.button == MouseButton::Left
+                                            {
+                                                entity.update(cx, |this, cx| this.end_scrub(cx));
+                                            }
+                                        }
+                                    });
+                                }
+                            })
+                            .absolute()
+                            .size_full(),
+                        )
+                })
+        });
+
+        h_stack()
+            .id(self.id.clone())
+            .announce(a11y)
+            .key_context(NUMBER_FIELD_CONTEXT)
+            .on_action(cx.listener(|this, _: &NumberFieldIncrement, _window, cx| {
+                this.step_by(1., cx);
+            }))
+            .on_action(cx.listener(|this, _: &NumberFieldDecrement, _window, cx| {
+                this.step_by(-1., cx);
+            }))
+            .on_action(cx.listener(|this, _: &NumberFieldPageUp, _window, cx| {
+                this.page_by(1., cx);
+            }))
+            .on_action(cx.listener(|this, _: &NumberFieldPageDown, _window, cx| {
+                this.page_by(-1., cx);
+            }))
+            .items_center()
+            .gap(metrics.gap)
+            .when(self.full_width, |this| this.w_full())
+            .when_some(label, |this, label| this.child(label))
+            .child(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::EntityInputHandler;
+    use gpui::{size, TestAppContext, VisualTestContext};
+    use std::cell::RefCell;
+    use std::ops::Deref;
+
+    struct TestView {
+        field: Entity<NumberFieldState>,
+    }
+
+    impl Render for TestView {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            div().size_full().child(self.field.clone())
+        }
+    }
+
+    /// A live field in a real window, and every value it reports.
+    fn open(
+        cx: &mut TestAppContext,
+        build: impl FnOnce(NumberField) -> NumberField,
+    ) -> (
+        Entity<NumberFieldState>,
+        Rc<RefCell<Vec<f64>>>,
+        &'static mut VisualTestContext,
+    ) {
+        open_after(crate::init, cx, build)
+    }
+
+    /// [`open`], initialized by `init` instead.
+    fn open_after(
+        init: impl FnOnce(&mut App),
+        cx: &mut TestAppContext,
+        build: impl FnOnce(NumberField) -> NumberField,
+    ) -> (
+        Entity<NumberFieldState>,
+        Rc<RefCell<Vec<f64>>>,
+        &'static mut VisualTestContext,
+    ) {
+        cx.update(init);
+        let window = cx.open_window(size(px(400.), px(300.)), |window, cx| {
+            let field = cx.new(|cx| {
+                NumberFieldState::new(build(number_field("test", "Amount", 5.)), window, cx)
+            });
+            TestView { field }
+        });
+        let field = window
+            .read_with(cx, |view, _cx| view.field.clone())
+            .expect("the window's root view is the test view");
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let sink = changes.clone();
+        cx.update(|cx| {
+            cx.subscribe(&field, move |_, event: &NumberFieldChanged, _| {
+                sink.borrow_mut().push(event.value)
+            })
+            .detach()
+        });
+        let cx = VisualTestContext::from_window(*window.deref(), cx).into_mut();
+        cx.run_until_parked();
+        (field, changes, cx)
+    }
+
+    fn focus(field: &Entity<NumberFieldState>, cx: &mut VisualTestContext) {
+        cx.update(|window, cx| {
+            let handle = field.read(cx).focus_handle(cx);
+            window.focus(&handle, cx);
+        });
+        cx.run_until_parked();
+    }
+
+    fn type_text(field: &Entity<NumberFieldState>, text: &str, cx: &mut VisualTestContext) {
+        let text = text.to_string();
+        cx.update(|_window, cx| {
+            let input = field.read(cx).input.clone();
+            input.update(cx, |input, cx| input.set_content(text, cx));
+        });
+    }
+
+    #[gpui::test]
+    fn the_arrow_keys_step_and_the_page_keys_page(cx: &mut TestAppContext) {
+        let (field, changes, cx) = open(cx, |field| field.range(0.0..=100.));
+        focus(&field, cx);
+
+        cx.simulate_keystrokes("up up down pageup");
+        cx.run_until_parked();
+
+        assert_eq!(*changes.borrow(), [6., 7., 6., 16.]);
+        cx.update(|_window, cx| {
+            assert_eq!(field.read(cx).text(cx), SharedString::from("16"));
+        });
+
+        cx.simulate_keystrokes("pagedown pagedown pagedown");
+        cx.run_until_parked();
+        assert_eq!(cx.update(|_window, cx| field.read(cx).value()), 0.);
+    }
+
+    /// The arrows still step under a keybinding preset, which
+    /// `init_with_input_bindings` registers before `bind_number_field_keys`.
+    #[gpui::test]
+    fn the_arrow_keys_step_under_the_readline_preset(cx: &mut TestAppContext) {
+        let (field, changes, cx) = open_after(
+            |cx| crate::init_with_input_bindings(cx, crate::input::InputBindings::readline()),
+            cx,
+            |field| field,
+        );
+        focus(&field, cx);
+
+        cx.simulate_keystrokes("up");
+        cx.run_until_parked();
+        assert_eq!(*changes.borrow(), [6.]);
+    }
+
+    /// Typed text is the value only once it is committed, and then clamped;
+    /// text that is not a number puts the value back.
+    #[gpui::test]
+    fn enter_commits_what_was_typed(cx: &mut TestAppContext) {
+        let (field, changes, cx) = open(cx, |field| field.max(10.));
+        focus(&field, cx);
+
+        type_text(&field, "250", cx);
+        assert_eq!(cx.update(|_window, cx| field.read(cx).value()), 5.);
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+        assert_eq!(*changes.borrow(), [10.]);
+
+        type_text(&field, "-", cx);
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+        cx.update(|_window, cx| {
+            assert_eq!(field.read(cx).value(), 10.);
+            assert_eq!(field.read(cx).text(cx), SharedString::from("10"));
+        });
+    }
+
+    #[gpui::test]
+    fn a_stepped_value_lands_on_the_grid(cx: &mut TestAppContext) {
+        let (field, _changes, cx) = open(cx, |field| field.step(0.1));
+        cx.update(|_window, cx| {
+            field.update(cx, |this, cx| {
+                this.set_value(2.37, cx);
+                this.step_by(1., cx);
+                assert_eq!(this.value(), 2.4);
+                this.set_value(2.37, cx);
+                this.step_by(-1., cx);
+                assert_eq!(this.value(), 2.3);
+                this.set_value(0.1, cx);
+                this.step_by(2., cx);
+                assert_eq!(this.text(cx), SharedString::from("0.3"));
+            });
+        });
+    }
+
+    #[gpui::test]
+    fn an_integer_field_rounds_and_filters(cx: &mut TestAppContext) {
+        let (field, _changes, cx) = open(cx, |field| field.integer(true));
+        focus(&field, cx);
+        cx.update(|window, cx| {
+            let input = field.read(cx).input.clone();
+            input.update(cx, |input, cx| {
+                input.select_all(&crate::input::bindings::SelectAll, window, cx);
+                input.replace_text_in_range(None, "4.6e1x", window, cx);
+                assert_eq!(input.content(), "4.61");
+                input.select_all(&crate::input::bindings::SelectAll, window, cx);
+                input.replace_text_in_range(None, "4.6", window, cx);
+            });
+            field.update(cx, |this, cx| {
+                this.commit(cx);
+                assert_eq!(this.value(), 5.);
+                assert_eq!(this.input.read(cx).content(), "5");
+                this.set_value(2.5, cx);
+                assert_eq!(this.value(), 3.);
+            });
+        });
+    }
+
+    #[gpui::test]
+    fn a_format_and_a_parse_replace_the_defaults(cx: &mut TestAppContext) {
+        let (field, _changes, cx) = open(cx, |field| {
+            field
+                .format(|value| format!("{value:.2}").replace('.', ","))
+                .parse(|text| text.replace(',', ".").parse().ok())
+        });
+        focus(&field, cx);
+        assert_eq!(
+            cx.update(|_window, cx| field.read(cx).text(cx)),
+            SharedString::from("5,00")
+        );
+
+        type_text(&field, "1,25", cx);
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+        assert_eq!(cx.update(|_window, cx| field.read(cx).value()), 1.25);
+    }
+
+    #[gpui::test]
+    fn scrubbing_moves_a_step_per_width(cx: &mut TestAppContext) {
+        let (field, changes, cx) = open(cx, |field| field.label("Amount"));
+        let at = |x: f32| gpui::point(px(x), px(16.));
+
+        cx.simulate_mouse_down(at(10.), MouseButton::Left, gpui::Modifiers::none());
+        cx.simulate_mouse_move(
+            at(10.) + gpui::point(SCRUB_STEP_WIDTH * 3., px(0.)),
+            MouseButton::Left,
+            gpui::Modifiers::none(),
+        );
+        cx.simulate_mouse_up(at(200.), MouseButton::Left, gpui::Modifiers::none());
+        cx.run_until_parked();
+
+        assert_eq!(*changes.borrow(), [8.]);
+        assert!(cx.update(|_window, cx| field.read(cx).scrub.is_none()));
+    }
+
+    #[test]
+    fn the_default_format_drops_float_noise() {
+        assert_eq!(default_format(0.1 + 0.2, false), "0.3");
+        assert_eq!(default_format(-0.0000001, false), "0");
+        assert_eq!(default_format(12.0, false), "12");
+        assert_eq!(default_format(2.5, true), "3");
+    }
+
+    #[gpui::test]
+    fn a_bounded_field_announces_a_number(cx: &mut TestAppContext) {
+        let node_for = |a11y: A11y| {
+            crate::a11y::test_support::announced_element(div().id("spin").announce(a11y))
+                .node
+                .expect("an element with an id and a role is a node")
+        };
+
+        let (bounded, _changes, cx) = open(cx, |field| field.range(0.0..=10.).step(0.5));
+        cx.update(|_window, cx| {
+            let a11y = bounded.read(cx).a11y();
+            assert_eq!(a11y.role(), Role::SpinButton);
+            let node = node_for(a11y);
+            assert_eq!(node.label(), Some("Amount"));
+            assert_eq!(node.numeric_value(), Some(5.));
+            assert_eq!(node.min_numeric_value(), Some(0.));
+            assert_eq!(node.max_numeric_value(), Some(10.));
+            assert_eq!(node.numeric_value_step(), Some(0.5));
+
+            bounded.update(cx, |this, _cx| this.max = None);
+            let node = node_for(bounded.read(cx).a11y());
+            assert_eq!(node.numeric_value(), None);
+            assert_eq!(node.value(), Some("5"));
+        });
+    }
+}
@@ -22,7 +22,7 @@
 //! ```
 
 use crate::element_id::for_entity;
-use crate::theme::{ActiveTheme, Themeable};
+use crate::theme::{with_theme, ActiveTheme, Elevation, Radius, Themeable};
 use gpui::{
     anchored, deferred, div, point, prelude::*, px, AnyElement, App, Context, DismissEvent,
     ElementId, Entity, EventEmitter, FocusHandle, Focusable, IntoElement, ParentElement, Pixels,
@@ -80,6 +80,8 @@ impl Render for PopoverPanel {
         let theme = cx.theme();
         let surface_color = theme.surface();
         let border_color = theme.border();
+        let radius = theme.radius(Radius::Medium);
+        let shadow = theme.elevation(Elevation::Overlay);
 
         let content = (self.content_render)(window, cx);
 
@@ -101,8 +103,8 @@ impl Render for PopoverPanel {
             .bg(surface_color)
             .border_1()
             .border_color(border_color)
-            .rounded_md()
-            .shadow_lg()
+            .rounded(radius)
+            .shadow(shadow)
             .overflow_hidden()
             .child(content)
     }
@@ -272,6 +274,7 @@ impl Render for PopoverState {
         };
 
         let offset = self.offset;
+        let overlay_theme = cx.theme().clone();
 
         div()
             .relative()
@@ -292,11 +295,12 @@ impl Render for PopoverState {
                 // pushed straight back out by its own margin. See
                 // `docs/overlays.md`.
                 this.child(
-                    deferred(
+                    deferred(with_theme(
+                        overlay_theme,
                         anchored()
                             .offset(offset)
                             .child(div().occlude().child(panel)),
-                    )
+                    ))
                     .with_priority(1),
                 )
             })
@@ -105,9 +105,9 @@ impl<T: Clone + PartialEq + 'static> Render for RadioGroup<T> {
         let dot_size = rems(0.5);
 
         let container = if orientation == Orientation::Vertical {
-            div().flex().flex_col().gap(rems(0.5))
+            div().flex().flex_col().gap(theme.space(2.))
         } else {
-            div().flex().flex_row().gap(rems(1.0)).items_center()
+            div().flex().flex_row().gap(theme.space(4.)).items_center()
         };
 
         container.id(self.id.clone()).children(
@@ -149,7 +149,7 @@ impl<T: Clone + PartialEq + 'static> Render for RadioGroup<T> {
                         .id(ElementId::NamedInteger("radio-option".into(), index as u64))
                         .flex()
                         .flex_row()
-                        .gap(rems(0.5))
+                        .gap(theme.space(2.))
                         .items_center()
                         .when(!is_disabled, |this| {
                             this.cursor_pointer()
@@ -129,7 +129,7 @@ use crate::a11y::{A11y, Announce};
 use crate::elements::listbox::{Listbox, ListboxFocus, LISTBOX_GAP};
 #[cfg(test)]
 use crate::elements::listbox::option_a11y;
-use crate::theme::{focus_ring, ActiveTheme, ControlSize, Themeable};
+use crate::theme::{focus_ring, with_theme, ActiveTheme, ControlSize, Themeable};
 use crate::traits::accessible::Accessible;
 use crate::traits::control_sized::ControlSized;
 use crate::traits::disableable::Disableable;
@@ -552,7 +552,11 @@ impl<T: Clone + PartialEq + 'static> SelectState<T> {
                 let popup = popup.debug_selector(|| "gpuikit-select-popup".into());
 
                 this.child(
-                    deferred(anchored().offset(point(px(0.), gap)).child(popup)).with_priority(1),
+                    deferred(with_theme(
+                        cx.theme().clone(),
+                        anchored().offset(point(px(0.), gap)).child(popup),
+                    ))
+                    .with_priority(1),
                 )
             })
     }
@@ -51,7 +51,7 @@ use gpui::{
 use crate::a11y::{A11y, Announce};
 use crate::element_id::scoped;
 use crate::icons::Icons;
-use crate::theme::{focus_ring, ActiveTheme, ControlMetrics, ControlSize, Themeable};
+use crate::theme::{focus_ring, with_theme, ActiveTheme, ControlMetrics, ControlSize, Themeable};
 use crate::traits::accessible::Accessible;
 use crate::traits::clickable::Clickable;
 use crate::traits::control_sized::ControlSized;
@@ -487,12 +487,13 @@ impl RenderOnce for Sidebar {
                     .w(layout.footprint)
                     .h_full()
                     .child(
-                        deferred(
+                        deferred(with_theme(
+                            cx.theme().clone(),
                             anchored()
                                 .position(point(px(0.), px(0.)))
                                 .snap_to_window()
                                 .child(drawer),
-                        )
+                        ))
                         // Below `Dialog`'s 10, so a modal opened from the
                         // sidebar still draws over the drawer.
                         .with_priority(2),
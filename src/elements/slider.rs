@@ -24,7 +24,7 @@
 
 use crate::element_id::scoped;
 use crate::layout::{h_stack, v_stack};
-use crate::theme::{ActiveTheme, Themeable};
+use crate::theme::{ActiveTheme, Elevation, Themeable};
 use crate::traits::disableable::Disableable;
 use crate::traits::labelable::Labelable;
 use crate::utils::element_manager::ElementManagerExt;
@@ -240,7 +240,7 @@ impl Render for Slider {
         v_stack()
             .id(self.id.clone())
             .w_full()
-            .gap(rems(0.25))
+            .gap(theme.space(1.))
             .when(label.is_some() || show_value, |this| {
                 this.child(
                     h_stack()
@@ -352,7 +352,7 @@ impl Render for Slider {
                             .rounded_full()
                             .border_1()
                             .border_color(thumb_border)
-                            .shadow_sm(),
+                            .shadow(theme.elevation(Elevation::Raised)),
                     ),
             )
     }
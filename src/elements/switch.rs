@@ -3,7 +3,7 @@
 //! A sliding switch control for toggling boolean values, similar to iOS-style switches.
 
 use crate::layout::h_stack;
-use crate::theme::{ActiveTheme, ControlSize, Themeable};
+use crate::theme::{ActiveTheme, ControlSize, Elevation, Themeable};
 use crate::traits::control_sized::ControlSized;
 use crate::traits::disableable::Disableable;
 use crate::traits::labelable::Labelable;
@@ -161,7 +161,7 @@ impl Render for Switch {
                             .size(track.thumb)
                             .bg(thumb_bg)
                             .rounded_full()
-                            .shadow_md(),
+                            .shadow(theme.elevation(Elevation::Floating)),
                     ),
             )
             .when_some(label, |this, label| {
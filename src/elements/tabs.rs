@@ -3,10 +3,10 @@
 //! A tabbed interface for organizing content into multiple panels.
 
 use crate::layout::h_stack;
-use crate::theme::{ActiveTheme, Themeable};
+use crate::theme::{ActiveTheme, Radius, Themeable};
 use crate::traits::disableable::Disableable;
 use gpui::{
-    div, prelude::*, px, rems, Context, ElementId, EventEmitter, InteractiveElement, IntoElement,
+    div, prelude::*, px, Context, ElementId, EventEmitter, InteractiveElement, IntoElement,
     MouseButton, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Window,
 };
 
@@ -129,7 +129,7 @@ impl Render for Tabs {
         let selected = self.selected.clone();
 
         let tab_list = h_stack()
-            .gap(rems(0.25))
+            .gap(theme.space(1.))
             .border_b_1()
             .border_color(theme.border())
             .pb(px(1.0));
@@ -169,15 +169,15 @@ impl Render for Tabs {
                             .flex()
                             .items_center()
                             .justify_center()
-                            .px(rems(0.75))
-                            .py(rems(0.5))
+                            .px(theme.space(3.))
+                            .py(theme.space(2.))
                             .text_sm()
                             .text_color(text_color)
                             .bg(bg)
                             .border_b_2()
                             .border_color(border_color)
                             .mb(px(-1.0)) // Overlap with container border
-                            .rounded_t(px(4.0))
+                            .rounded_t(theme.radius(Radius::Small))
                             .when(!is_disabled, |this| {
                                 this.cursor_pointer()
                                     .on_mouse_down(MouseButton::Left, |_, window, _| {
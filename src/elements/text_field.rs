@@ -34,6 +34,30 @@
 //!     .suffix(Adornment::text(".com"))
 //! ```
 //!
+//! A password is a [secure](InputState::secure) state, and the field can carry
+//! the button that shows it:
+//!
+//! ```ignore
+//! let password = cx.new(|cx| InputState::new_singleline(cx).secure(true));
+//!
+//! text_field(&password, cx)
+//!     .placeholder("Password")
+//!     .reveal_toggle(true)
+//! ```
+//!
+//! # What it announces
+//!
+//! `Role::TextInput` with the content as its value, or `Role::PasswordInput`
+//! with no value over a secure state. Both roles need a name, and a field
+//! takes the first of: the label of an enclosing
+//! [`Field`](crate::elements::field::Field), its own placeholder, the
+//! state's placeholder. A field with none of the three announces nothing, as
+//! every field did before it could, rather than trip the missing-name
+//! `debug_assert!` of `a11y`'s section 2.
+//!
+//! A field that announces gives the state's focus handle through
+//! [`A11y::focus_handle`], so the box and the text inside it are one tab stop.
+//!
 //! A button that acts on the field is composition, not a field feature:
 //!
 //! ```ignore
@@ -45,16 +69,24 @@
 //!
 //! [`Textarea`]: crate::elements::textarea::Textarea
 
+use std::rc::Rc;
+
 use gpui::{
-    div, prelude::*, AnyElement, App, ElementId, Entity, EntityId, FocusHandle, Focusable,
-    IntoElement, ParentElement, Rems, RenderOnce, SharedString, Styled, Svg, Window,
+    div, prelude::*, AnyElement, App, ElementId, Entity, EntityId, ExternalPaths, FocusHandle,
+    Focusable, IntoElement, ParentElement, Rems, RenderOnce, Role, SharedString, Styled, Svg,
+    Window,
 };
 
-use crate::element_id::for_entity;
-use crate::elements::input::{disabled_display, input};
+use crate::a11y::{A11y, Announce};
+use crate::element_id::{self, for_entity};
+use crate::elements::form;
+use crate::elements::icon_button::icon_button;
+use crate::elements::input::{disabled_display, input, FileDropHandler};
+use crate::icons::Icons;
 use crate::input::InputState;
 use crate::layout::h_stack;
 use crate::theme::{ActiveTheme, ControlSize, Themeable};
+use crate::traits::accessible::Accessible;
 use crate::traits::control_sized::ControlSized;
 use crate::traits::disableable::Disableable;
 
@@ -134,11 +166,22 @@ pub struct TextField {
     read_only: Option<bool>,
     size: ControlSize,
     element_id: Option<ElementId>,
+    /// Whether the state is secure, read when the field was built.
+    secure: bool,
+    /// The state's own placeholder, the name of last resort.
+    state_placeholder: SharedString,
+    /// See [`TextField::reveal_toggle`].
+    reveal_toggle: bool,
+    on_file_drop: Option<FileDropHandler>,
 }
 
 impl TextField {
     /// Creates a new field wrapping the given `InputState`.
     pub fn new(state: &Entity<InputState>, cx: &App) -> Self {
+        let (secure, state_placeholder) = {
+            let state = state.read(cx);
+            (state.is_secure(), state.placeholder().clone())
+        };
         Self {
             state: state.clone(),
             focus_handle: state.focus_handle(cx),
@@ -150,6 +193,10 @@ impl TextField {
             read_only: None,
             size: ControlSize::default(),
             element_id: None,
+            secure,
+            state_placeholder,
+            reveal_toggle: false,
+            on_file_drop: None,
         }
     }
 
@@ -210,6 +257,25 @@ impl TextField {
         self.read_only = Some(read_only);
         self
     }
+
+    /// Draw a button after the text that shows and hides a secure field's
+    /// text, through [`InputState::set_revealed`]. A field over a state that
+    /// is not [secure](InputState::secure), or a disabled one, draws none.
+    pub fn reveal_toggle(mut self, reveal_toggle: bool) -> Self {
+        self.reveal_toggle = reveal_toggle;
+        self
+    }
+
+    /// Call `handler` with files dropped on the field, once the caret has
+    /// moved to where they landed. Without one, file drops are ignored. See
+    /// [`drag`](crate::input::drag).
+    pub fn on_file_drop(
+        mut self,
+        handler: impl Fn(&ExternalPaths, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_file_drop = Some(Rc::new(handler));
+        self
+    }
 }
 
 impl Disableable for TextField {
@@ -238,9 +304,45 @@ impl Focusable for TextField {
     }
 }
 
+impl Accessible for TextField {
+    /// `Role::PasswordInput` over a secure state and `Role::TextInput`
+    /// otherwise, named as the module docs describe. The value is added in
+    /// `render`, which can read the content — and is never added for a
+    /// password.
+    fn a11y(&self) -> A11y {
+        let role = if self.secure {
+            Role::PasswordInput
+        } else {
+            Role::TextInput
+        };
+        let name = form::name_here()
+            .or_else(|| self.placeholder.clone())
+            .or_else(|| Some(self.state_placeholder.clone()).filter(|name| !name.is_empty()));
+        let a11y = match name {
+            Some(name) => A11y::new(role).name(name),
+            None => A11y::new(role),
+        };
+
+        if self.disabled {
+            a11y.not_focusable("a disabled field shows its value as static text, with no caret")
+        } else {
+            a11y.focus_handle(self.focus_handle.clone())
+        }
+    }
+}
+
 impl RenderOnce for TextField {
     fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
         let element_id = self.element_id();
+        let a11y = if self.secure {
+            self.a11y()
+        } else {
+            self.a11y()
+                .text_value(self.state.read(cx).content().to_string())
+        };
+        // Unnamed, a field stays out of the tree rather than announce a role
+        // it cannot name.
+        let a11y = Some(a11y).filter(|a11y| !a11y.is_missing_a_required_name());
 
         // Imposed before anything reads it back, so the chrome and the
         // enforcement in `InputState` cannot disagree within one frame.
@@ -280,8 +382,8 @@ impl RenderOnce for TextField {
             // `InputState`'s read-only support closes every editing path but
             // cannot close focus, so it is the answer to `read_only`, not to
             // `disabled`.
-            let value = self.state.read(cx).content();
-            let (text, is_placeholder) = disabled_display(value, self.placeholder.as_ref());
+            let value = self.state.read(cx).display_text();
+            let (text, is_placeholder) = disabled_display(&value, self.placeholder.as_ref());
             let color = if is_placeholder {
                 theme.input_placeholder()
             } else {
@@ -307,14 +409,40 @@ impl RenderOnce for TextField {
             if let Some(placeholder) = self.placeholder {
                 inner = inner.placeholder(placeholder);
             }
+            if let Some(handler) = self.on_file_drop {
+                inner = inner.on_file_drop(move |paths, window, cx| handler(paths, window, cx));
+            }
 
             inner.into_any_element()
         };
 
+        let reveal = (self.reveal_toggle && self.secure && !disabled).then(|| {
+            let revealed = self.state.read(cx).is_revealed();
+            let state = self.state.clone();
+            icon_button(
+                element_id::scoped(&element_id, "reveal"),
+                if revealed {
+                    Icons::mask_off()
+                } else {
+                    Icons::mask_on()
+                },
+            )
+            .box_size(metrics.ink)
+            .icon_size(metrics.ink)
+            .selected(revealed)
+            .on_click(move |_, _, cx| {
+                state.update(cx, |state, cx| {
+                    let revealed = state.is_revealed();
+                    state.set_revealed(!revealed, cx);
+                });
+            })
+        });
+
         let focus_handle = self.focus_handle.clone();
 
         h_stack()
             .id(element_id)
+            .when_some(a11y, |this, a11y| this.announce(a11y))
             .items_center()
             .h(metrics.height)
             .gap(metrics.gap)
@@ -353,6 +481,9 @@ impl RenderOnce for TextField {
             .when_some(self.suffix, |this, adornment| {
                 this.child(render_adornment(adornment, disabled, metrics.ink, cx))
             })
+            .when_some(reveal, |this, reveal| {
+                this.child(div().flex().flex_none().items_center().child(reveal))
+            })
     }
 }
 
@@ -485,6 +616,81 @@ mod tests {
         });
     }
 
+    /// What a field over `state` announces, drawn in a fresh window.
+    fn announce(
+        cx: &mut TestAppContext,
+        state: &Entity<InputState>,
+        build: impl FnOnce(TextField) -> TextField,
+    ) -> crate::a11y::test_support::Announced {
+        let cx = cx.add_empty_window();
+        cx.update(|window, cx| {
+            let field = build(text_field(state, cx));
+            crate::a11y::test_support::announced(field, window, cx)
+        })
+    }
+
+    #[gpui::test]
+    fn a_field_announces_its_text_as_its_value(cx: &mut TestAppContext) {
+        cx.update(crate::theme::init);
+        let state = cx.new(InputState::new_singleline);
+        state.update(cx, |state, cx| state.set_content("Ada", cx));
+
+        let announced = announce(cx, &state, |field| field.placeholder("Name"));
+
+        assert_eq!(announced.role, Some(Role::TextInput));
+        assert_eq!(announced.name(), Some("Name"));
+        assert_eq!(
+            announced.node.as_ref().and_then(|node| node.value()),
+            Some("Ada")
+        );
+    }
+
+    #[gpui::test]
+    fn a_field_with_no_name_announces_nothing(cx: &mut TestAppContext) {
+        cx.update(crate::theme::init);
+        let state = cx.new(InputState::new_singleline);
+
+        let announced = announce(cx, &state, |field| field);
+
+        assert_eq!(announced.role, None);
+    }
+
+    /// The point of a secure state, stated for the accessibility tree: the
+    /// role says "password", and the secret is not the value.
+    #[gpui::test]
+    fn a_secure_field_announces_a_password_without_its_value(cx: &mut TestAppContext) {
+        cx.update(crate::theme::init);
+        let state = cx.new(|cx| InputState::new_singleline(cx).secure(true));
+        state.update(cx, |state, cx| {
+            state.set_content("hunter2", cx);
+            state.set_placeholder("Password", cx);
+        });
+
+        let announced = announce(cx, &state, |field| field);
+
+        assert_eq!(announced.role, Some(Role::PasswordInput));
+        assert_eq!(announced.name(), Some("Password"));
+        assert_eq!(announced.node.as_ref().and_then(|node| node.value()), None);
+    }
+
+    #[gpui::test]
+    fn a_disabled_field_declines_focus_and_shows_no_secret(cx: &mut TestAppContext) {
+        cx.update(crate::theme::init);
+        let state = cx.new(|cx| InputState::new_singleline(cx).secure(true));
+        state.update(cx, |state, cx| state.set_content("hunter2", cx));
+
+        let a11y = cx.update(|cx| {
+            text_field(&state, cx)
+                .placeholder("Password")
+                .disabled(true)
+                .a11y()
+        });
+        assert!(a11y.focus_declined_because().is_some());
+
+        let shown = state.read_with(cx, |state, _| state.display_text());
+        assert_eq!(shown, "•••••••");
+    }
+
     /// The disabled display falls back from content to placeholder to nothing.
     /// A disabled field renders static text rather than a live `Input`, so
     /// this is the whole of what it shows.
@@ -2,14 +2,21 @@
 //!
 //! A styled wrapper around the `text_area()` element that provides form-friendly
 //! styling with borders, padding, and theme colors.
+//!
+//! A textarea is a fixed number of rows tall unless given
+//! [`max_rows`](Textarea::max_rows): then it grows with its wrapped content
+//! from [`min_rows`](Textarea::min_rows) up to that many, and scrolls past
+//! it — the shape of a chat composer.
+
+use std::rc::Rc;
 
 use gpui::{
-    div, prelude::*, App, ElementId, Entity, EntityId, FocusHandle, Focusable, IntoElement,
-    ParentElement, Pixels, Rems, RenderOnce, SharedString, Styled, Window,
+    div, prelude::*, App, ElementId, Entity, EntityId, ExternalPaths, FocusHandle, Focusable,
+    IntoElement, ParentElement, Pixels, Rems, RenderOnce, SharedString, Styled, Window,
 };
 
 use crate::element_id::for_entity;
-use crate::elements::input::{disabled_display, text_area};
+use crate::elements::input::{disabled_display, text_area, FileDropHandler};
 use crate::input::InputState;
 use crate::theme::{ActiveTheme, ControlSize, Themeable};
 use crate::traits::control_sized::ControlSized;
@@ -43,6 +50,9 @@ fn textarea_element_id(state_id: EntityId) -> ElementId {
 ///     .placeholder("Enter your message...")
 ///     .rows(4)
 ///     .disabled(false)
+///
+/// // Or one line, growing to eight as the message does
+/// textarea(&state, cx).min_rows(1).max_rows(8)
 /// ```
 pub fn textarea(state: &Entity<InputState>, cx: &App) -> Textarea {
     Textarea::new(state, cx)
@@ -57,13 +67,16 @@ pub struct Textarea {
     state: Entity<InputState>,
     focus_handle: FocusHandle,
     placeholder: Option<SharedString>,
-    rows: u32,
+    min_rows: u32,
+    /// `None` for a fixed `min_rows`, rather than growing with the content.
+    max_rows: Option<u32>,
     disabled: bool,
     /// `None` means "say nothing about read-only" — see [`Textarea::read_only`].
     read_only: Option<bool>,
     max_height: Option<Pixels>,
     element_id: Option<ElementId>,
     size: ControlSize,
+    on_file_drop: Option<FileDropHandler>,
 }
 
 impl Textarea {
@@ -75,12 +88,14 @@ impl Textarea {
             state: state.clone(),
             focus_handle: state.focus_handle(cx),
             placeholder: None,
-            rows: DEFAULT_ROWS,
+            min_rows: DEFAULT_ROWS,
+            max_rows: None,
             disabled: false,
             read_only: None,
             max_height: None,
             element_id: None,
             size: ControlSize::default(),
+            on_file_drop: None,
         }
     }
 
@@ -108,11 +123,42 @@ impl Textarea {
         self
     }
 
-    /// Sets the number of visible text rows (affects min-height).
+    /// Sets the number of visible text rows (affects min-height), and stops
+    /// the textarea growing with its content.
     ///
     /// Defaults to 3 rows.
     pub fn rows(mut self, rows: u32) -> Self {
-        self.rows = rows.max(1);
+        self.min_rows = rows.max(1);
+        self.max_rows = None;
+        self
+    }
+
+    /// Sets the fewest rows the textarea shows. Without
+    /// [`max_rows`](Self::max_rows), the same as [`rows`](Self::rows).
+    pub fn min_rows(mut self, rows: u32) -> Self {
+        self.min_rows = rows.max(1);
+        self
+    }
+
+    /// Grows the textarea with its wrapped content, from
+    /// [`min_rows`](Self::min_rows) up to `rows`, past which it scrolls.
+    ///
+    /// The rows are counted as the text wraps at the textarea's width in the
+    /// frame being drawn, so resizing it never shows a frame at the old
+    /// height.
+    pub fn max_rows(mut self, rows: u32) -> Self {
+        self.max_rows = Some(rows);
+        self
+    }
+
+    /// Calls `handler` with files dropped on the textarea, once the caret has
+    /// moved to where they landed. Without one, file drops are ignored. See
+    /// [`drag`](crate::input::drag).
+    pub fn on_file_drop(
+        mut self,
+        handler: impl Fn(&ExternalPaths, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_file_drop = Some(Rc::new(handler));
         self
     }
 
@@ -199,7 +245,7 @@ impl RenderOnce for Textarea {
         // box of text rather than a control with a line in it, so it uses the
         // rung's padding on both axes.
         let padding = metrics.padding_x;
-        let min_height = metrics.multiline_line_height() * self.rows as f32
+        let min_height = metrics.multiline_line_height() * self.min_rows as f32
             + (padding + Rems(BORDER_REMS)) * 2.0;
 
         // Determine colors based on state
@@ -256,14 +302,23 @@ impl RenderOnce for Textarea {
                 .child(text)
                 .into_any_element()
         } else {
+            // Growing, the element's height is left unset for it to measure
+            // its rows; the box then fits round it.
             let mut inner = text_area(&self.state, cx)
                 .control_size(self.size)
-                .size_full()
+                .w_full()
+                .map(|this| match self.max_rows {
+                    Some(max_rows) => this.auto_grow(self.min_rows, max_rows),
+                    None => this.h_full(),
+                })
                 .text_color(text_color);
 
             if let Some(placeholder) = self.placeholder {
                 inner = inner.placeholder(placeholder);
             }
+            if let Some(handler) = self.on_file_drop {
+                inner = inner.on_file_drop(move |paths, window, cx| handler(paths, window, cx));
+            }
 
             inner.into_any_element()
         };
@@ -272,7 +327,7 @@ impl RenderOnce for Textarea {
         div()
             .id(element_id)
             // A fixed height when disabled, for the reason above: static text
-            // grows the box, a live element does not.
+            // grows the box, a live element only when it is growing.
             .map(|this| {
                 if disabled {
                     this.h(min_height)
@@ -306,7 +361,9 @@ impl RenderOnce for Textarea {
 mod tests {
     use super::*;
     use crate::elements::input::tests::focused_input_window;
-    use gpui::TestAppContext;
+    use gpui::{px, TestAppContext, VisualTestContext};
+    use std::cell::Cell;
+    use std::rc::Rc;
 
     /// Draw one textarea, focused, in its own window, and type `keystrokes`
     /// into it. Reports what the state holds afterwards.
@@ -503,4 +560,91 @@ mod tests {
             assert_eq!(state.content(), "xkept");
         });
     }
+
+    /// Draw a growing textarea holding `content` inside a box whose width is
+    /// `width`'s, and report the input's height in rows, as drawn, and the
+    /// rows its text wraps to.
+    fn grown_rows(
+        cx: &mut VisualTestContext,
+        state: &Entity<InputState>,
+        content: &str,
+    ) -> (f32, usize) {
+        let content = content.to_string();
+        state.update(cx, |state, cx| state.set_content(content, cx));
+        cx.run_until_parked();
+        state.read_with(cx, |state, _| {
+            let height = state
+                .last_bounds
+                .expect("the textarea was drawn")
+                .size
+                .height;
+            let wrapped = state
+                .line_layouts
+                .iter()
+                .map(|line| line.visual_line_count)
+                .sum();
+            (height / state.line_height, wrapped)
+        })
+    }
+
+    fn growing_window<'a>(
+        cx: &'a mut TestAppContext,
+        state: &Entity<InputState>,
+        width: Rc<Cell<Pixels>>,
+    ) -> &'a mut VisualTestContext {
+        let for_render = state.clone();
+        focused_input_window(cx, state, move |_window, cx| {
+            div()
+                .w(width.get())
+                .child(textarea(&for_render, cx).min_rows(2).max_rows(4))
+                .into_any_element()
+        })
+    }
+
+    #[gpui::test]
+    fn a_growing_textarea_fits_its_lines_between_its_bounds(cx: &mut TestAppContext) {
+        let state = cx.update(|cx| cx.new(InputState::new_multiline));
+        let cx = growing_window(cx, &state, Rc::new(Cell::new(px(400.))));
+
+        assert_eq!(grown_rows(cx, &state, "one").0, 2.);
+        assert_eq!(grown_rows(cx, &state, "one\ntwo\nthree").0, 3.);
+        // Past the most rows it scrolls, with the cursor in view.
+        let (rows, wrapped) = grown_rows(cx, &state, "1\n2\n3\n4\n5\n6");
+        assert_eq!((rows, wrapped), (4., 6));
+        state.update(cx, |state, cx| {
+            state.set_selected_range(11..11);
+            cx.notify();
+        });
+        cx.run_until_parked();
+        state.read_with(cx, |state, _| {
+            assert_eq!(state.scroll_offset, state.line_height * 2.);
+        });
+    }
+
+    /// The one-frame jump: had the height been counted from the last frame's
+    /// wrap, the frame after a resize would be drawn at the old height.
+    #[gpui::test]
+    fn a_growing_textarea_rewraps_in_the_frame_it_is_resized(cx: &mut TestAppContext) {
+        let state = cx.update(|cx| cx.new(InputState::new_multiline));
+        let width = Rc::new(Cell::new(px(400.)));
+        let cx = growing_window(cx, &state, width.clone());
+
+        let words = "the quick brown fox jumps over the lazy dog ".repeat(2);
+        let (wide, wrapped) = grown_rows(cx, &state, &words);
+        assert_eq!(wide, wrapped as f32);
+
+        width.set(px(200.));
+        cx.update(|window, _cx| window.refresh());
+        cx.run_until_parked();
+        state.read_with(cx, |state, _| {
+            let rows = state.last_bounds.unwrap().size.height / state.line_height;
+            let wrapped: usize = state
+                .line_layouts
+                .iter()
+                .map(|line| line.visual_line_count)
+                .sum();
+            assert!(rows > wide, "narrower should be taller: {rows} vs {wide}");
+            assert_eq!(rows, (wrapped as f32).min(4.));
+        });
+    }
 }
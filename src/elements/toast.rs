@@ -24,11 +24,11 @@
 
 use crate::element_id::{for_entity, scoped};
 use crate::icons::Icons;
-use crate::theme::{ActiveTheme, Themeable};
+use crate::theme::{with_theme, ActiveTheme, Elevation, Radius, Themeable};
 use gpui::{
-    deferred, div, prelude::*, px, rems, AnyElement, App, ClickEvent, Context, ElementId, Entity,
-    Global, Hsla, InteractiveElement, IntoElement, MouseButton, ParentElement, Render,
-    SharedString, StatefulInteractiveElement, Styled, Svg, Window,
+    deferred, div, prelude::*, px, AnyElement, App, ClickEvent, Context, ElementId, Entity, Global,
+    Hsla, InteractiveElement, IntoElement, MouseButton, ParentElement, Render, SharedString,
+    StatefulInteractiveElement, Styled, Svg, Window,
 };
 use std::rc::Rc;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -366,6 +366,7 @@ impl Render for ToastManager {
 
         let position = self.position;
         let toasts: Vec<_> = self.toasts.clone();
+        let shape = cx.theme().shape();
 
         // Build the toast container with proper positioning
         let container = div()
@@ -379,7 +380,7 @@ impl Render for ToastManager {
             .bottom_0()
             .size_full()
             .flex()
-            .p_4()
+            .p(shape.space(4.))
             // Set alignment based on position
             .when(matches!(position, ToastPosition::TopLeft), |d| {
                 d.items_start().justify_start()
@@ -404,7 +405,7 @@ impl Render for ToastManager {
                 div()
                     .flex()
                     .flex_col()
-                    .gap_2()
+                    .gap(shape.space(2.))
                     .w(px(360.))
                     // Reverse order for bottom positions so newest appears closest to corner
                     .when(
@@ -423,7 +424,7 @@ impl Render for ToastManager {
                     ),
             );
 
-        deferred(container)
+        deferred(with_theme(cx.theme().clone(), container))
             .with_priority(15) // Higher than dialogs (10)
             .into_any_element()
     }
@@ -446,6 +447,7 @@ impl ToastManager {
             border_theme_color,
             variant_color,
             border_color,
+            shape,
         ) = {
             let theme = cx.theme();
             let variant = toast.variant;
@@ -456,6 +458,7 @@ impl ToastManager {
                 theme.border(),
                 variant.color(theme.as_ref()),
                 variant.border_color(theme.as_ref()),
+                theme.shape(),
             )
         };
 
@@ -479,13 +482,13 @@ impl ToastManager {
             .id(toast_id)
             .w_full()
             .flex()
-            .gap(rems(0.75))
-            .p(rems(0.75))
+            .gap(shape.space(3.))
+            .p(shape.space(3.))
             .bg(surface_color)
             .border_1()
             .border_color(border_color)
-            .rounded(rems(0.375))
-            .shadow_lg()
+            .rounded(shape.radius(Radius::Medium))
+            .shadow(shape.elevation(Elevation::Overlay))
             // Icon
             .when(matches!(icon_mode, ToastIcon::Default), |toast_div| {
                 toast_div.child(
@@ -503,7 +506,7 @@ impl ToastManager {
                     .flex_1()
                     .flex()
                     .flex_col()
-                    .gap(rems(0.25))
+                    .gap(shape.space(1.))
                     // Title
                     .when_some(title, |content, title| {
                         content.child(
@@ -526,7 +529,7 @@ impl ToastManager {
                     // Action button
                     .when_some(action, |content, (label, handler)| {
                         content.child(
-                            div().mt_1().child(
+                            div().mt(shape.space(1.)).child(
                                 div()
                                     .id(action_id)
                                     .text_sm()
@@ -559,7 +562,7 @@ impl ToastManager {
                     .flex()
                     .items_center()
                     .justify_center()
-                    .rounded(px(4.0))
+                    .rounded(shape.radius(Radius::Small))
                     .cursor_pointer()
                     .hover(move |div| div.bg(hover_bg))
                     .on_mouse_down(MouseButton::Left, |_, window, _| window.prevent_default())
@@ -1,7 +1,7 @@
 //! Toggle button component for gpuikit
 
 use crate::layout::h_stack;
-use crate::theme::{ActiveTheme, ControlSize, Themeable};
+use crate::theme::{ActiveTheme, ControlSize, Elevation, Themeable};
 use crate::traits::control_sized::ControlSized;
 use crate::traits::disableable::Disableable;
 use crate::traits::labelable::Labelable;
@@ -151,7 +151,7 @@ impl Render for Toggle {
                             .size(track.thumb)
                             .bg(thumb_bg)
                             .rounded_full()
-                            .shadow_sm(),
+                            .shadow(theme.elevation(Elevation::Raised)),
                     ),
             )
             .when_some(label, |this, label| {
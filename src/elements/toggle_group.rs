@@ -2,12 +2,12 @@
 //!
 //! A toggle group allows selecting one or multiple options from a group of toggle buttons.
 
-use crate::theme::{ActiveTheme, Themeable};
+use crate::theme::{ActiveTheme, Elevation, Radius, Themeable};
 use crate::traits::disableable::Disableable;
 use crate::traits::orientable::{Orientable, Orientation};
 use gpui::{
-    div, prelude::*, rems, Context, Div, ElementId, EventEmitter, FontWeight, Hsla,
-    InteractiveElement, IntoElement, MouseButton, ParentElement, Render, SharedString, Stateful,
+    div, prelude::*, Context, Div, ElementId, EventEmitter, FontWeight, Hsla, InteractiveElement,
+    IntoElement, MouseButton, ParentElement, Render, SharedString, Stateful,
     StatefulInteractiveElement, Styled, Window,
 };
 
@@ -209,9 +209,9 @@ impl<T: Clone + PartialEq + 'static> Render for ToggleGroup<T> {
             .bg(theme.surface_secondary())
             .border_1()
             .border_color(theme.border())
-            .rounded_md()
-            .p(rems(0.125))
-            .gap(rems(0.125))
+            .rounded(theme.radius(Radius::Medium))
+            .p(theme.space(0.5))
+            .gap(theme.space(0.5))
             .children(
                 self.options
                     .iter()
@@ -247,14 +247,14 @@ impl<T: Clone + PartialEq + 'static> Render for ToggleGroup<T> {
                             .flex()
                             .items_center()
                             .justify_center()
-                            .px(rems(0.75))
-                            .py(rems(0.375))
+                            .px(theme.space(3.))
+                            .py(theme.space(1.5))
                             .text_sm()
                             .font_weight(FontWeight::MEDIUM)
                             .when_some(bg, |this, bg| this.bg(bg))
                             .text_color(text_color)
                             .when(is_selected && !is_disabled, |this: Stateful<Div>| {
-                                this.shadow_sm()
+                                this.shadow(theme.elevation(Elevation::Raised))
                             })
                             // Apply rounded corners based on orientation and position
                             .when(orientation == Orientation::Horizontal, |this| {
@@ -1,8 +1,8 @@
 //! Simple text tooltip component.
 
-use crate::theme::{ActiveTheme, Themeable};
+use crate::theme::{ActiveTheme, Elevation, Radius, Themeable};
 use gpui::{
-    div, px, AnyView, App, AppContext, Context, IntoElement, ParentElement, Render, SharedString,
+    div, AnyView, App, AppContext, Context, Div, IntoElement, ParentElement, Render, SharedString,
     Styled, Window,
 };
 
@@ -25,24 +25,31 @@ impl Tooltip {
 
 impl Render for Tooltip {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<'_, Self>) -> impl IntoElement {
-        let theme = cx.theme();
-
-        div().pl(px(8.)).pt(px(10.)).child(
-            div()
-                .py(px(4.))
-                .px(px(8.))
-                .bg(theme.surface())
-                .border_1()
-                .border_color(theme.border())
-                .rounded(px(4.))
-                .shadow_md()
-                .text_sm()
-                .text_color(theme.fg())
-                .child(self.text.clone()),
-        )
+        tooltip_panel(self.text.clone(), cx)
     }
 }
 
+/// The tooltip's body, for an element that draws its own tooltip rather than
+/// going through `.tooltip()` — one that changes with what is under the
+/// pointer, say.
+pub(crate) fn tooltip_panel(text: SharedString, cx: &App) -> Div {
+    let theme = cx.theme();
+
+    div().pl(theme.space(2.)).pt(theme.space(2.5)).child(
+        div()
+            .py(theme.space(1.))
+            .px(theme.space(2.))
+            .bg(theme.surface())
+            .border_1()
+            .border_color(theme.border())
+            .rounded(theme.radius(Radius::Small))
+            .shadow(theme.elevation(Elevation::Floating))
+            .text_sm()
+            .text_color(theme.fg())
+            .child(text),
+    )
+}
+
 /// Convenience function for creating a text tooltip.
 pub fn tooltip(text: impl Into<SharedString>) -> impl Fn(&mut Window, &mut App) -> AnyView {
     Tooltip::text(text)
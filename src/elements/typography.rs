@@ -1,6 +1,7 @@
 //! Typography components for consistent text styling.
 //!
-//! Provides heading, paragraph, and text components with a consistent typography scale.
+//! Provides heading, paragraph, and text components sized from the theme's
+//! [`TypeScale`](crate::theme::TypeScale).
 //!
 //! # Examples
 //!
@@ -25,17 +26,22 @@
 //! let _ = h1("Title").align(TextAlign::Center).truncate(true);
 //! ```
 
-use crate::theme::{ActiveTheme, Themeable};
+use crate::theme::{ActiveTheme, TextMetrics, TextRole, Themeable};
 use gpui::{
-    div, rems, App, FontWeight, IntoElement, ParentElement, RenderOnce, SharedString, Styled,
-    Window,
+    div, App, Div, FontWeight, IntoElement, ParentElement, RenderOnce, SharedString, Styled, Window,
 };
 
-/// The typescale ratio (minor third).
-const TYPESCALE_RATIO: f32 = 1.2;
-
-/// Base font size in rems.
-const BASE_SIZE: f32 = 1.0;
+/// Size, line box, weight and family from one rung of the type scale.
+fn set_in(el: Div, metrics: TextMetrics) -> Div {
+    let el = el
+        .text_size(metrics.size)
+        .line_height(metrics.line_height)
+        .font_weight(metrics.weight);
+    match metrics.family {
+        Some(family) => el.font_family(family),
+        None => el,
+    }
+}
 
 /// Text alignment options.
 #[derive(Default, Clone, Copy, PartialEq, Eq)]
@@ -137,23 +143,11 @@ impl Heading {
         self.variant = TextVariant::Accent;
         self
     }
-
-    fn font_size(&self) -> f32 {
-        let scale_power = match self.level {
-            1 => 4,
-            2 => 3,
-            3 => 2,
-            4 => 1,
-            _ => 0,
-        };
-        BASE_SIZE * TYPESCALE_RATIO.powi(scale_power)
-    }
 }
 
 impl RenderOnce for Heading {
     fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
         let theme = cx.theme();
-        let size = self.font_size();
 
         let text_color = match self.variant {
             TextVariant::Default => theme.fg(),
@@ -162,11 +156,7 @@ impl RenderOnce for Heading {
             TextVariant::Accent => theme.accent(),
         };
 
-        let mut el = div()
-            .w_full()
-            .text_size(rems(size))
-            .line_height(rems(size * 1.2))
-            .font_weight(FontWeight::BOLD)
+        let mut el = set_in(div().w_full(), theme.text(TextRole::heading(self.level)))
             .text_color(text_color);
 
         el = match self.align {
@@ -260,11 +250,7 @@ impl RenderOnce for Paragraph {
             TextVariant::Accent => theme.accent(),
         };
 
-        let mut el = div()
-            .w_full()
-            .text_size(rems(BASE_SIZE))
-            .line_height(rems(BASE_SIZE * 1.5))
-            .text_color(text_color);
+        let mut el = set_in(div().w_full(), theme.text(TextRole::Body)).text_color(text_color);
 
         el = match self.align {
             TextAlign::Left => el,
@@ -381,27 +367,20 @@ impl RenderOnce for Text {
             TextVariant::Accent => theme.accent(),
         };
 
-        let size = if self.small {
-            BASE_SIZE * 0.875
-        } else if self.code {
-            BASE_SIZE * 0.875
+        let role = if self.code {
+            TextRole::Code
+        } else if self.small {
+            TextRole::Small
         } else {
-            BASE_SIZE
+            TextRole::Body
         };
 
-        let mut el = div()
-            .text_size(rems(size))
-            .line_height(rems(size * 1.5))
-            .text_color(text_color);
+        let mut el = set_in(div(), theme.text(role)).text_color(text_color);
 
         if self.bold {
             el = el.font_weight(FontWeight::BOLD);
         }
 
-        if self.code {
-            el = el.font_family("monospace");
-        }
-
         el = match self.align {
             TextAlign::Left => el,
             TextAlign::Center => el.text_center(),
@@ -473,7 +452,6 @@ impl Lead {
 impl RenderOnce for Lead {
     fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
         let theme = cx.theme();
-        let size = BASE_SIZE * TYPESCALE_RATIO; // One step larger than body
 
         let text_color = match self.variant {
             TextVariant::Default => theme.fg(),
@@ -482,11 +460,7 @@ impl RenderOnce for Lead {
             TextVariant::Accent => theme.accent(),
         };
 
-        let mut el = div()
-            .w_full()
-            .text_size(rems(size))
-            .line_height(rems(size * 1.5))
-            .text_color(text_color);
+        let mut el = set_in(div().w_full(), theme.text(TextRole::Lead)).text_color(text_color);
 
         el = match self.align {
             TextAlign::Left => el,
@@ -561,7 +535,6 @@ impl Small {
 impl RenderOnce for Small {
     fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
         let theme = cx.theme();
-        let size = BASE_SIZE * 0.875; // Smaller than body
 
         let text_color = match self.variant {
             TextVariant::Default => theme.fg(),
@@ -570,10 +543,7 @@ impl RenderOnce for Small {
             TextVariant::Accent => theme.accent(),
         };
 
-        let mut el = div()
-            .text_size(rems(size))
-            .line_height(rems(size * 1.5))
-            .text_color(text_color);
+        let mut el = set_in(div(), theme.text(TextRole::Small)).text_color(text_color);
 
         el = match self.align {
             TextAlign::Left => el,
@@ -625,15 +595,13 @@ impl RenderOnce for Blockquote {
     fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
         let theme = cx.theme();
 
-        let mut el = div()
-            .w_full()
-            .pl(rems(1.0))
-            .border_l_2()
-            .border_color(theme.border())
-            .text_size(rems(BASE_SIZE))
-            .line_height(rems(BASE_SIZE * 1.5))
-            .text_color(theme.fg_muted())
-            .italic();
+        let mut el = set_in(
+            div().w_full().pl(theme.space(4.)).border_l_2(),
+            theme.text(TextRole::Body),
+        )
+        .border_color(theme.border())
+        .text_color(theme.fg_muted())
+        .italic();
 
         el = match self.align {
             TextAlign::Left => el,
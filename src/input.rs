@@ -8,31 +8,80 @@
 //! - [`CursorBlink`]: Manages cursor blinking state for input components.
 //! - [`InputBindings`]: Configurable keybindings for input actions.
 //! - [`TextDirection`]: Bidirectional text support for RTL/LTR detection.
+//! - [`InputMask`]: Masks and formatters — phone numbers, card numbers,
+//!   one-time codes — that hold the content to a fixed shape.
+//! - [`validation`]: a length limit, a character filter and a hook that sees
+//!   each edit before it is applied.
+//! - [`completion`]: inline suggestions, drawn after the cursor and taken with
+//!   Tab.
+//! - [`selections`]: several carets and selections at once in a multiline
+//!   input, each edited together.
+//! - [`history`]: undo history that can be saved with a draft and restored,
+//!   and kept as a tree so that undoing and editing loses nothing.
+//! - [`search`]: find and replace — literal, ignoring case, or by regular
+//!   expression — with every match painted.
+//! - [`decorations`]: squiggly, dotted and straight underlines over ranges of
+//!   the text, for misspellings and lint warnings, with a pluggable checker.
+//! - [`drag`]: dragging a selection to move or copy it, within an input or
+//!   into another, and dropping text and files from the rest of the app.
+//! - [`tokens`]: `@mention` and `#channel` chips that the caret steps over and
+//!   Backspace removes whole, started by trigger characters.
+//! - [`kill_ring`]: the text readline's kill keys take, shared by every
+//!   input, for yanking back — with [`InputBindings::readline`] and
+//!   [`InputBindings::vim`], presets of readline's and vim's keys.
+//! - [`recall`]: shell-style history for single-line fields, stepped through
+//!   with Up and Down and filtered by what has been typed.
+//! - [`attributes`]: bold, italic, code and link spans over the text, toggled
+//!   over the selection and read and written as markdown.
 //!
 //! # Example
 //!
 //! ```ignore
 //! use gpui::Context;
-//! use gpuikit::input::{InputState, bind_input_keys};
+//! use gpuikit::input::InputState;
 //!
-//! // Initialize keybindings (typically in app initialization)
-//! bind_input_keys(cx, None);
+//! // Keybindings are registered by `gpuikit::init` (typically in app
+//! // initialization); `gpuikit::init_with_input_bindings` chooses others
+//! gpuikit::init(cx);
 //!
 //! // Create an input state
 //! let input = cx.new(|cx| InputState::new_singleline(cx));
 //! ```
 
+pub mod attributes;
 mod bidi;
 /// Input keybinding configuration & actions that can be bound (`Backspace`, `Copy`, etc.).
 ///
 /// Explicitly not exported using `pub use bindings::*` to avoid namespace pollution.
 pub mod bindings;
 mod blink;
+pub mod completion;
+pub mod decorations;
+pub mod drag;
 mod handler;
+pub mod history;
+pub mod kill_ring;
+pub mod mask;
+pub mod recall;
+pub mod search;
+pub mod selections;
 mod state;
+pub mod tokens;
+pub mod validation;
 
+pub use attributes::{Attribute, AttributeSpan};
 pub use bidi::{detect_base_direction, TextDirection};
 pub use bindings::{bind_input_keys, InputBindings, INPUT_CONTEXT};
 pub use blink::CursorBlink;
+pub use completion::{CompletionProvider, PrefixCompletions, SharedCompletionProvider};
+pub use decorations::{Checker, Decoration, DecorationStyle, SharedChecker, WordListChecker};
+pub use drag::DraggedText;
 pub use handler::*;
-pub use state::{InputLineLayout, InputState, InputStateEvent, SubmitOn};
+pub use history::{HistoryId, HistoryState, SavedHistory};
+pub use mask::{CreditCardMask, InputMask, Ipv4Mask, Masked, PatternMask};
+pub use recall::RecallHistory;
+pub use search::{InvalidPattern, SearchMode, SearchQuery};
+pub use selections::Selection;
+pub use state::{InputLineLayout, InputState, InputStateEvent, SubmitOn, VimMode};
+pub use tokens::{Token, TokenizedText, TriggerQuery};
+pub use validation::{Edit, Rejection, Validation, Validator};
@@ -0,0 +1,707 @@
+//! Attributed text: bold, italic, code and link spans over an input's
+//! content, for a composer that writes a little markdown without showing it.
+//!
+//! An [`InputState`](super::InputState) made with
+//! [`attributed(true)`](super::InputState::attributed) keeps a list of
+//! [`AttributeSpan`]s alongside its text, and `elements::input` draws each
+//! one as the markdown renderer draws the same span: bold, italic, code on a
+//! wash, links in the link colour and underlined. While it is on:
+//!
+//! - [`ToggleBold`](super::bindings::ToggleBold) and
+//!   [`ToggleItalic`](super::bindings::ToggleItalic) — `cmd-b` and `cmd-i` —
+//!   and [`ToggleCode`](super::bindings::ToggleCode), unbound, take the
+//!   attribute off the selection if all of it has it, and put it on
+//!   otherwise. With no selection they change what the next characters typed
+//!   at the caret get.
+//! - spans follow the text. Typing inside one extends it, and so does typing
+//!   at the end of a bold or italic one — but not of code or a link, which
+//!   end where they end. Deleting all of a span's text removes it.
+//! - each toggle is an undo step of its own, and undoing an edit puts back
+//!   the spans its text had.
+//!
+//! A [secure](super::InputState::secure) or [masked](super::InputState::mask)
+//! input is never attributed.
+//!
+//! # Markdown
+//!
+//! [`markdown`](super::InputState::markdown) writes the content out as
+//! markdown — `**bold**`, `*italic*`, `` `code` ``, `[text](url)` — escaping
+//! whatever would otherwise be read as markup, and
+//! [`set_markdown`](super::InputState::set_markdown) reads it back with the
+//! crate's [`markdown`] parser. Only inline markdown has
+//! somewhere to go: a heading, list or quote keeps its text and loses its
+//! shape, strikethrough is dropped, and spaces at the start of a line are
+//! markdown's to drop. [`rich_text`](super::InputState::rich_text) and
+//! [`set_rich_text`](super::InputState::set_rich_text) go through the
+//! [`RichText`] the markdown elements draw from instead.
+
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::markdown::{self, InlineStyle, RichText};
+
+/// A style an [`AttributeSpan`] gives its text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Attribute {
+    /// Bold, `**like this**`.
+    Bold,
+    /// Italic, `*like this*`.
+    Italic,
+    /// Inline code, `` `like this` ``.
+    Code,
+    /// A link to the URL it holds, `[like this](url)`.
+    Link(String),
+}
+
+impl Attribute {
+    /// Whether text typed at the end of a span of it takes it too.
+    fn extends(&self) -> bool {
+        matches!(self, Attribute::Bold | Attribute::Italic)
+    }
+
+    /// Where it goes among markdown's delimiters, outermost first: a link
+    /// can hold emphasis, and code can hold nothing.
+    fn nesting(&self) -> u8 {
+        match self {
+            Attribute::Link(_) => 0,
+            Attribute::Bold => 1,
+            Attribute::Italic => 2,
+            Attribute::Code => 3,
+        }
+    }
+
+    /// Whether two attributes are the same style, links to different URLs
+    /// included — at most one of each covers any character.
+    fn same_kind(&self, other: &Attribute) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// A range of an input's text with an [`Attribute`]. See the
+/// [module docs](self).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AttributeSpan {
+    /// The byte range of the content it covers. Never empty once given to an
+    /// input.
+    pub range: Range<usize>,
+    /// What it does to that text.
+    pub attribute: Attribute,
+}
+
+impl AttributeSpan {
+    /// `attribute` over `range`.
+    pub fn new(range: Range<usize>, attribute: Attribute) -> Self {
+        Self { range, attribute }
+    }
+}
+
+/// Sorts `spans` by where they start, after merging those of the same
+/// attribute that touch or overlap and dropping empty ones.
+pub(crate) fn normalize(spans: &mut Vec<AttributeSpan>) {
+    spans.retain(|span| span.range.start < span.range.end);
+    spans.sort_by(|a, b| (&a.attribute, a.range.start).cmp(&(&b.attribute, b.range.start)));
+    let mut merged: Vec<AttributeSpan> = Vec::with_capacity(spans.len());
+    for span in spans.drain(..) {
+        match merged.last_mut() {
+            Some(last)
+                if last.attribute == span.attribute && span.range.start <= last.range.end =>
+            {
+                last.range.end = last.range.end.max(span.range.end);
+            }
+            _ => merged.push(span),
+        }
+    }
+    merged.sort_by(|a, b| (a.range.start, &a.attribute).cmp(&(b.range.start, &b.attribute)));
+    *spans = merged;
+}
+
+/// Keeps the spans that fit `content` — non-empty, inside it, on character
+/// boundaries — normalized.
+pub(crate) fn sanitize(mut spans: Vec<AttributeSpan>, content: &str) -> Vec<AttributeSpan> {
+    spans.retain(|span| {
+        let range = &span.range;
+        range.start < range.end
+            && range.end <= content.len()
+            && content.is_char_boundary(range.start)
+            && content.is_char_boundary(range.end)
+    });
+    normalize(&mut spans);
+    spans
+}
+
+/// Moves `spans` for `range` of the text having been typed over with
+/// `new_len` bytes: those after it along, and those it lands in over the new
+/// text. One the edit covers entirely goes.
+pub(crate) fn shift(spans: &mut Vec<AttributeSpan>, range: &Range<usize>, new_len: usize) {
+    let moved = |offset: usize| offset - range.len() + new_len;
+    for span in spans.iter_mut() {
+        let own = &mut span.range;
+        own.start = if own.start < range.start {
+            own.start
+        } else if own.start >= range.end {
+            moved(own.start)
+        } else {
+            range.start
+        };
+        let typed_at_end = own.end == range.start && range.is_empty() && span.attribute.extends();
+        own.end = if own.end < range.start || (own.end == range.start && !typed_at_end) {
+            own.end
+        } else if own.end >= range.end {
+            moved(own.end)
+        } else {
+            range.start + new_len
+        };
+    }
+    spans.retain(|span| span.range.start < span.range.end);
+}
+
+/// Moves `spans` for `range` of the text having been put back as it was,
+/// `new_len` bytes long: what covered `range` is cut away, for the spans that
+/// went with the old text to be [restored](restore).
+pub(crate) fn cut(spans: &mut Vec<AttributeSpan>, range: &Range<usize>, new_len: usize) {
+    let moved = |offset: usize| offset - range.len() + new_len;
+    let mut kept = Vec::with_capacity(spans.len());
+    for span in spans.drain(..) {
+        let own = &span.range;
+        if own.start < range.start {
+            kept.push(AttributeSpan::new(
+                own.start..own.end.min(range.start),
+                span.attribute.clone(),
+            ));
+        }
+        if own.end > range.end {
+            kept.push(AttributeSpan::new(
+                moved(own.start.max(range.end))..moved(own.end),
+                span.attribute,
+            ));
+        }
+    }
+    normalize(&mut kept);
+    *spans = kept;
+}
+
+/// The parts of `spans` inside `range`, relative to its start — what an edit
+/// of `range` is about to take with its text.
+pub(crate) fn within(spans: &[AttributeSpan], range: &Range<usize>) -> Vec<AttributeSpan> {
+    spans
+        .iter()
+        .filter(|span| span.range.start < range.end && span.range.end > range.start)
+        .map(|span| {
+            let start = span.range.start.max(range.start) - range.start;
+            let end = span.range.end.min(range.end) - range.start;
+            AttributeSpan::new(start..end, span.attribute.clone())
+        })
+        .filter(|span| span.range.start < span.range.end)
+        .collect()
+}
+
+/// Puts back spans taken with [`within`], for text put back at `at`.
+pub(crate) fn restore(spans: &mut Vec<AttributeSpan>, taken: &[AttributeSpan], at: usize) {
+    spans.extend(taken.iter().map(|span| {
+        AttributeSpan::new(
+            at + span.range.start..at + span.range.end,
+            span.attribute.clone(),
+        )
+    }));
+    normalize(spans);
+}
+
+/// Whether every byte of a non-empty `range` has `attribute`.
+pub(crate) fn covers(spans: &[AttributeSpan], range: &Range<usize>, attribute: &Attribute) -> bool {
+    !range.is_empty()
+        && spans.iter().any(|span| {
+            span.attribute == *attribute
+                && span.range.start <= range.start
+                && range.end <= span.range.end
+        })
+}
+
+/// Takes the attributes `which` picks off `range`, splitting the spans that
+/// run past it.
+pub(crate) fn remove(
+    spans: &mut Vec<AttributeSpan>,
+    range: &Range<usize>,
+    which: impl Fn(&Attribute) -> bool,
+) {
+    let mut kept = Vec::with_capacity(spans.len());
+    for span in spans.drain(..) {
+        let own = &span.range;
+        if !which(&span.attribute) || own.end <= range.start || own.start >= range.end {
+            kept.push(span);
+            continue;
+        }
+        if own.start < range.start {
+            kept.push(AttributeSpan::new(
+                own.start..range.start,
+                span.attribute.clone(),
+            ));
+        }
+        if own.end > range.end {
+            kept.push(AttributeSpan::new(range.end..own.end, span.attribute));
+        }
+    }
+    normalize(&mut kept);
+    *spans = kept;
+}
+
+/// Gives `range` `attribute`, in place of any other link there for a link.
+pub(crate) fn add(spans: &mut Vec<AttributeSpan>, range: &Range<usize>, attribute: Attribute) {
+    if matches!(attribute, Attribute::Link(_)) {
+        remove(spans, range, |other| other.same_kind(&attribute));
+    }
+    spans.push(AttributeSpan::new(range.clone(), attribute));
+    normalize(spans);
+}
+
+/// What text typed at `offset` would get: the attributes of the spans it
+/// would land inside, or at the end of, for those that extend.
+pub(crate) fn at(spans: &[AttributeSpan], offset: usize) -> Vec<Attribute> {
+    let mut attributes: Vec<Attribute> = spans
+        .iter()
+        .filter(|span| {
+            span.range.start < offset
+                && (offset < span.range.end
+                    || (offset == span.range.end && span.attribute.extends()))
+        })
+        .map(|span| span.attribute.clone())
+        .collect();
+    attributes.sort();
+    attributes.dedup();
+    attributes
+}
+
+/// Turns `attribute` off in what typing at a caret would get if it is
+/// there, and on otherwise — in place of another link, for a link.
+pub(crate) fn toggle(typing: &mut Vec<Attribute>, attribute: Attribute) {
+    if let Some(index) = typing.iter().position(|other| *other == attribute) {
+        typing.remove(index);
+        return;
+    }
+    typing.retain(|other| !other.same_kind(&attribute));
+    typing.push(attribute);
+    typing.sort();
+}
+
+/// `0..len` cut where any span starts or ends, each piece with the
+/// attributes over it, outermost first.
+pub(crate) fn segments(
+    spans: &[AttributeSpan],
+    len: usize,
+) -> Vec<(Range<usize>, Vec<&Attribute>)> {
+    let mut edges: Vec<usize> = spans
+        .iter()
+        .flat_map(|span| [span.range.start.min(len), span.range.end.min(len)])
+        .chain([0, len])
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+    edges
+        .windows(2)
+        .map(|edge| {
+            let piece = edge[0]..edge[1];
+            let mut over: Vec<&Attribute> = spans
+                .iter()
+                .filter(|span| span.range.start <= piece.start && piece.end <= span.range.end)
+                .map(|span| &span.attribute)
+                .collect();
+            over.sort_by_key(|attribute| attribute.nesting());
+            (piece, over)
+        })
+        .collect()
+}
+
+/// `content` and its spans as the markdown elements' [`RichText`], each link
+/// span its own entry in the URL table.
+pub(crate) fn to_rich_text(content: &str, spans: &[AttributeSpan]) -> RichText {
+    let mut rich = RichText::new();
+    let links: Vec<(Range<usize>, u32)> = spans
+        .iter()
+        .filter_map(|span| match &span.attribute {
+            Attribute::Link(url) => Some((span.range.clone(), rich.add_link(url.clone()))),
+            _ => None,
+        })
+        .collect();
+    for (piece, over) in segments(spans, content.len()) {
+        let mut style = InlineStyle::new();
+        for attribute in over {
+            match attribute {
+                Attribute::Bold => style.bold = true,
+                Attribute::Italic => style.italic = true,
+                Attribute::Code => style.code = true,
+                Attribute::Link(_) => {
+                    style.link = links
+                        .iter()
+                        .find(|(range, _)| range.start <= piece.start && piece.end <= range.end)
+                        .map(|(_, index)| *index);
+                }
+            }
+        }
+        rich.push(&content[piece], style);
+    }
+    rich
+}
+
+/// The text of `rich`, and its bold, italic, code and link spans.
+/// Strikethrough has no attribute, and is dropped.
+pub(crate) fn from_rich_text(rich: &RichText) -> (String, Vec<AttributeSpan>) {
+    let mut text = String::new();
+    let mut spans = Vec::new();
+    for span in rich.spans() {
+        let range = text.len()..text.len() + span.text.len();
+        text.push_str(&span.text);
+        let style = span.style;
+        let link = style
+            .link
+            .and_then(|index| rich.links().get(index as usize))
+            .map(|url| Attribute::Link(url.to_string()));
+        let attributes = [
+            style.bold.then_some(Attribute::Bold),
+            style.italic.then_some(Attribute::Italic),
+            style.code.then_some(Attribute::Code),
+            link,
+        ];
+        spans.extend(
+            attributes
+                .into_iter()
+                .flatten()
+                .map(|attribute| AttributeSpan::new(range.clone(), attribute)),
+        );
+    }
+    normalize(&mut spans);
+    (text, spans)
+}
+
+/// Reads `source` as an attributed input would hold it: the inline styles of
+/// each block, the blocks a blank line apart, and every line break a newline.
+pub(crate) fn parse_markdown(source: &str) -> RichText {
+    use pulldown_cmark::{Event, Tag, TagEnd};
+
+    let mut rich = RichText::new();
+    let mut style = InlineStyle::new();
+    // A block has ended since the last text, so the next starts a new one.
+    let mut block_ended = false;
+    fn push(rich: &mut RichText, text: &str, style: InlineStyle, block_ended: &mut bool) {
+        if std::mem::take(block_ended) && !rich.is_empty() {
+            rich.push_plain("\n\n");
+        }
+        rich.push(text, style);
+    }
+    for event in markdown::parse(source) {
+        match event {
+            Event::Start(Tag::Strong) => style.bold = true,
+            Event::End(TagEnd::Strong) => style.bold = false,
+            Event::Start(Tag::Emphasis) => style.italic = true,
+            Event::End(TagEnd::Emphasis) => style.italic = false,
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                style.link = Some(rich.add_link(dest_url.to_string()));
+            }
+            Event::End(TagEnd::Link) => style.link = None,
+            Event::Text(text) => push(&mut rich, &text, style, &mut block_ended),
+            Event::Code(code) => {
+                let mut code_style = style;
+                code_style.code = true;
+                push(&mut rich, &code, code_style, &mut block_ended);
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                push(&mut rich, "\n", InlineStyle::new(), &mut block_ended)
+            }
+            Event::End(
+                TagEnd::Paragraph
+                | TagEnd::Heading(_)
+                | TagEnd::CodeBlock
+                | TagEnd::Item
+                | TagEnd::TableCell,
+            ) => block_ended = true,
+            _ => {}
+        }
+    }
+    rich
+}
+
+/// `content` and its spans written as markdown, for [`parse_markdown`] to
+/// read back. A newline is a hard break, two are a new paragraph.
+pub(crate) fn to_markdown(content: &str, spans: &[AttributeSpan]) -> String {
+    let mut out = String::new();
+    let mut rest = content;
+    let mut offset = 0;
+    loop {
+        let line_end = rest.find('\n').unwrap_or(rest.len());
+        let line = offset..offset + line_end;
+        write_line(&mut out, content, &within(spans, &line), line.start);
+        if line_end == rest.len() {
+            break;
+        }
+        let newlines = rest[line_end..]
+            .chars()
+            .take_while(|ch| *ch == '\n')
+            .count();
+        out.push_str(if newlines == 1 { "\\\n" } else { "\n\n" });
+        for _ in 2..newlines {
+            out.push_str("\\\n");
+        }
+        offset += line_end + newlines;
+        rest = &content[offset..];
+    }
+    out
+}
+
+/// Writes one line of `content`, starting at `at`, with `spans` relative to
+/// it. Every delimiter opened on the line is closed on it.
+fn write_line(out: &mut String, content: &str, spans: &[AttributeSpan], at: usize) {
+    let line_len = content[at..].find('\n').unwrap_or(content.len() - at);
+    let line = &content[at..at + line_len];
+    let mut open: Vec<&Attribute> = Vec::new();
+    for (piece, over) in segments(spans, line.len()) {
+        let text = &line[piece.clone()];
+        // Close from the innermost out, down to the first that has ended.
+        let keep = open
+            .iter()
+            .zip(&over)
+            .take_while(|(open, over)| open == over)
+            .count();
+        close(out, &mut open, keep);
+        // Whitespace stays outside emphasis, where markdown can see it open.
+        let opening = &over[open.len()..];
+        let body = if opening
+            .iter()
+            .any(|attribute| **attribute != Attribute::Code)
+        {
+            let trimmed = text.trim_start();
+            out.push_str(&text[..text.len() - trimmed.len()]);
+            trimmed
+        } else {
+            text
+        };
+        for attribute in opening {
+            out.push_str(&opener(attribute, body));
+            open.push(attribute);
+        }
+        if open.last() == Some(&&Attribute::Code) {
+            // Closed after each piece, so that its fence is sized for what
+            // it holds.
+            out.push_str(body);
+            open.pop();
+            out.push_str(&closer(&Attribute::Code, body));
+        } else {
+            escape(out, body, piece.start == 0);
+        }
+    }
+    close(out, &mut open, 0);
+}
+
+/// Closes `open` down to its first `keep`, innermost first, leaving trailing
+/// whitespace after the delimiters.
+fn close(out: &mut String, open: &mut Vec<&Attribute>, keep: usize) {
+    if open.len() <= keep {
+        return;
+    }
+    let trimmed = out.trim_end_matches([' ', '\t']).len();
+    let trailing = out.split_off(trimmed);
+    while open.len() > keep {
+        let attribute = open.pop().expect("longer than keep");
+        out.push_str(&closer(attribute, ""));
+    }
+    out.push_str(&trailing);
+}
+
+/// What opens `attribute`, around `text` for code.
+fn opener(attribute: &Attribute, text: &str) -> String {
+    match attribute {
+        Attribute::Bold => "**".into(),
+        Attribute::Italic => "*".into(),
+        Attribute::Link(_) => "[".into(),
+        Attribute::Code => {
+            let (fence, pad) = code_fence(text);
+            format!("{fence}{pad}")
+        }
+    }
+}
+
+/// What closes `attribute`, around `text` for code.
+fn closer(attribute: &Attribute, text: &str) -> String {
+    match attribute {
+        Attribute::Bold => "**".into(),
+        Attribute::Italic => "*".into(),
+        Attribute::Link(url) if url.contains([' ', '(', ')', '<', '>']) => {
+            format!("](<{}>)", url.replace('<', "%3C").replace('>', "%3E"))
+        }
+        Attribute::Link(url) => format!("]({url})"),
+        Attribute::Code => {
+            let (fence, pad) = code_fence(text);
+            format!("{pad}{fence}")
+        }
+    }
+}
+
+/// A run of backticks longer than any in `text`, and the space that keeps
+/// `text` from running into it if it starts or ends with one.
+fn code_fence(text: &str) -> (String, &'static str) {
+    let longest = text.split(|ch| ch != '`').map(str::len).max().unwrap_or(0);
+    let pad = if text.starts_with('`') || text.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    ("`".repeat(longest + 1), pad)
+}
+
+/// Writes `text` with a backslash before whatever markdown would read as
+/// markup — anywhere for inline syntax, and at the start of a line for block
+/// syntax.
+fn escape(out: &mut String, text: &str, line_start: bool) {
+    let mut at_start = line_start;
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        let block_marker = at_start && matches!(ch, '#' | '>' | '-' | '+' | '=');
+        let ordinal = ch.is_ascii_digit() && at_start && matches!(chars.peek(), Some('.' | ')'));
+        if block_marker || matches!(ch, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '~' | '&') {
+            out.push('\\');
+        }
+        out.push(ch);
+        if ordinal {
+            // `1.` starts a list; `1\.` does not.
+            while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                out.push(digit);
+            }
+            if let Some(mark) = chars.next() {
+                out.push('\\');
+                out.push(mark);
+            }
+        }
+        at_start = at_start && ch == ' ';
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bold(range: Range<usize>) -> AttributeSpan {
+        AttributeSpan::new(range, Attribute::Bold)
+    }
+
+    fn italic(range: Range<usize>) -> AttributeSpan {
+        AttributeSpan::new(range, Attribute::Italic)
+    }
+
+    fn code(range: Range<usize>) -> AttributeSpan {
+        AttributeSpan::new(range, Attribute::Code)
+    }
+
+    fn link(range: Range<usize>, url: &str) -> AttributeSpan {
+        AttributeSpan::new(range, Attribute::Link(url.into()))
+    }
+
+    #[test]
+    fn typing_extends_emphasis_but_not_code_or_links() {
+        let mut spans = vec![bold(0..4), code(5..9), link(10..14, "u")];
+        // At the end of each.
+        shift(&mut spans, &(14..14), 1);
+        shift(&mut spans, &(9..9), 1);
+        shift(&mut spans, &(4..4), 1);
+        assert_eq!(spans, [bold(0..5), code(6..10), link(12..16, "u")]);
+        // Inside one, and before one.
+        shift(&mut spans, &(7..7), 2);
+        shift(&mut spans, &(0..0), 1);
+        assert_eq!(spans, [bold(1..6), code(7..13), link(15..19, "u")]);
+    }
+
+    #[test]
+    fn deleting_shortens_spans_and_removes_covered_ones() {
+        let mut spans = vec![bold(0..4), italic(4..8), code(9..11)];
+        shift(&mut spans, &(3..10), 0);
+        assert_eq!(spans, [bold(0..3), code(3..4)]);
+    }
+
+    #[test]
+    fn undo_cuts_what_covered_the_text_and_restores_what_went_with_it() {
+        let spans = vec![bold(0..10), italic(4..6)];
+        let taken = within(&spans, &(3..5));
+        assert_eq!(taken, [bold(0..2), italic(1..2)]);
+
+        let mut after = spans.clone();
+        cut(&mut after, &(3..5), 2);
+        assert_eq!(after, [bold(0..3), bold(5..10), italic(5..6)]);
+        restore(&mut after, &taken, 3);
+        assert_eq!(after, spans);
+    }
+
+    #[test]
+    fn adding_and_removing_merge_and_split() {
+        let mut spans = vec![bold(0..3), bold(6..9)];
+        add(&mut spans, &(2..7), Attribute::Bold);
+        assert_eq!(spans, [bold(0..9)]);
+        assert!(covers(&spans, &(1..8), &Attribute::Bold));
+
+        remove(&mut spans, &(3..5), |attribute| {
+            *attribute == Attribute::Bold
+        });
+        assert_eq!(spans, [bold(0..3), bold(5..9)]);
+        assert!(!covers(&spans, &(1..8), &Attribute::Bold));
+
+        // A link replaces the one it lands on.
+        let mut spans = vec![link(0..6, "a")];
+        add(&mut spans, &(2..4), Attribute::Link("b".into()));
+        assert_eq!(spans, [link(0..2, "a"), link(2..4, "b"), link(4..6, "a")]);
+    }
+
+    #[test]
+    fn what_typing_at_an_offset_would_get() {
+        let spans = vec![bold(0..4), code(2..4), link(4..6, "u")];
+        assert_eq!(at(&spans, 3), [Attribute::Bold, Attribute::Code]);
+        assert_eq!(at(&spans, 4), [Attribute::Bold]);
+        assert_eq!(at(&spans, 0), []);
+    }
+
+    #[test]
+    fn markdown_round_trips() {
+        let content = "bold, italic and `code` at https://x\nsecond line\n\nnew *para*";
+        let spans = vec![
+            bold(0..4),
+            italic(6..12),
+            bold(6..12),
+            code(17..23),
+            link(27..36, "https://x.example/a b"),
+            bold(44..48),
+        ];
+        let markdown = to_markdown(content, &spans);
+        assert_eq!(
+            markdown,
+            "**bold**, ***italic*** and `` `code` `` at [https://x](<https://x.example/a b>)\\\n\
+             second **line**\n\nnew \\*para\\*"
+        );
+
+        let (text, read) = from_rich_text(&parse_markdown(&markdown));
+        assert_eq!(text, content);
+        assert_eq!(read, sanitize(spans, content));
+    }
+
+    #[test]
+    fn markup_typed_as_text_is_escaped() {
+        let content = "# not a heading\n1. not a list\n- nor this <b>";
+        let markdown = to_markdown(content, &[]);
+        let (text, spans) = from_rich_text(&parse_markdown(&markdown));
+        assert_eq!(text, content);
+        assert!(spans.is_empty());
+    }
+
+    #[test]
+    fn whitespace_stays_outside_delimiters() {
+        let content = "a bold  b";
+        assert_eq!(to_markdown(content, &[bold(1..8)]), "a **bold**  b");
+        let (text, spans) = from_rich_text(&parse_markdown("a **bold**  b"));
+        assert_eq!(text, content);
+        assert_eq!(spans, [bold(2..6)]);
+    }
+
+    #[test]
+    fn rich_text_carries_links_by_index() {
+        let content = "see here";
+        let spans = vec![link(4..8, "https://a.example"), bold(0..3)];
+        let rich = to_rich_text(content, &spans);
+        assert_eq!(rich.to_plain_text(), content);
+        assert_eq!(rich.link_ranges()[0].0, 4..8);
+        assert_eq!(from_rich_text(&rich).1, sanitize(spans, content));
+    }
+}
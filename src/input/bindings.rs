@@ -84,12 +84,61 @@ actions!(
         Undo,
         /// Redo the last undone edit.
         Redo,
+        /// Show the next of an input's suggested completions.
+        NextCompletion,
+        /// Show the previous of an input's suggested completions.
+        PreviousCompletion,
+        /// Select the word at the cursor, or add the next occurrence of the
+        /// selected text as another selection.
+        SelectNextOccurrence,
+        /// Select the next match of the input's search.
+        FindNext,
+        /// Select the previous match of the input's search.
+        FindPrevious,
+        /// Toggle bold over the selection of an attributed input.
+        ToggleBold,
+        /// Toggle italic over the selection of an attributed input.
+        ToggleItalic,
+        /// Toggle inline code over the selection of an attributed input.
+        ToggleCode,
+        /// Delete from the cursor to the end of the line, or the newline
+        /// there if the cursor is already at the end, into the kill ring.
+        KillToEndOfLine,
+        /// Delete from the cursor to the beginning of the line into the kill
+        /// ring.
+        KillToBeginningOfLine,
+        /// Delete the word before the cursor into the kill ring.
+        KillWordLeft,
+        /// Delete the word after the cursor into the kill ring.
+        KillWordRight,
+        /// Insert the last text killed at the cursor.
+        Yank,
+        /// Replace the text just yanked with the kill before it.
+        YankPop,
+        /// Swap the characters either side of the cursor, or the two before
+        /// it at the end of a line, and move past them.
+        Transpose,
+        /// Leave vim's insert mode for normal mode.
+        EnterNormalMode,
+        /// Leave vim's normal mode to insert at the cursor.
+        EnterInsertMode,
+        /// Leave vim's normal mode to insert after the cursor.
+        EnterInsertModeAfter,
     ]
 );
 
 /// The key context used for input element keybindings.
 pub const INPUT_CONTEXT: &str = "Input";
 
+/// The key context of an input in vim's normal mode, as the
+/// [vim preset](InputBindings::vim) binds it. An input element's context
+/// always carries `vim_mode`, set to `normal` or `insert`.
+pub const VIM_NORMAL_CONTEXT: &str = "Input && vim_mode == normal";
+
+/// The key context of an input in vim's insert mode — every input not in
+/// normal mode.
+pub const VIM_INSERT_CONTEXT: &str = "Input && vim_mode == insert";
+
 /// Keybindings configuration for input elements.
 ///
 /// Each field is an `Option<KeyBinding>` to allow:
@@ -241,6 +290,87 @@ pub struct InputBindings {
     /// Binding for blurring focus from the input.
     /// Default: `escape`
     pub escape: Option<KeyBinding>,
+
+    /// Binding for showing the next suggested completion.
+    /// Default: `alt-]`
+    pub next_completion: Option<KeyBinding>,
+
+    /// Binding for showing the previous suggested completion.
+    /// Default: `alt-[`
+    pub previous_completion: Option<KeyBinding>,
+
+    /// Binding for selecting the next occurrence of the selection.
+    /// Default: `cmd-d` (macOS) / `ctrl-d` (other platforms)
+    pub select_next_occurrence: Option<KeyBinding>,
+
+    /// Binding for selecting the next match of the input's search.
+    /// Default: `cmd-g` (macOS) / `f3` (other platforms)
+    pub find_next: Option<KeyBinding>,
+
+    /// Binding for selecting the previous match of the input's search.
+    /// Default: `cmd-shift-g` (macOS) / `shift-f3` (other platforms)
+    pub find_previous: Option<KeyBinding>,
+
+    /// Binding for toggling bold over the selection of an attributed input.
+    /// Default: `cmd-b` (macOS) / `ctrl-b` (other platforms)
+    pub toggle_bold: Option<KeyBinding>,
+
+    /// Binding for toggling italic over the selection of an attributed input.
+    /// Default: `cmd-i` (macOS) / `ctrl-i` (other platforms)
+    pub toggle_italic: Option<KeyBinding>,
+
+    /// Binding for toggling inline code over the selection of an attributed
+    /// input.
+    /// Default: unbound
+    pub toggle_code: Option<KeyBinding>,
+
+    /// Binding for killing to the end of the line.
+    /// Default: unbound; `ctrl-k` in [`readline`](Self::readline), `shift-d`
+    /// in normal mode in [`vim`](Self::vim)
+    pub kill_to_end_of_line: Option<KeyBinding>,
+
+    /// Binding for killing to the beginning of the line.
+    /// Default: unbound; `ctrl-u` in [`readline`](Self::readline)
+    pub kill_to_beginning_of_line: Option<KeyBinding>,
+
+    /// Binding for killing the word before the cursor.
+    /// Default: unbound; `ctrl-w` in [`readline`](Self::readline)
+    pub kill_word_left: Option<KeyBinding>,
+
+    /// Binding for killing the word after the cursor.
+    /// Default: unbound; `alt-d` in [`readline`](Self::readline)
+    pub kill_word_right: Option<KeyBinding>,
+
+    /// Binding for yanking the last kill.
+    /// Default: unbound; `ctrl-y` in [`readline`](Self::readline), `p` in
+    /// normal mode in [`vim`](Self::vim)
+    pub yank: Option<KeyBinding>,
+
+    /// Binding for swapping the text just yanked for the kill before it.
+    /// Default: unbound; `alt-y` in [`readline`](Self::readline)
+    pub yank_pop: Option<KeyBinding>,
+
+    /// Binding for transposing the characters at the cursor.
+    /// Default: unbound; `ctrl-t` in [`readline`](Self::readline)
+    pub transpose: Option<KeyBinding>,
+
+    /// Binding for leaving vim's insert mode.
+    /// Default: unbound; `escape` in insert mode in [`vim`](Self::vim)
+    pub enter_normal_mode: Option<KeyBinding>,
+
+    /// Binding for entering vim's insert mode at the cursor.
+    /// Default: unbound; `i` in normal mode in [`vim`](Self::vim)
+    pub enter_insert_mode: Option<KeyBinding>,
+
+    /// Binding for entering vim's insert mode after the cursor.
+    /// Default: unbound; `a` in normal mode in [`vim`](Self::vim)
+    pub enter_insert_mode_after: Option<KeyBinding>,
+
+    /// Further bindings, for a preset that puts one action on several keys —
+    /// readline's `ctrl-a` for [`Home`] beside `home`, vim's `h` for
+    /// [`Left`] beside `left`.
+    /// Default: none
+    pub extra: Vec<KeyBinding>,
 }
 
 impl Default for InputBindings {
@@ -298,6 +428,29 @@ impl Default for InputBindings {
                 undo: Some(KeyBinding::new("cmd-z", Undo, context)),
                 redo: Some(KeyBinding::new("cmd-shift-z", Redo, context)),
                 escape: Some(KeyBinding::new("escape", Escape, context)),
+                next_completion: Some(KeyBinding::new("alt-]", NextCompletion, context)),
+                previous_completion: Some(KeyBinding::new("alt-[", PreviousCompletion, context)),
+                select_next_occurrence: Some(KeyBinding::new(
+                    "cmd-d",
+                    SelectNextOccurrence,
+                    context,
+                )),
+                find_next: Some(KeyBinding::new("cmd-g", FindNext, context)),
+                find_previous: Some(KeyBinding::new("cmd-shift-g", FindPrevious, context)),
+                toggle_bold: Some(KeyBinding::new("cmd-b", ToggleBold, context)),
+                toggle_italic: Some(KeyBinding::new("cmd-i", ToggleItalic, context)),
+                toggle_code: None,
+                kill_to_end_of_line: None,
+                kill_to_beginning_of_line: None,
+                kill_word_left: None,
+                kill_word_right: None,
+                yank: None,
+                yank_pop: None,
+                transpose: None,
+                enter_normal_mode: None,
+                enter_insert_mode: None,
+                enter_insert_mode_after: None,
+                extra: Vec::new(),
             }
         }
 
@@ -355,6 +508,29 @@ impl Default for InputBindings {
                 undo: Some(KeyBinding::new("ctrl-z", Undo, context)),
                 redo: Some(KeyBinding::new("ctrl-shift-z", Redo, context)),
                 escape: Some(KeyBinding::new("escape", Escape, context)),
+                next_completion: Some(KeyBinding::new("alt-]", NextCompletion, context)),
+                previous_completion: Some(KeyBinding::new("alt-[", PreviousCompletion, context)),
+                select_next_occurrence: Some(KeyBinding::new(
+                    "ctrl-d",
+                    SelectNextOccurrence,
+                    context,
+                )),
+                find_next: Some(KeyBinding::new("f3", FindNext, context)),
+                find_previous: Some(KeyBinding::new("shift-f3", FindPrevious, context)),
+                toggle_bold: Some(KeyBinding::new("ctrl-b", ToggleBold, context)),
+                toggle_italic: Some(KeyBinding::new("ctrl-i", ToggleItalic, context)),
+                toggle_code: None,
+                kill_to_end_of_line: None,
+                kill_to_beginning_of_line: None,
+                kill_word_left: None,
+                kill_word_right: None,
+                yank: None,
+                yank_pop: None,
+                transpose: None,
+                enter_normal_mode: None,
+                enter_insert_mode: None,
+                enter_insert_mode_after: None,
+                extra: Vec::new(),
             }
         }
     }
@@ -406,6 +582,131 @@ impl InputBindings {
             undo: None,
             redo: None,
             escape: None,
+            next_completion: None,
+            previous_completion: None,
+            select_next_occurrence: None,
+            find_next: None,
+            find_previous: None,
+            toggle_bold: None,
+            toggle_italic: None,
+            toggle_code: None,
+            kill_to_end_of_line: None,
+            kill_to_beginning_of_line: None,
+            kill_word_left: None,
+            kill_word_right: None,
+            yank: None,
+            yank_pop: None,
+            transpose: None,
+            enter_normal_mode: None,
+            enter_insert_mode: None,
+            enter_insert_mode_after: None,
+            extra: Vec::new(),
+        }
+    }
+
+    /// The platform defaults with readline's — Emacs's — keys on top, for
+    /// users who expect a terminal's line editing:
+    ///
+    /// - `ctrl-a` and `ctrl-e` to the start and end of the line, `ctrl-b`,
+    ///   `ctrl-f`, `ctrl-p` and `ctrl-n` by a character or line, `alt-b` and
+    ///   `alt-f` by a word.
+    /// - `ctrl-d` and `ctrl-h` to delete forward and back.
+    /// - `ctrl-k`, `ctrl-u`, `ctrl-w` and `alt-d` to kill to the end or start
+    ///   of the line, or a word back or forward, into the
+    ///   [kill ring](super::kill_ring); `ctrl-y` to yank the last kill back,
+    ///   and `alt-y` straight after to swap it for the one before.
+    /// - `ctrl-t` to transpose the characters at the cursor.
+    ///
+    /// Whatever the defaults had on those keys gives way: off macOS that is
+    /// select-all on `ctrl-a`, bold on `ctrl-b` and select-next-occurrence on
+    /// `ctrl-d`.
+    ///
+    /// ```ignore
+    /// gpuikit::init_with_input_bindings(cx, InputBindings::readline());
+    /// ```
+    pub fn readline() -> Self {
+        let context = Some(INPUT_CONTEXT);
+        let mut bindings = Self::default();
+
+        #[cfg(target_os = "macos")]
+        {
+            bindings.delete_to_end_of_line = None;
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            bindings.select_all = None;
+            bindings.toggle_bold = None;
+            bindings.select_next_occurrence = None;
+        }
+
+        bindings.kill_to_end_of_line = Some(KeyBinding::new("ctrl-k", KillToEndOfLine, context));
+        bindings.kill_to_beginning_of_line =
+            Some(KeyBinding::new("ctrl-u", KillToBeginningOfLine, context));
+        bindings.kill_word_left = Some(KeyBinding::new("ctrl-w", KillWordLeft, context));
+        bindings.kill_word_right = Some(KeyBinding::new("alt-d", KillWordRight, context));
+        bindings.yank = Some(KeyBinding::new("ctrl-y", Yank, context));
+        bindings.yank_pop = Some(KeyBinding::new("alt-y", YankPop, context));
+        bindings.transpose = Some(KeyBinding::new("ctrl-t", Transpose, context));
+        bindings.extra = vec![
+            KeyBinding::new("ctrl-a", Home, context),
+            KeyBinding::new("ctrl-e", End, context),
+            KeyBinding::new("ctrl-b", Left, context),
+            KeyBinding::new("ctrl-f", Right, context),
+            KeyBinding::new("ctrl-p", Up, context),
+            KeyBinding::new("ctrl-n", Down, context),
+            KeyBinding::new("alt-b", WordLeft, context),
+            KeyBinding::new("alt-f", WordRight, context),
+            KeyBinding::new("ctrl-d", Delete, context),
+            KeyBinding::new("ctrl-h", Backspace, context),
+        ];
+        bindings
+    }
+
+    /// The platform defaults with a minimal vim on top. Inputs start in
+    /// insert mode, where every default key works as before, and `escape`
+    /// goes to normal mode — see [`InputState::vim_mode`]. There, typing
+    /// inserts nothing, and:
+    ///
+    /// - `h`, `j`, `k` and `l` move by a character or line, `w` and `b` by a
+    ///   word, `0` and `$` to the start and end of the line.
+    /// - `x` deletes the character under the cursor, `shift-d` kills to the
+    ///   end of the line and `p` yanks the last kill back.
+    /// - `u` undoes and `ctrl-r` redoes.
+    /// - `i` and `a` go back to insert mode, at the cursor or after it.
+    /// - `escape` blurs the input, as it does without the preset.
+    ///
+    /// ```ignore
+    /// gpuikit::init_with_input_bindings(cx, InputBindings::vim());
+    /// ```
+    ///
+    /// [`InputState::vim_mode`]: crate::input::InputState::vim_mode
+    pub fn vim() -> Self {
+        let normal = Some(VIM_NORMAL_CONTEXT);
+        Self {
+            escape: Some(KeyBinding::new("escape", Escape, normal)),
+            enter_normal_mode: Some(KeyBinding::new(
+                "escape",
+                EnterNormalMode,
+                Some(VIM_INSERT_CONTEXT),
+            )),
+            enter_insert_mode: Some(KeyBinding::new("i", EnterInsertMode, normal)),
+            enter_insert_mode_after: Some(KeyBinding::new("a", EnterInsertModeAfter, normal)),
+            kill_to_end_of_line: Some(KeyBinding::new("shift-d", KillToEndOfLine, normal)),
+            yank: Some(KeyBinding::new("p", Yank, normal)),
+            extra: vec![
+                KeyBinding::new("h", Left, normal),
+                KeyBinding::new("l", Right, normal),
+                KeyBinding::new("j", Down, normal),
+                KeyBinding::new("k", Up, normal),
+                KeyBinding::new("w", WordRight, normal),
+                KeyBinding::new("b", WordLeft, normal),
+                KeyBinding::new("0", Home, normal),
+                KeyBinding::new("$", End, normal),
+                KeyBinding::new("x", Delete, normal),
+                KeyBinding::new("u", Undo, normal),
+                KeyBinding::new("ctrl-r", Redo, normal),
+            ],
+            ..Self::default()
         }
     }
 
@@ -453,6 +754,35 @@ impl InputBindings {
             undo: self.undo.or(defaults.undo),
             redo: self.redo.or(defaults.redo),
             escape: self.escape.or(defaults.escape),
+            next_completion: self.next_completion.or(defaults.next_completion),
+            previous_completion: self.previous_completion.or(defaults.previous_completion),
+            select_next_occurrence: self
+                .select_next_occurrence
+                .or(defaults.select_next_occurrence),
+            find_next: self.find_next.or(defaults.find_next),
+            find_previous: self.find_previous.or(defaults.find_previous),
+            toggle_bold: self.toggle_bold.or(defaults.toggle_bold),
+            toggle_italic: self.toggle_italic.or(defaults.toggle_italic),
+            toggle_code: self.toggle_code.or(defaults.toggle_code),
+            kill_to_end_of_line: self.kill_to_end_of_line.or(defaults.kill_to_end_of_line),
+            kill_to_beginning_of_line: self
+                .kill_to_beginning_of_line
+                .or(defaults.kill_to_beginning_of_line),
+            kill_word_left: self.kill_word_left.or(defaults.kill_word_left),
+            kill_word_right: self.kill_word_right.or(defaults.kill_word_right),
+            yank: self.yank.or(defaults.yank),
+            yank_pop: self.yank_pop.or(defaults.yank_pop),
+            transpose: self.transpose.or(defaults.transpose),
+            enter_normal_mode: self.enter_normal_mode.or(defaults.enter_normal_mode),
+            enter_insert_mode: self.enter_insert_mode.or(defaults.enter_insert_mode),
+            enter_insert_mode_after: self
+                .enter_insert_mode_after
+                .or(defaults.enter_insert_mode_after),
+            extra: if self.extra.is_empty() {
+                defaults.extra
+            } else {
+                self.extra
+            },
         }
     }
 
@@ -494,7 +824,26 @@ impl InputBindings {
             self.undo,
             self.redo,
             self.escape,
+            self.next_completion,
+            self.previous_completion,
+            self.select_next_occurrence,
+            self.find_next,
+            self.find_previous,
+            self.toggle_bold,
+            self.toggle_italic,
+            self.toggle_code,
+            self.kill_to_end_of_line,
+            self.kill_to_beginning_of_line,
+            self.kill_word_left,
+            self.kill_word_right,
+            self.yank,
+            self.yank_pop,
+            self.transpose,
+            self.enter_normal_mode,
+            self.enter_insert_mode,
+            self.enter_insert_mode_after,
         ];
+        bindings.extend(self.extra.into_iter().map(Some));
 
         // Add additional macOS-specific bindings for Home/End
         // Mac keyboards don't have Home/End keys, so cmd-left/right are standard
@@ -515,6 +864,13 @@ impl InputBindings {
 /// provided, they are used exactly as-is - fields set to `None` will not have
 /// any keybinding registered for that action.
 ///
+/// [`crate::init`] already calls this with the defaults. To start an app with
+/// other bindings, pass them to [`crate::init_with_input_bindings`] instead of
+/// calling this again after `init`: a second call re-registers the plain
+/// `Input` arrows, Enter and Escape after the components that bind those keys
+/// under `Input`, and the later registration wins. The examples below show the
+/// bindings either way.
+///
 /// # Examples
 ///
 /// Use all platform defaults:
@@ -541,6 +897,14 @@ impl InputBindings {
 /// });
 /// ```
 ///
+/// Use a preset — readline's keys, or a minimal vim:
+///
+/// ```ignore
+/// gpuikit::init_with_input_bindings(cx, InputBindings::readline());
+/// // or
+/// gpuikit::init_with_input_bindings(cx, InputBindings::vim());
+/// ```
+///
 /// Use [`InputBindings::empty()`] with [`merged_with_defaults()`](InputBindings::merged_with_defaults)
 /// if you only want to specify a few custom bindings and fill in the rest with defaults:
 ///
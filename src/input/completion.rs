@@ -0,0 +1,122 @@
+//! Inline completion: a suggested continuation of what was typed, drawn dimmed
+//! after the cursor, the way a shell suggests the rest of a command.
+//!
+//! An [`InputState`](super::InputState) given a [`CompletionProvider`] asks it
+//! for candidates every time its text changes. The first candidate is drawn as
+//! ghost text after the cursor by `elements::input`, in the placeholder
+//! colour, and:
+//!
+//! - **Tab** or **Right** takes it, as one ordinary edit — one undo step,
+//!   checked by the input's [validation](super::validation) like anything
+//!   typed;
+//! - [`NextCompletion`](super::bindings::NextCompletion) and
+//!   [`PreviousCompletion`](super::bindings::PreviousCompletion) — `alt-]`
+//!   and `alt-[` by default — cycle through the others;
+//! - typing on, moving the cursor, or selecting hides it until the text next
+//!   changes.
+//!
+//! A suggestion is only offered where it can be drawn without covering
+//! anything: in a single-line input, with the cursor at the end of the text
+//! and nothing selected or being composed. A [secure](super::InputState::secure)
+//! or read-only input is never asked.
+//!
+//! # Tab
+//!
+//! No binding is added for Tab. The input's own `tab` binding already outranks
+//! `a11y::bind_focus_keys`' focus traversal — [`crate::init`] registers the two
+//! in that order on purpose — and the input's Tab handler takes a shown
+//! suggestion before it does anything else. With no suggestion showing, Tab
+//! does exactly what it did before.
+
+use std::rc::Rc;
+
+/// Supplies the candidates an input suggests.
+///
+/// Any `Fn(&str) -> Vec<String>` is one, so a provider can be a closure:
+///
+/// ```ignore
+/// InputState::new_singleline(cx).completions(|text: &str| {
+///     if "checkout".starts_with(text) && !text.is_empty() {
+///         vec!["checkout"[text.len()..].to_string()]
+///     } else {
+///         Vec::new()
+///     }
+/// })
+/// ```
+pub trait CompletionProvider: 'static {
+    /// What could follow `text` — the whole content of the input — best
+    /// first. Each candidate is only the part to add, not the whole line.
+    /// Empty candidates are ignored.
+    ///
+    /// Called on every change to the text, so it should be quick; a provider
+    /// with slow work to do should answer from what it has and update itself
+    /// in the background.
+    fn completions(&self, text: &str) -> Vec<String>;
+}
+
+impl<F> CompletionProvider for F
+where
+    F: Fn(&str) -> Vec<String> + 'static,
+{
+    fn completions(&self, text: &str) -> Vec<String> {
+        self(text)
+    }
+}
+
+/// A provider set with [`set_completion_provider`](super::InputState::set_completion_provider).
+pub type SharedCompletionProvider = Rc<dyn CompletionProvider>;
+
+/// Completes the text to any of a fixed list of lines that it starts, in the
+/// list's order — a command list, or a shell's history with the most recent
+/// first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrefixCompletions {
+    lines: Vec<String>,
+}
+
+impl PrefixCompletions {
+    /// Completes to `lines`, earliest first.
+    pub fn new(lines: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            lines: lines.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl CompletionProvider for PrefixCompletions {
+    /// Nothing for empty text: a suggestion before anything is typed is the
+    /// placeholder's job.
+    fn completions(&self, text: &str) -> Vec<String> {
+        if text.is_empty() {
+            return Vec::new();
+        }
+        let mut completions: Vec<String> = Vec::new();
+        for line in &self.lines {
+            if let Some(rest) = line.strip_prefix(text) {
+                if !rest.is_empty() && !completions.iter().any(|seen| seen == rest) {
+                    completions.push(rest.to_string());
+                }
+            }
+        }
+        completions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_completions_offer_the_rest_of_each_match_once() {
+        let commands = PrefixCompletions::new(["git status", "git stash", "git status", "ls"]);
+        assert_eq!(commands.completions("git st"), ["atus", "ash"]);
+        assert!(commands.completions("").is_empty());
+        assert!(commands.completions("ls").is_empty());
+    }
+
+    #[test]
+    fn a_closure_is_a_provider() {
+        let upper = |text: &str| vec![text.to_uppercase()];
+        assert_eq!(upper.completions("ab"), ["AB"]);
+    }
+}
@@ -0,0 +1,287 @@
+//! Underlines over ranges of an input's text: misspellings, lint warnings,
+//! anything that wants pointing at without changing the text.
+//!
+//! An [`InputState`](super::InputState) has decorations from two places:
+//!
+//! - its owner's, set with [`set_decorations`](super::InputState::set_decorations)
+//!   — a linter's results, say, worked out elsewhere. Once set they follow the
+//!   text: an edit before one moves it along, an edit after it leaves it
+//!   alone, and an edit inside it removes it, since what it pointed at is no
+//!   longer there. Replacing the whole content keeps those outside the part
+//!   that changed.
+//! - its [`Checker`]'s, given with [`checker`](super::InputState::checker).
+//!   The checker is asked again every time the text changes, and what it
+//!   says replaces what it said before. [`WordListChecker`] is a simple one,
+//!   marking each word not in a list.
+//!
+//! `elements::input` underlines each decoration in its [`DecorationStyle`] —
+//! squiggly, dotted or straight — and its colour, or the theme's danger
+//! colour. Hovering one with a [`message`](Decoration::message) shows the
+//! message in a tooltip.
+//!
+//! # Context menus
+//!
+//! [`hovered_decoration`](super::InputState::hovered_decoration) is the
+//! decoration under the pointer, so a context menu wrapped round the field
+//! can offer fixes for whatever was right-clicked:
+//!
+//! ```ignore
+//! let state = self.input.clone();
+//! context_menu("notes", text_field(&self.input, cx)).menu(move |menu, _window, cx| {
+//!     let Some(decoration) = state.read(cx).hovered_decoration().cloned() else {
+//!         return menu;
+//!     };
+//!     let word = state.read(cx).content()[decoration.range.clone()].to_string();
+//!     menu.items(suggestions_for(&word).into_iter().map(|suggestion| {
+//!         let state = state.clone();
+//!         let range = decoration.range.clone();
+//!         menu_item(suggestion.clone()).on_click(move |_window, cx| {
+//!             state.update(cx, |state, cx| {
+//!                 state.set_selected_range(range.clone());
+//!                 state.insert_text(&suggestion, cx);
+//!             });
+//!         })
+//!     }))
+//! })
+//! ```
+//!
+//! A [secure](super::InputState::secure) input paints no decorations, and
+//! its checker is never asked: where the underlines fall would give away the
+//! shape of the secret.
+
+use std::collections::HashSet;
+use std::ops::Range;
+use std::rc::Rc;
+
+use gpui::{Hsla, SharedString};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// How a [`Decoration`] is underlined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecorationStyle {
+    /// A wavy line, as under a misspelling or an error.
+    #[default]
+    Squiggly,
+    /// A row of dots, for a hint or something merely unusual.
+    Dotted,
+    /// A plain line.
+    Straight,
+}
+
+/// An underline over a range of an input's text. See the [module docs](self).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoration {
+    /// The byte range underlined. Never empty once given to an input.
+    pub range: Range<usize>,
+    /// How it is underlined.
+    pub style: DecorationStyle,
+    /// Its colour. `None` for the theme's danger colour.
+    pub color: Option<Hsla>,
+    /// Shown in a tooltip while the pointer is over it.
+    pub message: Option<SharedString>,
+}
+
+impl Decoration {
+    /// A squiggly underline over `range`, in the theme's danger colour.
+    pub fn new(range: Range<usize>) -> Self {
+        Self {
+            range,
+            style: DecorationStyle::default(),
+            color: None,
+            message: None,
+        }
+    }
+
+    /// Sets how it is underlined.
+    pub fn style(mut self, style: DecorationStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets its colour.
+    pub fn color(mut self, color: impl Into<Hsla>) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+
+    /// Sets the message shown while the pointer is over it.
+    pub fn message(mut self, message: impl Into<SharedString>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+/// Finds what an input should underline, given with
+/// [`checker`](super::InputState::checker). A closure from the text to its
+/// decorations implements it, as this one marking every `TODO` does:
+///
+/// ```ignore
+/// InputState::new_multiline(cx).checker(|text: &str| {
+///     text.match_indices("TODO")
+///         .map(|(at, found)| {
+///             Decoration::new(at..at + found.len()).style(DecorationStyle::Dotted)
+///         })
+///         .collect::<Vec<_>>()
+/// })
+/// ```
+pub trait Checker: 'static {
+    /// What to underline in `text` — the whole content of the input. Ranges
+    /// that are empty, run past the end or split a character are ignored.
+    ///
+    /// Each answer replaces the last one whole, after every edit, and nothing
+    /// is painted until it returns. A spell checker still loading its
+    /// dictionary, or a linter run in another process, can answer with what
+    /// it already knows and pass the rest to
+    /// [`set_decorations`](super::InputState::set_decorations), which an edit
+    /// moves along rather than replacing.
+    fn check(&self, text: &str) -> Vec<Decoration>;
+}
+
+impl<F> Checker for F
+where
+    F: Fn(&str) -> Vec<Decoration> + 'static,
+{
+    fn check(&self, text: &str) -> Vec<Decoration> {
+        self(text)
+    }
+}
+
+/// A checker set with [`set_checker`](super::InputState::set_checker).
+pub type SharedChecker = Rc<dyn Checker>;
+
+/// Underlines every word not in a fixed list, ignoring case — a dictionary,
+/// at its simplest.
+///
+/// A word is a run of letters, digits and apostrophes as Unicode splits text
+/// into words; one with a digit in it is never marked.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WordListChecker {
+    words: HashSet<String>,
+}
+
+impl WordListChecker {
+    /// Accepts `words`, and nothing else.
+    pub fn new(words: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        Self {
+            words: words
+                .into_iter()
+                .map(|word| word.as_ref().to_lowercase())
+                .collect(),
+        }
+    }
+
+    /// Accepts `word` as well.
+    pub fn add_word(&mut self, word: impl AsRef<str>) {
+        self.words.insert(word.as_ref().to_lowercase());
+    }
+
+    /// Whether `word` is in the list.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+}
+
+impl Checker for WordListChecker {
+    fn check(&self, text: &str) -> Vec<Decoration> {
+        text.unicode_word_indices()
+            .filter(|(_, word)| !word.chars().any(|c| c.is_numeric()))
+            .filter(|(_, word)| !self.contains(word))
+            .map(|(at, word)| {
+                Decoration::new(at..at + word.len()).message(format!("Unknown word “{word}”"))
+            })
+            .collect()
+    }
+}
+
+/// Keeps the decorations that fit `content` — non-empty, inside it, on
+/// character boundaries — in order of where they start.
+pub(crate) fn sanitize(mut decorations: Vec<Decoration>, content: &str) -> Vec<Decoration> {
+    decorations.retain(|decoration| {
+        let range = &decoration.range;
+        range.start < range.end
+            && range.end <= content.len()
+            && content.is_char_boundary(range.start)
+            && content.is_char_boundary(range.end)
+    });
+    decorations.sort_by_key(|decoration| decoration.range.start);
+    decorations
+}
+
+/// Moves `decorations` for `range` of the text having been replaced by
+/// `new_len` bytes: those after it along, those inside or across it out.
+pub(crate) fn shift(decorations: &mut Vec<Decoration>, range: &Range<usize>, new_len: usize) {
+    decorations.retain_mut(|decoration| {
+        let own = &mut decoration.range;
+        if own.end <= range.start {
+            true
+        } else if own.start >= range.end {
+            own.start = own.start - range.len() + new_len;
+            own.end = own.end - range.len() + new_len;
+            true
+        } else {
+            false
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(decorations: &[Decoration]) -> Vec<Range<usize>> {
+        decorations
+            .iter()
+            .map(|decoration| decoration.range.clone())
+            .collect()
+    }
+
+    #[test]
+    fn word_list_marks_unknown_words_only() {
+        let checker = WordListChecker::new(["the", "cat", "don't"]);
+        let found = checker.check("The cta sat, don't 42 times");
+        assert_eq!(ranges(&found), [4..7, 8..11, 22..27]);
+        assert_eq!(found[0].message.as_deref(), Some("Unknown word “cta”"));
+        assert_eq!(found[0].style, DecorationStyle::Squiggly);
+    }
+
+    #[test]
+    fn a_closure_is_a_checker() {
+        let todos = |text: &str| {
+            text.match_indices("TODO")
+                .map(|(at, found)| Decoration::new(at..at + found.len()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ranges(&todos.check("a TODO b TODO")), [2..6, 9..13]);
+    }
+
+    #[test]
+    fn sanitize_drops_what_does_not_fit_and_sorts() {
+        let content = "añb";
+        let decorations = vec![
+            Decoration::new(3..4),
+            Decoration::new(0..0),
+            Decoration::new(1..2),
+            Decoration::new(0..1),
+            Decoration::new(3..9),
+        ];
+        assert_eq!(ranges(&sanitize(decorations, content)), [0..1, 3..4]);
+    }
+
+    #[test]
+    fn edits_move_what_follows_and_remove_what_they_touch() {
+        let mut decorations = vec![
+            Decoration::new(0..3),
+            Decoration::new(4..7),
+            Decoration::new(8..11),
+        ];
+        // Typing at the start of one moves it.
+        shift(&mut decorations, &(4..4), 2);
+        assert_eq!(ranges(&decorations), [0..3, 6..9, 10..13]);
+        // Deleting up to the end of one leaves it.
+        shift(&mut decorations, &(3..6), 0);
+        assert_eq!(ranges(&decorations), [0..3, 3..6, 7..10]);
+        // Typing inside one removes it.
+        shift(&mut decorations, &(4..4), 1);
+        assert_eq!(ranges(&decorations), [0..3, 8..11]);
+    }
+}
@@ -0,0 +1,114 @@
+//! Dragging text into and around inputs.
+//!
+//! A press inside the selection of an [`InputState`](super::InputState),
+//! followed by a drag, picks the selected text up instead of starting a new
+//! selection. Where the mouse is released decides what happens to it:
+//!
+//! - over the same input, the text moves to the caret shown under the mouse,
+//!   as one undo step.
+//! - over another input, it is inserted there and removed from where it
+//!   came from — one undo step in each.
+//! - with alt held as the mouse is released, it is copied rather than moved.
+//! - anywhere else, nothing happens.
+//!
+//! A press inside the selection that is released without a drag is a click
+//! after all, and puts the caret where it landed. A
+//! [secure](super::InputState::secure) input's text is never picked up, and a
+//! read-only input takes no drops and gives up copies only.
+//!
+//! # From outside the input
+//!
+//! gpui hands an app the files dropped on its windows, and nothing else from
+//! the platform, so text dragged from outside an input comes from the app's
+//! own elements: any element dragging a [`DraggedText`] can drop it into an
+//! input, where it lands at the caret under the mouse.
+//!
+//! ```ignore
+//! div()
+//!     .id("snippet")
+//!     .on_drag(DraggedText("Hello!".into()), |text, _offset, _window, cx| {
+//!         cx.new(|_| SnippetPreview(text.0.clone()))
+//!     })
+//! ```
+//!
+//! Files are the owner's to handle: `TextField::on_file_drop` and
+//! `Textarea::on_file_drop` are called with the
+//! [`ExternalPaths`](gpui::ExternalPaths) dropped, with the caret already
+//! moved to where they were dropped — for inserting a path there, or
+//! attaching the files. Without a handler, a file drop is ignored.
+
+use std::ops::Range;
+
+use gpui::{Global, SharedString, WeakEntity};
+
+use super::InputState;
+
+/// Text dragged onto an input from another element of the app. Give it to
+/// the element's `on_drag`, and an input it is dropped on inserts it. See
+/// the [module docs](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DraggedText(pub SharedString);
+
+/// A selection picked up out of an input and not yet dropped. A global
+/// while the mouse is held down, so that whichever input it is released over
+/// can take it.
+pub(crate) struct TextDrag {
+    /// The input it was picked up from.
+    pub(crate) source: WeakEntity<InputState>,
+    /// Where it was in the source when it was picked up.
+    pub(crate) range: Range<usize>,
+    /// The text picked up.
+    pub(crate) text: String,
+}
+
+impl Global for TextDrag {}
+
+/// The one edit that moves `range` of `content` to offset `to`: the range it
+/// replaces, what replaces it, and where the moved text ends up. `None` for a
+/// drop inside or at either end of `range`, which moves nothing.
+pub(crate) fn move_edit(
+    content: &str,
+    range: Range<usize>,
+    to: usize,
+) -> Option<(Range<usize>, String, Range<usize>)> {
+    let moved = &content[range.clone()];
+    if to < range.start {
+        let text = format!("{moved}{}", &content[to..range.start]);
+        Some((to..range.end, text, to..to + moved.len()))
+    } else if to > range.end {
+        let text = format!("{}{moved}", &content[range.end..to]);
+        Some((range.start..to, text, to - moved.len()..to))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(content: &str, range: Range<usize>, to: usize) -> (String, Range<usize>) {
+        let (replaced, text, selection) = move_edit(content, range, to).unwrap();
+        let mut content = content.to_string();
+        content.replace_range(replaced, &text);
+        (content, selection)
+    }
+
+    #[test]
+    fn a_range_moves_back_or_forward_in_one_edit() {
+        let (moved, selection) = apply("one two three", 4..8, 0);
+        assert_eq!(moved, "two one three");
+        assert_eq!(&moved[selection], "two ");
+
+        let (moved, selection) = apply("one two three", 0..4, 13);
+        assert_eq!(moved, "two threeone ");
+        assert_eq!(&moved[selection], "one ");
+    }
+
+    #[test]
+    fn a_drop_onto_itself_moves_nothing() {
+        assert_eq!(move_edit("one two", 4..7, 4), None);
+        assert_eq!(move_edit("one two", 4..7, 5), None);
+        assert_eq!(move_edit("one two", 4..7, 7), None);
+    }
+}
@@ -0,0 +1,584 @@
+//! Undo history that can be saved, restored, and kept as a tree.
+//!
+//! An [`InputState`](super::InputState) records each edit as a patch — the
+//! range it changed and the text that was there — so that history costs what
+//! was edited rather than a copy of the content per step.
+//!
+//! # Saving
+//!
+//! [`save_history`](super::InputState::save_history) returns a
+//! [`SavedHistory`]: the content, the selection, and every patch, as one
+//! serde value. Write it wherever a draft is kept, and
+//! [`restore_history`](super::InputState::restore_history) it into a fresh
+//! input to get the draft back with its undo intact — after closing and
+//! reopening a panel, or the app. A secure input records no history, and
+//! saves nothing.
+//!
+//! A history whose states do not form a tree is dropped when it is restored.
+//! Each patch is checked as it is used instead: one that no longer fits the
+//! content — a file edited by hand, or saved against a different draft —
+//! empties the history instead of panicking, and the content is left as it
+//! was.
+//!
+//! # Branches
+//!
+//! By default history is a line: an edit made after undoing discards what
+//! could have been redone, as in most text fields. With
+//! [`undo_tree(true)`](super::InputState::undo_tree) it is a tree instead.
+//! The discarded states stay as a branch; redo follows the branch visited
+//! last; and [`history_states`](super::InputState::history_states) lists
+//! every state, for
+//! [`jump_to_history_state`](super::InputState::jump_to_history_state) to
+//! return to — undoing up to where the two branches meet, then redoing down.
+//!
+//! Either way history holds at most a thousand states. Past that the oldest
+//! go first; in a tree, so does any branch that left the line to the current
+//! state before the new oldest one.
+
+use std::collections::HashSet;
+use std::ops::Range;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use super::attributes::{self, AttributeSpan};
+use super::selections::Selection;
+use super::tokens::{self, Token};
+
+/// Maximum number of history entries to keep.
+pub(crate) const MAX_HISTORY_LEN: usize = 1000;
+
+/// A patch-based history entry for memory-efficient undo/redo operations.
+/// Instead of storing the full content, we store only the change needed to reverse the edit.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct HistoryEntry {
+    /// The byte range that was modified (after the edit, for undo; before the edit, for redo).
+    pub(crate) range: Range<usize>,
+    /// The text that was replaced (to restore on undo).
+    pub(crate) old_text: String,
+    /// The length of the new text that replaced old_text (to know how much to remove on undo).
+    pub(crate) new_text_len: usize,
+    /// The selection range before the edit.
+    pub(crate) selected_range: Range<usize>,
+    /// Whether the selection was reversed before the edit.
+    pub(crate) selection_reversed: bool,
+    /// The other selections before the edit, if there were several.
+    pub(crate) extra_selections: Vec<Selection>,
+    /// The tokens in `old_text`, relative to its start, to put back with it.
+    #[serde(default)]
+    pub(crate) tokens: Vec<Token>,
+    /// The attribute spans over `old_text`, relative to its start, to put
+    /// back with it.
+    #[serde(default)]
+    pub(crate) attributes: Vec<AttributeSpan>,
+    /// Whether the patch changes attributes only, putting back the text it
+    /// replaces as it was. Tokens and the owner's decorations stay put.
+    #[serde(default)]
+    pub(crate) restyle: bool,
+    /// Timestamp for grouping consecutive edits. Not saved: a restored entry
+    /// never groups with the next edit.
+    #[serde(skip)]
+    pub(crate) timestamp: Option<Instant>,
+}
+
+impl HistoryEntry {
+    /// Apply this patch to undo an edit, returning the reverse patch for redo.
+    /// `None`, with `content`, `tokens` and `attributes` untouched, if the
+    /// patch does not fit.
+    pub(crate) fn apply_undo(
+        &self,
+        content: &mut String,
+        tokens: &mut Vec<Token>,
+        spans: &mut Vec<AttributeSpan>,
+    ) -> Option<HistoryEntry> {
+        let undo_start = self.range.start;
+        let undo_end = self.range.start + self.new_text_len;
+        if undo_end > content.len()
+            || !content.is_char_boundary(undo_start)
+            || !content.is_char_boundary(undo_end)
+        {
+            return None;
+        }
+
+        // Capture what we're about to remove (the "new" text that was inserted)
+        let removed_text = content[undo_start..undo_end].to_string();
+
+        let removed = undo_start..undo_end;
+        let removed_tokens = tokens::within(tokens, &removed);
+        let removed_attributes = attributes::within(spans, &removed);
+
+        // Replace with the old text
+        content.replace_range(removed.clone(), &self.old_text);
+        if !self.restyle {
+            tokens::shift(tokens, &removed, self.old_text.len());
+            tokens::restore(tokens, &self.tokens, undo_start);
+        }
+        attributes::cut(spans, &removed, self.old_text.len());
+        attributes::restore(spans, &self.attributes, undo_start);
+
+        // Return reverse patch for redo
+        Some(HistoryEntry {
+            range: undo_start..undo_start + self.old_text.len(),
+            old_text: removed_text,
+            new_text_len: self.old_text.len(),
+            selected_range: self.selected_range.clone(),
+            selection_reversed: self.selection_reversed,
+            extra_selections: self.extra_selections.clone(),
+            tokens: removed_tokens,
+            attributes: removed_attributes,
+            restyle: self.restyle,
+            timestamp: self.timestamp,
+        })
+    }
+
+    /// Apply this patch to redo an edit, returning the reverse patch for undo.
+    pub(crate) fn apply_redo(
+        &self,
+        content: &mut String,
+        tokens: &mut Vec<Token>,
+        spans: &mut Vec<AttributeSpan>,
+    ) -> Option<HistoryEntry> {
+        // Redo is the same operation as undo - we're reversing the undo
+        self.apply_undo(content, tokens, spans)
+    }
+}
+
+/// Names one state in an input's history. Stable for as long as the state is
+/// kept, and across a save and restore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct HistoryId(u64);
+
+/// One state in an input's history, as
+/// [`history_states`](super::InputState::history_states) lists it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryState {
+    /// This state.
+    pub id: HistoryId,
+    /// The state it was edited from. `None` for the oldest kept.
+    pub parent: Option<HistoryId>,
+    /// Whether the content is in this state now.
+    pub is_current: bool,
+}
+
+/// An input's content, selection and undo history, as
+/// [`save_history`](super::InputState::save_history) captures them. See the
+/// [module docs](self).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedHistory {
+    pub(crate) content: String,
+    #[serde(default)]
+    pub(crate) tokens: Vec<Token>,
+    #[serde(default)]
+    pub(crate) attributes: Vec<AttributeSpan>,
+    pub(crate) selected_range: Range<usize>,
+    pub(crate) history: History,
+}
+
+impl SavedHistory {
+    /// The content as it was saved.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Node {
+    id: HistoryId,
+    parent: Option<HistoryId>,
+    /// Undoes this state while it is current or before the current one, and
+    /// redoes it otherwise: each traversal swaps it for its reverse. `None`
+    /// for the oldest state, which has nothing to undo.
+    patch: Option<HistoryEntry>,
+    /// The child redo goes to: the one visited, or made, last.
+    redo_child: Option<HistoryId>,
+}
+
+/// Every state the content has been in, as a tree of patches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct History {
+    nodes: Vec<Node>,
+    current: HistoryId,
+    next_id: u64,
+    branching: bool,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            nodes: vec![Node {
+                id: HistoryId(0),
+                parent: None,
+                patch: None,
+                redo_child: None,
+            }],
+            current: HistoryId(0),
+            next_id: 1,
+            branching: false,
+        }
+    }
+}
+
+impl History {
+    /// Forgets every state but the current one, keeping the mode.
+    pub(crate) fn clear(&mut self) {
+        *self = Self {
+            branching: self.branching,
+            ..Self::default()
+        };
+    }
+
+    pub(crate) fn is_branching(&self) -> bool {
+        self.branching
+    }
+
+    /// Turns the tree on or off. Turning it off keeps the line that undo and
+    /// redo would walk, and drops the other branches.
+    pub(crate) fn set_branching(&mut self, branching: bool) {
+        self.branching = branching;
+        if branching {
+            return;
+        }
+        let mut keep: HashSet<HistoryId> = self.ancestors(self.current).collect();
+        let mut next = Some(self.current);
+        while let Some(id) = next {
+            keep.insert(id);
+            next = self.node(id).redo_child;
+        }
+        self.nodes.retain(|node| keep.contains(&node.id));
+    }
+
+    pub(crate) fn can_undo(&self) -> bool {
+        self.node(self.current).parent.is_some()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        self.node(self.current).redo_child.is_some()
+    }
+
+    pub(crate) fn current(&self) -> HistoryId {
+        self.current
+    }
+
+    /// The patch that made the current state, while it has not been undone.
+    pub(crate) fn latest(&self) -> Option<&HistoryEntry> {
+        self.node(self.current).patch.as_ref()
+    }
+
+    pub(crate) fn latest_mut(&mut self) -> Option<&mut HistoryEntry> {
+        let index = self.index(self.current)?;
+        self.nodes[index].patch.as_mut()
+    }
+
+    /// Records an edit from the current state as a new current state. Outside
+    /// a tree, this is where redo is lost.
+    pub(crate) fn record(&mut self, entry: HistoryEntry) {
+        if !self.branching {
+            let mut doomed = self.descendants(self.current);
+            doomed.remove(&self.current);
+            self.nodes.retain(|node| !doomed.contains(&node.id));
+        }
+
+        let id = HistoryId(self.next_id);
+        self.next_id += 1;
+        self.nodes.push(Node {
+            id,
+            parent: Some(self.current),
+            patch: Some(entry),
+            redo_child: None,
+        });
+        self.node_mut(self.current).redo_child = Some(id);
+        self.current = id;
+        self.trim();
+    }
+
+    /// Forgets the current state, which must have just been recorded, going
+    /// back to the one before it without touching the content.
+    pub(crate) fn discard_latest(&mut self) {
+        let Some(parent) = self.node(self.current).parent else {
+            return;
+        };
+        let current = self.current;
+        self.nodes.retain(|node| node.id != current);
+        // Redo goes back to the branch made last before this one, if any.
+        let previous = self
+            .nodes
+            .iter()
+            .rev()
+            .find(|node| node.parent == Some(parent))
+            .map(|node| node.id);
+        self.node_mut(parent).redo_child = previous;
+        self.current = parent;
+    }
+
+    /// Undoes the current state's patch on `content`, its `tokens` and its
+    /// attribute `spans`, returning the patch — which holds the selection to
+    /// restore. Empties the history if the patch does not fit.
+    pub(crate) fn undo(
+        &mut self,
+        content: &mut String,
+        tokens: &mut Vec<Token>,
+        spans: &mut Vec<AttributeSpan>,
+    ) -> Option<HistoryEntry> {
+        let index = self.index(self.current)?;
+        let parent = self.nodes[index].parent?;
+        let patch = self.nodes[index].patch.take()?;
+        let Some(reverse) = patch.apply_undo(content, tokens, spans) else {
+            self.clear();
+            return None;
+        };
+        self.nodes[index].patch = Some(reverse);
+        self.node_mut(parent).redo_child = Some(self.current);
+        self.current = parent;
+        Some(patch)
+    }
+
+    /// Redoes the next state's patch on `content`, its `tokens` and its
+    /// attribute `spans`, returning the reverse patch now recorded for it.
+    pub(crate) fn redo(
+        &mut self,
+        content: &mut String,
+        tokens: &mut Vec<Token>,
+        spans: &mut Vec<AttributeSpan>,
+    ) -> Option<HistoryEntry> {
+        let child = self.node(self.current).redo_child?;
+        let index = self.index(child)?;
+        let patch = self.nodes[index].patch.take()?;
+        let Some(reverse) = patch.apply_redo(content, tokens, spans) else {
+            self.clear();
+            return None;
+        };
+        self.nodes[index].patch = Some(reverse.clone());
+        self.current = child;
+        Some(reverse)
+    }
+
+    /// Whether this is a tree the rest of `History` can walk: states in id
+    /// order, the oldest with no parent and every other edited from an older
+    /// one that is kept, and the current state among them. A saved history
+    /// edited by hand may not be.
+    pub(crate) fn is_well_formed(&self) -> bool {
+        let Some((root, rest)) = self.nodes.split_first() else {
+            return false;
+        };
+        root.parent.is_none()
+            && root.patch.is_none()
+            && self.nodes.windows(2).all(|pair| pair[0].id < pair[1].id)
+            && self.next_id > self.nodes[self.nodes.len() - 1].id.0
+            && rest.iter().all(|node| {
+                node.patch.is_some()
+                    && node
+                        .parent
+                        .is_some_and(|parent| parent < node.id && self.contains(parent))
+            })
+            && self.nodes.iter().all(|node| {
+                node.redo_child.is_none_or(|child| {
+                    self.node_opt(child)
+                        .is_some_and(|child| child.parent == Some(node.id))
+                })
+            })
+            && self.contains(self.current)
+    }
+
+    /// Whether `id` is a state that is kept.
+    pub(crate) fn contains(&self, id: HistoryId) -> bool {
+        self.index(id).is_some()
+    }
+
+    /// Undoes and redoes until `target`, which must be kept and not current,
+    /// is current. Returns where the last patch applied starts, or `None` if
+    /// a patch did not fit — leaving the content part of the way there.
+    pub(crate) fn jump(
+        &mut self,
+        target: HistoryId,
+        content: &mut String,
+        tokens: &mut Vec<Token>,
+        spans: &mut Vec<AttributeSpan>,
+    ) -> Option<usize> {
+        let mut path: Vec<HistoryId> = std::iter::once(target)
+            .chain(self.ancestors(target))
+            .collect();
+        let mut last = None;
+        while !path.contains(&self.current) {
+            last = Some(self.undo(content, tokens, spans)?.range.start);
+        }
+        // `path` runs from `target` up; redo walks the part below `current`
+        // down again.
+        let meet = path.iter().position(|id| *id == self.current)?;
+        path.truncate(meet);
+        for id in path.into_iter().rev() {
+            self.node_mut(self.current).redo_child = Some(id);
+            last = Some(self.redo(content, tokens, spans)?.range.start);
+        }
+        last
+    }
+
+    /// Every state, oldest first.
+    pub(crate) fn states(&self) -> Vec<HistoryState> {
+        self.nodes
+            .iter()
+            .map(|node| HistoryState {
+                id: node.id,
+                parent: node.parent,
+                is_current: node.id == self.current,
+            })
+            .collect()
+    }
+
+    /// Drops the oldest states while there are more than
+    /// [`MAX_HISTORY_LEN`] patches.
+    fn trim(&mut self) {
+        while self.nodes.len() > MAX_HISTORY_LEN + 1 {
+            let root = self.nodes[0].id;
+            let Some(new_root) = std::iter::once(self.current)
+                .chain(self.ancestors(self.current))
+                .find(|id| self.node(*id).parent == Some(root))
+            else {
+                return;
+            };
+            let kept = self.descendants(new_root);
+            self.nodes.retain(|node| kept.contains(&node.id));
+            let node = self.node_mut(new_root);
+            node.parent = None;
+            node.patch = None;
+        }
+    }
+
+    /// `id` and every state below it. A state is always made after the one
+    /// it was edited from, so one pass in id order finds them all.
+    fn descendants(&self, id: HistoryId) -> HashSet<HistoryId> {
+        let mut found = HashSet::from([id]);
+        for node in &self.nodes {
+            if node.parent.is_some_and(|parent| found.contains(&parent)) {
+                found.insert(node.id);
+            }
+        }
+        found
+    }
+
+    fn ancestors(&self, id: HistoryId) -> impl Iterator<Item = HistoryId> + '_ {
+        std::iter::successors(self.node_opt(id).and_then(|node| node.parent), |id| {
+            self.node_opt(*id).and_then(|node| node.parent)
+        })
+    }
+
+    /// Where `id` is in `nodes`, which are always in id order.
+    fn index(&self, id: HistoryId) -> Option<usize> {
+        self.nodes.binary_search_by_key(&id, |node| node.id).ok()
+    }
+
+    fn node_opt(&self, id: HistoryId) -> Option<&Node> {
+        self.index(id).map(|index| &self.nodes[index])
+    }
+
+    fn node(&self, id: HistoryId) -> &Node {
+        self.node_opt(id).expect("history state is kept")
+    }
+
+    fn node_mut(&mut self, id: HistoryId) -> &mut Node {
+        let index = self.index(id).expect("history state is kept");
+        &mut self.nodes[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An edit of `content`: `range` becomes `text`, recorded in `history`.
+    fn edit(history: &mut History, content: &mut String, range: Range<usize>, text: &str) {
+        history.record(HistoryEntry {
+            range: range.start..range.start + text.len(),
+            old_text: content[range.clone()].to_string(),
+            new_text_len: text.len(),
+            selected_range: range.clone(),
+            selection_reversed: false,
+            extra_selections: Vec::new(),
+            tokens: Vec::new(),
+            attributes: Vec::new(),
+            restyle: false,
+            timestamp: None,
+        });
+        content.replace_range(range, text);
+    }
+
+    #[test]
+    fn a_line_drops_redo_on_a_new_edit() {
+        let mut history = History::default();
+        let mut content = String::new();
+        edit(&mut history, &mut content, 0..0, "a");
+        edit(&mut history, &mut content, 1..1, "b");
+        history.undo(&mut content, &mut Vec::new(), &mut Vec::new());
+        edit(&mut history, &mut content, 1..1, "c");
+        assert_eq!(content, "ac");
+        assert!(!history.can_redo());
+        assert_eq!(history.states().len(), 3);
+    }
+
+    #[test]
+    fn a_tree_keeps_the_branch_and_jumps_across() {
+        let mut history = History::default();
+        history.set_branching(true);
+        let mut content = String::new();
+        edit(&mut history, &mut content, 0..0, "a");
+        edit(&mut history, &mut content, 1..1, "b");
+        let ab = history.current();
+        history.undo(&mut content, &mut Vec::new(), &mut Vec::new());
+        edit(&mut history, &mut content, 1..1, "c");
+        assert_eq!(content, "ac");
+        assert_eq!(history.states().len(), 4);
+
+        history
+            .jump(ab, &mut content, &mut Vec::new(), &mut Vec::new())
+            .unwrap();
+        assert_eq!(content, "ab");
+        history.undo(&mut content, &mut Vec::new(), &mut Vec::new());
+        history.redo(&mut content, &mut Vec::new(), &mut Vec::new());
+        assert_eq!(content, "ab", "redo follows the branch visited last");
+    }
+
+    #[test]
+    fn a_patch_that_does_not_fit_empties_the_history() {
+        let mut history = History::default();
+        let mut content = String::new();
+        edit(&mut history, &mut content, 0..0, "héllo");
+        let mut other = String::from("h");
+        assert!(history
+            .undo(&mut other, &mut Vec::new(), &mut Vec::new())
+            .is_none());
+        assert_eq!(other, "h");
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn a_history_that_is_not_a_tree_is_not_well_formed() {
+        let mut history = History::default();
+        let mut content = String::new();
+        edit(&mut history, &mut content, 0..0, "a");
+        edit(&mut history, &mut content, 1..1, "b");
+        assert!(history.is_well_formed());
+
+        let mut orphaned = history.clone();
+        orphaned.nodes.remove(1);
+        assert!(!orphaned.is_well_formed());
+
+        let mut lost = history.clone();
+        lost.current = HistoryId(7);
+        assert!(!lost.is_well_formed());
+    }
+
+    #[test]
+    fn history_is_trimmed_from_the_oldest() {
+        let mut history = History::default();
+        let mut content = String::new();
+        for _ in 0..MAX_HISTORY_LEN + 5 {
+            let end = content.len();
+            edit(&mut history, &mut content, end..end, "x");
+        }
+        assert_eq!(history.states().len(), MAX_HISTORY_LEN + 1);
+        while history
+            .undo(&mut content, &mut Vec::new(), &mut Vec::new())
+            .is_some()
+        {}
+        assert_eq!(content.len(), 5);
+    }
+}
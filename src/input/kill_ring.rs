@@ -0,0 +1,95 @@
+//! The kill ring: text taken by the readline kill actions, for yanking back.
+//!
+//! One ring serves every input in the app, as Emacs's serves every buffer, so
+//! text killed in one field can be yanked into another. It is not the system
+//! clipboard — `cmd-c` and `cmd-v` go on working as they did.
+//!
+//! Kills made one after another without moving the caret in between join up
+//! into a single entry, so that `ctrl-k ctrl-k` takes a line and its newline
+//! together and `ctrl-y` puts both back. [`YankPop`](super::bindings::YankPop),
+//! straight after a yank, swaps the yanked text for the entry before it.
+//!
+//! The ring is a gpui global, there once anything has been killed:
+//! `cx.try_global::<KillRing>()` reads it.
+
+use std::collections::VecDeque;
+
+use gpui::Global;
+
+/// How many kills the ring keeps before dropping the oldest.
+const MAX_KILLS: usize = 60;
+
+/// Killed text, newest first.
+#[derive(Debug, Default)]
+pub struct KillRing {
+    entries: VecDeque<String>,
+}
+
+impl Global for KillRing {}
+
+impl KillRing {
+    /// Records `text` as killed. With `join` set, it joins the newest entry
+    /// instead: after it for a kill forward, before it for a kill backward.
+    pub fn kill(&mut self, text: String, join: Option<Join>) {
+        match (join, self.entries.front_mut()) {
+            (Some(Join::After), Some(newest)) => newest.push_str(&text),
+            (Some(Join::Before), Some(newest)) => newest.insert_str(0, &text),
+            _ => {
+                self.entries.push_front(text);
+                self.entries.truncate(MAX_KILLS);
+            }
+        }
+    }
+
+    /// The entry `back` kills before the newest, counting round again past
+    /// the oldest. `None` while nothing has been killed.
+    pub fn get(&self, back: usize) -> Option<&str> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        self.entries
+            .get(back % self.entries.len())
+            .map(String::as_str)
+    }
+}
+
+/// Which end of the newest kill another kill joins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Join {
+    /// The text killed came after the caret.
+    After,
+    /// The text killed came before the caret.
+    Before,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kills_join_up_or_start_an_entry() {
+        let mut ring = KillRing::default();
+        assert_eq!(ring.get(0), None);
+
+        ring.kill("line".into(), None);
+        ring.kill("\n".into(), Some(Join::After));
+        ring.kill("word ".into(), Some(Join::Before));
+        assert_eq!(ring.get(0), Some("word line\n"));
+
+        ring.kill("other".into(), None);
+        assert_eq!(ring.get(0), Some("other"));
+        assert_eq!(ring.get(1), Some("word line\n"));
+        // Round again past the oldest.
+        assert_eq!(ring.get(2), Some("other"));
+    }
+
+    #[test]
+    fn the_oldest_kills_drop_off() {
+        let mut ring = KillRing::default();
+        for index in 0..MAX_KILLS + 5 {
+            ring.kill(index.to_string(), None);
+        }
+        assert_eq!(ring.get(0), Some("64"));
+        assert_eq!(ring.get(MAX_KILLS - 1), Some("5"));
+    }
+}
@@ -0,0 +1,437 @@
+//! Input masks: a fixed shape for what an input holds, such as a phone number,
+//! a card number, a one-time code or an IP address.
+//!
+//! A masked [`InputState`](super::InputState) keeps two strings apart. The
+//! *raw* value is what was typed: `5551234567`. The *content* is what is
+//! shown: `(555) 123-4567`. Every edit is made to the raw value and the content
+//! is formatted again from it, so:
+//!
+//! - a character that fits no slot is refused, and one typed past the last
+//!   slot is dropped;
+//! - pasting `(555) 123-4567` or `555.123.4567` into a phone mask both land
+//!   as the same ten digits;
+//! - the cursor moves over separators as though they were not there — typing
+//!   the third digit of `(555` puts the cursor after `(555) `, and backspace
+//!   just after a separator deletes the digit before it;
+//! - [`InputState::raw_value`](super::InputState::raw_value) reads the value
+//!   without its separators, which is what a form wants to submit.
+//!
+//! The unfilled rest of the template — `___-____` — is drawn after the content
+//! in the placeholder colour by `elements::input`, so the shape of what is
+//! expected is visible before it is typed. That is the triage document's
+//! "one field with a segmented mask" for one-time codes: one input, so paste,
+//! selection, screen readers and password managers all see one value.
+//!
+//! # Built-in masks
+//!
+//! | mask                            | shows                 |
+//! |---------------------------------|-----------------------|
+//! | [`PatternMask::new`]`("99/99")` | any fixed pattern     |
+//! | [`PatternMask::phone`]          | `(555) 123-4567`      |
+//! | [`PatternMask::otp`]`(6)`       | `123456`              |
+//! | [`CreditCardMask`]              | `4242 4242 4242 4242` |
+//! | [`Ipv4Mask`]                    | `192.168.0.1`         |
+//!
+//! Anything else implements [`InputMask`].
+//!
+//! Masks format single-line input; a mask on a multiline input still
+//! constrains what it holds, but its slots are not drawn.
+
+/// How a masked input turns typed text into a value, and a value into text.
+///
+/// `raw` strings — the value, without separators — go in and out of both
+/// methods; see the [module docs](self) for what the input does with them.
+pub trait InputMask: 'static {
+    /// The longest prefix of the characters in `raw` that this mask can hold,
+    /// with every character that fits no slot dropped. Called with what was
+    /// there before an edit and what was typed or pasted, spliced together,
+    /// so it must not treat any character as a separator.
+    fn accept(&self, raw: &str) -> String;
+
+    /// The value a whole piece of formatted text stands for: what
+    /// [`set_content`](super::InputState::set_content), or a paste over the
+    /// entire content, is read through. Unlike [`accept`](Self::accept), a
+    /// separator in `text` matching one in the mask may be skipped as a
+    /// separator rather than read as a value character.
+    ///
+    /// The default is [`accept`](Self::accept), which is right for a mask
+    /// whose separators are never value characters.
+    fn unformat(&self, text: &str) -> String {
+        self.accept(text)
+    }
+
+    /// How `raw`, a value [`accept`](Self::accept) returned, is shown.
+    fn format(&self, raw: &str) -> Masked;
+}
+
+/// A value as a mask shows it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Masked {
+    /// The content: the value with its separators.
+    pub text: String,
+    /// The byte offset in `text` of each character of the value, in order.
+    pub raw_offsets: Vec<usize>,
+    /// The template's unfilled remainder, drawn after `text`: `___-____`.
+    /// Empty for a mask with no fixed length.
+    pub slots: String,
+}
+
+impl Masked {
+    /// Text in which every character is a value character.
+    fn plain(text: &str) -> Self {
+        Masked {
+            text: text.to_string(),
+            raw_offsets: text.char_indices().map(|(offset, _)| offset).collect(),
+            slots: String::new(),
+        }
+    }
+
+    /// How many value characters come before byte offset `offset` of
+    /// [`text`](Self::text).
+    pub fn raw_index(&self, offset: usize) -> usize {
+        self.raw_offsets.partition_point(|raw| *raw < offset)
+    }
+
+    /// Where the cursor goes when `index` value characters are before it:
+    /// just before the next value character, so after any separators that
+    /// lead up to it, or at the end.
+    pub fn offset_for_raw_index(&self, index: usize) -> usize {
+        self.raw_offsets
+            .get(index)
+            .copied()
+            .unwrap_or(self.text.len())
+    }
+}
+
+/// What one slot of a [`PatternMask`] takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    /// `9`: an ASCII digit.
+    Digit,
+    /// `a`: a letter.
+    Letter,
+    /// `*`: a letter or an ASCII digit.
+    Alphanumeric,
+}
+
+impl Slot {
+    fn fits(&self, c: char) -> bool {
+        match self {
+            Slot::Digit => c.is_ascii_digit(),
+            Slot::Letter => c.is_alphabetic(),
+            Slot::Alphanumeric => c.is_alphabetic() || c.is_ascii_digit(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Slot(Slot),
+    Literal(char),
+}
+
+/// A mask with a fixed pattern: `9` is a digit, `a` a letter, `*` either, and
+/// anything else is a separator shown as written. `\` makes the next
+/// character a separator, so `\9` shows a literal `9`.
+///
+/// Separators up to the next slot are shown as soon as the slot before them is
+/// filled — `(555) ` rather than `(555` — so the cursor is always where the
+/// next character will land.
+///
+/// ```ignore
+/// let expiry = cx.new(|cx| InputState::new_singleline(cx).mask(PatternMask::new("99/99")));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternMask {
+    tokens: Vec<Token>,
+    slot_char: char,
+}
+
+impl PatternMask {
+    /// A mask for `pattern`. See the type docs for its syntax.
+    pub fn new(pattern: &str) -> Self {
+        let mut tokens = Vec::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            tokens.push(match c {
+                '9' => Token::Slot(Slot::Digit),
+                'a' => Token::Slot(Slot::Letter),
+                '*' => Token::Slot(Slot::Alphanumeric),
+                '\\' => Token::Literal(chars.next().unwrap_or('\\')),
+                c => Token::Literal(c),
+            });
+        }
+        Self {
+            tokens,
+            slot_char: '_',
+        }
+    }
+
+    /// A North American phone number: `(555) 123-4567`.
+    pub fn phone() -> Self {
+        Self::new("(999) 999-9999")
+    }
+
+    /// A one-time code of `digits` digits, each unfilled one shown as `•`.
+    pub fn otp(digits: usize) -> Self {
+        Self::new(&"9".repeat(digits)).slot_char('•')
+    }
+
+    /// The character an unfilled slot is drawn as. `_` by default.
+    pub fn slot_char(mut self, slot_char: char) -> Self {
+        self.slot_char = slot_char;
+        self
+    }
+
+    /// How many value characters the pattern holds.
+    pub fn capacity(&self) -> usize {
+        self.tokens
+            .iter()
+            .filter(|token| matches!(token, Token::Slot(_)))
+            .count()
+    }
+
+    fn slots(&self) -> impl Iterator<Item = Slot> + '_ {
+        self.tokens.iter().filter_map(|token| match token {
+            Token::Slot(slot) => Some(*slot),
+            Token::Literal(_) => None,
+        })
+    }
+}
+
+impl InputMask for PatternMask {
+    fn accept(&self, raw: &str) -> String {
+        let mut slots = self.slots().peekable();
+        let mut accepted = String::new();
+        for c in raw.chars() {
+            let Some(slot) = slots.peek() else {
+                break;
+            };
+            if slot.fits(c) {
+                accepted.push(c);
+                slots.next();
+            }
+        }
+        accepted
+    }
+
+    fn unformat(&self, text: &str) -> String {
+        let mut tokens = self.tokens.iter().peekable();
+        let mut raw = String::new();
+        'chars: for c in text.chars() {
+            while let Some(token) = tokens.peek() {
+                match token {
+                    Token::Literal(literal) => {
+                        tokens.next();
+                        if *literal == c {
+                            continue 'chars;
+                        }
+                    }
+                    Token::Slot(slot) => {
+                        if slot.fits(c) {
+                            raw.push(c);
+                            tokens.next();
+                        }
+                        continue 'chars;
+                    }
+                }
+            }
+            break;
+        }
+        raw
+    }
+
+    fn format(&self, raw: &str) -> Masked {
+        let mut masked = Masked::default();
+        let mut raw = raw.chars().peekable();
+        let mut tokens = self.tokens.iter();
+        let mut pending = String::new();
+        for token in tokens.by_ref() {
+            match token {
+                Token::Literal(literal) => pending.push(*literal),
+                Token::Slot(_) => {
+                    let Some(c) = raw.next() else {
+                        masked.slots.push_str(&pending);
+                        masked.slots.push(self.slot_char);
+                        break;
+                    };
+                    masked.text.push_str(&pending);
+                    pending.clear();
+                    masked.raw_offsets.push(masked.text.len());
+                    masked.text.push(c);
+                    if raw.peek().is_none() {
+                        // Show the separators up to the next slot now, so
+                        // the cursor sits where the next character lands.
+                        for token in tokens.by_ref() {
+                            match token {
+                                Token::Literal(literal) => masked.text.push(*literal),
+                                Token::Slot(_) => {
+                                    masked.slots.push(self.slot_char);
+                                    break;
+                                }
+                            }
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+        for token in tokens {
+            masked.slots.push(match token {
+                Token::Literal(literal) => *literal,
+                Token::Slot(_) => self.slot_char,
+            });
+        }
+        masked
+    }
+}
+
+/// A payment card number, grouped as the card's issuer prints it: four groups
+/// of four, or 4-6-5 for American Express (numbers starting `34` or `37`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CreditCardMask;
+
+impl CreditCardMask {
+    fn pattern(raw: &str) -> PatternMask {
+        if raw.starts_with("34") || raw.starts_with("37") {
+            PatternMask::new("9999 999999 99999")
+        } else {
+            PatternMask::new("9999 9999 9999 9999")
+        }
+    }
+}
+
+impl InputMask for CreditCardMask {
+    fn accept(&self, raw: &str) -> String {
+        let digits: String = raw.chars().filter(char::is_ascii_digit).collect();
+        Self::pattern(&digits).accept(&digits)
+    }
+
+    fn format(&self, raw: &str) -> Masked {
+        Self::pattern(raw).format(raw)
+    }
+}
+
+/// A dotted IPv4 address.
+///
+/// Unlike a [`PatternMask`], the dots are part of the value — `1.2.3.4` and
+/// `100.200.30.4` are both four groups — so they are typed, and a fourth digit
+/// in a group, or a digit that would take it past 255, starts the next group
+/// with the dot filled in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ipv4Mask;
+
+impl InputMask for Ipv4Mask {
+    fn accept(&self, raw: &str) -> String {
+        let mut accepted = String::new();
+        let mut groups = 1;
+        let mut group = String::new();
+        for c in raw.chars() {
+            match c {
+                '.' if !group.is_empty() && groups < 4 => {
+                    accepted.push('.');
+                    groups += 1;
+                    group.clear();
+                }
+                '0'..='9' => {
+                    let mut grown = group.clone();
+                    grown.push(c);
+                    if grown.len() <= 3 && grown.parse::<u16>().is_ok_and(|value| value <= 255) {
+                        group = grown;
+                    } else if groups < 4 {
+                        accepted.push('.');
+                        groups += 1;
+                        group = c.to_string();
+                    } else {
+                        break;
+                    }
+                    accepted.push(c);
+                }
+                _ => {}
+            }
+        }
+        accepted
+    }
+
+    fn format(&self, raw: &str) -> Masked {
+        Masked::plain(raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_pattern_shows_separators_up_to_the_next_slot() {
+        let phone = PatternMask::phone();
+        assert_eq!(phone.format("").text, "");
+        assert_eq!(phone.format("").slots, "(___) ___-____");
+        assert_eq!(phone.format("5").text, "(5");
+        assert_eq!(phone.format("555").text, "(555) ");
+        assert_eq!(phone.format("555").slots, "___-____");
+        let full = phone.format("5551234567");
+        assert_eq!(full.text, "(555) 123-4567");
+        assert_eq!(full.slots, "");
+        assert_eq!(full.raw_offsets, vec![1, 2, 3, 6, 7, 8, 10, 11, 12, 13]);
+    }
+
+    #[test]
+    fn accept_drops_what_fits_no_slot_and_stops_when_full() {
+        let phone = PatternMask::phone();
+        assert_eq!(phone.accept("(555) 123-4567"), "5551234567");
+        assert_eq!(phone.accept("555x1234567890"), "5551234567");
+        assert_eq!(PatternMask::new("aa-99").accept("1a2b3c4"), "ab34");
+    }
+
+    /// A separator that is also a value character is skipped as a separator
+    /// only where the pattern has it.
+    #[test]
+    fn unformat_reads_separators_in_place() {
+        let mask = PatternMask::new("+1 (999) 999-9999");
+        assert_eq!(mask.unformat("+1 (555) 123-4567"), "5551234567");
+        assert_eq!(mask.accept("15551234567"), "1555123456");
+        assert_eq!(mask.unformat(&mask.format("1555").text), "1555");
+    }
+
+    #[test]
+    fn an_escaped_slot_character_is_a_separator() {
+        let mask = PatternMask::new("\\9-9");
+        assert_eq!(mask.format("4").text, "9-4");
+        assert_eq!(mask.capacity(), 1);
+    }
+
+    #[test]
+    fn cursor_offsets_skip_separators() {
+        let masked = PatternMask::phone().format("5551");
+        assert_eq!(masked.text, "(555) 1");
+        assert_eq!(masked.offset_for_raw_index(0), 1);
+        assert_eq!(masked.offset_for_raw_index(3), 6);
+        assert_eq!(masked.offset_for_raw_index(4), masked.text.len());
+        assert_eq!(masked.raw_index(6), 3);
+        assert_eq!(masked.raw_index(4), 3);
+    }
+
+    #[test]
+    fn a_card_is_grouped_by_issuer() {
+        let mask = CreditCardMask;
+        assert_eq!(
+            mask.format(&mask.accept("4242424242424242999")).text,
+            "4242 4242 4242 4242"
+        );
+        assert_eq!(
+            mask.format(&mask.accept("378282246310005")).text,
+            "3782 822463 10005"
+        );
+    }
+
+    #[test]
+    fn an_address_fills_in_its_dots() {
+        let mask = Ipv4Mask;
+        assert_eq!(mask.accept("192168001"), "192.168.001");
+        assert_eq!(mask.accept("10.0.0.1"), "10.0.0.1");
+        assert_eq!(mask.accept("300"), "30.0");
+        assert_eq!(mask.accept("1.2.3.4.5"), "1.2.3.45");
+        assert_eq!(mask.accept("..1x"), "1");
+    }
+}
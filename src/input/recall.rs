@@ -0,0 +1,252 @@
+//! Shell-style history for single-line fields: Up and Down step back through
+//! what was submitted before.
+//!
+//! Give an [`InputState`](super::InputState) a [`RecallHistory`] with
+//! [`recall`](super::InputState::recall) and every
+//! [`Submit`](super::InputStateEvent::Submit) records the content. In a
+//! single-line input, Up then replaces the content with the submission before
+//! the one shown, and Down with the one after; Down past the newest puts back
+//! the draft — whatever was in the field when Up was first pressed.
+//!
+//! The draft also filters: only submissions that start with it are stepped
+//! through, so typing `git` and pressing Up finds the last `git` command. An
+//! empty draft matches everything. Editing a recalled entry makes it the
+//! draft for the next Up.
+//!
+//! A multiline input records submissions but leaves Up and Down to move
+//! between lines, and a [secure](super::InputState::secure) input records
+//! nothing.
+//!
+//! # Popups on the same keys
+//!
+//! `Combobox`, `Command` and `Mentions` bind Up and Down under their own
+//! context, after `bind_input_keys`, so while their popup has a use for the
+//! keys the field never sees them. Only a key they hand back — a closed
+//! `Command` palette's, or a `Mentions` field with nothing to suggest — comes
+//! through to recall.
+//!
+//! # Saving
+//!
+//! A `RecallHistory` is a serde value: write
+//! [`recall_history`](super::InputState::recall_history) out when the app
+//! closes and pass it back to [`recall`](super::InputState::recall) when it
+//! opens. Which entry is being shown is not saved.
+
+use serde::{Deserialize, Serialize};
+
+/// How many submissions a history keeps unless told otherwise.
+const DEFAULT_LIMIT: usize = 100;
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+/// Submitted text, for Up and Down to recall. See the [module docs](self).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecallHistory {
+    /// Oldest first, with no two the same.
+    entries: Vec<String>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+    #[serde(skip)]
+    walk: Option<Walk>,
+}
+
+/// Where Up and Down have got to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Walk {
+    /// The entry shown.
+    index: usize,
+    /// What was in the field before the first Up, and the prefix entries are
+    /// filtered on.
+    draft: String,
+}
+
+impl Default for RecallHistory {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            limit: DEFAULT_LIMIT,
+            walk: None,
+        }
+    }
+}
+
+impl RecallHistory {
+    /// An empty history keeping the last hundred submissions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps the last `limit` submissions rather than a hundred.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self.truncate();
+        self
+    }
+
+    /// The submissions, oldest first.
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Records `text` as the newest submission. Empty text is skipped, and
+    /// text submitted before moves up to newest rather than appearing twice.
+    pub fn push(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        self.walk = None;
+        if text.is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| *entry != text);
+        self.entries.push(text);
+        self.truncate();
+    }
+
+    /// Forgets every submission.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.walk = None;
+    }
+
+    /// Whether the field is showing a recalled entry rather than a draft.
+    pub fn is_recalling(&self) -> bool {
+        self.walk.is_some()
+    }
+
+    fn truncate(&mut self) {
+        let excess = self.entries.len().saturating_sub(self.limit);
+        self.entries.drain(..excess);
+        self.walk = None;
+    }
+
+    /// Ends the walk if `shown` is no longer the entry it left in the field:
+    /// the field was edited, or set from outside.
+    fn check_walk(&mut self, shown: &str) {
+        if let Some(walk) = &self.walk {
+            if self.entries.get(walk.index).map(String::as_str) != Some(shown) {
+                self.walk = None;
+            }
+        }
+    }
+
+    /// The next older entry to show in place of `shown`, if any.
+    pub(crate) fn older(&mut self, shown: &str) -> Option<&str> {
+        self.check_walk(shown);
+        let (from, draft) = match self.walk.take() {
+            Some(walk) => (walk.index, walk.draft),
+            None => (self.entries.len(), shown.to_string()),
+        };
+        let found = self.entries[..from]
+            .iter()
+            .rposition(|entry| entry.starts_with(&draft) && entry != shown);
+        match found {
+            Some(index) => {
+                self.walk = Some(Walk { index, draft });
+                Some(&self.entries[index])
+            }
+            None => {
+                // Nothing older: stay on the entry shown, if it was one.
+                if from < self.entries.len() {
+                    self.walk = Some(Walk { index: from, draft });
+                }
+                None
+            }
+        }
+    }
+
+    /// The next newer entry to show in place of `shown` — or, past the
+    /// newest, the draft. `None` when no entry is being shown.
+    pub(crate) fn newer(&mut self, shown: &str) -> Option<String> {
+        self.check_walk(shown);
+        let walk = self.walk.take()?;
+        let found = self.entries[walk.index + 1..]
+            .iter()
+            .position(|entry| entry.starts_with(&walk.draft) && entry != shown);
+        match found {
+            Some(offset) => {
+                let index = walk.index + 1 + offset;
+                self.walk = Some(Walk {
+                    index,
+                    draft: walk.draft,
+                });
+                Some(self.entries[index].clone())
+            }
+            None => Some(walk.draft),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(entries: &[&str]) -> RecallHistory {
+        let mut history = RecallHistory::new();
+        for entry in entries {
+            history.push(*entry);
+        }
+        history
+    }
+
+    #[test]
+    fn up_and_down_walk_back_and_return_to_the_draft() {
+        let mut history = history(&["one", "two", "three"]);
+        assert_eq!(history.older(""), Some("three"));
+        assert_eq!(history.older("three"), Some("two"));
+        assert_eq!(history.older("two"), Some("one"));
+        assert_eq!(history.older("one"), None);
+        assert!(history.is_recalling());
+
+        assert_eq!(history.newer("one").as_deref(), Some("two"));
+        assert_eq!(history.newer("two").as_deref(), Some("three"));
+        assert_eq!(history.newer("three").as_deref(), Some(""));
+        assert!(!history.is_recalling());
+        assert_eq!(history.newer(""), None);
+    }
+
+    #[test]
+    fn the_draft_filters_by_prefix() {
+        let mut history = history(&["git status", "ls", "git push", "cargo test"]);
+        assert_eq!(history.older("git"), Some("git push"));
+        assert_eq!(history.older("git push"), Some("git status"));
+        assert_eq!(history.older("git status"), None);
+        assert_eq!(history.newer("git status").as_deref(), Some("git push"));
+        assert_eq!(history.newer("git push").as_deref(), Some("git"));
+    }
+
+    #[test]
+    fn editing_a_recalled_entry_starts_a_new_walk() {
+        let mut history = history(&["cargo build", "cargo test", "ls"]);
+        assert_eq!(history.older(""), Some("ls"));
+        assert_eq!(history.older("ls"), Some("cargo test"));
+        // Typed over: the edited text is the new draft.
+        assert_eq!(history.older("cargo"), Some("cargo test"));
+        assert_eq!(history.newer("cargo test").as_deref(), Some("cargo"));
+    }
+
+    #[test]
+    fn repeats_move_to_newest_and_the_oldest_drop_off() {
+        let history = history(&["a", "b", "", "a"]);
+        assert_eq!(history.entries(), ["b", "a"]);
+
+        let mut history = history.limit(3);
+        history.push("c");
+        history.push("d");
+        assert_eq!(history.entries(), ["a", "c", "d"]);
+    }
+
+    #[test]
+    fn saves_entries_but_not_the_walk() {
+        let mut history = history(&["one", "two"]).limit(5);
+        history.older("");
+        let saved = serde_json::to_string(&history).unwrap();
+        let restored: RecallHistory = serde_json::from_str(&saved).unwrap();
+        assert_eq!(restored.entries(), ["one", "two"]);
+        assert!(!restored.is_recalling());
+        assert_eq!(restored, history.limit(5));
+
+        let old: RecallHistory = serde_json::from_str(r#"{"entries":["x"]}"#).unwrap();
+        assert_eq!(old.limit, DEFAULT_LIMIT);
+    }
+}
@@ -0,0 +1,240 @@
+//! Find and replace within an input's content.
+//!
+//! [`set_search`](super::InputState::set_search) gives an
+//! [`InputState`](super::InputState) a [`SearchQuery`], and from then on it
+//! keeps the query's matches up to date with every edit, undo and redo.
+//! `elements::input` paints each match behind the text, and the one that is
+//! also the selection in the selection colour over it.
+//!
+//! A query matches in one of three ways, as its [`SearchMode`] says: the text
+//! exactly, the text ignoring case, or the text as a regular expression, in
+//! which `^` and `$` are the ends of a line and a replacement may name the
+//! pattern's groups — `$1`, `${name}`. A match is never empty: a pattern like
+//! `a*` finds the runs of `a` and nothing in between.
+//!
+//! [`find_next`](super::InputState::find_next) and
+//! [`find_previous`](super::InputState::find_previous) — bound to
+//! [`FindNext`](super::bindings::FindNext) and
+//! [`FindPrevious`](super::bindings::FindPrevious) — select the next match
+//! after the selection, or the one before it, wrapping round at the ends, and
+//! scroll it into view.
+//!
+//! [`replace_next`](super::InputState::replace_next) replaces the selected
+//! match and selects the next one; [`replace_all`](super::InputState::replace_all)
+//! replaces every match at once. Either is a single undo step, and each
+//! replacement passes the input's [validation](super::validation) as though
+//! typed. A masked input can be searched, but not replaced in — its content is
+//! held to the mask's shape — and a [secure](super::InputState::secure) input
+//! has no matches at all.
+
+use std::fmt;
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+
+/// How a [`SearchQuery`] matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    /// The text exactly as written.
+    #[default]
+    Literal,
+    /// The text, ignoring case.
+    CaseInsensitive,
+    /// The text as a regular expression, in the syntax of the `regex` crate.
+    Regex,
+}
+
+/// What an input searches for. See the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    text: String,
+    mode: SearchMode,
+    regex: Regex,
+}
+
+impl SearchQuery {
+    /// A query for `text`, matched as `mode` says. Fails only for a
+    /// [`SearchMode::Regex`] pattern that does not parse.
+    pub fn new(text: impl Into<String>, mode: SearchMode) -> Result<Self, InvalidPattern> {
+        let text = text.into();
+        let pattern = match mode {
+            SearchMode::Regex => text.clone(),
+            SearchMode::Literal | SearchMode::CaseInsensitive => regex::escape(&text),
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(mode == SearchMode::CaseInsensitive)
+            .multi_line(true)
+            .build()
+            .map_err(|error| InvalidPattern {
+                message: error.to_string(),
+            })?;
+        Ok(Self { text, mode, regex })
+    }
+
+    /// A query for `text` exactly as written.
+    pub fn literal(text: impl Into<String>) -> Self {
+        Self::new(text, SearchMode::Literal).expect("an escaped pattern parses")
+    }
+
+    /// A query for `text`, ignoring case.
+    pub fn case_insensitive(text: impl Into<String>) -> Self {
+        Self::new(text, SearchMode::CaseInsensitive).expect("an escaped pattern parses")
+    }
+
+    /// A query for the regular expression `pattern`.
+    pub fn regex(pattern: impl Into<String>) -> Result<Self, InvalidPattern> {
+        Self::new(pattern, SearchMode::Regex)
+    }
+
+    /// The text or pattern, as given.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// How it matches.
+    pub fn mode(&self) -> SearchMode {
+        self.mode
+    }
+
+    /// Every non-empty match in `content`, in order. None for an empty query.
+    pub(crate) fn find_all(&self, content: &str) -> Vec<Range<usize>> {
+        if self.text.is_empty() {
+            return Vec::new();
+        }
+        self.regex
+            .find_iter(content)
+            .map(|found| found.range())
+            .filter(|range| !range.is_empty())
+            .collect()
+    }
+
+    /// What the match at `range` of `content` is replaced with: `replacement`
+    /// as it is, or for a regular expression with the groups it names filled
+    /// in from the match.
+    pub(crate) fn expand(&self, content: &str, range: &Range<usize>, replacement: &str) -> String {
+        if self.mode != SearchMode::Regex {
+            return replacement.to_string();
+        }
+        // Matched again where it was found, so that look-around and `^` see
+        // the content around it, not just the match.
+        match self.regex.captures_at(content, range.start) {
+            Some(captures) if captures.get_match().range() == *range => {
+                let mut expanded = String::new();
+                captures.expand(replacement, &mut expanded);
+                expanded
+            }
+            _ => replacement.to_string(),
+        }
+    }
+}
+
+impl PartialEq for SearchQuery {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text && self.mode == other.mode
+    }
+}
+
+impl Eq for SearchQuery {}
+
+/// A [`SearchMode::Regex`] pattern that does not parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidPattern {
+    /// What the parser said, pointing at where the pattern went wrong.
+    pub message: String,
+}
+
+impl fmt::Display for InvalidPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid search pattern: {}", self.message)
+    }
+}
+
+impl std::error::Error for InvalidPattern {}
+
+/// The first of `matches` that starts at or after `offset`, wrapping round to
+/// the first.
+pub(crate) fn next_match(matches: &[Range<usize>], offset: usize) -> Option<usize> {
+    if matches.is_empty() {
+        return None;
+    }
+    Some(matches.partition_point(|found| found.start < offset) % matches.len())
+}
+
+/// The last of `matches` that ends at or before `offset`, wrapping round to
+/// the last.
+pub(crate) fn previous_match(matches: &[Range<usize>], offset: usize) -> Option<usize> {
+    if matches.is_empty() {
+        return None;
+    }
+    let before = matches.partition_point(|found| found.end <= offset);
+    Some(before.checked_sub(1).unwrap_or(matches.len() - 1))
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)] // a slice of one match
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_mode_matches_as_it_says() {
+        let content = "Foo foo FOO f.o";
+        assert_eq!(SearchQuery::literal("foo").find_all(content), [4..7]);
+        assert_eq!(
+            SearchQuery::case_insensitive("foo").find_all(content),
+            [0..3, 4..7, 8..11]
+        );
+        assert_eq!(SearchQuery::literal("f.o").find_all(content), [12..15]);
+        assert_eq!(
+            SearchQuery::regex("f.o").unwrap().find_all(content),
+            [4..7, 12..15]
+        );
+    }
+
+    #[test]
+    fn case_insensitive_matches_keep_their_byte_ranges() {
+        // `ẞ` and `ß` differ in length, and `İ` before them would shift a
+        // search of lower-cased text; the match is where the content has it.
+        let content = "xİy straße";
+        assert_eq!(
+            SearchQuery::case_insensitive("STRAẞE").find_all(content),
+            [5..12]
+        );
+    }
+
+    #[test]
+    fn empty_queries_and_empty_matches_find_nothing() {
+        assert!(SearchQuery::literal("").find_all("abc").is_empty());
+        assert_eq!(SearchQuery::regex("a*").unwrap().find_all("baab"), [1..3]);
+    }
+
+    #[test]
+    fn a_pattern_that_does_not_parse_is_an_error() {
+        let error = SearchQuery::regex("(unclosed").unwrap_err();
+        assert!(error.to_string().starts_with("invalid search pattern"));
+    }
+
+    #[test]
+    fn regex_lines_and_groups() {
+        let content = "key = 1\nother = 2";
+        let query = SearchQuery::regex(r"^(\w+) = (\d)$").unwrap();
+        let matches = query.find_all(content);
+        assert_eq!(matches, [0..7, 8..17]);
+        assert_eq!(query.expand(content, &matches[1], "$2 => $1"), "2 => other");
+        assert_eq!(
+            SearchQuery::literal("key").expand(content, &(0..3), "$1"),
+            "$1"
+        );
+    }
+
+    #[test]
+    fn next_and_previous_wrap() {
+        let matches = [2..4, 6..8, 10..12];
+        assert_eq!(next_match(&matches, 0), Some(0));
+        assert_eq!(next_match(&matches, 4), Some(1));
+        assert_eq!(next_match(&matches, 11), Some(0));
+        assert_eq!(previous_match(&matches, 12), Some(2));
+        assert_eq!(previous_match(&matches, 6), Some(0));
+        assert_eq!(previous_match(&matches, 3), Some(2));
+        assert_eq!(next_match(&[], 0), None);
+    }
+}
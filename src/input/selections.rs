@@ -0,0 +1,180 @@
+//! More than one selection at once, for a multiline input that edits like a
+//! code editor.
+//!
+//! An [`InputState`](super::InputState) always has a *primary* selection — the
+//! one [`selected_range`](super::InputState::selected_range) reports, the one
+//! the view scrolls to and an IME composes at — and a multiline input can hold
+//! any number of others besides. They are added by:
+//!
+//! - **alt-click**, which adds a caret where it lands;
+//! - **alt-drag**, which replaces every selection with a column: one per
+//!   visual row between where the drag started and where it is, each spanning
+//!   the same horizontal extent, clamped to the end of a row that is shorter;
+//! - [`SelectNextOccurrence`](super::bindings::SelectNextOccurrence) —
+//!   `cmd-d`, or `ctrl-d` off macOS — which selects the word at a bare caret,
+//!   and with a selection adds the next occurrence of its text after it,
+//!   wrapping at the end.
+//!
+//! The newest selection is always the primary one. Escape, a plain click,
+//! select-all, or moving to the start or end of the text goes back to one.
+//!
+//! # Editing
+//!
+//! Every movement and selection action moves each selection on its own, and
+//! selections that come to overlap become one. Every edit — typing, paste,
+//! the deletes, Tab, Enter — is made at every selection, from the first to
+//! the last, and is recorded as a single undo step; undoing it brings every
+//! selection back. Each edit passes [validation](super::validation) on its
+//! own, against the text the edits before it have left, so a length limit is
+//! shared between them in document order.
+//!
+//! A paste whose lines are as many as the selections puts one line in each,
+//! and copy joins the selected texts with newlines, so the two round-trip.
+//!
+//! An IME composition only ever happens at the primary selection: starting
+//! one drops the others. A single-line or masked input never has more than
+//! one selection.
+
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+/// One selection: a range of the content, and which end the caret is at.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Selection {
+    /// The byte range selected. Empty for a bare caret.
+    pub range: Range<usize>,
+    /// Whether the caret is at `range.start` rather than `range.end`.
+    pub reversed: bool,
+}
+
+impl Selection {
+    /// A bare caret at `offset`.
+    pub fn caret(offset: usize) -> Self {
+        Self {
+            range: offset..offset,
+            reversed: false,
+        }
+    }
+
+    /// Where the caret is.
+    pub fn cursor(&self) -> usize {
+        if self.reversed {
+            self.range.start
+        } else {
+            self.range.end
+        }
+    }
+}
+
+/// Sorts `selections` into document order and makes any that overlap — or
+/// any two carets in the same place — into one. Returns where the selection
+/// that was at `primary` ended up.
+pub(crate) fn merge(selections: &mut Vec<Selection>, primary: usize) -> usize {
+    let mut indexed: Vec<(usize, Selection)> = selections.drain(..).enumerate().collect();
+    indexed.sort_by_key(|(_, selection)| (selection.range.start, selection.range.end));
+
+    let mut merged_primary = 0;
+    for (index, selection) in indexed {
+        let overlaps = selections.last().is_some_and(|last: &Selection| {
+            selection.range.start < last.range.end
+                || (selection.range.start == last.range.end
+                    && (selection.range.is_empty() || last.range.is_empty()))
+        });
+        if overlaps {
+            let last = selections.last_mut().unwrap();
+            last.range.end = last.range.end.max(selection.range.end);
+        } else {
+            selections.push(selection);
+        }
+        if index == primary {
+            merged_primary = selections.len() - 1;
+        }
+    }
+    merged_primary
+}
+
+/// The first occurrence of `needle` in `content` at or after `after`, wrapping
+/// round to the start, that overlaps none of `taken`.
+pub(crate) fn next_occurrence(
+    content: &str,
+    needle: &str,
+    after: usize,
+    taken: &[Selection],
+) -> Option<Range<usize>> {
+    if needle.is_empty() {
+        return None;
+    }
+    let free = |range: &Range<usize>| {
+        taken.iter().all(|selection| {
+            range.end <= selection.range.start || range.start >= selection.range.end
+        })
+    };
+    let after = after.min(content.len());
+    let wrapped = content[..after]
+        .match_indices(needle)
+        .map(|(start, _)| start);
+    content[after..]
+        .match_indices(needle)
+        .map(|(start, _)| start + after)
+        .chain(wrapped)
+        .map(|start| start..start + needle.len())
+        .find(free)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(selections: &[Selection]) -> Vec<Range<usize>> {
+        selections.iter().map(|s| s.range.clone()).collect()
+    }
+
+    #[test]
+    fn merging_sorts_and_joins_overlaps() {
+        let mut selections = vec![
+            Selection {
+                range: 8..10,
+                reversed: false,
+            },
+            Selection::caret(2),
+            Selection {
+                range: 9..12,
+                reversed: false,
+            },
+            Selection::caret(2),
+        ];
+        let primary = merge(&mut selections, 2);
+        assert_eq!(ranges(&selections), [2..2, 8..12]);
+        assert_eq!(primary, 1);
+    }
+
+    #[test]
+    fn selections_that_only_touch_stay_apart() {
+        let mut selections = vec![
+            Selection {
+                range: 0..3,
+                reversed: false,
+            },
+            Selection {
+                range: 3..6,
+                reversed: false,
+            },
+        ];
+        merge(&mut selections, 0);
+        assert_eq!(ranges(&selections), [0..3, 3..6]);
+    }
+
+    #[test]
+    fn the_next_occurrence_wraps_and_skips_what_is_taken() {
+        let content = "foo bar foo baz foo";
+        let taken = [Selection {
+            range: 8..11,
+            reversed: false,
+        }];
+        assert_eq!(next_occurrence(content, "foo", 11, &taken), Some(16..19));
+        let taken = [taken[0].clone(), Selection::caret(0)];
+        assert_eq!(next_occurrence(content, "foo", 19, &taken), Some(0..3));
+        assert_eq!(next_occurrence(content, "qux", 0, &taken), None);
+    }
+}
@@ -1,4 +1,5 @@
 use std::ops::Range;
+use std::rc::Rc;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -8,17 +9,33 @@ use gpui::{
     Window, WrappedLine,
 };
 
+use super::attributes::{self, Attribute, AttributeSpan};
 use super::blink::CursorBlink;
+use super::completion::{CompletionProvider, SharedCompletionProvider};
+use super::decorations::{self, Checker, Decoration, SharedChecker};
+use super::drag::{self, TextDrag};
 use super::handler::EntityInputHandler;
+use super::history::{History, HistoryEntry, HistoryId, HistoryState, SavedHistory};
+use super::kill_ring::{Join, KillRing};
+use super::mask::{InputMask, Masked};
+use super::recall::RecallHistory;
+use super::search::{self, SearchQuery};
+use super::selections::{self, Selection};
+use super::tokens::{self, Token, TokenizedText, TriggerQuery};
+use super::validation::{self, Edit, Rejection, Validation, Validator};
+use crate::markdown::{InlinePalette, InlineStyle, RichText};
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::bidi::{detect_base_direction, TextDirection};
 use super::bindings::{
     Backspace, Copy, Cut, Delete, DeleteToBeginningOfLine, DeleteToEndOfLine, DeleteWordLeft,
-    DeleteWordRight, Down, End, Enter, Home, InsertNewline, Left, MoveToBeginning, MoveToEnd,
-    Paste, Redo, Right, SelectAll, SelectDown, SelectLeft, SelectRight, SelectToBeginning,
-    SelectToEnd, SelectUp, SelectWordLeft, SelectWordRight, Submit, Tab, Undo, Up, WordLeft,
-    WordRight,
+    DeleteWordRight, Down, End, Enter, EnterInsertMode, EnterInsertModeAfter, EnterNormalMode,
+    FindNext, FindPrevious, Home, InsertNewline, KillToBeginningOfLine, KillToEndOfLine,
+    KillWordLeft, KillWordRight, Left, MoveToBeginning, MoveToEnd, NextCompletion, Paste,
+    PreviousCompletion, Redo, Right, SelectAll, SelectDown, SelectLeft, SelectNextOccurrence,
+    SelectRight, SelectToBeginning, SelectToEnd, SelectUp, SelectWordLeft, SelectWordRight, Submit,
+    Tab, ToggleBold, ToggleCode, ToggleItalic, Transpose, Undo, Up, WordLeft, WordRight, Yank,
+    YankPop,
 };
 
 /// Default interval for grouping consecutive edits into a single undo entry.
@@ -27,9 +44,6 @@ const DEFAULT_GROUP_INTERVAL: Duration = Duration::from_millis(300);
 /// Default interval for cursor blinking.
 const DEFAULT_BLINK_INTERVAL: Duration = Duration::from_millis(500);
 
-/// Maximum number of history entries to keep.
-const MAX_HISTORY_LEN: usize = 1000;
-
 /// Events emitted by InputState when significant changes occur.
 #[derive(Clone, Debug)]
 pub enum InputStateEvent {
@@ -47,6 +61,19 @@ pub enum InputStateEvent {
     /// submit mode ([`InputState::submit_on`]). The content is left in place —
     /// the subscriber decides whether to read and clear it.
     Submit,
+    /// Emitted when an edit was cut short or refused by the input's
+    /// [`char_filter`](InputState::char_filter), [`max_len`](InputState::max_len)
+    /// or [`validate`](InputState::validate) hook — see
+    /// [`validation`](super::validation). Whatever part of the edit survived
+    /// has been applied, with its own `TextChanged`.
+    Rejected(Rejection),
+    /// Emitted when the [attributes](super::attributes) over the text change
+    /// without the text changing: a toggle, or
+    /// [`set_attributes`](InputState::set_attributes).
+    AttributesChanged,
+    /// Emitted when the input switches between vim's normal and insert
+    /// modes — see [`InputState::vim_mode`].
+    VimModeChanged(VimMode),
 }
 
 impl EventEmitter<InputStateEvent> for InputState {}
@@ -66,52 +93,26 @@ pub enum SubmitOn {
     CmdEnter,
 }
 
-/// A patch-based history entry for memory-efficient undo/redo operations.
-/// Instead of storing the full content, we store only the change needed to reverse the edit.
-#[derive(Clone, Debug)]
-struct HistoryEntry {
-    /// The byte range that was modified (after the edit, for undo; before the edit, for redo).
-    range: Range<usize>,
-    /// The text that was replaced (to restore on undo).
-    old_text: String,
-    /// The length of the new text that replaced old_text (to know how much to remove on undo).
-    new_text_len: usize,
-    /// The selection range before the edit.
-    selected_range: Range<usize>,
-    /// Whether the selection was reversed before the edit.
-    selection_reversed: bool,
-    /// Timestamp for grouping consecutive edits.
-    timestamp: Instant,
+/// Which of vim's modes an input is in, for the
+/// [vim preset](super::InputBindings::vim). Every input is in insert mode
+/// until a binding sends it to normal mode; without the preset, none does.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VimMode {
+    /// Typing inserts text, and every key works as it does without vim.
+    #[default]
+    Insert,
+    /// Typing inserts nothing: keys are commands.
+    Normal,
 }
 
-impl HistoryEntry {
-    /// Apply this patch to undo an edit, returning the reverse patch for redo.
-    fn apply_undo(&self, content: &mut String) -> HistoryEntry {
-        let undo_start = self.range.start;
-        let undo_end = (self.range.start + self.new_text_len).min(content.len());
-
-        // Capture what we're about to remove (the "new" text that was inserted)
-        let removed_text = content[undo_start..undo_end].to_string();
-
-        // Replace with the old text
-        content.replace_range(undo_start..undo_end, &self.old_text);
-
-        // Return reverse patch for redo
-        HistoryEntry {
-            range: undo_start..undo_start + self.old_text.len(),
-            old_text: removed_text,
-            new_text_len: self.old_text.len(),
-            selected_range: self.selected_range.clone(),
-            selection_reversed: self.selection_reversed,
-            timestamp: self.timestamp,
+impl VimMode {
+    /// The value of `vim_mode` in the input element's key context.
+    pub fn context_value(self) -> &'static str {
+        match self {
+            VimMode::Insert => "insert",
+            VimMode::Normal => "normal",
         }
     }
-
-    /// Apply this patch to redo an edit, returning the reverse patch for undo.
-    fn apply_redo(&self, content: &mut String) -> HistoryEntry {
-        // Redo is the same operation as undo - we're reversing the undo
-        self.apply_undo(content)
-    }
 }
 
 /// `Input` is the state model for text input components. It handles:
@@ -126,6 +127,9 @@ pub struct InputState {
     placeholder: SharedString,
     selected_range: Range<usize>,
     selection_reversed: bool,
+    /// Selections besides the primary one, in document order. Only ever
+    /// non-empty in a multiline input. See [`super::selections`].
+    extra_selections: Vec<Selection>,
     marked_range: Option<Range<usize>>,
     pub(crate) line_height: Pixels,
     pub(crate) line_layouts: Vec<InputLineLayout>,
@@ -135,6 +139,13 @@ pub struct InputState {
     is_selecting: bool,
     last_click_position: Option<Point<Pixels>>,
     click_count: usize,
+    /// Where an alt-drag started, while one is under way.
+    column_origin: Option<Point<Pixels>>,
+    /// A press inside the selection, until it turns out to be a click or
+    /// the start of a drag.
+    drag_press: Option<DragPress>,
+    /// Where text dragged over the input would land, while it is.
+    pub(crate) drop_caret: Option<usize>,
     /// Scroll offset - vertical for multiline, horizontal for single-line
     pub(crate) scroll_offset: Pixels,
     pub(crate) available_height: Pixels,
@@ -149,10 +160,70 @@ pub struct InputState {
     read_only: bool,
     /// Which keystroke fires [`InputStateEvent::Submit`], if any.
     submit_on: Option<SubmitOn>,
-    /// Stack of previous states for undo.
-    undo_stack: Vec<HistoryEntry>,
-    /// Stack of undone states for redo.
-    redo_stack: Vec<HistoryEntry>,
+    /// The shape the content is held to, if any. See [`super::mask`].
+    mask: Option<Rc<dyn InputMask>>,
+    /// Whether the content is a secret. See [`secure`](InputState::secure).
+    secure: bool,
+    /// Whether a secure input is showing its text anyway, because the user
+    /// asked to see it.
+    revealed: bool,
+    /// The most characters the content may hold.
+    max_len: Option<usize>,
+    /// Which characters an edit may bring in.
+    char_filter: Option<Rc<dyn Fn(char) -> bool>>,
+    /// Sees each edit before it is applied.
+    validator: Option<Validator>,
+    /// Asked for suggestions whenever the text changes.
+    completion_provider: Option<SharedCompletionProvider>,
+    /// What the provider last suggested, best first.
+    completions: Vec<String>,
+    /// Which of `completions` is shown.
+    completion_index: usize,
+    /// How long the content was when `completions` were asked for. They are
+    /// shown only while the cursor is still at that end.
+    completion_anchor: usize,
+    /// Every edit, for undo and redo. See [`history`](super::history).
+    history: History,
+    /// What the content is being searched for. See [`search`](super::search).
+    search: Option<SearchQuery>,
+    /// Where `search` matches the content as it now is, in order.
+    search_matches: Vec<Range<usize>>,
+    /// The owner's decorations, moved along by each edit. See
+    /// [`decorations`](super::decorations).
+    decorations: Vec<Decoration>,
+    /// Asked what to underline whenever the text changes.
+    checker: Option<SharedChecker>,
+    /// What the checker last said.
+    checked: Vec<Decoration>,
+    /// Where in the content the pointer is, while it is over the input.
+    hover_offset: Option<usize>,
+    /// Ranges edited as a unit, in order. See [`tokens`](super::tokens).
+    tokens: Vec<Token>,
+    /// The characters that start a [`TriggerQuery`].
+    token_triggers: Vec<char>,
+    /// Whether the text carries bold, italic, code and link spans. See
+    /// [`attributes`](super::attributes).
+    attributed: bool,
+    /// Those spans, in order.
+    attributes: Vec<AttributeSpan>,
+    /// What text typed at a caret gets, once a toggle with nothing selected
+    /// has made it differ from what the spans around the caret would give.
+    typing_attributes: Option<(usize, Vec<Attribute>)>,
+    /// The colours attributed text is drawn in, as the element last resolved
+    /// them from the theme.
+    inline_palette: InlinePalette,
+    /// Where the caret was left by a kill, for a kill straight after to join
+    /// it in the kill ring.
+    last_kill: Option<usize>,
+    /// What the last yank inserted and which kill it was, for a yank-pop
+    /// straight after to swap.
+    last_yank: Option<(Range<usize>, usize)>,
+    /// Which of vim's modes the input is in.
+    vim_mode: VimMode,
+    /// Submissions for Up and Down to recall, if the owner gave any.
+    recall: Option<RecallHistory>,
+    /// Where the element last laid the text out, in window coordinates.
+    pub(crate) last_bounds: Option<Bounds<Pixels>>,
     /// Interval for grouping consecutive edits.
     group_interval: Duration,
     /// Optional cursor blink state for cursor blinking.
@@ -182,6 +253,83 @@ pub struct InputLineLayout {
     pub visual_line_count: usize,
     /// The base text direction for this line (LTR or RTL).
     pub direction: TextDirection,
+    /// For a line painted as bullets, the byte offset in the line of each
+    /// grapheme the bullets stand for, then the line's length. The shaped
+    /// text is not the content then, so offsets into one are mapped to the
+    /// other through this rather than used directly.
+    pub(crate) obscured: Option<Arc<[usize]>>,
+}
+
+/// What a secure input paints for each grapheme of its content.
+const OBSCURING_CHAR: char = '•';
+
+/// How far the mouse moves, pressed inside the selection, before the
+/// selection is picked up and dragged.
+const DRAG_THRESHOLD: f64 = 4.;
+
+/// A press inside the selection that may become a drag. See
+/// [`drag`](super::drag).
+#[derive(Debug, Clone, Copy)]
+struct DragPress {
+    /// Where it was, in text coordinates.
+    at: Point<Pixels>,
+    /// The offset it would have put the caret at, had it been a click.
+    offset: usize,
+    alt: bool,
+}
+
+impl InputLineLayout {
+    /// Where byte `offset` of this line falls in the shaped text.
+    fn shaped_offset(&self, offset: usize) -> usize {
+        match &self.obscured {
+            Some(graphemes) => {
+                graphemes[..graphemes.len() - 1].partition_point(|start| *start < offset)
+                    * OBSCURING_CHAR.len_utf8()
+            }
+            None => offset,
+        }
+    }
+
+    /// The byte offset in this line that byte `shaped` of the shaped text
+    /// stands for.
+    fn content_offset(&self, shaped: usize) -> usize {
+        let offset = match &self.obscured {
+            Some(graphemes) => {
+                let index = shaped / OBSCURING_CHAR.len_utf8();
+                graphemes[index.min(graphemes.len() - 1)]
+            }
+            None => shaped,
+        };
+        offset.min(self.text_range.len())
+    }
+
+    /// Where byte `offset` of this line is drawn, relative to the line's
+    /// origin. `None` for a line with nothing shaped, or past its end.
+    pub(crate) fn position_for_index(
+        &self,
+        offset: usize,
+        line_height: Pixels,
+    ) -> Option<Point<Pixels>> {
+        self.wrapped_line
+            .as_ref()?
+            .position_for_index(self.shaped_offset(offset), line_height)
+    }
+
+    /// The byte offset in this line nearest to `position`, relative to the
+    /// line's origin.
+    pub(crate) fn closest_index_for_position(
+        &self,
+        position: Point<Pixels>,
+        line_height: Pixels,
+    ) -> usize {
+        let Some(wrapped) = &self.wrapped_line else {
+            return 0;
+        };
+        let shaped = wrapped
+            .closest_index_for_position(position, line_height)
+            .unwrap_or_else(|closest| closest);
+        self.content_offset(shaped)
+    }
 }
 
 impl InputState {
@@ -207,6 +355,7 @@ impl InputState {
             placeholder: SharedString::default(),
             selected_range: 0..0,
             selection_reversed: false,
+            extra_selections: Vec::new(),
             marked_range: None,
             line_height: px(0.),
             line_layouts: Vec::new(),
@@ -216,15 +365,44 @@ impl InputState {
             is_selecting: false,
             last_click_position: None,
             click_count: 0,
+            column_origin: None,
+            drag_press: None,
+            drop_caret: None,
             scroll_offset: px(0.),
             available_height: px(0.),
             available_width: px(0.),
             multiline: false,
             read_only: false,
             submit_on: None,
-            undo_stack: Vec::new(),
+            mask: None,
+            secure: false,
+            revealed: false,
+            max_len: None,
+            char_filter: None,
+            validator: None,
+            completion_provider: None,
+            completions: Vec::new(),
+            completion_index: 0,
+            completion_anchor: 0,
+            history: History::default(),
+            search: None,
+            search_matches: Vec::new(),
+            decorations: Vec::new(),
+            checker: None,
+            checked: Vec::new(),
+            hover_offset: None,
+            tokens: Vec::new(),
+            token_triggers: Vec::new(),
+            attributed: false,
+            attributes: Vec::new(),
+            typing_attributes: None,
+            inline_palette: InlinePalette::default(),
+            last_kill: None,
+            last_yank: None,
+            vim_mode: VimMode::default(),
+            recall: None,
+            last_bounds: None,
             cached_utf16_len: None,
-            redo_stack: Vec::new(),
             group_interval: DEFAULT_GROUP_INTERVAL,
             cursor_blink: Some(cursor_blink),
             _subscriptions: vec![blink_subscription],
@@ -287,6 +465,350 @@ impl InputState {
         self.submit_on
     }
 
+    /// Records each submission in `history`, for Up and Down to recall in a
+    /// single-line input. See [`recall`](super::recall).
+    pub fn recall(mut self, history: RecallHistory) -> Self {
+        self.recall = Some(history);
+        self
+    }
+
+    /// Sets or clears the recall history after construction.
+    pub fn set_recall(&mut self, history: Option<RecallHistory>) {
+        self.recall = history;
+    }
+
+    /// The recall history, with every submission recorded so far — what to
+    /// save for the next session.
+    pub fn recall_history(&self) -> Option<&RecallHistory> {
+        self.recall.as_ref()
+    }
+
+    /// Holds the content to `mask`: a phone number, a card number, a
+    /// one-time code. See [`mask`](super::mask) for what that changes.
+    pub fn mask(mut self, mask: impl InputMask) -> Self {
+        self.mask = Some(Rc::new(mask));
+        self
+    }
+
+    /// Sets or clears the mask after construction. The current content is
+    /// read through the new mask, as [`set_content`](Self::set_content) would.
+    pub fn set_mask(&mut self, mask: Option<Rc<dyn InputMask>>, cx: &mut Context<Self>) {
+        self.mask = mask;
+        let content = std::mem::take(&mut self.content);
+        self.set_content(content, cx);
+    }
+
+    /// Returns whether a mask is set.
+    pub fn is_masked(&self) -> bool {
+        self.mask.is_some()
+    }
+
+    /// The value without a mask's separators: `5551234567` for
+    /// `(555) 123-4567`. The content itself when no mask is set.
+    pub fn raw_value(&self) -> String {
+        match &self.mask {
+            Some(mask) => mask.unformat(&self.content),
+            None => self.content.clone(),
+        }
+    }
+
+    /// The content as the mask sees it, if one is set.
+    pub(crate) fn masked(&self) -> Option<Masked> {
+        self.mask
+            .as_ref()
+            .map(|mask| mask.format(&mask.unformat(&self.content)))
+    }
+
+    /// Limits the content to `max_len` characters. Typing past it does
+    /// nothing, and a paste is cut short; either emits
+    /// [`Rejected`](InputStateEvent::Rejected). Content already longer — from
+    /// [`set_content`](Self::set_content) — is left as it is, and can only
+    /// shrink.
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Sets or clears the length limit after construction.
+    pub fn set_max_len(&mut self, max_len: Option<usize>) {
+        self.max_len = max_len;
+    }
+
+    /// The length limit, in characters, if one is set.
+    pub fn length_limit(&self) -> Option<usize> {
+        self.max_len
+    }
+
+    /// Only lets characters `allow` accepts into the content; any other
+    /// character typed or pasted is dropped, and the rest of the edit is
+    /// applied.
+    ///
+    /// ```ignore
+    /// InputState::new_singleline(cx).char_filter(|c| c.is_ascii_hexdigit())
+    /// ```
+    pub fn char_filter(mut self, allow: impl Fn(char) -> bool + 'static) -> Self {
+        self.char_filter = Some(Rc::new(allow));
+        self
+    }
+
+    /// Sets or clears the character filter after construction.
+    pub fn set_char_filter(&mut self, allow: Option<Rc<dyn Fn(char) -> bool>>) {
+        self.char_filter = allow;
+    }
+
+    /// Calls `validate` with every edit — typing, paste, cut, the delete
+    /// family, a committed IME composition — after the
+    /// [`char_filter`](Self::char_filter) and [`max_len`](Self::max_len) have
+    /// had their turn and before it is applied. See
+    /// [`validation`](super::validation).
+    ///
+    /// ```ignore
+    /// InputState::new_singleline(cx).validate(|edit| {
+    ///     if edit.result().parse::<u8>().is_ok() || edit.result().is_empty() {
+    ///         Validation::Accept
+    ///     } else {
+    ///         Validation::Reject
+    ///     }
+    /// })
+    /// ```
+    pub fn validate(mut self, validate: impl Fn(&Edit) -> Validation + 'static) -> Self {
+        self.validator = Some(Rc::new(validate));
+        self
+    }
+
+    /// Sets or clears the validate hook after construction.
+    pub fn set_validate(&mut self, validate: Option<Validator>) {
+        self.validator = validate;
+    }
+
+    /// Runs an edit of `range` to `text` past the filter, the length limit
+    /// and the validate hook, emitting [`InputStateEvent::Rejected`] for the
+    /// first of them that takes something out. `None` when nothing of an
+    /// insertion is left, or the hook refused the edit.
+    fn admit(&self, range: &Range<usize>, text: &str, cx: &mut Context<Self>) -> Option<String> {
+        let mut rejection = None;
+        let mut text = text.to_string();
+
+        if let Some(allow) = &self.char_filter {
+            let (kept, dropped) = validation::filter_chars(&text, allow.as_ref());
+            if dropped {
+                rejection = Some(Rejection::Filtered);
+                text = kept;
+            }
+        }
+
+        if let Some(max_len) = self.max_len {
+            let kept = self.content.chars().count() - self.content[range.clone()].chars().count();
+            let fitted = validation::truncate_chars(&text, max_len.saturating_sub(kept));
+            if fitted.len() < text.len() {
+                rejection = rejection.or(Some(Rejection::TooLong));
+                text.truncate(fitted.len());
+            }
+        }
+
+        // An insertion that lost everything is no edit at all — not a
+        // deletion of the selection it was meant to replace.
+        let admitted = if text.is_empty() && rejection.is_some() {
+            None
+        } else if let Some(validate) = &self.validator {
+            let edit = Edit {
+                content: &self.content,
+                range,
+                text: &text,
+            };
+            match validate(&edit) {
+                Validation::Accept => Some(text),
+                Validation::Replace(replacement) => Some(replacement),
+                Validation::Reject => {
+                    rejection = rejection.or(Some(Rejection::Invalid));
+                    None
+                }
+            }
+        } else {
+            Some(text)
+        };
+
+        if let Some(rejection) = rejection {
+            cx.emit(InputStateEvent::Rejected(rejection));
+        }
+        admitted
+    }
+
+    /// Suggests a continuation of the text from `provider`, drawn as ghost
+    /// text after the cursor and taken with Tab or Right. See
+    /// [`completion`](super::completion).
+    pub fn completions(mut self, provider: impl CompletionProvider) -> Self {
+        self.completion_provider = Some(Rc::new(provider));
+        self
+    }
+
+    /// Sets or clears the completion provider after construction.
+    pub fn set_completion_provider(
+        &mut self,
+        provider: Option<SharedCompletionProvider>,
+        cx: &mut Context<Self>,
+    ) {
+        self.completion_provider = provider;
+        self.refresh_completions();
+        cx.notify();
+    }
+
+    /// The suggested continuation being shown, if there is one.
+    pub fn completion(&self) -> Option<&str> {
+        if !self.can_complete() || self.completion_anchor != self.content.len() {
+            return None;
+        }
+        self.completions
+            .get(self.completion_index)
+            .map(String::as_str)
+    }
+
+    /// Takes the suggestion being shown, as an ordinary edit. Returns whether
+    /// there was one.
+    pub fn accept_completion(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        let Some(completion) = self.completion().map(str::to_string) else {
+            return false;
+        };
+        self.replace_text_in_range(None, &completion, window, cx);
+        true
+    }
+
+    /// Whether a suggestion could be drawn here without covering anything:
+    /// see the [module docs](super::completion).
+    fn can_complete(&self) -> bool {
+        !self.multiline
+            && self.extra_selections.is_empty()
+            && !self.secure
+            && !self.read_only
+            && self.marked_range.is_none()
+            && self.selected_range.is_empty()
+            && self.selected_range.end == self.content.len()
+    }
+
+    /// Asks the provider again, for the text as it now is.
+    fn refresh_completions(&mut self) {
+        self.completions.clear();
+        self.completion_index = 0;
+        self.completion_anchor = self.content.len();
+        if let Some(provider) = self.completion_provider.clone() {
+            if self.can_complete() {
+                self.completions = provider
+                    .completions(&self.content)
+                    .into_iter()
+                    .filter(|completion| !completion.is_empty())
+                    .collect();
+            }
+        }
+    }
+
+    /// Shows the next suggestion, or the previous one for `-1`. Propagates
+    /// when there is nothing to cycle, so the key can mean something else.
+    fn cycle_completion(&mut self, delta: isize, cx: &mut Context<Self>) {
+        if self.completion().is_none() || self.completions.len() < 2 {
+            cx.propagate();
+            return;
+        }
+        let len = self.completions.len() as isize;
+        self.completion_index = (self.completion_index as isize + delta).rem_euclid(len) as usize;
+        cx.notify();
+    }
+
+    pub(crate) fn next_completion(
+        &mut self,
+        _: &NextCompletion,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.cycle_completion(1, cx);
+    }
+
+    pub(crate) fn previous_completion(
+        &mut self,
+        _: &PreviousCompletion,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.cycle_completion(-1, cx);
+    }
+
+    /// Asks for new suggestions, then tells subscribers the text changed.
+    fn text_changed(&mut self, cx: &mut Context<Self>) {
+        self.refresh_completions();
+        self.refresh_search();
+        self.refresh_checks();
+        cx.emit(InputStateEvent::TextChanged);
+    }
+
+    /// Makes this a secure input, for a password or another secret.
+    ///
+    /// A secure input paints one bullet per character instead of its text,
+    /// refuses copy and cut, keeps no undo history — an undo stack is a copy
+    /// of every prefix of the secret, held for as long as the input lives —
+    /// and moves by word as though the whole content were one word, so the
+    /// cursor does not give away where the spaces are. A field wrapping it
+    /// announces `Role::PasswordInput` rather than a text input with a value.
+    ///
+    /// [`set_revealed`](Self::set_revealed) shows the text again without
+    /// lifting the rest.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Turns secure mode on or off after construction. Turning it on drops
+    /// the undo and redo history recorded so far.
+    pub fn set_secure(&mut self, secure: bool, cx: &mut Context<Self>) {
+        if self.secure == secure {
+            return;
+        }
+        self.secure = secure;
+        if secure {
+            self.history.clear();
+        }
+        self.refresh_search();
+        self.refresh_checks();
+        self.needs_layout = true;
+        cx.notify();
+    }
+
+    /// Returns whether this input is secure — see [`secure`](Self::secure).
+    pub fn is_secure(&self) -> bool {
+        self.secure
+    }
+
+    /// Shows or hides a secure input's text. Only what is painted changes:
+    /// copy, cut and undo stay off while the text is visible.
+    pub fn set_revealed(&mut self, revealed: bool, cx: &mut Context<Self>) {
+        if self.revealed == revealed {
+            return;
+        }
+        self.revealed = revealed;
+        self.needs_layout = true;
+        cx.notify();
+    }
+
+    /// Returns whether a secure input is showing its text.
+    pub fn is_revealed(&self) -> bool {
+        self.revealed
+    }
+
+    /// Whether the content is painted as bullets.
+    pub(crate) fn is_obscured(&self) -> bool {
+        self.secure && !self.revealed
+    }
+
+    /// The content as it is painted: one bullet per grapheme while
+    /// [obscured](Self::is_obscured), the content itself otherwise.
+    pub(crate) fn display_text(&self) -> String {
+        if self.is_obscured() {
+            OBSCURING_CHAR
+                .to_string()
+                .repeat(self.content.graphemes(true).count())
+        } else {
+            self.content.clone()
+        }
+    }
+
     /// Enables or disables cursor blinking.
     ///
     /// Cursor blinking is enabled by default. Call `cursor_blink(false)` to disable it.
@@ -362,35 +884,180 @@ impl InputState {
     /// Sets the text content, resetting selection to the beginning.
     /// This clears the undo/redo history.
     ///
+    /// With a [`mask`](Self::mask), `content` may be the raw value or the
+    /// formatted one; either is shown formatted.
+    ///
     /// Programmatic, so [`read_only`](Self::read_only) does not apply.
     pub fn set_content(&mut self, content: impl Into<String>, cx: &mut Context<Self>) {
         let content = content.into();
-        self.content = if self.multiline {
+        let before = std::mem::take(&mut self.content);
+        self.content = if let Some(mask) = &self.mask {
+            mask.format(&mask.unformat(&content)).text
+        } else if self.multiline {
             content
         } else {
             // Strip newlines for single-line input
             content.replace('\n', " ").replace('\r', "")
         };
+        self.shift_decorations_from(&before);
+        if !self.tokens.is_empty() {
+            let (range, new_len) = changed_region(&before, &self.content);
+            tokens::shift(&mut self.tokens, &range, new_len);
+        }
+        if !self.attributes.is_empty() {
+            let (range, new_len) = changed_region(&before, &self.content);
+            attributes::shift(&mut self.attributes, &range, new_len);
+        }
+        self.typing_attributes = None;
         self.selected_range = 0..0;
         self.selection_reversed = false;
+        self.extra_selections.clear();
         self.marked_range = None;
         self.needs_layout = true;
-        self.undo_stack.clear();
-        self.redo_stack.clear();
+        self.history.clear();
         self.cached_utf16_len = None;
         self.pause_cursor_blink(cx);
-        cx.emit(InputStateEvent::TextChanged);
+        self.text_changed(cx);
         cx.notify();
     }
 
     /// Returns whether undo is available.
     pub fn can_undo(&self) -> bool {
-        !self.undo_stack.is_empty()
+        self.history.can_undo()
     }
 
     /// Returns whether redo is available.
     pub fn can_redo(&self) -> bool {
-        !self.redo_stack.is_empty()
+        self.history.can_redo()
+    }
+
+    /// Keeps undo history as a tree: an edit made after undoing starts a new
+    /// branch instead of discarding what could have been redone. See
+    /// [`history`](super::history).
+    pub fn undo_tree(mut self, undo_tree: bool) -> Self {
+        self.history.set_branching(undo_tree);
+        self
+    }
+
+    /// Turns the undo tree on or off after construction. Turning it off
+    /// keeps the states undo and redo can reach, and drops other branches.
+    pub fn set_undo_tree(&mut self, undo_tree: bool) {
+        self.history.set_branching(undo_tree);
+    }
+
+    /// Returns whether undo history is kept as a tree.
+    pub fn is_undo_tree(&self) -> bool {
+        self.history.is_branching()
+    }
+
+    /// Every state in the undo history, oldest first — one per edit, and one
+    /// for the oldest kept. Without an [undo tree](Self::undo_tree) they form
+    /// a line.
+    pub fn history_states(&self) -> Vec<HistoryState> {
+        self.history.states()
+    }
+
+    /// The state the content is in now.
+    pub fn current_history_state(&self) -> HistoryId {
+        self.history.current()
+    }
+
+    /// Undoes and redoes until the content is in state `id`, as one change.
+    /// Returns false if `id` is not kept, or if a [restored](Self::restore_history)
+    /// patch did not fit the content — which empties the history.
+    ///
+    /// Programmatic, so [`read_only`](Self::read_only) does not apply.
+    pub fn jump_to_history_state(&mut self, id: HistoryId, cx: &mut Context<Self>) -> bool {
+        if !self.history.contains(id) {
+            return false;
+        }
+        if id == self.history.current() {
+            return true;
+        }
+        let before = self.content.clone();
+        let at = self.history.jump(
+            id,
+            &mut self.content,
+            &mut self.tokens,
+            &mut self.attributes,
+        );
+        self.shift_decorations_from(&before);
+        let caret = self.floor_char_boundary(at.unwrap_or(self.cursor_offset()));
+        self.selected_range = caret..caret;
+        self.selection_reversed = false;
+        self.extra_selections.clear();
+        self.marked_range = None;
+        self.needs_layout = true;
+        self.cached_utf16_len = None;
+        self.scroll_to_cursor();
+        self.text_changed(cx);
+        cx.notify();
+        at.is_some()
+    }
+
+    /// The content, selection and undo history, to be
+    /// [restored](Self::restore_history) later — into this input or a new
+    /// one. `None` for a [secure](Self::secure) input, whose content is not
+    /// to be written anywhere. See [`history`](super::history).
+    pub fn save_history(&self) -> Option<SavedHistory> {
+        if self.secure {
+            return None;
+        }
+        Some(SavedHistory {
+            content: self.content.clone(),
+            tokens: self.tokens.clone(),
+            attributes: self.attributes.clone(),
+            selected_range: self.selected_range.clone(),
+            history: self.history.clone(),
+        })
+    }
+
+    /// Puts back what [`save_history`](Self::save_history) returned: the
+    /// content as saved, not checked or masked, the selection, and the undo
+    /// history, kept as a tree or a line as this input's
+    /// [`undo_tree`](Self::undo_tree) says.
+    pub fn restore_history(&mut self, saved: SavedHistory, cx: &mut Context<Self>) {
+        let branching = self.history.is_branching();
+        let before = std::mem::replace(&mut self.content, saved.content);
+        self.shift_decorations_from(&before);
+        self.tokens = tokens::sanitize(saved.tokens, &self.content);
+        self.attributes = if self.attributed {
+            attributes::sanitize(saved.attributes, &self.content)
+        } else {
+            Vec::new()
+        };
+        self.typing_attributes = None;
+        self.history = saved.history;
+        if self.secure || !self.history.is_well_formed() {
+            self.history = History::default();
+        }
+        self.history.set_branching(branching);
+        self.selected_range = self.clamp_range(&saved.selected_range);
+        self.selection_reversed = false;
+        self.extra_selections.clear();
+        self.marked_range = None;
+        self.needs_layout = true;
+        self.cached_utf16_len = None;
+        self.scroll_to_cursor();
+        self.text_changed(cx);
+        cx.notify();
+    }
+
+    /// `offset`, clamped to the content and moved back to a char boundary.
+    fn floor_char_boundary(&self, offset: usize) -> usize {
+        let mut offset = offset.min(self.content.len());
+        while !self.content.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    }
+
+    /// `range`, clamped to the content on char boundaries: a range read back
+    /// from a saved history may not fit the content it is put with.
+    fn clamp_range(&self, range: &Range<usize>) -> Range<usize> {
+        let start = self.floor_char_boundary(range.start);
+        let end = self.floor_char_boundary(range.end);
+        start.min(end)..end
     }
 
     /// Sets the interval for grouping consecutive edits into a single undo entry.
@@ -400,42 +1067,73 @@ impl InputState {
 
     /// Records a patch for undo. Called before making changes to content.
     /// Returns true if a new entry was created, false if grouped with previous.
-    fn push_undo_patch(&mut self, range: Range<usize>, new_text_len: usize) {
-        // Don't record during IME composition
-        if self.marked_range.is_some() {
-            return;
+    fn push_undo_patch(&mut self, range: Range<usize>, new_text_len: usize) -> bool {
+        // Don't record during IME composition, nor anything at all of a
+        // secret.
+        if self.marked_range.is_some() || self.secure {
+            return false;
         }
 
         let now = Instant::now();
 
         // Check if we should group with the last entry
-        if let Some(last) = self.undo_stack.last() {
-            if now.duration_since(last.timestamp) < self.group_interval {
+        if let Some(last) = self.history.latest() {
+            if last
+                .timestamp
+                .is_some_and(|timestamp| now.duration_since(timestamp) < self.group_interval)
+            {
                 // Within group interval - extend the existing patch
                 // We need to merge this edit with the previous one
-                return;
+                return false;
             }
         }
 
         // Capture the text that will be replaced
         let old_text = self.content[range.clone()].to_string();
 
-        self.undo_stack.push(HistoryEntry {
+        self.history.record(HistoryEntry {
             range: range.start..range.start + new_text_len,
             old_text,
             new_text_len,
             selected_range: self.selected_range.clone(),
             selection_reversed: self.selection_reversed,
-            timestamp: now,
+            extra_selections: self.extra_selections.clone(),
+            tokens: tokens::within(&self.tokens, &range),
+            attributes: attributes::within(&self.attributes, &range),
+            restyle: false,
+            timestamp: Some(now),
         });
+        true
+    }
 
-        // Limit history size
-        if self.undo_stack.len() > MAX_HISTORY_LEN {
-            self.undo_stack.remove(0);
+    /// Records a patch for undo of the attributes over `range` about to
+    /// change, the text staying as it is. Always an undo step of its own.
+    fn push_restyle_patch(&mut self, range: Range<usize>) {
+        if self.marked_range.is_some() || self.secure {
+            return;
         }
+        self.break_undo_group();
+        let text = self.content[range.clone()].to_string();
+        self.history.record(HistoryEntry {
+            range: range.clone(),
+            new_text_len: text.len(),
+            old_text: text,
+            selected_range: self.selected_range.clone(),
+            selection_reversed: self.selection_reversed,
+            extra_selections: self.extra_selections.clone(),
+            tokens: Vec::new(),
+            attributes: attributes::within(&self.attributes, &range),
+            restyle: true,
+            timestamp: None,
+        });
+    }
 
-        // New edit invalidates redo stack
-        self.redo_stack.clear();
+    /// Ends the undo step being grouped, so that the next edit is a step of
+    /// its own however soon it comes.
+    fn break_undo_group(&mut self) {
+        if let Some(latest) = self.history.latest_mut() {
+            latest.timestamp = None;
+        }
     }
 
     /// Undoes the last edit by applying the reverse patch.
@@ -443,25 +1141,7 @@ impl InputState {
         if self.read_only {
             return;
         }
-
-        if let Some(entry) = self.undo_stack.pop() {
-            // Remember selection to restore
-            let selected_range = entry.selected_range.clone();
-            let selection_reversed = entry.selection_reversed;
-
-            // Apply the undo patch and get the redo patch
-            let redo_entry = entry.apply_undo(&mut self.content);
-            self.redo_stack.push(redo_entry);
-
-            // Restore selection state
-            self.selected_range = selected_range;
-            self.selection_reversed = selection_reversed;
-            self.needs_layout = true;
-            self.cached_utf16_len = None;
-            self.scroll_to_cursor();
-            cx.emit(InputStateEvent::Undo);
-            cx.notify();
-        }
+        self.undo_action(cx);
     }
 
     /// Redoes the last undone edit by applying the forward patch.
@@ -469,24 +1149,7 @@ impl InputState {
         if self.read_only {
             return;
         }
-
-        if let Some(entry) = self.redo_stack.pop() {
-            // Apply the redo patch and get the undo patch
-            let undo_entry = entry.apply_redo(&mut self.content);
-
-            // The undo entry contains the selection state after the original edit
-            // We need to restore cursor to end of inserted text
-            let cursor_pos = undo_entry.range.start;
-            self.selected_range = cursor_pos..cursor_pos;
-            self.selection_reversed = false;
-
-            self.undo_stack.push(undo_entry);
-            self.needs_layout = true;
-            self.cached_utf16_len = None;
-            self.scroll_to_cursor();
-            cx.emit(InputStateEvent::Redo);
-            cx.notify();
-        }
+        self.redo_action(cx);
     }
 
     /// Returns the placeholder text shown when content is empty.
@@ -533,2145 +1196,4891 @@ impl InputState {
         let range = range.start.min(self.content.len())..range.end.min(self.content.len());
         self.selected_range = range;
         self.selection_reversed = false;
+        self.extra_selections.clear();
     }
 
-    /// Returns the selected text range in UTF-16 offsets (for IME).
-    pub fn selected_text_range_utf16(&self) -> Range<usize> {
-        self.range_to_utf16(&self.selected_range)
+    /// Every selection, the primary one included, in document order. There
+    /// is more than one only in a multiline input: see
+    /// [`selections`](super::selections).
+    pub fn selections(&self) -> Vec<Selection> {
+        let mut all = self.extra_selections.clone();
+        all.push(self.primary_selection());
+        all.sort_by_key(|selection| selection.range.start);
+        all
     }
 
-    /// Inserts text at the current cursor position, replacing any selection.
-    ///
-    /// Deliberately **not** gated by [`read_only`](Self::read_only): this is
-    /// the owner's programmatic API, and read-only is about what the *user*
-    /// can do — the same split as assigning to a `readonly` input's value from
-    /// script in a browser.
-    pub fn insert_text(&mut self, text: &str, cx: &mut Context<Self>) {
-        let range = self
-            .marked_range
-            .clone()
-            .unwrap_or(self.selected_range.clone());
-        let range = range.start.min(self.content.len())..range.end.min(self.content.len());
+    /// Whether there is more than one selection.
+    pub fn has_multiple_selections(&self) -> bool {
+        !self.extra_selections.is_empty()
+    }
 
-        let sanitized_text;
-        let text_to_insert = if self.multiline {
-            text
-        } else {
-            sanitized_text = text.replace('\n', " ").replace('\r', "");
-            &sanitized_text
+    /// Adds a selection of `range`, which becomes the primary one. In a
+    /// single-line or masked input, which hold only one, it replaces the
+    /// selection instead.
+    pub fn add_selection(&mut self, range: Range<usize>, cx: &mut Context<Self>) {
+        let range = range.start.min(self.content.len())..range.end.min(self.content.len());
+        let selection = Selection {
+            range,
+            reversed: false,
         };
-
-        // Record patch for undo before modifying content
-        self.push_undo_patch(range.clone(), text_to_insert.len());
-
-        // Update cached UTF-16 length incrementally if available
-        if let Some(cached_len) = self.cached_utf16_len {
-            let removed_utf16_len: usize = self.content[range.clone()]
-                .chars()
-                .map(|c| c.len_utf16())
-                .sum();
-            let added_utf16_len: usize = text_to_insert.chars().map(|c| c.len_utf16()).sum();
-            self.cached_utf16_len = Some(cached_len - removed_utf16_len + added_utf16_len);
+        if self.allows_multiple_selections() {
+            let mut extras = std::mem::take(&mut self.extra_selections);
+            extras.push(self.primary_selection());
+            self.set_selections(selection, extras);
+        } else {
+            self.set_selections(selection, Vec::new());
         }
-
-        self.content.replace_range(range.clone(), text_to_insert);
-        self.selected_range =
-            range.start + text_to_insert.len()..range.start + text_to_insert.len();
-        self.marked_range.take();
-        self.needs_layout = true;
-        self.pause_cursor_blink(cx);
-        cx.emit(InputStateEvent::TextChanged);
+        self.completions.clear();
+        self.scroll_to_cursor();
         cx.notify();
     }
 
-    /// Deletes the character before the cursor (convenience method for benchmarks).
-    ///
-    /// Programmatic, so [`read_only`](Self::read_only) does not apply — unlike
-    /// the `backspace` action, which it does.
-    pub fn delete_backward(&mut self, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
-            self.select_to(self.previous_boundary(self.cursor_offset()), cx);
+    /// Drops every selection but the primary one. Returns whether there were
+    /// any others.
+    pub fn collapse_selections(&mut self, cx: &mut Context<Self>) -> bool {
+        if self.extra_selections.is_empty() {
+            return false;
         }
-        self.insert_text("", cx);
+        self.extra_selections.clear();
+        cx.notify();
+        true
     }
 
-    /// Undoes the last edit (convenience method without Window).
-    ///
-    /// Programmatic, so [`read_only`](Self::read_only) does not apply — unlike
-    /// the `undo` action, which it does.
-    pub fn undo_action(&mut self, cx: &mut Context<Self>) {
-        if let Some(entry) = self.undo_stack.pop() {
-            let selected_range = entry.selected_range.clone();
-            let selection_reversed = entry.selection_reversed;
-
-            let redo_entry = entry.apply_undo(&mut self.content);
-            self.redo_stack.push(redo_entry);
+    fn allows_multiple_selections(&self) -> bool {
+        self.multiline && self.mask.is_none()
+    }
 
-            self.selected_range = selected_range;
-            self.selection_reversed = selection_reversed;
-            self.needs_layout = true;
-            self.cached_utf16_len = None;
-            self.scroll_to_cursor();
-            cx.emit(InputStateEvent::Undo);
-            cx.notify();
+    fn primary_selection(&self) -> Selection {
+        Selection {
+            range: self.selected_range.clone(),
+            reversed: self.selection_reversed,
         }
     }
 
-    /// Redoes the last undone edit (convenience method without Window).
-    ///
-    /// Programmatic, so [`read_only`](Self::read_only) does not apply — unlike
-    /// the `redo` action, which it does.
-    pub fn redo_action(&mut self, cx: &mut Context<Self>) {
-        if let Some(entry) = self.redo_stack.pop() {
-            let undo_entry = entry.apply_redo(&mut self.content);
-
-            let cursor_pos = undo_entry.range.start;
-            self.selected_range = cursor_pos..cursor_pos;
-            self.selection_reversed = false;
+    /// Makes `primary` the primary selection and `extras` the others, joining
+    /// any that overlap.
+    fn set_selections(&mut self, primary: Selection, extras: Vec<Selection>) {
+        let mut all = extras;
+        all.push(primary);
+        let newest = all.len() - 1;
+        let primary = selections::merge(&mut all, newest);
+        let primary = all.remove(primary);
+        self.selected_range = primary.range;
+        self.selection_reversed = primary.reversed;
+        self.extra_selections = all;
+    }
+
+    /// Runs `f` once for each selection, with that selection in the place of
+    /// the primary one, so every movement action written for one selection
+    /// moves them all. The primary goes last, so whatever it scrolls to is
+    /// where the view ends up.
+    fn each_selection(
+        &mut self,
+        cx: &mut Context<Self>,
+        mut f: impl FnMut(&mut Self, &mut Context<Self>),
+    ) {
+        if self.extra_selections.is_empty() {
+            f(self, cx);
+            return;
+        }
+        let primary = self.primary_selection();
+        let mut moved = Vec::new();
+        for selection in std::mem::take(&mut self.extra_selections) {
+            self.selected_range = selection.range;
+            self.selection_reversed = selection.reversed;
+            f(self, cx);
+            moved.push(self.primary_selection());
+        }
+        self.selected_range = primary.range;
+        self.selection_reversed = primary.reversed;
+        f(self, cx);
+        self.set_selections(self.primary_selection(), moved);
+    }
+
+    /// Replaces every selection with text, as one undo step: with `texts[i]`
+    /// if there is one text per selection, otherwise with `texts[0]`. Each
+    /// replacement passes [`admit`](Self::admit) on its own, first to last.
+    fn replace_selections(&mut self, texts: &[&str], cx: &mut Context<Self>) {
+        let mut all = self.extra_selections.clone();
+        all.push(self.primary_selection());
+        let newest = all.len() - 1;
+        let primary = selections::merge(&mut all, newest);
+
+        let span = all[0].range.start..all[all.len() - 1].range.end;
+        let recorded = self.push_undo_patch(span.clone(), span.len());
+
+        // Edits are made first to last, each range moved along by what the
+        // edits before it did, so each is admitted against the text as it
+        // will be and a length limit goes to the earliest; `shift` is how far
+        // the edits so far have moved what follows them.
+        let count = all.len();
+        let mut shift = 0isize;
+        let mut changed = false;
+        for (index, selection) in all.iter_mut().enumerate() {
+            let text = if texts.len() == count {
+                texts[index]
+            } else {
+                texts.first().copied().unwrap_or_default()
+            };
+            let start = (selection.range.start as isize + shift) as usize;
+            let end = (selection.range.end as isize + shift) as usize;
+            selection.range = start..end;
+            let Some(text) = self.admit(&selection.range, text, cx) else {
+                continue;
+            };
+            changed = true;
+            self.content.replace_range(selection.range.clone(), &text);
+            self.shift_spans(&selection.range, text.len());
+            shift += text.len() as isize - selection.range.len() as isize;
+            *selection = Selection::caret(start + text.len());
+        }
 
-            self.undo_stack.push(undo_entry);
-            self.needs_layout = true;
-            self.cached_utf16_len = None;
-            self.scroll_to_cursor();
-            cx.emit(InputStateEvent::Redo);
-            cx.notify();
+        if !changed {
+            if recorded {
+                self.history.discard_latest();
+            }
+            return;
+        }
+        if recorded {
+            let new_len = (span.len() as isize + shift) as usize;
+            let entry = self.history.latest_mut().expect("just recorded");
+            entry.range = span.start..span.start + new_len;
+            entry.new_text_len = new_len;
         }
-    }
 
-    /// Selects all text.
-    pub fn select_all(&mut self, _: &SelectAll, _: &mut Window, cx: &mut Context<Self>) {
-        self.selected_range = 0..self.content.len();
-        self.selection_reversed = false;
+        let primary = all.remove(primary);
+        self.set_selections(primary, all);
+        self.marked_range = None;
+        self.cached_utf16_len = None;
+        self.needs_layout = true;
+        self.scroll_to_cursor();
+        self.pause_cursor_blink(cx);
+        self.text_changed(cx);
         cx.notify();
     }
 
-    pub(crate) fn left(&mut self, _: &Left, _: &mut Window, cx: &mut Context<Self>) {
+    /// Selects the word at a bare caret; with a selection, adds the next
+    /// occurrence of its text as a new primary selection.
+    pub(crate) fn select_next_occurrence(
+        &mut self,
+        _: &SelectNextOccurrence,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         if self.selected_range.is_empty() {
-            let new_pos = self.previous_boundary(self.cursor_offset());
-            self.move_to(new_pos, cx);
-        } else {
-            self.move_to(self.selected_range.start, cx);
+            let (start, end) = self.word_range_at(self.cursor_offset());
+            if start == end {
+                cx.propagate();
+                return;
+            }
+            let mut extras = std::mem::take(&mut self.extra_selections);
+            extras.retain(|selection| selection.range != self.selected_range);
+            self.set_selections(
+                Selection {
+                    range: start..end,
+                    reversed: false,
+                },
+                extras,
+            );
+            cx.notify();
+            return;
+        }
+        if !self.allows_multiple_selections() {
+            cx.propagate();
+            return;
+        }
+        let needle = self.content[self.selected_range.clone()].to_string();
+        let found = selections::next_occurrence(
+            &self.content,
+            &needle,
+            self.selected_range.end,
+            &self.selections(),
+        );
+        if let Some(range) = found {
+            self.add_selection(range, cx);
         }
     }
 
-    pub(crate) fn right(&mut self, _: &Right, _: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
-            let new_pos = self.next_boundary(self.cursor_offset());
-            self.move_to(new_pos, cx);
+    /// Every visual row between `from` and `to`, each selected from `from.x`
+    /// to `to.x`: the selections an alt-drag makes. The row under `to` is
+    /// last.
+    fn column_selections(&self, from: Point<Pixels>, to: Point<Pixels>) -> Vec<Selection> {
+        let line_height = self.line_height.max(px(1.));
+        let first = (from.y / line_height).floor().max(0.) as usize;
+        let last = (to.y / line_height).floor().max(0.) as usize;
+        let rows: Vec<usize> = if first <= last {
+            (first..=last).collect()
         } else {
-            self.move_to(self.selected_range.end, cx);
-        }
+            (last..=first).rev().collect()
+        };
+        rows.into_iter()
+            .map(|row| {
+                let y = line_height * (row as f32 + 0.5);
+                let anchor = self.index_for_position(point(from.x, y));
+                let head = self.index_for_position(point(to.x, y));
+                Selection {
+                    range: anchor.min(head)..anchor.max(head),
+                    reversed: head < anchor,
+                }
+            })
+            .collect()
     }
 
-    pub(crate) fn up(&mut self, _: &Up, _window: &mut Window, cx: &mut Context<Self>) {
-        self.pause_cursor_blink(cx);
-        if !self.multiline {
-            // In single-line mode, up moves to start
-            self.selected_range = 0..0;
-            self.selection_reversed = false;
-            self.scroll_to_cursor();
-            cx.notify();
-            return;
+    /// With several selections and text in any of them, every selection's
+    /// text, joined with newlines.
+    fn multiple_selected_text(&self) -> Option<String> {
+        if self.extra_selections.is_empty() {
+            return None;
         }
-        if let Some(new_offset) = self.move_vertically(self.cursor_offset(), -1) {
-            self.selected_range = new_offset..new_offset;
-            self.selection_reversed = false;
-            self.scroll_to_cursor();
-            cx.notify();
+        let selections = self.selections();
+        if selections
+            .iter()
+            .all(|selection| selection.range.is_empty())
+        {
+            return None;
         }
+        let texts: Vec<&str> = selections
+            .iter()
+            .map(|selection| &self.content[selection.range.clone()])
+            .collect();
+        Some(texts.join("\n"))
+    }
+
+    /// Searches the content for `query`, or stops searching with `None`.
+    /// Matches are kept up to date as the content changes, and painted; the
+    /// selection does not move until [`find_next`](Self::find_next). See
+    /// [`search`](super::search).
+    pub fn set_search(&mut self, query: Option<SearchQuery>, cx: &mut Context<Self>) {
+        self.search = query;
+        self.refresh_search();
+        cx.notify();
     }
 
-    pub(crate) fn down(&mut self, _: &Down, _window: &mut Window, cx: &mut Context<Self>) {
-        self.pause_cursor_blink(cx);
-        if !self.multiline {
-            // In single-line mode, down moves to end
-            let end = self.content.len();
-            self.selected_range = end..end;
-            self.selection_reversed = false;
-            self.scroll_to_cursor();
-            cx.notify();
-            return;
-        }
-        if let Some(new_offset) = self.move_vertically(self.cursor_offset(), 1) {
-            self.selected_range = new_offset..new_offset;
-            self.selection_reversed = false;
-            self.scroll_to_cursor();
-            cx.notify();
-        }
+    /// What the content is being searched for, if anything.
+    pub fn search_query(&self) -> Option<&SearchQuery> {
+        self.search.as_ref()
     }
 
-    pub(crate) fn select_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
-        self.select_to(self.previous_boundary(self.cursor_offset()), cx);
+    /// Where the search matches the content, in order.
+    pub fn search_matches(&self) -> &[Range<usize>] {
+        &self.search_matches
     }
 
-    pub(crate) fn select_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
-        self.select_to(self.next_boundary(self.cursor_offset()), cx);
+    /// Which of [`search_matches`](Self::search_matches) is selected, if one
+    /// is.
+    pub fn active_search_match(&self) -> Option<usize> {
+        self.search_matches
+            .binary_search_by_key(&self.selected_range.start, |found| found.start)
+            .ok()
+            .filter(|index| self.search_matches[*index] == self.selected_range)
     }
 
-    pub(crate) fn select_up(&mut self, _: &SelectUp, _window: &mut Window, cx: &mut Context<Self>) {
-        self.pause_cursor_blink(cx);
-        if !self.multiline {
-            // In single-line mode, select_up selects to start
-            self.select_to(0, cx);
-            return;
-        }
-        if let Some(new_offset) = self.move_vertically(self.cursor_offset(), -1) {
-            if self.selection_reversed {
-                self.selected_range.start = new_offset;
-            } else {
-                self.selected_range.end = new_offset;
-            }
-            if self.selected_range.end < self.selected_range.start {
-                self.selection_reversed = !self.selection_reversed;
-                self.selected_range = self.selected_range.end..self.selected_range.start;
-            }
-            self.scroll_to_cursor();
-            cx.notify();
-        }
+    /// Selects the first match after the selection, wrapping round to the
+    /// first in the content, and scrolls to it. Returns false if there is no
+    /// match.
+    pub fn find_next(&mut self, cx: &mut Context<Self>) -> bool {
+        let Some(index) = search::next_match(&self.search_matches, self.selected_range.end) else {
+            return false;
+        };
+        self.select_search_match(index, cx);
+        true
     }
 
-    pub(crate) fn select_down(
-        &mut self,
-        _: &SelectDown,
-        _window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        self.pause_cursor_blink(cx);
-        if !self.multiline {
-            // In single-line mode, select_down selects to end
-            self.select_to(self.content.len(), cx);
-            return;
-        }
-        if let Some(new_offset) = self.move_vertically(self.cursor_offset(), 1) {
-            if self.selection_reversed {
-                self.selected_range.start = new_offset;
+    /// Selects the last match before the selection, wrapping round to the
+    /// last in the content, and scrolls to it. Returns false if there is no
+    /// match.
+    pub fn find_previous(&mut self, cx: &mut Context<Self>) -> bool {
+        let Some(index) = search::previous_match(&self.search_matches, self.selected_range.start)
+        else {
+            return false;
+        };
+        self.select_search_match(index, cx);
+        true
+    }
+
+    /// Replaces the selected match with `replacement`, as one undo step, and
+    /// selects the next. With no match selected it only finds the next, for
+    /// the user to see before it is replaced. Returns whether a match was
+    /// replaced. Never in a masked input.
+    ///
+    /// Programmatic, so [`read_only`](Self::read_only) does not apply.
+    pub fn replace_next(&mut self, replacement: &str, cx: &mut Context<Self>) -> bool {
+        let Some(query) = self.search.clone().filter(|_| self.mask.is_none()) else {
+            return false;
+        };
+        let Some(index) = self.active_search_match() else {
+            self.find_next(cx);
+            return false;
+        };
+        let text = query.expand(&self.content, &self.search_matches[index], replacement);
+        self.extra_selections.clear();
+        self.marked_range = None;
+        self.break_undo_group();
+        self.insert_text(&text, cx);
+        self.break_undo_group();
+        self.find_next(cx);
+        true
+    }
+
+    /// Replaces every match with `replacement`, as one undo step, leaving the
+    /// cursor after the last. Returns how many were replaced. Never in a
+    /// masked input.
+    ///
+    /// Programmatic, so [`read_only`](Self::read_only) does not apply.
+    pub fn replace_all(&mut self, replacement: &str, cx: &mut Context<Self>) -> usize {
+        let Some(query) = self.search.clone().filter(|_| self.mask.is_none()) else {
+            return 0;
+        };
+        let matches = self.search_matches.clone();
+        let (Some(first), Some(last)) = (matches.first(), matches.last()) else {
+            return 0;
+        };
+        // Expanded against the content as it was found in, before any of it
+        // is replaced.
+        let texts: Vec<String> = matches
+            .iter()
+            .map(|found| query.expand(&self.content, found, replacement))
+            .collect();
+
+        self.marked_range = None;
+        self.break_undo_group();
+        let span = first.start..last.end;
+        let recorded = self.push_undo_patch(span.clone(), span.len());
+
+        // First to last, each match moved along by what the replacements
+        // before it did, as `replace_selections` does: each is admitted
+        // against the text as it will be, so a length limit goes to the
+        // earliest matches rather than the latest.
+        let mut replaced = 0;
+        let mut shift = 0isize;
+        for (found, text) in matches.iter().zip(&texts) {
+            let start = (found.start as isize + shift) as usize;
+            let found = start..start + found.len();
+            let Some(text) = self.admit(&found, text, cx) else {
+                continue;
+            };
+            let text = if self.multiline {
+                text
             } else {
-                self.selected_range.end = new_offset;
-            }
-            if self.selected_range.end < self.selected_range.start {
-                self.selection_reversed = !self.selection_reversed;
-                self.selected_range = self.selected_range.end..self.selected_range.start;
+                text.replace('\n', " ").replace('\r', "")
+            };
+            self.content.replace_range(found.clone(), &text);
+            self.shift_spans(&found, text.len());
+            shift += text.len() as isize - found.len() as isize;
+            replaced += 1;
+        }
+
+        if replaced == 0 {
+            if recorded {
+                self.history.discard_latest();
             }
-            self.scroll_to_cursor();
-            cx.notify();
+            return 0;
         }
-    }
+        let new_len = (span.len() as isize + shift) as usize;
+        if recorded {
+            let entry = self.history.latest_mut().expect("just recorded");
+            entry.range = span.start..span.start + new_len;
+            entry.new_text_len = new_len;
+        }
+        self.break_undo_group();
 
-    pub(crate) fn home(&mut self, _: &Home, _: &mut Window, cx: &mut Context<Self>) {
-        let line_start = self.find_line_start(self.cursor_offset());
-        self.move_to(line_start, cx);
+        let cursor = span.start + new_len;
+        self.selected_range = cursor..cursor;
+        self.selection_reversed = false;
+        self.extra_selections.clear();
+        self.cached_utf16_len = None;
+        self.needs_layout = true;
+        self.scroll_to_cursor();
+        self.pause_cursor_blink(cx);
+        self.text_changed(cx);
+        cx.notify();
+        replaced
     }
 
-    pub(crate) fn end(&mut self, _: &End, _: &mut Window, cx: &mut Context<Self>) {
-        let line_end = self.find_line_end(self.cursor_offset());
-        self.move_to(line_end, cx);
+    /// Finds the search's matches again, in the content as it now is.
+    fn refresh_search(&mut self) {
+        self.search_matches = match &self.search {
+            Some(query) if !self.secure => query.find_all(&self.content),
+            _ => Vec::new(),
+        };
     }
 
-    pub(crate) fn move_to_beginning(
-        &mut self,
-        _: &MoveToBeginning,
-        _: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        self.move_to(0, cx);
+    fn select_search_match(&mut self, index: usize, cx: &mut Context<Self>) {
+        let found = self.search_matches[index].clone();
+        self.extra_selections.clear();
+        self.completions.clear();
+        self.selected_range = found;
+        self.selection_reversed = false;
+        self.pause_cursor_blink(cx);
+        self.scroll_to_cursor();
+        cx.notify();
     }
 
-    pub(crate) fn move_to_end(&mut self, _: &MoveToEnd, _: &mut Window, cx: &mut Context<Self>) {
-        self.move_to(self.content.len(), cx);
+    pub(crate) fn next_match(&mut self, _: &FindNext, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.find_next(cx) {
+            cx.propagate();
+        }
     }
 
-    pub(crate) fn select_to_beginning(
+    pub(crate) fn previous_match(
         &mut self,
-        _: &SelectToBeginning,
+        _: &FindPrevious,
         _: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        self.select_to(0, cx);
+        if !self.find_previous(cx) {
+            cx.propagate();
+        }
     }
 
-    pub(crate) fn select_to_end(
-        &mut self,
-        _: &SelectToEnd,
-        _: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        self.select_to(self.content.len(), cx);
+    /// Underlines each range `checker` finds, asking it again whenever the
+    /// text changes. See [`decorations`](super::decorations).
+    pub fn checker(mut self, checker: impl Checker) -> Self {
+        self.checker = Some(Rc::new(checker));
+        self
     }
 
-    pub(crate) fn word_left(&mut self, _: &WordLeft, _: &mut Window, cx: &mut Context<Self>) {
-        let new_pos = self.previous_word_boundary(self.cursor_offset());
-        self.move_to(new_pos, cx);
+    /// Sets or clears the checker after construction.
+    pub fn set_checker(&mut self, checker: Option<SharedChecker>, cx: &mut Context<Self>) {
+        self.checker = checker;
+        self.refresh_checks();
+        cx.notify();
     }
 
-    pub(crate) fn word_right(&mut self, _: &WordRight, _: &mut Window, cx: &mut Context<Self>) {
-        let new_pos = self.next_word_boundary(self.cursor_offset());
-        self.move_to(new_pos, cx);
+    /// Underlines `decorations`, in place of those set before, and moves them
+    /// along with each edit from now on. Those that are empty, run past the
+    /// end or split a character are dropped. The checker's are kept.
+    pub fn set_decorations(&mut self, decorations: Vec<Decoration>, cx: &mut Context<Self>) {
+        self.decorations = decorations::sanitize(decorations, &self.content);
+        cx.notify();
     }
 
-    pub(crate) fn select_word_left(
-        &mut self,
-        _: &SelectWordLeft,
-        _: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        let new_pos = self.previous_word_boundary(self.cursor_offset());
-        self.select_to(new_pos, cx);
+    /// Every decoration — the owner's, then the checker's — where it now is.
+    /// None in a [secure](Self::secure) input.
+    pub fn decorations(&self) -> impl Iterator<Item = &Decoration> {
+        // The checker's are already empty in a secure input.
+        let owned: &[Decoration] = if self.secure { &[] } else { &self.decorations };
+        owned.iter().chain(&self.checked)
     }
 
-    pub(crate) fn select_word_right(
-        &mut self,
-        _: &SelectWordRight,
-        _: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        let new_pos = self.next_word_boundary(self.cursor_offset());
-        self.select_to(new_pos, cx);
+    /// The decoration over `offset`, counting either end — the owner's first.
+    pub fn decoration_at(&self, offset: usize) -> Option<&Decoration> {
+        self.decorations()
+            .find(|decoration| (decoration.range.start..=decoration.range.end).contains(&offset))
     }
 
-    pub(crate) fn enter(&mut self, _: &Enter, window: &mut Window, cx: &mut Context<Self>) {
-        // Submitting is not an edit, so a read-only input still submits;
-        // only the newline is refused.
-        if self.submit_on == Some(SubmitOn::Enter) {
-            cx.emit(InputStateEvent::Submit);
-        } else if self.multiline && !self.read_only {
-            self.replace_text_in_range(None, "\n", window, cx);
-        }
+    /// The decoration under the pointer, if there is one: what a context
+    /// menu opened over the input is about.
+    pub fn hovered_decoration(&self) -> Option<&Decoration> {
+        self.decoration_at(self.hover_offset?)
     }
 
-    pub(crate) fn submit(&mut self, _: &Submit, _window: &mut Window, cx: &mut Context<Self>) {
-        // Fires in both modes: cmd-enter submitting alongside plain enter is
-        // harmless muscle memory; without a mode it stays inert.
-        if self.submit_on.is_some() {
-            cx.emit(InputStateEvent::Submit);
+    /// Notes where the pointer is — a point in the element, or `None` once it
+    /// has left.
+    pub(crate) fn hover(&mut self, position: Option<Point<Pixels>>, cx: &mut Context<Self>) {
+        let before = self.hovered_decoration().cloned();
+        self.hover_offset = position.map(|position| self.index_for_position(position));
+        if self.hovered_decoration() != before.as_ref() {
+            cx.notify();
         }
     }
 
-    pub(crate) fn insert_newline(
-        &mut self,
-        _: &InsertNewline,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        if self.multiline && !self.read_only {
-            self.replace_text_in_range(None, "\n", window, cx);
-        }
+    /// Asks the checker what to underline in the content as it now is.
+    fn refresh_checks(&mut self) {
+        self.checked = match &self.checker {
+            Some(checker) if !self.secure => {
+                decorations::sanitize(checker.check(&self.content), &self.content)
+            }
+            _ => Vec::new(),
+        };
     }
 
-    pub(crate) fn tab(&mut self, _: &Tab, window: &mut Window, cx: &mut Context<Self>) {
-        if self.read_only {
-            return;
-        }
-        self.replace_text_in_range(None, "\t", window, cx);
+    /// Moves the owner's decorations for `range` having been replaced by
+    /// `new_len` bytes.
+    fn shift_decorations(&mut self, range: &Range<usize>, new_len: usize) {
+        decorations::shift(&mut self.decorations, range, new_len);
     }
 
-    pub(crate) fn backspace(&mut self, _: &Backspace, window: &mut Window, cx: &mut Context<Self>) {
-        // Guarded here rather than left to `replace_text_in_range`: each of
-        // these *moves the selection* before deleting through it, so a
-        // read-only field would otherwise answer a delete key by silently
-        // eating a grapheme of the user's selection.
-        if self.read_only {
-            return;
-        }
+    /// Starts a [`TriggerQuery`] at each of `triggers` typed at the start of a
+    /// word — `['@', '#']`, for a chat composer. See [`tokens`](super::tokens).
+    pub fn token_triggers(mut self, triggers: impl IntoIterator<Item = char>) -> Self {
+        self.token_triggers = triggers.into_iter().collect();
+        self
+    }
 
-        if self.selected_range.is_empty() {
-            self.select_to(self.previous_boundary(self.cursor_offset()), cx);
-        }
-        self.replace_text_in_range(None, "", window, cx);
+    /// Sets the trigger characters after construction.
+    pub fn set_token_triggers(&mut self, triggers: impl IntoIterator<Item = char>) {
+        self.token_triggers = triggers.into_iter().collect();
     }
 
-    pub(crate) fn delete(&mut self, _: &Delete, window: &mut Window, cx: &mut Context<Self>) {
-        // Guarded here rather than left to `replace_text_in_range`: each of
-        // these *moves the selection* before deleting through it, so a
-        // read-only field would otherwise answer a delete key by silently
-        // eating a grapheme of the user's selection.
-        if self.read_only {
-            return;
-        }
+    /// Every token, in order, where it now is.
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
 
-        if self.selected_range.is_empty() {
-            self.select_to(self.next_boundary(self.cursor_offset()), cx);
+    /// The token over `offset`, counting either end.
+    pub fn token_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens
+            .iter()
+            .find(|token| (token.range.start..=token.range.end).contains(&offset))
+    }
+
+    /// The word being typed after a [trigger](Self::token_triggers), while
+    /// there is one caret and it is at the end of such a word. Never in a
+    /// [secure](Self::secure) or [masked](Self::mask) input.
+    pub fn trigger_query(&self) -> Option<TriggerQuery> {
+        if self.secure
+            || self.mask.is_some()
+            || !self.selected_range.is_empty()
+            || !self.extra_selections.is_empty()
+        {
+            return None;
         }
-        self.replace_text_in_range(None, "", window, cx);
+        tokens::trigger_query(
+            &self.content,
+            self.cursor_offset(),
+            &self.token_triggers,
+            &self.tokens,
+        )
     }
 
-    pub(crate) fn delete_word_left(
+    /// Replaces `range` with `text` as a token standing for `payload`, as one
+    /// undo step, and puts the caret after it and a space. `range` is usually
+    /// a [`TriggerQuery`]'s; an empty one inserts the token there.
+    ///
+    /// Returns false, changing nothing, for a range that does not fit the
+    /// content, an empty `text`, or a [secure](Self::secure) or
+    /// [masked](Self::mask) input — and changing only the text if the input's
+    /// [validation](super::validation) would not let `text` in as it is.
+    ///
+    /// Programmatic, so [`read_only`](Self::read_only) does not apply.
+    pub fn insert_token(
         &mut self,
-        _: &DeleteWordLeft,
-        window: &mut Window,
+        range: Range<usize>,
+        text: &str,
+        payload: impl Into<String>,
         cx: &mut Context<Self>,
-    ) {
-        // Guarded here rather than left to `replace_text_in_range`: each of
-        // these *moves the selection* before deleting through it, so a
-        // read-only field would otherwise answer a delete key by silently
-        // eating a grapheme of the user's selection.
-        if self.read_only {
-            return;
+    ) -> bool {
+        if self.secure
+            || self.mask.is_some()
+            || text.is_empty()
+            || range.start > range.end
+            || range.end > self.content.len()
+            || !self.content.is_char_boundary(range.start)
+            || !self.content.is_char_boundary(range.end)
+        {
+            return false;
         }
+        // A space already there is stepped over rather than doubled.
+        let spaced = self.content[range.end..]
+            .chars()
+            .next()
+            .filter(|ch| ch.is_whitespace());
+        let inserted = match spaced {
+            Some(_) => text.to_string(),
+            None => format!("{text} "),
+        };
 
-        if self.selected_range.is_empty() {
-            self.select_to(self.previous_word_boundary(self.cursor_offset()), cx);
+        self.extra_selections.clear();
+        self.marked_range = None;
+        self.selected_range = range.clone();
+        self.selection_reversed = false;
+        self.break_undo_group();
+        self.insert_text(&inserted, cx);
+        self.break_undo_group();
+
+        let token = range.start..range.start + text.len();
+        if self.content.get(token.clone()) != Some(text) {
+            return false;
         }
-        self.replace_text_in_range(None, "", window, cx);
+        self.tokens.push(Token::new(token, payload));
+        self.tokens.sort_by_key(|token| token.range.start);
+        if let Some(space) = spaced {
+            let caret = (self.cursor_offset() + space.len_utf8()).min(self.content.len());
+            self.selected_range = caret..caret;
+        }
+        self.scroll_to_cursor();
+        cx.notify();
+        true
     }
 
-    pub(crate) fn delete_word_right(
-        &mut self,
-        _: &DeleteWordRight,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        // Guarded here rather than left to `replace_text_in_range`: each of
-        // these *moves the selection* before deleting through it, so a
-        // read-only field would otherwise answer a delete key by silently
-        // eating a grapheme of the user's selection.
-        if self.read_only {
-            return;
+    /// The content and its tokens, to be put back with
+    /// [`set_tokenized_text`](Self::set_tokenized_text).
+    pub fn tokenized_text(&self) -> TokenizedText {
+        TokenizedText {
+            text: self.content.clone(),
+            tokens: self.tokens.clone(),
         }
+    }
 
-        if self.selected_range.is_empty() {
-            self.select_to(self.next_word_boundary(self.cursor_offset()), cx);
-        }
-        self.replace_text_in_range(None, "", window, cx);
+    /// Sets the content and its tokens, as [`set_content`](Self::set_content)
+    /// does the content. Tokens that do not fit the content, or overlap one
+    /// before them, are dropped; a [secure](Self::secure) or
+    /// [masked](Self::mask) input keeps none.
+    pub fn set_tokenized_text(&mut self, value: TokenizedText, cx: &mut Context<Self>) {
+        self.set_content(value.text, cx);
+        self.tokens = if self.secure || self.mask.is_some() {
+            Vec::new()
+        } else {
+            tokens::sanitize(value.tokens, &self.content)
+        };
     }
 
-    pub(crate) fn delete_to_beginning_of_line(
-        &mut self,
-        _: &DeleteToBeginningOfLine,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        // Guarded here rather than left to `replace_text_in_range`: each of
-        // these *moves the selection* before deleting through it, so a
-        // read-only field would otherwise answer a delete key by silently
-        // eating a grapheme of the user's selection.
-        if self.read_only {
+    /// Gives the text bold, italic, code and link spans, toggled with
+    /// `cmd-b` and `cmd-i` and read and written as markdown. See
+    /// [`attributes`](super::attributes). Never in a [secure](Self::secure) or
+    /// [masked](Self::mask) input.
+    pub fn attributed(mut self, attributed: bool) -> Self {
+        self.attributed = attributed;
+        self
+    }
+
+    /// Turns attributes on or off after construction. Turning them off
+    /// drops the spans the text has.
+    pub fn set_attributed(&mut self, attributed: bool, cx: &mut Context<Self>) {
+        if self.attributed == attributed {
             return;
         }
-
-        if self.selected_range.is_empty() {
-            self.select_to(self.find_line_start(self.cursor_offset()), cx);
+        self.attributed = attributed;
+        if !attributed {
+            self.set_attributes(Vec::new(), cx);
         }
-        self.replace_text_in_range(None, "", window, cx);
     }
 
-    pub(crate) fn delete_to_end_of_line(
-        &mut self,
-        _: &DeleteToEndOfLine,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        // Guarded here rather than left to `replace_text_in_range`: each of
-        // these *moves the selection* before deleting through it, so a
-        // read-only field would otherwise answer a delete key by silently
-        // eating a grapheme of the user's selection.
-        if self.read_only {
-            return;
-        }
+    /// Returns whether the text carries attributes — see
+    /// [`attributed`](Self::attributed).
+    pub fn is_attributed(&self) -> bool {
+        self.attributed
+    }
 
-        if self.selected_range.is_empty() {
-            self.select_to(self.find_line_end(self.cursor_offset()), cx);
-        }
-        self.replace_text_in_range(None, "", window, cx);
+    /// Whether attributes can be given to the text as it now is.
+    fn takes_attributes(&self) -> bool {
+        self.attributed && !self.secure && self.mask.is_none()
     }
 
-    pub(crate) fn paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
-        if self.read_only {
-            return;
+    /// Every attribute span, in order of where it starts, where it now is.
+    pub fn attributes(&self) -> &[AttributeSpan] {
+        &self.attributes
+    }
+
+    /// Replaces the attribute spans. Spans that do not fit the content are
+    /// dropped, and those of one attribute that touch are merged; an input
+    /// that is not [attributed](Self::attributed) keeps none. Not an undo
+    /// step.
+    pub fn set_attributes(&mut self, spans: Vec<AttributeSpan>, cx: &mut Context<Self>) {
+        self.attributes = if self.takes_attributes() {
+            attributes::sanitize(spans, &self.content)
+        } else {
+            Vec::new()
+        };
+        self.typing_attributes = None;
+        self.needs_layout = true;
+        cx.emit(InputStateEvent::AttributesChanged);
+        cx.notify();
+    }
+
+    /// Takes `attribute` off every selection if all of their text has it, and
+    /// puts it on all of it otherwise, as one undo step. With nothing
+    /// selected, changes what text typed at the caret gets instead.
+    ///
+    /// Returns false, changing nothing, if the input is not
+    /// [attributed](Self::attributed) or is in the middle of an IME
+    /// composition.
+    ///
+    /// Programmatic, so [`read_only`](Self::read_only) does not apply.
+    pub fn toggle_attribute(&mut self, attribute: Attribute, cx: &mut Context<Self>) -> bool {
+        if !self.takes_attributes() || self.marked_range.is_some() {
+            return false;
         }
+        let ranges: Vec<Range<usize>> = self
+            .selections()
+            .into_iter()
+            .map(|selection| selection.range)
+            .filter(|range| !range.is_empty())
+            .collect();
+        let (Some(first), Some(last)) = (ranges.first(), ranges.last()) else {
+            let caret = self.cursor_offset();
+            let mut typing = match self.typing_attributes.take() {
+                Some((at, typing)) if at == caret => typing,
+                _ => attributes::at(&self.attributes, caret),
+            };
+            attributes::toggle(&mut typing, attribute);
+            self.typing_attributes = Some((caret, typing));
+            return true;
+        };
 
-        if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
-            if self.multiline {
-                self.replace_text_in_range(None, &text, window, cx);
+        let on = !ranges
+            .iter()
+            .all(|range| attributes::covers(&self.attributes, range, &attribute));
+        self.push_restyle_patch(first.start..last.end);
+        for range in &ranges {
+            if on {
+                attributes::add(&mut self.attributes, range, attribute.clone());
             } else {
-                // Strip newlines for single-line input
-                let text = text.replace('\n', " ").replace('\r', "");
-                self.replace_text_in_range(None, &text, window, cx);
+                attributes::remove(&mut self.attributes, range, |other| *other == attribute);
             }
         }
+        self.typing_attributes = None;
+        self.needs_layout = true;
+        cx.emit(InputStateEvent::AttributesChanged);
+        cx.notify();
+        true
     }
 
-    pub(crate) fn copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
-            // Nothing to copy here, so the action is not ours to consume. gpui
-            // clears `propagate_event` before every bubble-phase listener, so
-            // returning without this call would silently swallow the action and
-            // stop anything further out on the focus path from handling it —
-            // a selection elsewhere in the window, for instance.
-            cx.propagate();
-            return;
-        }
-
-        cx.write_to_clipboard(ClipboardItem::new_string(
-            self.content[self.selected_range.clone()].to_string(),
-        ));
+    /// The content as markdown, its attributes as markup and anything else
+    /// that would read as markup escaped. See [`attributes`](super::attributes).
+    pub fn markdown(&self) -> String {
+        attributes::to_markdown(&self.content, &self.attributes)
     }
 
-    pub(crate) fn cut(&mut self, _: &Cut, window: &mut Window, cx: &mut Context<Self>) {
-        // A read-only input still *takes* its text — taking is not an edit —
-        // so cut keeps the copy and drops the removal.
-        let read_only = self.read_only;
-
-        if !self.selected_range.is_empty() {
-            // Cut selected text
-            cx.write_to_clipboard(ClipboardItem::new_string(
-                self.content[self.selected_range.clone()].to_string(),
-            ));
-            if read_only {
-                return;
-            }
-            self.replace_text_in_range(None, "", window, cx);
-        } else {
-            // No selection: cut the entire current line (including newline)
-            let cursor = self.cursor_offset();
-            let line_start = self.find_line_start(cursor);
-            let line_end = self.find_line_end(cursor);
+    /// Sets the content from `source`, read as markdown: its text as the
+    /// content and its bold, italic, code and links as attributes — as
+    /// [`set_content`](Self::set_content) does, with the undo history cleared.
+    /// An input that is not [attributed](Self::attributed) keeps the text
+    /// only.
+    pub fn set_markdown(&mut self, source: &str, cx: &mut Context<Self>) {
+        self.set_rich_text(&attributes::parse_markdown(source), cx);
+    }
 
-            // Include the newline character if there is one after the line
-            let cut_end = if line_end < self.content.len() {
-                line_end + 1 // Include the newline
-            } else if line_start > 0 {
-                // Last line with no trailing newline - include preceding newline instead
-                line_end
-            } else {
-                line_end
-            };
+    /// The content and its attributes as the markdown elements'
+    /// [`RichText`].
+    pub fn rich_text(&self) -> RichText {
+        attributes::to_rich_text(&self.content, &self.attributes)
+    }
 
-            // For last line, also remove the preceding newline if it exists
-            let cut_start = if line_end >= self.content.len() && line_start > 0 {
-                line_start - 1 // Include preceding newline for last line
-            } else {
-                line_start
-            };
+    /// Sets the content and its attributes from `rich`, as
+    /// [`set_markdown`](Self::set_markdown) does. Strikethrough is dropped.
+    pub fn set_rich_text(&mut self, rich: &RichText, cx: &mut Context<Self>) {
+        let (text, spans) = attributes::from_rich_text(rich);
+        self.set_content(text, cx);
+        self.set_attributes(spans, cx);
+    }
 
-            let line_text = self.content[cut_start..cut_end].to_string();
-            cx.write_to_clipboard(ClipboardItem::new_string(line_text));
+    /// Sets the colours attributed text is shaped in, laying it out again if
+    /// they changed. Code's wash is the element's to paint, under the
+    /// selection, so a code background here is not used.
+    pub(crate) fn set_inline_palette(&mut self, palette: InlinePalette) {
+        let palette = InlinePalette {
+            code_background: None,
+            ..palette
+        };
+        if self.inline_palette != palette {
+            self.inline_palette = palette;
+            self.needs_layout = true;
+        }
+    }
 
-            if read_only {
-                return;
-            }
+    /// Whether the text is drawn with its attributes: not when it is drawn
+    /// as bullets.
+    fn shows_attributes(&self) -> bool {
+        self.attributed && !self.is_obscured()
+    }
 
-            self.selected_range = cut_start..cut_end;
-            self.replace_text_in_range(None, "", window, cx);
+    /// The ranges drawn as inline code, for the element to paint a wash
+    /// behind.
+    pub(crate) fn code_ranges(&self) -> Vec<Range<usize>> {
+        if !self.shows_attributes() {
+            return Vec::new();
         }
+        self.attributes
+            .iter()
+            .filter(|span| span.attribute == Attribute::Code)
+            .map(|span| span.range.clone())
+            .collect()
+    }
+
+    /// The runs `line` of the content is shaped with, one for each piece its
+    /// attributes cut it into.
+    fn attributed_runs(&self, line: Range<usize>, text_style: &TextStyle) -> Vec<TextRun> {
+        let mut base = text_style.clone();
+        base.background_color = None;
+        base.underline = None;
+        base.strikethrough = None;
+        let spans = attributes::within(&self.attributes, &line);
+        attributes::segments(&spans, line.len())
+            .into_iter()
+            .map(|(piece, over)| {
+                let mut style = InlineStyle::new();
+                for attribute in over {
+                    match attribute {
+                        Attribute::Bold => style.bold = true,
+                        Attribute::Italic => style.italic = true,
+                        Attribute::Code => style.code = true,
+                        Attribute::Link(_) => style.link = Some(0),
+                    }
+                }
+                base.clone()
+                    .highlight(style.to_highlight_style_with(&self.inline_palette))
+                    .to_run(piece.len())
+            })
+            .collect()
     }
 
-    pub(crate) fn on_mouse_down(
+    pub(crate) fn toggle_bold(&mut self, _: &ToggleBold, _: &mut Window, cx: &mut Context<Self>) {
+        self.toggle_attribute_action(Attribute::Bold, cx);
+    }
+
+    pub(crate) fn toggle_italic(
         &mut self,
-        position: Point<Pixels>,
-        click_count: usize,
-        shift: bool,
-        window: &mut Window,
+        _: &ToggleItalic,
+        _: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        window.focus(&self.focus_handle, cx);
-        self.is_selecting = true;
+        self.toggle_attribute_action(Attribute::Italic, cx);
+    }
 
-        let is_same_position = self
-            .last_click_position
-            .map(|last| {
-                let threshold = px(4.);
-                (position.x - last.x).abs() < threshold && (position.y - last.y).abs() < threshold
-            })
-            .unwrap_or(false);
+    pub(crate) fn toggle_code(&mut self, _: &ToggleCode, _: &mut Window, cx: &mut Context<Self>) {
+        self.toggle_attribute_action(Attribute::Code, cx);
+    }
 
-        if is_same_position && click_count > 1 {
-            self.click_count = click_count;
-        } else {
-            self.click_count = 1;
+    /// Toggles `attribute` for a keystroke, which passes the keystroke on
+    /// from an input without attributes — `cmd-b` may mean something to
+    /// what is round it.
+    fn toggle_attribute_action(&mut self, attribute: Attribute, cx: &mut Context<Self>) {
+        if self.read_only || !self.toggle_attribute(attribute, cx) {
+            cx.propagate();
         }
-        self.last_click_position = Some(position);
+    }
 
-        let clicked_offset = self.index_for_position(position);
+    /// Where a caret at `offset` would have been drawn in the last frame, in
+    /// window coordinates: a line's height tall and no wider than a point.
+    /// What a popup that follows the text is anchored to. `None` before the
+    /// input is first drawn, or for an offset past the text drawn then.
+    ///
+    /// The text before an edit is laid out as it was, so the offset where a
+    /// [`TriggerQuery`] starts is already in place in the frame after its
+    /// trigger is typed, and stays put while the query grows.
+    pub fn caret_bounds_at(&self, offset: usize) -> Option<Bounds<Pixels>> {
+        let bounds = self.last_bounds?;
+        let line = self
+            .line_layouts
+            .iter()
+            .find(|line| line.text_range.start <= offset && offset <= line.text_range.end)?;
+        let local = line
+            .position_for_index(offset - line.text_range.start, self.line_height)
+            .unwrap_or_default();
+        let origin = if self.multiline {
+            point(
+                bounds.left() + local.x,
+                bounds.top() + line.y_offset + local.y - self.scroll_offset,
+            )
+        } else {
+            let y_offset = (bounds.size.height - self.line_height).max(px(0.)) / 2.;
+            point(
+                bounds.left() + local.x - self.scroll_offset,
+                bounds.top() + y_offset + local.y,
+            )
+        };
+        Some(Bounds::new(origin, gpui::size(px(0.), self.line_height)))
+    }
 
-        match self.click_count {
-            2 => {
-                let (word_start, word_end) = self.word_range_at(clicked_offset);
-                self.selected_range = word_start..word_end;
-                self.selection_reversed = false;
-                cx.notify();
-            }
-            3 => {
-                let line_start = self.find_line_start(clicked_offset);
-                let line_end = self.find_line_end(clicked_offset);
-                let line_end_with_newline = if line_end < self.content.len() {
-                    line_end + 1
-                } else {
-                    line_end
-                };
-                self.selected_range = line_start..line_end_with_newline;
-                self.selection_reversed = false;
-                cx.notify();
-            }
-            _ => {
-                if shift {
-                    self.select_to(clicked_offset, cx);
-                } else {
-                    self.move_to(clicked_offset, cx);
+    /// Moves the owner's decorations and the tokens for `range` having been
+    /// replaced by `new_len` bytes. Undo and redo move the tokens themselves.
+    ///
+    /// Attributes move too, and text typed where a toggle left
+    /// [typing attributes](Self::toggle_attribute) takes those instead. An
+    /// edit also ends a run of kills, and the yank a yank-pop would replace.
+    fn shift_spans(&mut self, range: &Range<usize>, new_len: usize) {
+        self.last_kill = None;
+        self.last_yank = None;
+        self.shift_decorations(range, new_len);
+        tokens::shift(&mut self.tokens, range, new_len);
+        attributes::shift(&mut self.attributes, range, new_len);
+        match self.typing_attributes.take() {
+            Some((at, typing)) if range.is_empty() && range.start == at && new_len > 0 => {
+                let typed = at..at + new_len;
+                attributes::remove(&mut self.attributes, &typed, |_| true);
+                for attribute in &typing {
+                    attributes::add(&mut self.attributes, &typed, attribute.clone());
                 }
+                self.typing_attributes = Some((typed.end, typing));
             }
+            _ => {}
         }
     }
 
-    pub(crate) fn on_mouse_up(&mut self, _cx: &mut Context<Self>) {
-        self.is_selecting = false;
+    /// Moves the owner's decorations for the content having changed, all at
+    /// once, from `before`: as one edit over the part that differs.
+    fn shift_decorations_from(&mut self, before: &str) {
+        if self.decorations.is_empty() {
+            return;
+        }
+        let (range, new_len) = changed_region(before, &self.content);
+        self.shift_decorations(&range, new_len);
     }
 
-    pub(crate) fn on_mouse_move(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
-        if self.is_selecting && self.click_count == 1 {
-            self.select_to(self.index_for_position(position), cx);
-        }
+    /// Returns the selected text range in UTF-16 offsets (for IME).
+    pub fn selected_text_range_utf16(&self) -> Range<usize> {
+        self.range_to_utf16(&self.selected_range)
     }
 
-    fn move_to(&mut self, offset: usize, cx: &mut Context<Self>) {
-        self.pause_cursor_blink(cx);
-        let offset = offset.min(self.content.len());
-        self.selected_range = offset..offset;
-        self.selection_reversed = false;
-        self.scroll_to_cursor();
-        cx.notify();
-    }
-
-    fn select_to(&mut self, offset: usize, cx: &mut Context<Self>) {
-        self.pause_cursor_blink(cx);
-        let offset = offset.min(self.content.len());
-        if self.selection_reversed {
-            self.selected_range.start = offset;
-        } else {
-            self.selected_range.end = offset;
-        }
-        if self.selected_range.end < self.selected_range.start {
-            self.selection_reversed = !self.selection_reversed;
-            self.selected_range = self.selected_range.end..self.selected_range.start;
+    /// Inserts text at the current cursor position, replacing any selection.
+    ///
+    /// Deliberately **not** gated by [`read_only`](Self::read_only): this is
+    /// the owner's programmatic API, and read-only is about what the *user*
+    /// can do — the same split as assigning to a `readonly` input's value from
+    /// script in a browser.
+    pub fn insert_text(&mut self, text: &str, cx: &mut Context<Self>) {
+        if self.marked_range.is_none() && !self.extra_selections.is_empty() {
+            self.replace_selections(&[text], cx);
+            return;
         }
-        self.scroll_to_cursor();
-        cx.notify();
-    }
-
-    pub(crate) fn find_line_start(&self, offset: usize) -> usize {
-        self.content[..offset.min(self.content.len())]
-            .rfind('\n')
-            .map(|pos| pos + 1)
-            .unwrap_or(0)
-    }
 
-    pub(crate) fn find_line_end(&self, offset: usize) -> usize {
-        self.content[offset.min(self.content.len())..]
-            .find('\n')
-            .map(|pos| offset + pos)
-            .unwrap_or(self.content.len())
-    }
+        let range = self
+            .marked_range
+            .clone()
+            .unwrap_or(self.selected_range.clone());
+        let range = range.start.min(self.content.len())..range.end.min(self.content.len());
 
-    /// Returns the text direction for a specific line layout by index.
-    pub fn line_direction(&self, line_idx: usize) -> TextDirection {
-        self.line_layouts
-            .get(line_idx)
-            .map(|layout| layout.direction)
-            .unwrap_or_default()
-    }
+        let text = match self.admit(&range, text, cx) {
+            Some(text) => text,
+            // A refused commit still ends the composition, whose text is
+            // already in the content.
+            None if self.marked_range.is_some() => String::new(),
+            None => return,
+        };
 
-    /// Returns the text direction at a given byte offset in the content.
-    pub fn direction_at_offset(&self, offset: usize) -> TextDirection {
-        let offset = offset.min(self.content.len());
-        for layout in &self.line_layouts {
-            if offset >= layout.text_range.start && offset <= layout.text_range.end {
-                return layout.direction;
-            }
+        if let Some(mask) = self.mask.clone() {
+            self.replace_masked(range, &text, mask.as_ref(), cx);
+            return;
         }
-        TextDirection::default()
-    }
-
-    fn move_vertically(&self, offset: usize, direction: i32) -> Option<usize> {
-        let (visual_line_idx, x_pixels) = self.find_visual_line_and_x_offset(offset);
-        let target_visual_line_idx = (visual_line_idx as i32 + direction).max(0) as usize;
-
-        let mut current_visual_line = 0;
-        for layout in self.line_layouts.iter() {
-            let visual_lines_in_layout = layout.visual_line_count;
-
-            if target_visual_line_idx < current_visual_line + visual_lines_in_layout {
-                let visual_line_within_layout = target_visual_line_idx - current_visual_line;
-
-                if layout.text_range.is_empty() {
-                    return Some(layout.text_range.start);
-                }
 
-                if let Some(wrapped) = &layout.wrapped_line {
-                    let y_within_wrapped = self.line_height * visual_line_within_layout as f32;
-                    let target_point = point(px(x_pixels), y_within_wrapped);
+        let sanitized_text;
+        let text_to_insert = if self.multiline {
+            &text
+        } else {
+            sanitized_text = text.replace('\n', " ").replace('\r', "");
+            &sanitized_text
+        };
 
-                    let closest_result =
-                        wrapped.closest_index_for_position(target_point, self.line_height);
+        // Record patch for undo before modifying content
+        self.push_undo_patch(range.clone(), text_to_insert.len());
 
-                    let closest_idx = closest_result.unwrap_or_else(|closest| closest);
-                    let clamped = closest_idx.min(wrapped.text.len());
-                    let result = layout.text_range.start + clamped;
+        // Update cached UTF-16 length incrementally if available
+        if let Some(cached_len) = self.cached_utf16_len {
+            let removed_utf16_len: usize = self.content[range.clone()]
+                .chars()
+                .map(|c| c.len_utf16())
+                .sum();
+            let added_utf16_len: usize = text_to_insert.chars().map(|c| c.len_utf16()).sum();
+            self.cached_utf16_len = Some(cached_len - removed_utf16_len + added_utf16_len);
+        }
 
-                    return Some(result);
-                }
+        self.content.replace_range(range.clone(), text_to_insert);
+        self.shift_spans(&range, text_to_insert.len());
+        self.selected_range =
+            range.start + text_to_insert.len()..range.start + text_to_insert.len();
+        self.marked_range.take();
+        self.needs_layout = true;
+        self.pause_cursor_blink(cx);
+        self.text_changed(cx);
+        cx.notify();
+    }
 
-                return Some(layout.text_range.start);
+    /// Replaces `range` of the formatted content with `text` through `mask`:
+    /// the edit is made to the raw value, and the content is formatted again
+    /// from the result.
+    fn replace_masked(
+        &mut self,
+        range: Range<usize>,
+        text: &str,
+        mask: &dyn InputMask,
+        cx: &mut Context<Self>,
+    ) {
+        let before = mask.format(&mask.unformat(&self.content));
+        let raw: Vec<char> = mask.unformat(&self.content).chars().collect();
+        let mut start = before.raw_index(range.start);
+        let mut end = before.raw_index(range.end);
+
+        // A delete that covered only separators takes the value character
+        // beyond them, in the direction it was going: backspace selects
+        // leftwards, so its selection is reversed.
+        if text.is_empty() && start == end && !range.is_empty() {
+            if self.selection_reversed {
+                start = start.saturating_sub(1);
+            } else {
+                end = (end + 1).min(raw.len());
             }
-
-            current_visual_line += visual_lines_in_layout;
         }
 
-        if direction > 0 {
-            Some(self.content.len())
+        let replaces_everything = range.start == 0 && range.end >= self.content.len();
+        let (value, cursor) = if replaces_everything && text.chars().count() > 1 {
+            // A paste over the whole content: read it as formatted, so a
+            // pasted `+1 (555) 123-4567` keeps its `1` out of the value. A
+            // single typed character is always a value character.
+            let value = mask.unformat(text);
+            let cursor = value.chars().count();
+            (value, cursor)
         } else {
-            None
+            let head: String = raw[..start].iter().collect();
+            let tail: String = raw[end..].iter().collect();
+            let cursor = mask.accept(&format!("{head}{text}")).chars().count();
+            (mask.accept(&format!("{head}{text}{tail}")), cursor)
+        };
+        let after = mask.format(&value);
+
+        if after.text != self.content {
+            // Patch only what differs, so undo restores exactly this edit.
+            let prefix = common_prefix(&self.content, &after.text);
+            let suffix = common_suffix(&self.content[prefix..], &after.text[prefix..]);
+            let replaced = prefix..self.content.len() - suffix;
+            let inserted = &after.text[prefix..after.text.len() - suffix];
+            self.push_undo_patch(replaced.clone(), inserted.len());
+            self.shift_spans(&replaced, inserted.len());
+            self.content.replace_range(replaced, inserted);
+            self.cached_utf16_len = None;
+            self.needs_layout = true;
+            self.text_changed(cx);
         }
+
+        let offset = after.offset_for_raw_index(cursor);
+        self.selected_range = offset..offset;
+        self.selection_reversed = false;
+        self.marked_range = None;
+        self.pause_cursor_blink(cx);
+        cx.notify();
     }
 
-    fn find_visual_line_and_x_offset(&self, offset: usize) -> (usize, f32) {
-        if self.line_layouts.is_empty() {
-            return (0, 0.0);
+    /// Deletes the character before the cursor (convenience method for benchmarks).
+    ///
+    /// Programmatic, so [`read_only`](Self::read_only) does not apply — unlike
+    /// the `backspace` action, which it does.
+    pub fn delete_backward(&mut self, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.select_to(self.previous_boundary(self.cursor_offset()), cx);
         }
+        self.insert_text("", cx);
+    }
 
-        let mut visual_line_idx = 0;
-
-        for line in &self.line_layouts {
-            if line.text_range.is_empty() {
-                if offset == line.text_range.start {
-                    return (visual_line_idx, 0.0);
-                }
-            } else if offset >= line.text_range.start && offset <= line.text_range.end {
-                if let Some(wrapped) = &line.wrapped_line {
-                    let local_offset = (offset - line.text_range.start).min(wrapped.text.len());
-                    if let Some(position) =
-                        wrapped.position_for_index(local_offset, self.line_height)
-                    {
-                        let visual_line_within = (position.y / self.line_height).floor() as usize;
-                        return (visual_line_idx + visual_line_within, position.x.into());
-                    }
-                }
-                return (visual_line_idx, 0.0);
+    /// Undoes the last edit (convenience method without Window).
+    ///
+    /// Programmatic, so [`read_only`](Self::read_only) does not apply — unlike
+    /// the `undo` action, which it does.
+    pub fn undo_action(&mut self, cx: &mut Context<Self>) {
+        if let Some(entry) =
+            self.history
+                .undo(&mut self.content, &mut self.tokens, &mut self.attributes)
+        {
+            // Restore the selection from before the edit, clamped: an entry
+            // from a restored history may not fit the content
+            let primary = Selection {
+                range: self.clamp_range(&entry.selected_range),
+                reversed: entry.selection_reversed,
+            };
+            let extras = entry
+                .extra_selections
+                .iter()
+                .map(|selection| Selection {
+                    range: self.clamp_range(&selection.range),
+                    reversed: selection.reversed,
+                })
+                .collect();
+            self.set_selections(primary, extras);
+            self.needs_layout = true;
+            self.cached_utf16_len = None;
+            self.typing_attributes = None;
+            if !entry.restyle {
+                self.shift_decorations(
+                    &(entry.range.start..entry.range.start + entry.new_text_len),
+                    entry.old_text.len(),
+                );
             }
-            visual_line_idx += line.visual_line_count;
+            self.refresh_search();
+            self.refresh_checks();
+            self.scroll_to_cursor();
+            cx.emit(InputStateEvent::Undo);
+            cx.notify();
         }
-
-        (visual_line_idx.saturating_sub(1), 0.0)
     }
 
-    pub(crate) fn index_for_position(&self, position: Point<Pixels>) -> usize {
-        if self.content.is_empty() {
-            return 0;
+    /// Redoes the last undone edit (convenience method without Window).
+    ///
+    /// Programmatic, so [`read_only`](Self::read_only) does not apply — unlike
+    /// the `redo` action, which it does.
+    pub fn redo_action(&mut self, cx: &mut Context<Self>) {
+        if let Some(entry) =
+            self.history
+                .redo(&mut self.content, &mut self.tokens, &mut self.attributes)
+        {
+            // The undo entry now recorded covers the redone text; the
+            // cursor goes to its start, or for a restyle, the text restyled
+            // is selected again
+            let cursor_pos = entry.range.start;
+            self.selected_range = if entry.restyle {
+                entry.range.clone()
+            } else {
+                cursor_pos..cursor_pos
+            };
+            self.selection_reversed = false;
+            self.extra_selections.clear();
+            self.needs_layout = true;
+            self.cached_utf16_len = None;
+            self.typing_attributes = None;
+            if !entry.restyle {
+                self.shift_decorations(
+                    &(entry.range.start..entry.range.start + entry.old_text.len()),
+                    entry.new_text_len,
+                );
+            }
+            self.refresh_search();
+            self.refresh_checks();
+            self.scroll_to_cursor();
+            cx.emit(InputStateEvent::Redo);
+            cx.notify();
         }
+    }
 
-        for line in self.line_layouts.iter() {
-            let line_height_total = self.line_height * line.visual_line_count as f32;
-
-            if position.y >= line.y_offset && position.y < line.y_offset + line_height_total {
-                if line.text_range.is_empty() {
-                    return line.text_range.start;
-                }
-
-                if let Some(wrapped) = &line.wrapped_line {
-                    let relative_y = position.y - line.y_offset;
-                    let relative_point = point(position.x, relative_y);
-
-                    let closest_result =
-                        wrapped.closest_index_for_position(relative_point, self.line_height);
+    /// Selects all text.
+    pub fn select_all(&mut self, _: &SelectAll, _: &mut Window, cx: &mut Context<Self>) {
+        self.extra_selections.clear();
+        self.selected_range = 0..self.content.len();
+        self.selection_reversed = false;
+        cx.notify();
+    }
 
-                    let local_idx = closest_result.unwrap_or_else(|closest| closest);
-                    let clamped = local_idx.min(wrapped.text.len());
-                    return line.text_range.start + clamped;
-                }
-                return line.text_range.start;
+    pub(crate) fn left(&mut self, _: &Left, _: &mut Window, cx: &mut Context<Self>) {
+        self.each_selection(cx, |this, cx| {
+            if this.selected_range.is_empty() {
+                let new_pos = this.previous_boundary(this.cursor_offset());
+                this.move_to(new_pos, cx);
+            } else {
+                this.move_to(this.selected_range.start, cx);
             }
-        }
-
-        self.content.len()
+        });
     }
 
-    pub(crate) fn scroll_to_cursor(&mut self) {
-        if self.line_layouts.is_empty() {
+    pub(crate) fn right(&mut self, _: &Right, window: &mut Window, cx: &mut Context<Self>) {
+        // A suggestion is only shown with the cursor at the end, where Right
+        // has nowhere else to go.
+        if self.accept_completion(window, cx) {
             return;
         }
+        self.each_selection(cx, |this, cx| {
+            if this.selected_range.is_empty() {
+                let new_pos = this.next_boundary(this.cursor_offset());
+                this.move_to(new_pos, cx);
+            } else {
+                this.move_to(this.selected_range.end, cx);
+            }
+        });
+    }
 
-        let cursor_offset = if self.selection_reversed {
-            self.selected_range.start
-        } else {
-            self.selected_range.end
-        };
-
-        if self.multiline {
-            self.scroll_to_cursor_vertical(cursor_offset);
-        } else {
-            self.scroll_to_cursor_horizontal(cursor_offset);
+    pub(crate) fn up(&mut self, _: &Up, window: &mut Window, cx: &mut Context<Self>) {
+        if self.recall_older(window, cx) {
+            return;
         }
+        self.each_selection(cx, |this, cx| {
+            this.pause_cursor_blink(cx);
+            if !this.multiline {
+                // In single-line mode, up moves to start
+                this.selected_range = 0..0;
+                this.selection_reversed = false;
+                this.scroll_to_cursor();
+                cx.notify();
+                return;
+            }
+            if let Some(new_offset) = this.move_vertically(this.cursor_offset(), -1) {
+                this.selected_range = new_offset..new_offset;
+                this.selection_reversed = false;
+                this.scroll_to_cursor();
+                cx.notify();
+            }
+        });
     }
 
-    fn scroll_to_cursor_vertical(&mut self, cursor_offset: usize) {
-        if self.available_height <= px(0.) {
+    pub(crate) fn down(&mut self, _: &Down, window: &mut Window, cx: &mut Context<Self>) {
+        if self.recall_newer(window, cx) {
             return;
         }
+        self.each_selection(cx, |this, cx| {
+            this.pause_cursor_blink(cx);
+            if !this.multiline {
+                // In single-line mode, down moves to end
+                let end = this.content.len();
+                this.selected_range = end..end;
+                this.selection_reversed = false;
+                this.scroll_to_cursor();
+                cx.notify();
+                return;
+            }
+            if let Some(new_offset) = this.move_vertically(this.cursor_offset(), 1) {
+                this.selected_range = new_offset..new_offset;
+                this.selection_reversed = false;
+                this.scroll_to_cursor();
+                cx.notify();
+            }
+        });
+    }
 
-        let line_height = self.line_height;
+    pub(crate) fn select_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.each_selection(cx, |this, cx| {
+            this.select_to(this.previous_boundary(this.cursor_offset()), cx);
+        });
+    }
 
-        for line in &self.line_layouts {
-            let is_cursor_in_line = if line.text_range.is_empty() {
-                cursor_offset == line.text_range.start
-            } else {
-                line.text_range.contains(&cursor_offset)
-                    || (cursor_offset == line.text_range.end && cursor_offset == self.content.len())
-            };
+    pub(crate) fn select_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.each_selection(cx, |this, cx| {
+            this.select_to(this.next_boundary(this.cursor_offset()), cx);
+        });
+    }
 
-            if is_cursor_in_line {
-                let cursor_visual_y = if let Some(wrapped) = &line.wrapped_line {
-                    let local_offset = cursor_offset.saturating_sub(line.text_range.start);
-                    if let Some(position) =
-                        wrapped.position_for_index(local_offset, self.line_height)
-                    {
-                        line.y_offset + position.y
-                    } else {
-                        line.y_offset
-                    }
+    pub(crate) fn select_up(&mut self, _: &SelectUp, _window: &mut Window, cx: &mut Context<Self>) {
+        self.each_selection(cx, |this, cx| {
+            this.pause_cursor_blink(cx);
+            if !this.multiline {
+                // In single-line mode, select_up selects to start
+                this.select_to(0, cx);
+                return;
+            }
+            if let Some(new_offset) = this.move_vertically(this.cursor_offset(), -1) {
+                if this.selection_reversed {
+                    this.selected_range.start = new_offset;
                 } else {
-                    line.y_offset
-                };
-
-                let visible_top = self.scroll_offset;
-                let visible_bottom = self.scroll_offset + self.available_height;
-
-                if cursor_visual_y < visible_top {
-                    self.scroll_offset = cursor_visual_y;
-                } else if cursor_visual_y + line_height > visible_bottom {
-                    self.scroll_offset = (cursor_visual_y + line_height) - self.available_height;
+                    this.selected_range.end = new_offset;
                 }
-
-                self.scroll_offset = self.scroll_offset.max(px(0.));
-                break;
+                if this.selected_range.end < this.selected_range.start {
+                    this.selection_reversed = !this.selection_reversed;
+                    this.selected_range = this.selected_range.end..this.selected_range.start;
+                }
+                this.scroll_to_cursor();
+                cx.notify();
             }
-        }
+        });
     }
 
-    fn scroll_to_cursor_horizontal(&mut self, cursor_offset: usize) {
-        if self.available_width <= px(0.) {
-            return;
-        }
-
-        // For single-line input, get cursor x position from the first (only) line
-        let Some(line) = self.line_layouts.first() else {
-            return;
-        };
-
-        let cursor_x = if let Some(wrapped) = &line.wrapped_line {
-            let local_offset = cursor_offset.saturating_sub(line.text_range.start);
-            wrapped
-                .position_for_index(local_offset, self.line_height)
-                .map(|p| p.x)
-                .unwrap_or(px(0.))
-        } else {
-            px(0.)
-        };
-
-        let visible_left = self.scroll_offset;
-        let visible_right = self.scroll_offset + self.available_width;
-
-        // Add some padding so cursor isn't right at the edge
-        let padding = px(2.0);
+    pub(crate) fn select_down(
+        &mut self,
+        _: &SelectDown,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.each_selection(cx, |this, cx| {
+            this.pause_cursor_blink(cx);
+            if !this.multiline {
+                // In single-line mode, select_down selects to end
+                this.select_to(this.content.len(), cx);
+                return;
+            }
+            if let Some(new_offset) = this.move_vertically(this.cursor_offset(), 1) {
+                if this.selection_reversed {
+                    this.selected_range.start = new_offset;
+                } else {
+                    this.selected_range.end = new_offset;
+                }
+                if this.selected_range.end < this.selected_range.start {
+                    this.selection_reversed = !this.selection_reversed;
+                    this.selected_range = this.selected_range.end..this.selected_range.start;
+                }
+                this.scroll_to_cursor();
+                cx.notify();
+            }
+        });
+    }
 
-        if cursor_x < visible_left + padding {
-            self.scroll_offset = (cursor_x - padding).max(px(0.));
-        } else if cursor_x > visible_right - padding {
-            self.scroll_offset = cursor_x - self.available_width + padding;
-        }
+    pub(crate) fn home(&mut self, _: &Home, _: &mut Window, cx: &mut Context<Self>) {
+        self.each_selection(cx, |this, cx| {
+            let line_start = this.find_line_start(this.cursor_offset());
+            this.move_to(line_start, cx);
+        });
+    }
 
-        self.scroll_offset = self.scroll_offset.max(px(0.));
+    pub(crate) fn end(&mut self, _: &End, _: &mut Window, cx: &mut Context<Self>) {
+        self.each_selection(cx, |this, cx| {
+            let line_end = this.find_line_end(this.cursor_offset());
+            this.move_to(line_end, cx);
+        });
     }
 
-    pub(crate) fn update_line_layouts(
+    pub(crate) fn move_to_beginning(
         &mut self,
-        width: Pixels,
-        line_height: Pixels,
-        text_style: &TextStyle,
-        window: &mut Window,
+        _: &MoveToBeginning,
+        _: &mut Window,
+        cx: &mut Context<Self>,
     ) {
-        self.line_height = line_height;
-        self.set_text_style(text_style);
-
-        if !self.needs_layout && self.wrap_width == Some(width) {
-            return;
-        }
-
-        self.line_layouts.clear();
-        self.wrap_width = Some(width);
-
-        let text_color = text_style.color;
-        let font_size = text_style.font_size.to_pixels(window.rem_size());
-
-        if self.content.is_empty() {
-            self.line_layouts.push(InputLineLayout {
-                text_range: 0..0,
-                wrapped_line: None,
-                y_offset: px(0.),
-                visual_line_count: 1,
-                direction: TextDirection::default(),
-            });
-            self.needs_layout = false;
-            return;
-        }
-
-        let mut last_direction = TextDirection::default();
-
-        let mut y_offset = px(0.);
-        let mut current_pos = 0;
+        self.extra_selections.clear();
+        self.move_to(0, cx);
+    }
 
-        while current_pos < self.content.len() {
-            let line_end = self.content[current_pos..]
-                .find('\n')
-                .map(|pos| current_pos + pos)
-                .unwrap_or(self.content.len());
+    pub(crate) fn move_to_end(&mut self, _: &MoveToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.extra_selections.clear();
+        self.move_to(self.content.len(), cx);
+    }
 
-            let line_text = &self.content[current_pos..line_end];
+    pub(crate) fn select_to_beginning(
+        &mut self,
+        _: &SelectToBeginning,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.extra_selections.clear();
+        self.select_to(0, cx);
+    }
 
-            if line_text.is_empty() {
-                self.line_layouts.push(InputLineLayout {
-                    text_range: current_pos..current_pos,
-                    wrapped_line: None,
-                    y_offset,
-                    visual_line_count: 1,
-                    direction: last_direction,
-                });
-                y_offset += line_height;
-            } else {
-                let direction = detect_base_direction(line_text);
-                last_direction = direction;
-                let run = TextRun {
-                    len: line_text.len(),
-                    font: text_style.font(),
-                    color: text_color,
-                    background_color: None,
-                    underline: None,
-                    strikethrough: None,
-                };
+    pub(crate) fn select_to_end(
+        &mut self,
+        _: &SelectToEnd,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.extra_selections.clear();
+        self.select_to(self.content.len(), cx);
+    }
 
-                let wrapped_lines = window
-                    .text_system()
-                    .shape_text(
-                        SharedString::from(line_text.to_string()),
-                        font_size,
-                        &[run],
-                        Some(width),
-                        None,
-                    )
-                    .unwrap_or_default();
+    pub(crate) fn word_left(&mut self, _: &WordLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.each_selection(cx, |this, cx| {
+            let new_pos = this.previous_word_boundary(this.cursor_offset());
+            this.move_to(new_pos, cx);
+        });
+    }
 
-                for wrapped in wrapped_lines {
-                    let visual_line_count = wrapped.wrap_boundaries().len() + 1;
-                    let line_height_total = line_height * visual_line_count as f32;
+    pub(crate) fn word_right(&mut self, _: &WordRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.each_selection(cx, |this, cx| {
+            let new_pos = this.next_word_boundary(this.cursor_offset());
+            this.move_to(new_pos, cx);
+        });
+    }
 
-                    self.line_layouts.push(InputLineLayout {
-                        text_range: current_pos..line_end,
-                        wrapped_line: Some(Arc::new(wrapped)),
-                        y_offset,
-                        visual_line_count,
-                        direction,
-                    });
+    pub(crate) fn select_word_left(
+        &mut self,
+        _: &SelectWordLeft,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.each_selection(cx, |this, cx| {
+            let new_pos = this.previous_word_boundary(this.cursor_offset());
+            this.select_to(new_pos, cx);
+        });
+    }
 
-                    y_offset += line_height_total;
-                }
-            }
+    pub(crate) fn select_word_right(
+        &mut self,
+        _: &SelectWordRight,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.each_selection(cx, |this, cx| {
+            let new_pos = this.next_word_boundary(this.cursor_offset());
+            this.select_to(new_pos, cx);
+        });
+    }
 
-            current_pos = if line_end < self.content.len() {
-                line_end + 1
-            } else {
-                self.content.len()
-            };
+    pub(crate) fn enter(&mut self, _: &Enter, window: &mut Window, cx: &mut Context<Self>) {
+        // Submitting is not an edit, so a read-only input still submits;
+        // only the newline is refused.
+        if self.submit_on == Some(SubmitOn::Enter) {
+            self.emit_submit(cx);
+        } else if self.multiline && !self.read_only {
+            self.replace_text_in_range(None, "\n", window, cx);
         }
+    }
 
-        if self.content.ends_with('\n') {
-            self.line_layouts.push(InputLineLayout {
-                text_range: self.content.len()..self.content.len(),
-                wrapped_line: None,
-                y_offset,
-                visual_line_count: 1,
-                direction: last_direction,
-            });
+    pub(crate) fn submit(&mut self, _: &Submit, _window: &mut Window, cx: &mut Context<Self>) {
+        // Fires in both modes: cmd-enter submitting alongside plain enter is
+        // harmless muscle memory; without a mode it stays inert.
+        if self.submit_on.is_some() {
+            self.emit_submit(cx);
         }
-
-        self.needs_layout = false;
-        self.scroll_to_cursor();
     }
 
-    pub(crate) fn total_content_height(&self) -> Pixels {
-        self.line_layouts
-            .last()
-            .map(|last| last.y_offset + self.line_height * last.visual_line_count as f32)
-            .unwrap_or(px(0.))
+    /// Emits [`InputStateEvent::Submit`], recording the content for recall
+    /// first.
+    fn emit_submit(&mut self, cx: &mut Context<Self>) {
+        if let Some(recall) = self.recall.as_mut().filter(|_| !self.secure) {
+            recall.push(self.content.clone());
+        }
+        cx.emit(InputStateEvent::Submit);
     }
 
-    /// Returns true if the scroll position is at the top.
-    pub fn at_top(&self) -> bool {
-        self.scroll_offset <= px(0.)
+    /// Replaces a single-line input's content with the next older
+    /// submission, if there is one to recall.
+    fn recall_older(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        if self.multiline || self.secure || self.read_only {
+            return false;
+        }
+        let Some(recall) = self.recall.as_mut() else {
+            return false;
+        };
+        let Some(text) = recall.older(&self.content).map(str::to_string) else {
+            return false;
+        };
+        self.show_recalled(&text, window, cx);
+        true
     }
 
-    /// Returns true if the scroll position is at the bottom.
-    pub fn at_bottom(&self) -> bool {
-        let content_height = self.total_content_height();
-        let visible_height = self.available_height;
-
-        if content_height <= visible_height {
-            return true;
+    /// Replaces a single-line input's content with the next newer
+    /// submission, or past the newest with the draft.
+    fn recall_newer(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        if self.multiline || self.secure || self.read_only {
+            return false;
         }
+        let Some(text) = self
+            .recall
+            .as_mut()
+            .and_then(|recall| recall.newer(&self.content))
+        else {
+            return false;
+        };
+        self.show_recalled(&text, window, cx);
+        true
+    }
 
-        self.scroll_offset + visible_height >= content_height
+    /// Puts recalled `text` in place of the content, as one undo step, with
+    /// the cursor at its end.
+    fn show_recalled(&mut self, text: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.selected_range = 0..self.content.len();
+        self.selection_reversed = false;
+        self.break_undo_group();
+        self.replace_text_in_range(None, text, window, cx);
+        self.break_undo_group();
+        self.pause_cursor_blink(cx);
     }
 
-    /// Returns the scroll progress as a value from 0.0 (top) to 1.0 (bottom).
-    pub fn scroll_progress(&self) -> f32 {
-        let content_height = self.total_content_height();
-        let visible_height = self.available_height;
-        let max_scroll = content_height - visible_height;
-
-        if max_scroll <= px(0.) {
-            return 0.0;
+    pub(crate) fn insert_newline(
+        &mut self,
+        _: &InsertNewline,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.multiline && !self.read_only {
+            self.replace_text_in_range(None, "\n", window, cx);
         }
-
-        (self.scroll_offset / max_scroll).clamp(0.0, 1.0)
     }
 
-    /// Returns how far the content is scrolled from the top in pixels.
-    pub fn distance_from_top(&self) -> Pixels {
-        self.scroll_offset.max(px(0.))
+    pub(crate) fn tab(&mut self, _: &Tab, window: &mut Window, cx: &mut Context<Self>) {
+        if self.accept_completion(window, cx) {
+            return;
+        }
+        if self.read_only {
+            return;
+        }
+        self.replace_text_in_range(None, "\t", window, cx);
     }
 
-    /// Returns how far the content is from the bottom in pixels.
-    pub fn distance_from_bottom(&self) -> Pixels {
-        let content_height = self.total_content_height();
-        let visible_height = self.available_height;
-        let max_scroll = content_height - visible_height;
-
-        if max_scroll <= px(0.) {
-            return px(0.);
+    pub(crate) fn backspace(&mut self, _: &Backspace, window: &mut Window, cx: &mut Context<Self>) {
+        // Guarded here rather than left to `replace_text_in_range`: each of
+        // these *moves the selection* before deleting through it, so a
+        // read-only field would otherwise answer a delete key by silently
+        // eating a grapheme of the user's selection.
+        if self.read_only {
+            return;
         }
 
-        (max_scroll - self.scroll_offset).max(px(0.))
+        self.each_selection(cx, |this, cx| {
+            if this.selected_range.is_empty() {
+                this.select_to(this.previous_boundary(this.cursor_offset()), cx);
+            }
+        });
+        self.replace_text_in_range(None, "", window, cx);
     }
 
-    fn offset_from_utf16(&self, offset: usize) -> usize {
-        // Fast path: if offset is 0, return 0
-        if offset == 0 {
-            return 0;
+    pub(crate) fn delete(&mut self, _: &Delete, window: &mut Window, cx: &mut Context<Self>) {
+        // Guarded here rather than left to `replace_text_in_range`: each of
+        // these *moves the selection* before deleting through it, so a
+        // read-only field would otherwise answer a delete key by silently
+        // eating a grapheme of the user's selection.
+        if self.read_only {
+            return;
         }
 
-        // Fast path: if we have cached length and offset is at or past end
-        if let Some(utf16_len) = self.cached_utf16_len {
-            if offset >= utf16_len {
-                return self.content.len();
+        self.each_selection(cx, |this, cx| {
+            if this.selected_range.is_empty() {
+                this.select_to(this.next_boundary(this.cursor_offset()), cx);
             }
-        }
+        });
+        self.replace_text_in_range(None, "", window, cx);
+    }
 
-        let mut utf8_offset = 0;
-        let mut utf16_count = 0;
+    pub(crate) fn delete_word_left(
+        &mut self,
+        _: &DeleteWordLeft,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Guarded here rather than left to `replace_text_in_range`: each of
+        // these *moves the selection* before deleting through it, so a
+        // read-only field would otherwise answer a delete key by silently
+        // eating a grapheme of the user's selection.
+        if self.read_only {
+            return;
+        }
 
-        for character in self.content.chars() {
-            if utf16_count >= offset {
-                break;
+        self.each_selection(cx, |this, cx| {
+            if this.selected_range.is_empty() {
+                this.select_to(this.previous_word_boundary(this.cursor_offset()), cx);
             }
-            utf16_count += character.len_utf16();
-            utf8_offset += character.len_utf8();
+        });
+        self.replace_text_in_range(None, "", window, cx);
+    }
+
+    pub(crate) fn delete_word_right(
+        &mut self,
+        _: &DeleteWordRight,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Guarded here rather than left to `replace_text_in_range`: each of
+        // these *moves the selection* before deleting through it, so a
+        // read-only field would otherwise answer a delete key by silently
+        // eating a grapheme of the user's selection.
+        if self.read_only {
+            return;
         }
 
-        utf8_offset.min(self.content.len())
+        self.each_selection(cx, |this, cx| {
+            if this.selected_range.is_empty() {
+                this.select_to(this.next_word_boundary(this.cursor_offset()), cx);
+            }
+        });
+        self.replace_text_in_range(None, "", window, cx);
     }
 
-    fn offset_to_utf16(&self, offset: usize) -> usize {
-        // Fast path: if offset is 0, return 0
-        if offset == 0 {
-            return 0;
+    pub(crate) fn delete_to_beginning_of_line(
+        &mut self,
+        _: &DeleteToBeginningOfLine,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Guarded here rather than left to `replace_text_in_range`: each of
+        // these *moves the selection* before deleting through it, so a
+        // read-only field would otherwise answer a delete key by silently
+        // eating a grapheme of the user's selection.
+        if self.read_only {
+            return;
         }
 
-        // Fast path: if offset is at or past end, return cached length
-        if offset >= self.content.len() {
-            return self.utf16_len();
-        }
+        self.each_selection(cx, |this, cx| {
+            if this.selected_range.is_empty() {
+                this.select_to(this.find_line_start(this.cursor_offset()), cx);
+            }
+        });
+        self.replace_text_in_range(None, "", window, cx);
+    }
 
-        let mut utf16_offset = 0;
-        let mut utf8_count = 0;
+    pub(crate) fn delete_to_end_of_line(
+        &mut self,
+        _: &DeleteToEndOfLine,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Guarded here rather than left to `replace_text_in_range`: each of
+        // these *moves the selection* before deleting through it, so a
+        // read-only field would otherwise answer a delete key by silently
+        // eating a grapheme of the user's selection.
+        if self.read_only {
+            return;
+        }
 
-        for character in self.content.chars() {
-            if utf8_count >= offset {
-                break;
+        self.each_selection(cx, |this, cx| {
+            if this.selected_range.is_empty() {
+                this.select_to(this.find_line_end(this.cursor_offset()), cx);
             }
-            utf8_count += character.len_utf8();
-            utf16_offset += character.len_utf16();
-        }
+        });
+        self.replace_text_in_range(None, "", window, cx);
+    }
 
-        utf16_offset
+    pub(crate) fn kill_to_end_of_line(
+        &mut self,
+        _: &KillToEndOfLine,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // At the end of a line, the newline goes.
+        let caret = self.cursor_offset();
+        let line_end = self.find_line_end(caret);
+        let to = if line_end == caret {
+            self.next_boundary(caret)
+        } else {
+            line_end
+        };
+        self.kill_to(to, window, cx);
     }
 
-    /// Returns the UTF-16 length of the content, computing and caching if necessary.
-    fn utf16_len(&self) -> usize {
-        if let Some(len) = self.cached_utf16_len {
-            return len;
-        }
-        self.content.chars().map(|c| c.len_utf16()).sum()
+    pub(crate) fn kill_to_beginning_of_line(
+        &mut self,
+        _: &KillToBeginningOfLine,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.kill_to(self.find_line_start(self.cursor_offset()), window, cx);
     }
 
-    fn range_to_utf16(&self, range: &Range<usize>) -> Range<usize> {
-        self.offset_to_utf16(range.start)..self.offset_to_utf16(range.end)
+    pub(crate) fn kill_word_left(
+        &mut self,
+        _: &KillWordLeft,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.kill_to(
+            self.previous_word_boundary(self.cursor_offset()),
+            window,
+            cx,
+        );
     }
 
-    fn range_from_utf16(&self, range_utf16: &Range<usize>) -> Range<usize> {
-        self.offset_from_utf16(range_utf16.start)..self.offset_from_utf16(range_utf16.end)
+    pub(crate) fn kill_word_right(
+        &mut self,
+        _: &KillWordRight,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.kill_to(self.next_word_boundary(self.cursor_offset()), window, cx);
     }
 
-    fn previous_boundary(&self, offset: usize) -> usize {
-        if offset == 0 {
-            return 0;
+    /// Deletes from the cursor to `to`, or the selection if there is one,
+    /// into the [kill ring](super::kill_ring) — joining the last kill if it
+    /// left the cursor here. Each kill is an undo step of its own.
+    fn kill_to(&mut self, to: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.extra_selections.clear();
+        let caret = self.cursor_offset();
+        let range = if self.selected_range.is_empty() {
+            caret.min(to)..caret.max(to)
+        } else {
+            self.selected_range.clone()
+        };
+        if range.is_empty() {
+            return;
         }
-
-        let text_before = &self.content[..offset.min(self.content.len())];
-        text_before
-            .grapheme_indices(true)
-            .map(|(i, _)| i)
-            .next_back()
-            .unwrap_or(0)
+        // A secret never goes into the ring, where any input could yank it;
+        // a kill there only deletes.
+        if !self.secure {
+            let join = (self.last_kill == Some(caret)).then_some(if range.start < caret {
+                Join::Before
+            } else {
+                Join::After
+            });
+            let text = self.content[range.clone()].to_string();
+            cx.default_global::<KillRing>().kill(text, join);
+        }
+        // As with cut, a read-only input still gives its text up.
+        if self.read_only {
+            return;
+        }
+        self.selected_range = range;
+        self.selection_reversed = false;
+        self.break_undo_group();
+        self.replace_text_in_range(None, "", window, cx);
+        self.break_undo_group();
+        self.last_kill = Some(self.cursor_offset());
     }
 
-    fn next_boundary(&self, offset: usize) -> usize {
-        if offset >= self.content.len() {
-            return self.content.len();
+    pub(crate) fn yank(&mut self, _: &Yank, window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
         }
-
-        let text_after = &self.content[offset..];
-        text_after
-            .grapheme_indices(true)
-            .nth(1)
-            .map(|(i, _)| offset + i)
-            .unwrap_or(self.content.len())
+        let Some(text) = cx
+            .try_global::<KillRing>()
+            .and_then(|ring| ring.get(0))
+            .map(str::to_string)
+        else {
+            return;
+        };
+        self.extra_selections.clear();
+        let start = self.selected_range.start;
+        self.break_undo_group();
+        self.replace_text_in_range(None, &text, window, cx);
+        self.break_undo_group();
+        self.last_yank = Some((start..self.cursor_offset(), 0));
     }
 
-    fn previous_word_boundary(&self, offset: usize) -> usize {
-        if offset == 0 {
-            return 0;
+    pub(crate) fn yank_pop(&mut self, _: &YankPop, window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
         }
-
-        let text_before = &self.content[..offset.min(self.content.len())];
-
-        let mut last_word_start = 0;
-        for (idx, _) in text_before.unicode_word_indices() {
-            if idx < offset {
-                last_word_start = idx;
-            }
+        // Only straight after a yank, with the cursor where it left it.
+        let Some((range, back)) = self.last_yank.clone() else {
+            return;
+        };
+        if self.selected_range != (range.end..range.end) || !self.extra_selections.is_empty() {
+            return;
         }
+        let Some(text) = cx
+            .try_global::<KillRing>()
+            .and_then(|ring| ring.get(back + 1))
+            .map(str::to_string)
+        else {
+            return;
+        };
+        self.selected_range = range.clone();
+        self.replace_text_in_range(None, &text, window, cx);
+        self.break_undo_group();
+        self.last_yank = Some((range.start..self.cursor_offset(), back + 1));
+    }
 
-        if last_word_start == 0 && offset > 0 {
-            let trimmed = text_before.trim_end();
-            if trimmed.is_empty() {
-                return 0;
-            }
-            for (idx, _) in trimmed.unicode_word_indices() {
-                last_word_start = idx;
-            }
+    pub(crate) fn transpose(&mut self, _: &Transpose, window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
+        self.extra_selections.clear();
+        let caret = self.cursor_offset();
+        let line_start = self.find_line_start(caret);
+        // At the end of a line, the two characters before the cursor swap.
+        let at = if caret == self.find_line_end(caret) {
+            self.previous_boundary(caret)
+        } else {
+            caret
+        };
+        if at <= line_start {
+            return;
         }
+        let before = self.previous_boundary(at);
+        let after = self.next_boundary(at);
+        let swapped = format!("{}{}", &self.content[at..after], &self.content[before..at]);
+        self.selected_range = before..after;
+        self.selection_reversed = false;
+        self.break_undo_group();
+        self.replace_text_in_range(None, &swapped, window, cx);
+        self.break_undo_group();
+    }
 
-        last_word_start
+    /// Which of vim's modes the input is in. Only the
+    /// [vim preset](super::InputBindings::vim) ever leaves insert mode. In
+    /// normal mode the element takes no typed text or IME composition, so a
+    /// key does only what it is bound to.
+    pub fn vim_mode(&self) -> VimMode {
+        self.vim_mode
     }
 
-    fn next_word_boundary(&self, offset: usize) -> usize {
-        if offset >= self.content.len() {
-            return self.content.len();
+    /// Switches vim's mode, emitting [`InputStateEvent::VimModeChanged`].
+    pub fn set_vim_mode(&mut self, mode: VimMode, cx: &mut Context<Self>) {
+        if self.vim_mode == mode {
+            return;
         }
-
-        let text_after = &self.content[offset..];
-
-        for (idx, word) in text_after.unicode_word_indices() {
-            let word_end = offset + idx + word.len();
-            if word_end > offset {
-                return word_end;
-            }
-        }
-
-        self.content.len()
+        self.vim_mode = mode;
+        self.marked_range = None;
+        cx.emit(InputStateEvent::VimModeChanged(mode));
+        cx.notify();
     }
 
-    fn word_range_at(&self, offset: usize) -> (usize, usize) {
-        let offset = offset.min(self.content.len());
-
-        for (idx, word) in self.content.unicode_word_indices() {
-            let word_end = idx + word.len();
-            if offset >= idx && offset <= word_end {
-                return (idx, word_end);
-            }
+    pub(crate) fn enter_normal_mode(
+        &mut self,
+        _: &EnterNormalMode,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Back onto the character typed last, as vim does, and with one
+        // caret: normal mode's commands are each at one place.
+        let mut caret = self.cursor_offset();
+        if caret > self.find_line_start(caret) {
+            caret = self.previous_boundary(caret);
         }
-
-        (offset, offset)
+        self.extra_selections.clear();
+        self.selected_range = caret..caret;
+        self.selection_reversed = false;
+        self.break_undo_group();
+        self.set_vim_mode(VimMode::Normal, cx);
     }
-}
 
-impl EntityInputHandler for InputState {
-    fn text_for_range(
+    pub(crate) fn enter_insert_mode(
         &mut self,
-        range_utf16: Range<usize>,
-        adjusted_range: &mut Option<Range<usize>>,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
-    ) -> Option<String> {
-        let range = self.range_from_utf16(&range_utf16);
-        let clamped_range = range.start.min(self.content.len())..range.end.min(self.content.len());
-        adjusted_range.replace(self.range_to_utf16(&clamped_range));
-        Some(self.content[clamped_range].to_string())
+        _: &EnterInsertMode,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_vim_mode(VimMode::Insert, cx);
     }
 
-    fn selected_text_range(
+    pub(crate) fn enter_insert_mode_after(
         &mut self,
-        _ignore_disabled_input: bool,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
-    ) -> Option<UTF16Selection> {
-        Some(UTF16Selection {
-            range: self.range_to_utf16(&self.selected_range),
-            reversed: self.selection_reversed,
-        })
+        _: &EnterInsertModeAfter,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let caret = self.cursor_offset();
+        if caret < self.find_line_end(caret) {
+            let after = self.next_boundary(caret);
+            self.selected_range = after..after;
+            self.selection_reversed = false;
+        }
+        self.set_vim_mode(VimMode::Insert, cx);
     }
 
-    fn marked_text_range(
-        &self,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
-    ) -> Option<Range<usize>> {
-        self.marked_range
-            .as_ref()
-            .map(|range| self.range_to_utf16(range))
-    }
+    pub(crate) fn paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_only {
+            return;
+        }
 
-    fn unmark_text(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
-        self.marked_range = None;
+        if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
+            let lines: Vec<&str> = text.lines().collect();
+            if !self.extra_selections.is_empty() && lines.len() == self.extra_selections.len() + 1 {
+                // One line per selection: what copying them wrote.
+                self.replace_selections(&lines, cx);
+            } else if self.multiline {
+                self.replace_text_in_range(None, &text, window, cx);
+            } else {
+                // Strip newlines for single-line input
+                let text = text.replace('\n', " ").replace('\r', "");
+                self.replace_text_in_range(None, &text, window, cx);
+            }
+        }
     }
 
-    fn replace_text_in_range(
-        &mut self,
-        range_utf16: Option<Range<usize>>,
-        new_text: &str,
-        _window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        // The backstop, and the only guard that catches plain typing: gpui
-        // hands a character to the focused element's IME handler, not through
-        // an action. Every editing action also funnels through here, so
-        // read-only holds even if a future one forgets to check.
-        if self.read_only {
+    pub(crate) fn copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
+        // Consumed rather than propagated: the selection is this input's, and
+        // what is refused is taking it.
+        if self.secure {
+            return;
+        }
+        if let Some(text) = self.multiple_selected_text() {
+            cx.write_to_clipboard(ClipboardItem::new_string(text));
+            return;
+        }
+        if self.selected_range.is_empty() {
+            // Nothing to copy here, so the action is not ours to consume. gpui
+            // clears `propagate_event` before every bubble-phase listener, so
+            // returning without this call would silently swallow the action and
+            // stop anything further out on the focus path from handling it —
+            // a selection elsewhere in the window, for instance.
+            cx.propagate();
             return;
         }
 
-        let range = range_utf16
-            .as_ref()
-            .map(|range_utf16| self.range_from_utf16(range_utf16))
-            .or(self.marked_range.clone())
-            .unwrap_or(self.selected_range.clone());
+        cx.write_to_clipboard(ClipboardItem::new_string(
+            self.content[self.selected_range.clone()].to_string(),
+        ));
+    }
 
-        let range = range.start.min(self.content.len())..range.end.min(self.content.len());
+    pub(crate) fn cut(&mut self, _: &Cut, window: &mut Window, cx: &mut Context<Self>) {
+        // A secure input's text cannot be taken, and a cut that only removed
+        // would be a surprise delete.
+        if self.secure {
+            return;
+        }
+        // A read-only input still *takes* its text — taking is not an edit —
+        // so cut keeps the copy and drops the removal.
+        let read_only = self.read_only;
 
-        // Strip newlines for single-line input
-        let sanitized_text;
-        let text_to_insert = if self.multiline {
-            new_text
+        if let Some(text) = self.multiple_selected_text() {
+            cx.write_to_clipboard(ClipboardItem::new_string(text));
+            if !read_only {
+                self.replace_selections(&[""], cx);
+            }
+            return;
+        }
+        // Several bare carets cut the primary one's line, as one would.
+        self.extra_selections.clear();
+
+        if !self.selected_range.is_empty() {
+            // Cut selected text
+            cx.write_to_clipboard(ClipboardItem::new_string(
+                self.content[self.selected_range.clone()].to_string(),
+            ));
+            if read_only {
+                return;
+            }
+            self.replace_text_in_range(None, "", window, cx);
         } else {
-            sanitized_text = new_text.replace('\n', " ").replace('\r', "");
-            &sanitized_text
-        };
+            // No selection: cut the entire current line (including newline)
+            let cursor = self.cursor_offset();
+            let line_start = self.find_line_start(cursor);
+            let line_end = self.find_line_end(cursor);
 
-        // Record patch for undo before modifying content
-        self.push_undo_patch(range.clone(), text_to_insert.len());
+            // Include the newline character if there is one after the line
+            let cut_end = if line_end < self.content.len() {
+                line_end + 1 // Include the newline
+            } else if line_start > 0 {
+                // Last line with no trailing newline - include preceding newline instead
+                line_end
+            } else {
+                line_end
+            };
 
-        // Update cached UTF-16 length incrementally if available
-        if let Some(cached_len) = self.cached_utf16_len {
-            let removed_utf16_len: usize = self.content[range.clone()]
-                .chars()
-                .map(|c| c.len_utf16())
-                .sum();
-            let added_utf16_len: usize = text_to_insert.chars().map(|c| c.len_utf16()).sum();
-            self.cached_utf16_len = Some(cached_len - removed_utf16_len + added_utf16_len);
-        }
+            // For last line, also remove the preceding newline if it exists
+            let cut_start = if line_end >= self.content.len() && line_start > 0 {
+                line_start - 1 // Include preceding newline for last line
+            } else {
+                line_start
+            };
 
-        self.content.replace_range(range.clone(), text_to_insert);
-        self.selected_range =
-            range.start + text_to_insert.len()..range.start + text_to_insert.len();
-        self.marked_range.take();
-        self.needs_layout = true;
-        self.pause_cursor_blink(cx);
-        cx.emit(InputStateEvent::TextChanged);
-        cx.notify();
+            let line_text = self.content[cut_start..cut_end].to_string();
+            cx.write_to_clipboard(ClipboardItem::new_string(line_text));
+
+            if read_only {
+                return;
+            }
+
+            self.selected_range = cut_start..cut_end;
+            self.replace_text_in_range(None, "", window, cx);
+        }
     }
 
-    fn replace_and_mark_text_in_range(
+    pub(crate) fn on_mouse_down(
         &mut self,
-        range_utf16: Option<Range<usize>>,
-        new_text: &str,
-        new_selected_range_utf16: Option<Range<usize>>,
-        _window: &mut Window,
+        position: Point<Pixels>,
+        click_count: usize,
+        shift: bool,
+        alt: bool,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        // Where an IME composition lands. Guarded for the same reason as
-        // `replace_text_in_range`.
-        if self.read_only {
-            return;
-        }
-
-        let range = range_utf16
-            .as_ref()
-            .map(|range_utf16| self.range_from_utf16(range_utf16))
-            .or(self.marked_range.clone())
-            .unwrap_or(self.selected_range.clone());
+        window.focus(&self.focus_handle, cx);
+        self.drag_press = None;
 
-        let range = range.start.min(self.content.len())..range.end.min(self.content.len());
+        let is_same_position = self
+            .last_click_position
+            .map(|last| {
+                let threshold = px(4.);
+                (position.x - last.x).abs() < threshold && (position.y - last.y).abs() < threshold
+            })
+            .unwrap_or(false);
 
-        // Strip newlines for single-line input
-        let sanitized_text;
-        let text_to_insert = if self.multiline {
-            new_text
+        if is_same_position && click_count > 1 {
+            self.click_count = click_count;
         } else {
-            sanitized_text = new_text.replace('\n', " ").replace('\r', "");
-            &sanitized_text
-        };
-
-        // Update cached UTF-16 length incrementally if available
-        if let Some(cached_len) = self.cached_utf16_len {
-            let removed_utf16_len: usize = self.content[range.clone()]
-                .chars()
-                .map(|c| c.len_utf16())
-                .sum();
-            let added_utf16_len: usize = text_to_insert.chars().map(|c| c.len_utf16()).sum();
-            self.cached_utf16_len = Some(cached_len - removed_utf16_len + added_utf16_len);
+            self.click_count = 1;
         }
+        self.last_click_position = Some(position);
 
-        self.content.replace_range(range.clone(), text_to_insert);
+        let clicked_offset = self.index_for_position(position);
 
-        if !text_to_insert.is_empty() {
-            self.marked_range = Some(range.start..range.start + text_to_insert.len());
-        } else {
-            self.marked_range = None;
+        // Inside the selection a press may pick it up: a click or a drag,
+        // the mouse will tell.
+        if self.click_count == 1 && !shift && self.can_drag_from(clicked_offset) {
+            self.drag_press = Some(DragPress {
+                at: position,
+                offset: clicked_offset,
+                alt,
+            });
+            return;
         }
 
-        self.selected_range = new_selected_range_utf16
-            .as_ref()
-            .map(|range_utf16| self.range_from_utf16(range_utf16))
-            .map(|new_range| new_range.start + range.start..new_range.end + range.start)
-            .unwrap_or_else(|| {
-                range.start + text_to_insert.len()..range.start + text_to_insert.len()
-            });
+        self.is_selecting = true;
+        self.column_origin = None;
+        if !alt {
+            self.extra_selections.clear();
+        }
 
-        self.needs_layout = true;
-        cx.emit(InputStateEvent::TextChanged);
-        cx.notify();
+        match self.click_count {
+            2 => {
+                let (word_start, word_end) = self.word_range_at(clicked_offset);
+                self.selected_range = word_start..word_end;
+                self.selection_reversed = false;
+                cx.notify();
+            }
+            3 => {
+                let line_start = self.find_line_start(clicked_offset);
+                let line_end = self.find_line_end(clicked_offset);
+                let line_end_with_newline = if line_end < self.content.len() {
+                    line_end + 1
+                } else {
+                    line_end
+                };
+                self.selected_range = line_start..line_end_with_newline;
+                self.selection_reversed = false;
+                cx.notify();
+            }
+            _ => {
+                if alt && self.allows_multiple_selections() {
+                    self.column_origin = Some(position);
+                    self.add_selection(clicked_offset..clicked_offset, cx);
+                } else if shift {
+                    self.select_to(clicked_offset, cx);
+                } else {
+                    self.move_to(clicked_offset, cx);
+                }
+            }
+        }
     }
 
-    fn bounds_for_range(
+    pub(crate) fn on_mouse_up(&mut self, cx: &mut Context<Self>) {
+        self.is_selecting = false;
+        self.column_origin = None;
+        if self.drop_caret.take().is_some() {
+            cx.notify();
+        }
+        // Pressed inside the selection and let go where it was: a click.
+        if let Some(press) = self.drag_press.take() {
+            if press.alt && self.allows_multiple_selections() {
+                self.add_selection(press.offset..press.offset, cx);
+            } else {
+                self.move_to(press.offset, cx);
+            }
+        }
+    }
+
+    /// Whether a press at `offset` picks up the selection rather than
+    /// starting another.
+    fn can_drag_from(&self, offset: usize) -> bool {
+        !self.secure
+            && self.extra_selections.is_empty()
+            && !self.selected_range.is_empty()
+            && self.selected_range.start <= offset
+            && offset <= self.selected_range.end
+    }
+
+    /// Shows where dragged text would land, for the pointer at `position`
+    /// over the input, or hides it for `None`.
+    pub(crate) fn drag_over(&mut self, position: Option<Point<Pixels>>, cx: &mut Context<Self>) {
+        let caret = position
+            .filter(|_| !self.read_only && cx.has_global::<TextDrag>())
+            .map(|position| self.index_for_position(position));
+        if caret != self.drop_caret {
+            self.drop_caret = caret;
+            cx.notify();
+        }
+    }
+
+    /// Takes `drag`, released at `position`: moves it here, or with `copy`
+    /// copies it. Returns whether it came from another input and should now
+    /// be removed from there.
+    pub(crate) fn drop_text_drag(
         &mut self,
-        range_utf16: Range<usize>,
-        bounds: Bounds<Pixels>,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
-    ) -> Option<Bounds<Pixels>> {
-        let range = self.range_from_utf16(&range_utf16);
+        drag: &TextDrag,
+        position: Point<Pixels>,
+        copy: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.drop_caret = None;
+        if self.read_only {
+            return false;
+        }
+        let to = self.index_for_position(position);
+        if drag.source.entity_id() != cx.entity_id() {
+            // Whatever a filter, limit or mask here kept out stays behind.
+            let inserted = self.insert_dropped(to, &drag.text, window, cx);
+            return inserted && !copy;
+        }
+        // Edited since it was picked up, somehow: all that is left to do is
+        // copy it.
+        let intact = self.content.get(drag.range.clone()) == Some(drag.text.as_str());
+        if copy || !intact {
+            self.insert_dropped(to, &drag.text, window, cx);
+        } else if let Some((range, text, moved)) =
+            drag::move_edit(&self.content, drag.range.clone(), to)
+        {
+            self.extra_selections.clear();
+            self.selected_range = range;
+            self.selection_reversed = false;
+            self.break_undo_group();
+            self.replace_text_in_range(None, &text, window, cx);
+            self.break_undo_group();
+            self.selected_range = moved;
+        }
+        window.focus(&self.focus_handle, cx);
+        false
+    }
 
-        for line in &self.line_layouts {
-            if line.text_range.is_empty() {
-                if range.start == line.text_range.start {
-                    return Some(Bounds::from_corners(
-                        point(bounds.left(), bounds.top() + line.y_offset),
-                        point(
-                            bounds.left() + px(4.),
-                            bounds.top() + line.y_offset + self.line_height,
-                        ),
-                    ));
+    /// Removes text dragged from here into another input, unless it has
+    /// changed since, or the input is read-only.
+    pub(crate) fn remove_dragged(
+        &mut self,
+        drag: &TextDrag,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.read_only || self.content.get(drag.range.clone()) != Some(drag.text.as_str()) {
+            return;
+        }
+        self.extra_selections.clear();
+        self.selected_range = drag.range.clone();
+        self.selection_reversed = false;
+        self.break_undo_group();
+        self.replace_text_in_range(None, "", window, cx);
+        self.break_undo_group();
+    }
+
+    /// Inserts text dropped from outside the input where it was dropped.
+    pub(crate) fn drop_text(
+        &mut self,
+        text: &str,
+        position: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.read_only {
+            return;
+        }
+        let to = self.index_for_position(position);
+        self.insert_dropped(to, text, window, cx);
+    }
+
+    /// Puts the caret where files were dropped, for the owner's handler to
+    /// insert at.
+    pub(crate) fn drop_files(
+        &mut self,
+        position: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        window.focus(&self.focus_handle, cx);
+        self.extra_selections.clear();
+        self.move_to(self.index_for_position(position), cx);
+    }
+
+    /// Inserts dropped `text` at `to`, as one undo step, and selects it.
+    /// Returns whether it went in whole and unchanged.
+    fn insert_dropped(
+        &mut self,
+        to: usize,
+        text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let text = if self.multiline {
+            text.to_string()
+        } else {
+            text.replace('\n', " ").replace('\r', "")
+        };
+        let expected = format!("{}{text}{}", &self.content[..to], &self.content[to..]);
+        self.extra_selections.clear();
+        self.selected_range = to..to;
+        self.selection_reversed = false;
+        self.break_undo_group();
+        self.replace_text_in_range(None, &text, window, cx);
+        self.break_undo_group();
+        self.selected_range = to..self.cursor_offset().max(to);
+        window.focus(&self.focus_handle, cx);
+        self.content == expected
+    }
+
+    pub(crate) fn on_mouse_move(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        if let Some(press) = self.drag_press {
+            if (position - press.at).magnitude() > DRAG_THRESHOLD {
+                self.drag_press = None;
+                let range = self.selected_range.clone();
+                let text = self.content[range.clone()].to_string();
+                let source = cx.entity().downgrade();
+                cx.set_global(TextDrag {
+                    source,
+                    range,
+                    text,
+                });
+            }
+            return;
+        }
+        if let Some(origin) = self.column_origin.filter(|_| self.is_selecting) {
+            let mut rows = self.column_selections(origin, position);
+            // Until the drag leaves the caret the click added, it is a click.
+            if rows.len() == 1 && rows[0].range.is_empty() {
+                return;
+            }
+            let primary = rows.pop().unwrap_or_default();
+            self.set_selections(primary, rows);
+            self.scroll_to_cursor();
+            cx.notify();
+            return;
+        }
+        if self.is_selecting && self.click_count == 1 {
+            self.select_to(self.index_for_position(position), cx);
+        }
+    }
+
+    fn move_to(&mut self, offset: usize, cx: &mut Context<Self>) {
+        self.pause_cursor_blink(cx);
+        // Moving away drops the suggestion until the text next changes.
+        self.completions.clear();
+        let offset = offset.min(self.content.len());
+        let offset = tokens::snap(&self.tokens, offset, Some(offset > self.cursor_offset()));
+        self.selected_range = offset..offset;
+        self.selection_reversed = false;
+        self.scroll_to_cursor();
+        cx.notify();
+    }
+
+    fn select_to(&mut self, offset: usize, cx: &mut Context<Self>) {
+        self.pause_cursor_blink(cx);
+        self.completions.clear();
+        let offset = offset.min(self.content.len());
+        let offset = tokens::snap(&self.tokens, offset, Some(offset > self.cursor_offset()));
+        if self.selection_reversed {
+            self.selected_range.start = offset;
+        } else {
+            self.selected_range.end = offset;
+        }
+        if self.selected_range.end < self.selected_range.start {
+            self.selection_reversed = !self.selection_reversed;
+            self.selected_range = self.selected_range.end..self.selected_range.start;
+        }
+        self.scroll_to_cursor();
+        cx.notify();
+    }
+
+    pub(crate) fn find_line_start(&self, offset: usize) -> usize {
+        self.content[..offset.min(self.content.len())]
+            .rfind('\n')
+            .map(|pos| pos + 1)
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn find_line_end(&self, offset: usize) -> usize {
+        self.content[offset.min(self.content.len())..]
+            .find('\n')
+            .map(|pos| offset + pos)
+            .unwrap_or(self.content.len())
+    }
+
+    /// Returns the text direction for a specific line layout by index.
+    pub fn line_direction(&self, line_idx: usize) -> TextDirection {
+        self.line_layouts
+            .get(line_idx)
+            .map(|layout| layout.direction)
+            .unwrap_or_default()
+    }
+
+    /// Returns the text direction at a given byte offset in the content.
+    pub fn direction_at_offset(&self, offset: usize) -> TextDirection {
+        let offset = offset.min(self.content.len());
+        for layout in &self.line_layouts {
+            if offset >= layout.text_range.start && offset <= layout.text_range.end {
+                return layout.direction;
+            }
+        }
+        TextDirection::default()
+    }
+
+    fn move_vertically(&self, offset: usize, direction: i32) -> Option<usize> {
+        let (visual_line_idx, x_pixels) = self.find_visual_line_and_x_offset(offset);
+        let target_visual_line_idx = (visual_line_idx as i32 + direction).max(0) as usize;
+
+        let mut current_visual_line = 0;
+        for layout in self.line_layouts.iter() {
+            let visual_lines_in_layout = layout.visual_line_count;
+
+            if target_visual_line_idx < current_visual_line + visual_lines_in_layout {
+                let visual_line_within_layout = target_visual_line_idx - current_visual_line;
+
+                if layout.text_range.is_empty() {
+                    return Some(layout.text_range.start);
                 }
-            } else if line.text_range.contains(&range.start) {
-                if let Some(wrapped) = &line.wrapped_line {
-                    let local_start = range.start - line.text_range.start;
-                    let local_end = (range.end - line.text_range.start).min(wrapped.text.len());
 
-                    let start_pos = wrapped
-                        .position_for_index(local_start, self.line_height)
-                        .unwrap_or(point(px(0.), px(0.)));
-                    let end_pos = wrapped
-                        .position_for_index(local_end, self.line_height)
-                        .unwrap_or_else(|| {
-                            let last_line_y =
-                                self.line_height * (line.visual_line_count - 1) as f32;
-                            point(wrapped.width(), last_line_y)
-                        });
+                if layout.wrapped_line.is_some() {
+                    let y_within_wrapped = self.line_height * visual_line_within_layout as f32;
+                    let target_point = point(px(x_pixels), y_within_wrapped);
 
-                    let start_visual_line = (start_pos.y / self.line_height).floor() as usize;
-                    let end_visual_line = (end_pos.y / self.line_height).floor() as usize;
+                    let closest = layout.closest_index_for_position(target_point, self.line_height);
+                    let result = layout.text_range.start + closest;
 
-                    if start_visual_line == end_visual_line {
-                        return Some(Bounds::from_corners(
-                            point(
-                                bounds.left() + start_pos.x,
-                                bounds.top() + line.y_offset + start_pos.y,
-                            ),
-                            point(
-                                bounds.left() + end_pos.x,
-                                bounds.top() + line.y_offset + start_pos.y + self.line_height,
-                            ),
-                        ));
-                    } else {
-                        return Some(Bounds::from_corners(
-                            point(
-                                bounds.left() + start_pos.x,
-                                bounds.top() + line.y_offset + start_pos.y,
-                            ),
-                            point(
-                                bounds.left() + wrapped.width(),
-                                bounds.top() + line.y_offset + start_pos.y + self.line_height,
-                            ),
-                        ));
-                    }
+                    return Some(result);
                 }
+
+                return Some(layout.text_range.start);
             }
+
+            current_visual_line += visual_lines_in_layout;
         }
-        None
+
+        if direction > 0 {
+            Some(self.content.len())
+        } else {
+            None
+        }
+    }
+
+    fn find_visual_line_and_x_offset(&self, offset: usize) -> (usize, f32) {
+        if self.line_layouts.is_empty() {
+            return (0, 0.0);
+        }
+
+        let mut visual_line_idx = 0;
+
+        for line in &self.line_layouts {
+            if line.text_range.is_empty() {
+                if offset == line.text_range.start {
+                    return (visual_line_idx, 0.0);
+                }
+            } else if offset >= line.text_range.start && offset <= line.text_range.end {
+                if line.wrapped_line.is_some() {
+                    let local_offset = (offset - line.text_range.start).min(line.text_range.len());
+                    if let Some(position) = line.position_for_index(local_offset, self.line_height)
+                    {
+                        let visual_line_within = (position.y / self.line_height).floor() as usize;
+                        return (visual_line_idx + visual_line_within, position.x.into());
+                    }
+                }
+                return (visual_line_idx, 0.0);
+            }
+            visual_line_idx += line.visual_line_count;
+        }
+
+        (visual_line_idx.saturating_sub(1), 0.0)
+    }
+
+    /// The offset nearest `position`, outside any token.
+    pub(crate) fn index_for_position(&self, position: Point<Pixels>) -> usize {
+        tokens::snap(&self.tokens, self.text_index_for_position(position), None)
+    }
+
+    fn text_index_for_position(&self, position: Point<Pixels>) -> usize {
+        if self.content.is_empty() {
+            return 0;
+        }
+
+        for line in self.line_layouts.iter() {
+            let line_height_total = self.line_height * line.visual_line_count as f32;
+
+            if position.y >= line.y_offset && position.y < line.y_offset + line_height_total {
+                if line.text_range.is_empty() {
+                    return line.text_range.start;
+                }
+
+                if line.wrapped_line.is_some() {
+                    let relative_y = position.y - line.y_offset;
+                    let relative_point = point(position.x, relative_y);
+
+                    return line.text_range.start
+                        + line.closest_index_for_position(relative_point, self.line_height);
+                }
+                return line.text_range.start;
+            }
+        }
+
+        self.content.len()
+    }
+
+    pub(crate) fn scroll_to_cursor(&mut self) {
+        if self.line_layouts.is_empty() {
+            return;
+        }
+
+        let cursor_offset = if self.selection_reversed {
+            self.selected_range.start
+        } else {
+            self.selected_range.end
+        };
+
+        if self.multiline {
+            self.scroll_to_cursor_vertical(cursor_offset);
+        } else {
+            self.scroll_to_cursor_horizontal(cursor_offset);
+        }
+    }
+
+    fn scroll_to_cursor_vertical(&mut self, cursor_offset: usize) {
+        if self.available_height <= px(0.) {
+            return;
+        }
+
+        let line_height = self.line_height;
+
+        for line in &self.line_layouts {
+            let is_cursor_in_line = if line.text_range.is_empty() {
+                cursor_offset == line.text_range.start
+            } else {
+                line.text_range.contains(&cursor_offset)
+                    || (cursor_offset == line.text_range.end && cursor_offset == self.content.len())
+            };
+
+            if is_cursor_in_line {
+                let cursor_visual_y = if line.wrapped_line.is_some() {
+                    let local_offset = cursor_offset.saturating_sub(line.text_range.start);
+                    if let Some(position) = line.position_for_index(local_offset, self.line_height)
+                    {
+                        line.y_offset + position.y
+                    } else {
+                        line.y_offset
+                    }
+                } else {
+                    line.y_offset
+                };
+
+                let visible_top = self.scroll_offset;
+                let visible_bottom = self.scroll_offset + self.available_height;
+
+                if cursor_visual_y < visible_top {
+                    self.scroll_offset = cursor_visual_y;
+                } else if cursor_visual_y + line_height > visible_bottom {
+                    self.scroll_offset = (cursor_visual_y + line_height) - self.available_height;
+                }
+
+                self.scroll_offset = self.scroll_offset.max(px(0.));
+                break;
+            }
+        }
+    }
+
+    fn scroll_to_cursor_horizontal(&mut self, cursor_offset: usize) {
+        if self.available_width <= px(0.) {
+            return;
+        }
+
+        // For single-line input, get cursor x position from the first (only) line
+        let Some(line) = self.line_layouts.first() else {
+            return;
+        };
+
+        let cursor_x = if line.wrapped_line.is_some() {
+            let local_offset = cursor_offset.saturating_sub(line.text_range.start);
+            line.position_for_index(local_offset, self.line_height)
+                .map(|p| p.x)
+                .unwrap_or(px(0.))
+        } else {
+            px(0.)
+        };
+
+        let visible_left = self.scroll_offset;
+        let visible_right = self.scroll_offset + self.available_width;
+
+        // Add some padding so cursor isn't right at the edge
+        let padding = px(2.0);
+
+        if cursor_x < visible_left + padding {
+            self.scroll_offset = (cursor_x - padding).max(px(0.));
+        } else if cursor_x > visible_right - padding {
+            self.scroll_offset = cursor_x - self.available_width + padding;
+        }
+
+        self.scroll_offset = self.scroll_offset.max(px(0.));
+    }
+
+    pub(crate) fn update_line_layouts(
+        &mut self,
+        width: Pixels,
+        line_height: Pixels,
+        text_style: &TextStyle,
+        window: &mut Window,
+    ) {
+        self.line_height = line_height;
+        self.set_text_style(text_style);
+
+        if !self.needs_layout && self.wrap_width == Some(width) {
+            return;
+        }
+
+        self.line_layouts.clear();
+        self.wrap_width = Some(width);
+
+        let text_color = text_style.color;
+        let font_size = text_style.font_size.to_pixels(window.rem_size());
+
+        if self.content.is_empty() {
+            self.line_layouts.push(InputLineLayout {
+                text_range: 0..0,
+                wrapped_line: None,
+                y_offset: px(0.),
+                visual_line_count: 1,
+                direction: TextDirection::default(),
+                obscured: None,
+            });
+            self.needs_layout = false;
+            return;
+        }
+
+        let mut last_direction = TextDirection::default();
+
+        let mut y_offset = px(0.);
+        let mut current_pos = 0;
+
+        while current_pos < self.content.len() {
+            let line_end = self.content[current_pos..]
+                .find('\n')
+                .map(|pos| current_pos + pos)
+                .unwrap_or(self.content.len());
+
+            let line_text = &self.content[current_pos..line_end];
+
+            if line_text.is_empty() {
+                self.line_layouts.push(InputLineLayout {
+                    text_range: current_pos..current_pos,
+                    wrapped_line: None,
+                    y_offset,
+                    visual_line_count: 1,
+                    direction: last_direction,
+                    obscured: None,
+                });
+                y_offset += line_height;
+            } else {
+                let direction = detect_base_direction(line_text);
+                last_direction = direction;
+                let obscured: Option<Arc<[usize]>> = self.is_obscured().then(|| {
+                    line_text
+                        .grapheme_indices(true)
+                        .map(|(offset, _)| offset)
+                        .chain([line_text.len()])
+                        .collect()
+                });
+                let shaped_text = match &obscured {
+                    Some(graphemes) => OBSCURING_CHAR.to_string().repeat(graphemes.len() - 1),
+                    None => line_text.to_string(),
+                };
+                let runs = if self.shows_attributes() {
+                    self.attributed_runs(current_pos..line_end, text_style)
+                } else {
+                    vec![TextRun {
+                        len: shaped_text.len(),
+                        font: text_style.font(),
+                        color: text_color,
+                        background_color: None,
+                        underline: None,
+                        strikethrough: None,
+                    }]
+                };
+
+                let wrapped_lines = window
+                    .text_system()
+                    .shape_text(
+                        SharedString::from(shaped_text),
+                        font_size,
+                        &runs,
+                        Some(width),
+                        None,
+                    )
+                    .unwrap_or_default();
+
+                for wrapped in wrapped_lines {
+                    let visual_line_count = wrapped.wrap_boundaries().len() + 1;
+                    let line_height_total = line_height * visual_line_count as f32;
+
+                    self.line_layouts.push(InputLineLayout {
+                        text_range: current_pos..line_end,
+                        wrapped_line: Some(Arc::new(wrapped)),
+                        y_offset,
+                        visual_line_count,
+                        direction,
+                        obscured: obscured.clone(),
+                    });
+
+                    y_offset += line_height_total;
+                }
+            }
+
+            current_pos = if line_end < self.content.len() {
+                line_end + 1
+            } else {
+                self.content.len()
+            };
+        }
+
+        if self.content.ends_with('\n') {
+            self.line_layouts.push(InputLineLayout {
+                text_range: self.content.len()..self.content.len(),
+                wrapped_line: None,
+                y_offset,
+                visual_line_count: 1,
+                direction: last_direction,
+                obscured: None,
+            });
+        }
+
+        self.needs_layout = false;
+        self.scroll_to_cursor();
+    }
+
+    /// Lays the content out `width` wide — or, before there is a width,
+    /// keeps the last layout — and returns the height that shows it in
+    /// between `min_rows` and `max_rows` lines, scrolled to the cursor within
+    /// that height.
+    pub(crate) fn fit_rows(
+        &mut self,
+        width: Option<Pixels>,
+        line_height: Pixels,
+        text_style: &TextStyle,
+        (min_rows, max_rows): (u32, u32),
+        window: &mut Window,
+    ) -> Pixels {
+        if let Some(width) = width {
+            self.update_line_layouts(width, line_height, text_style, window);
+        }
+        let rows: usize = self
+            .line_layouts
+            .iter()
+            .map(|line| line.visual_line_count)
+            .sum();
+        let rows = u32::try_from(rows)
+            .unwrap_or(u32::MAX)
+            .clamp(min_rows, max_rows);
+        let height = line_height * rows as f32;
+        // The layout above scrolled against the old height; a field that has
+        // grown to show everything has nothing to scroll.
+        self.available_height = height;
+        let max_scroll = (self.total_content_height() - height).max(px(0.));
+        self.scroll_offset = self.scroll_offset.min(max_scroll);
+        self.scroll_to_cursor();
+        height
+    }
+
+    pub(crate) fn total_content_height(&self) -> Pixels {
+        self.line_layouts
+            .last()
+            .map(|last| last.y_offset + self.line_height * last.visual_line_count as f32)
+            .unwrap_or(px(0.))
+    }
+
+    /// Returns true if the scroll position is at the top.
+    pub fn at_top(&self) -> bool {
+        self.scroll_offset <= px(0.)
+    }
+
+    /// Returns true if the scroll position is at the bottom.
+    pub fn at_bottom(&self) -> bool {
+        let content_height = self.total_content_height();
+        let visible_height = self.available_height;
+
+        if content_height <= visible_height {
+            return true;
+        }
+
+        self.scroll_offset + visible_height >= content_height
+    }
+
+    /// Returns the scroll progress as a value from 0.0 (top) to 1.0 (bottom).
+    pub fn scroll_progress(&self) -> f32 {
+        let content_height = self.total_content_height();
+        let visible_height = self.available_height;
+        let max_scroll = content_height - visible_height;
+
+        if max_scroll <= px(0.) {
+            return 0.0;
+        }
+
+        (self.scroll_offset / max_scroll).clamp(0.0, 1.0)
+    }
+
+    /// Returns how far the content is scrolled from the top in pixels.
+    pub fn distance_from_top(&self) -> Pixels {
+        self.scroll_offset.max(px(0.))
+    }
+
+    /// Returns how far the content is from the bottom in pixels.
+    pub fn distance_from_bottom(&self) -> Pixels {
+        let content_height = self.total_content_height();
+        let visible_height = self.available_height;
+        let max_scroll = content_height - visible_height;
+
+        if max_scroll <= px(0.) {
+            return px(0.);
+        }
+
+        (max_scroll - self.scroll_offset).max(px(0.))
+    }
+
+    fn offset_from_utf16(&self, offset: usize) -> usize {
+        // Fast path: if offset is 0, return 0
+        if offset == 0 {
+            return 0;
+        }
+
+        // Fast path: if we have cached length and offset is at or past end
+        if let Some(utf16_len) = self.cached_utf16_len {
+            if offset >= utf16_len {
+                return self.content.len();
+            }
+        }
+
+        let mut utf8_offset = 0;
+        let mut utf16_count = 0;
+
+        for character in self.content.chars() {
+            if utf16_count >= offset {
+                break;
+            }
+            utf16_count += character.len_utf16();
+            utf8_offset += character.len_utf8();
+        }
+
+        utf8_offset.min(self.content.len())
+    }
+
+    fn offset_to_utf16(&self, offset: usize) -> usize {
+        // Fast path: if offset is 0, return 0
+        if offset == 0 {
+            return 0;
+        }
+
+        // Fast path: if offset is at or past end, return cached length
+        if offset >= self.content.len() {
+            return self.utf16_len();
+        }
+
+        let mut utf16_offset = 0;
+        let mut utf8_count = 0;
+
+        for character in self.content.chars() {
+            if utf8_count >= offset {
+                break;
+            }
+            utf8_count += character.len_utf8();
+            utf16_offset += character.len_utf16();
+        }
+
+        utf16_offset
+    }
+
+    /// Returns the UTF-16 length of the content, computing and caching if necessary.
+    fn utf16_len(&self) -> usize {
+        if let Some(len) = self.cached_utf16_len {
+            return len;
+        }
+        self.content.chars().map(|c| c.len_utf16()).sum()
+    }
+
+    fn range_to_utf16(&self, range: &Range<usize>) -> Range<usize> {
+        self.offset_to_utf16(range.start)..self.offset_to_utf16(range.end)
+    }
+
+    fn range_from_utf16(&self, range_utf16: &Range<usize>) -> Range<usize> {
+        self.offset_from_utf16(range_utf16.start)..self.offset_from_utf16(range_utf16.end)
+    }
+
+    fn previous_boundary(&self, offset: usize) -> usize {
+        if offset == 0 {
+            return 0;
+        }
+
+        let text_before = &self.content[..offset.min(self.content.len())];
+        text_before
+            .grapheme_indices(true)
+            .map(|(i, _)| i)
+            .next_back()
+            .unwrap_or(0)
+    }
+
+    fn next_boundary(&self, offset: usize) -> usize {
+        if offset >= self.content.len() {
+            return self.content.len();
+        }
+
+        let text_after = &self.content[offset..];
+        text_after
+            .grapheme_indices(true)
+            .nth(1)
+            .map(|(i, _)| offset + i)
+            .unwrap_or(self.content.len())
+    }
+
+    fn previous_word_boundary(&self, offset: usize) -> usize {
+        if offset == 0 || self.secure {
+            return 0;
+        }
+
+        let text_before = &self.content[..offset.min(self.content.len())];
+
+        let mut last_word_start = 0;
+        for (idx, _) in text_before.unicode_word_indices() {
+            if idx < offset {
+                last_word_start = idx;
+            }
+        }
+
+        if last_word_start == 0 && offset > 0 {
+            let trimmed = text_before.trim_end();
+            if trimmed.is_empty() {
+                return 0;
+            }
+            for (idx, _) in trimmed.unicode_word_indices() {
+                last_word_start = idx;
+            }
+        }
+
+        last_word_start
+    }
+
+    fn next_word_boundary(&self, offset: usize) -> usize {
+        if offset >= self.content.len() || self.secure {
+            return self.content.len();
+        }
+
+        let text_after = &self.content[offset..];
+
+        for (idx, word) in text_after.unicode_word_indices() {
+            let word_end = offset + idx + word.len();
+            if word_end > offset {
+                return word_end;
+            }
+        }
+
+        self.content.len()
+    }
+
+    fn word_range_at(&self, offset: usize) -> (usize, usize) {
+        if self.secure {
+            return (0, self.content.len());
+        }
+        let offset = offset.min(self.content.len());
+
+        for (idx, word) in self.content.unicode_word_indices() {
+            let word_end = idx + word.len();
+            if offset >= idx && offset <= word_end {
+                // A word in a token is the whole token.
+                return (
+                    tokens::snap(&self.tokens, idx, Some(false)),
+                    tokens::snap(&self.tokens, word_end, Some(true)),
+                );
+            }
+        }
+
+        (offset, offset)
+    }
+}
+
+/// The part of `before` that differs from `after`, and how long what
+/// replaced it is.
+fn changed_region(before: &str, after: &str) -> (Range<usize>, usize) {
+    let prefix = common_prefix(before, after);
+    let suffix = common_suffix(&before[prefix..], &after[prefix..]);
+    (prefix..before.len() - suffix, after.len() - prefix - suffix)
+}
+
+/// The length in bytes of the longest common prefix of `a` and `b`, on a
+/// character boundary of both.
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map_or_else(|| a.len().min(b.len()), |((offset, _), _)| offset)
+}
+
+/// The length in bytes of the longest common suffix of `a` and `b`, on a
+/// character boundary of both.
+fn common_suffix(a: &str, b: &str) -> usize {
+    a.chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(x, y)| x == y)
+        .map(|(x, _)| x.len_utf8())
+        .sum()
+}
+
+impl EntityInputHandler for InputState {
+    fn text_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        adjusted_range: &mut Option<Range<usize>>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<String> {
+        let range = self.range_from_utf16(&range_utf16);
+        let clamped_range = range.start.min(self.content.len())..range.end.min(self.content.len());
+        adjusted_range.replace(self.range_to_utf16(&clamped_range));
+        Some(self.content[clamped_range].to_string())
+    }
+
+    fn selected_text_range(
+        &mut self,
+        _ignore_disabled_input: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<UTF16Selection> {
+        Some(UTF16Selection {
+            range: self.range_to_utf16(&self.selected_range),
+            reversed: self.selection_reversed,
+        })
+    }
+
+    fn marked_text_range(
+        &self,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<Range<usize>> {
+        self.marked_range
+            .as_ref()
+            .map(|range| self.range_to_utf16(range))
+    }
+
+    fn unmark_text(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
+        self.marked_range = None;
+    }
+
+    fn replace_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        new_text: &str,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // The backstop, and the only guard that catches plain typing: gpui
+        // hands a character to the focused element's IME handler, not through
+        // an action. Every editing action also funnels through here, so
+        // read-only holds even if a future one forgets to check.
+        if self.read_only {
+            return;
+        }
+
+        // Typing, and every editing action, lands at every selection.
+        if range_utf16.is_none() && self.marked_range.is_none() && !self.extra_selections.is_empty()
+        {
+            self.replace_selections(&[new_text], cx);
+            return;
+        }
+
+        let range = range_utf16
+            .as_ref()
+            .map(|range_utf16| self.range_from_utf16(range_utf16))
+            .or(self.marked_range.clone())
+            .unwrap_or(self.selected_range.clone());
+
+        let range = range.start.min(self.content.len())..range.end.min(self.content.len());
+
+        let new_text = match self.admit(&range, new_text, cx) {
+            Some(text) => text,
+            // A refused commit still ends the composition, whose text is
+            // already in the content.
+            None if self.marked_range.is_some() => String::new(),
+            None => return,
+        };
+
+        if let Some(mask) = self.mask.clone() {
+            self.replace_masked(range, &new_text, mask.as_ref(), cx);
+            return;
+        }
+
+        // Strip newlines for single-line input
+        let sanitized_text;
+        let text_to_insert = if self.multiline {
+            &new_text
+        } else {
+            sanitized_text = new_text.replace('\n', " ").replace('\r', "");
+            &sanitized_text
+        };
+
+        // Record patch for undo before modifying content
+        self.push_undo_patch(range.clone(), text_to_insert.len());
+
+        // Update cached UTF-16 length incrementally if available
+        if let Some(cached_len) = self.cached_utf16_len {
+            let removed_utf16_len: usize = self.content[range.clone()]
+                .chars()
+                .map(|c| c.len_utf16())
+                .sum();
+            let added_utf16_len: usize = text_to_insert.chars().map(|c| c.len_utf16()).sum();
+            self.cached_utf16_len = Some(cached_len - removed_utf16_len + added_utf16_len);
+        }
+
+        self.content.replace_range(range.clone(), text_to_insert);
+        self.shift_spans(&range, text_to_insert.len());
+        self.selected_range =
+            range.start + text_to_insert.len()..range.start + text_to_insert.len();
+        self.marked_range.take();
+        self.needs_layout = true;
+        self.pause_cursor_blink(cx);
+        self.text_changed(cx);
+        cx.notify();
+    }
+
+    fn replace_and_mark_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        new_text: &str,
+        new_selected_range_utf16: Option<Range<usize>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Where an IME composition lands. Guarded for the same reason as
+        // `replace_text_in_range`.
+        if self.read_only {
+            return;
+        }
+        // The IME composes at one place, the primary selection.
+        self.extra_selections.clear();
+
+        let range = range_utf16
+            .as_ref()
+            .map(|range_utf16| self.range_from_utf16(range_utf16))
+            .or(self.marked_range.clone())
+            .unwrap_or(self.selected_range.clone());
+
+        let range = range.start.min(self.content.len())..range.end.min(self.content.len());
+
+        // A mask re-formats the content on every edit, which would move a
+        // composition out from under the IME, so a masked input takes each
+        // composition step as committed text.
+        if let Some(mask) = self.mask.clone() {
+            self.replace_masked(range, new_text, mask.as_ref(), cx);
+            return;
+        }
+
+        // Strip newlines for single-line input
+        let sanitized_text;
+        let text_to_insert = if self.multiline {
+            new_text
+        } else {
+            sanitized_text = new_text.replace('\n', " ").replace('\r', "");
+            &sanitized_text
+        };
+
+        // Update cached UTF-16 length incrementally if available
+        if let Some(cached_len) = self.cached_utf16_len {
+            let removed_utf16_len: usize = self.content[range.clone()]
+                .chars()
+                .map(|c| c.len_utf16())
+                .sum();
+            let added_utf16_len: usize = text_to_insert.chars().map(|c| c.len_utf16()).sum();
+            self.cached_utf16_len = Some(cached_len - removed_utf16_len + added_utf16_len);
+        }
+
+        self.content.replace_range(range.clone(), text_to_insert);
+        self.shift_spans(&range, text_to_insert.len());
+
+        if !text_to_insert.is_empty() {
+            self.marked_range = Some(range.start..range.start + text_to_insert.len());
+        } else {
+            self.marked_range = None;
+        }
+
+        self.selected_range = new_selected_range_utf16
+            .as_ref()
+            .map(|range_utf16| self.range_from_utf16(range_utf16))
+            .map(|new_range| new_range.start + range.start..new_range.end + range.start)
+            .unwrap_or_else(|| {
+                range.start + text_to_insert.len()..range.start + text_to_insert.len()
+            });
+
+        self.needs_layout = true;
+        self.text_changed(cx);
+        cx.notify();
+    }
+
+    fn bounds_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        bounds: Bounds<Pixels>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<Bounds<Pixels>> {
+        let range = self.range_from_utf16(&range_utf16);
+
+        for line in &self.line_layouts {
+            if line.text_range.is_empty() {
+                if range.start == line.text_range.start {
+                    return Some(Bounds::from_corners(
+                        point(bounds.left(), bounds.top() + line.y_offset),
+                        point(
+                            bounds.left() + px(4.),
+                            bounds.top() + line.y_offset + self.line_height,
+                        ),
+                    ));
+                }
+            } else if line.text_range.contains(&range.start) {
+                if let Some(wrapped) = &line.wrapped_line {
+                    let local_start = range.start - line.text_range.start;
+                    let local_end = (range.end - line.text_range.start).min(line.text_range.len());
+
+                    let start_pos = line
+                        .position_for_index(local_start, self.line_height)
+                        .unwrap_or(point(px(0.), px(0.)));
+                    let end_pos = line
+                        .position_for_index(local_end, self.line_height)
+                        .unwrap_or_else(|| {
+                            let last_line_y =
+                                self.line_height * (line.visual_line_count - 1) as f32;
+                            point(wrapped.width(), last_line_y)
+                        });
+
+                    let start_visual_line = (start_pos.y / self.line_height).floor() as usize;
+                    let end_visual_line = (end_pos.y / self.line_height).floor() as usize;
+
+                    if start_visual_line == end_visual_line {
+                        return Some(Bounds::from_corners(
+                            point(
+                                bounds.left() + start_pos.x,
+                                bounds.top() + line.y_offset + start_pos.y,
+                            ),
+                            point(
+                                bounds.left() + end_pos.x,
+                                bounds.top() + line.y_offset + start_pos.y + self.line_height,
+                            ),
+                        ));
+                    } else {
+                        return Some(Bounds::from_corners(
+                            point(
+                                bounds.left() + start_pos.x,
+                                bounds.top() + line.y_offset + start_pos.y,
+                            ),
+                            point(
+                                bounds.left() + wrapped.width(),
+                                bounds.top() + line.y_offset + start_pos.y + self.line_height,
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn character_index_for_point(
+        &mut self,
+        point: Point<Pixels>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<usize> {
+        let index = self.index_for_position(point);
+        Some(self.offset_to_utf16(index))
+    }
+}
+
+impl Focusable for InputState {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::completion::PrefixCompletions;
+    use crate::input::decorations::{DecorationStyle, WordListChecker};
+    use crate::input::mask::{CreditCardMask, PatternMask};
+    use gpui::{
+        div, AppContext, Entity, InteractiveElement, IntoElement, ParentElement, Render,
+        TestAppContext, TextStyle, WindowHandle,
+    };
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct TestView {
+        input: Entity<InputState>,
+    }
+
+    impl Render for TestView {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div()
+        }
+    }
+
+    fn create_test_input(
+        cx: &mut TestAppContext,
+        content: &str,
+        range: std::ops::Range<usize>,
+    ) -> WindowHandle<TestView> {
+        cx.add_window(|_window, cx| {
+            let input = cx.new(|cx| {
+                let mut input = InputState::new_multiline(cx);
+                input.content = content.to_string();
+                input.selected_range = range;
+                input
+            });
+            TestView { input }
+        })
+    }
+
+    /// A real `Input` under a `div` that handles `Copy`, so the action travels
+    /// the actual dispatch tree. `TestView` cannot serve here: it renders a
+    /// bare `div()`, so no input element is ever in the tree.
+    struct OuterCopyView {
+        input: Entity<InputState>,
+        outer_ran: Rc<Cell<bool>>,
+    }
+
+    impl Render for OuterCopyView {
+        fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            let outer_ran = self.outer_ran.clone();
+            div()
+                .on_action::<Copy>(move |_, _, _| outer_ran.set(true))
+                .child(crate::elements::input::text_area(&self.input, cx))
+        }
+    }
+
+    /// Focuses a rendered input holding `"hello world"` with `range` selected,
+    /// dispatches `Copy` at it, and reports whether the handler *outside* the
+    /// input ran.
+    fn dispatch_copy_at_focused_input(
+        cx: &mut TestAppContext,
+        range: std::ops::Range<usize>,
+    ) -> bool {
+        // Rendering any real element needs the theme bound, or `cx.theme()`
+        // panics on the unbound global. Only the theme, not all of
+        // `gpuikit::init`, which would bind keys these tests do not want.
+        cx.update(crate::theme::init);
+
+        let outer_ran = Rc::new(Cell::new(false));
+        let (view, cx) = cx.add_window_view({
+            let outer_ran = outer_ran.clone();
+            move |_window, cx| {
+                let input = cx.new(|cx| {
+                    let mut input = InputState::new_multiline(cx);
+                    input.content = "hello world".to_string();
+                    input.selected_range = range;
+                    input
+                });
+                OuterCopyView { input, outer_ran }
+            }
+        });
+
+        cx.update(|window, cx| {
+            let handle = view.read(cx).input.focus_handle(cx);
+            window.focus(&handle, cx);
+        });
+        cx.run_until_parked();
+
+        cx.dispatch_action(Copy);
+
+        outer_ran.get()
+    }
+
+    fn create_test_input_with_layout(
+        cx: &mut TestAppContext,
+        content: &str,
+        range: std::ops::Range<usize>,
+    ) -> WindowHandle<TestView> {
+        let view = cx.add_window(|window, cx| {
+            let input = cx.new(|cx| {
+                let mut input = InputState::new_multiline(cx);
+                input.content = content.to_string();
+                input.selected_range = range;
+                input.update_line_layouts(px(500.), px(20.), &TextStyle::default(), window);
+                input
+            });
+            TestView { input }
+        });
+        view
+    }
+
+    // ============================================================
+    // BASIC MOVEMENT
+    // ============================================================
+
+    #[gpui::test]
+    fn test_left_at_start_of_content(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.left(&Left, window, cx);
+                assert_eq!(input.selected_range, 0..0);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_left_moves_by_grapheme(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 3..3);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.left(&Left, window, cx);
+                assert_eq!(input.selected_range, 2..2);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_left_collapses_selection_to_start(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 1..4);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.left(&Left, window, cx);
+                assert_eq!(input.selected_range, 1..1);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_left_stops_at_end_of_line(cx: &mut TestAppContext) {
+        // "ab\ncd" - cursor at position 3 (start of "cd", after newline)
+        // Pressing left should move to position 2 (end of "ab", before newline)
+        let view = create_test_input(cx, "ab\ncd", 3..3);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.left(&Left, window, cx);
+                assert_eq!(input.selected_range, 2..2); // cursor at end of line 1
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_right_at_end_of_content(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 5..5);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range, 5..5);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_right_moves_by_grapheme(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 2..2);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range, 3..3);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_right_collapses_selection_to_end(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 1..4);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range, 4..4);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_right_stops_at_end_of_line(cx: &mut TestAppContext) {
+        // "ab\ncd" - cursor at position 1 (after 'a')
+        // Pressing right should move to position 2 (end of "ab", before newline)
+        let view = create_test_input(cx, "ab\ncd", 1..1);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range, 2..2); // cursor at end of line 1
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_right_crosses_newline(cx: &mut TestAppContext) {
+        // "ab\ncd" - cursor at position 2 (end of "ab", before newline)
+        // Pressing right should move to position 3 (after newline, start of "cd")
+        let view = create_test_input(cx, "ab\ncd", 2..2);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range, 3..3); // cursor at start of line 2
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_left_crosses_newline(cx: &mut TestAppContext) {
+        // "ab\ncd" - cursor at position 2 (end of "ab", before newline)
+        // Pressing left should move to position 1 (after 'a')
+        let view = create_test_input(cx, "ab\ncd", 2..2);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.left(&Left, window, cx);
+                assert_eq!(input.selected_range, 1..1);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_home_moves_to_line_start(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "first\nsecond", 9..9);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.home(&Home, window, cx);
+                assert_eq!(input.selected_range, 6..6);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_end_moves_to_line_end(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "first\nsecond", 8..8);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.end(&End, window, cx);
+                assert_eq!(input.selected_range, 12..12);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_move_to_beginning(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "first\nsecond\nthird", 9..9);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.move_to_beginning(&MoveToBeginning, window, cx);
+                assert_eq!(input.selected_range, 0..0);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_move_to_end(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "first\nsecond\nthird", 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.move_to_end(&MoveToEnd, window, cx);
+                assert_eq!(input.selected_range, 18..18);
+            });
+        })
+        .unwrap();
+    }
+
+    // ============================================================
+    // WORD MOVEMENT
+    // ============================================================
+
+    #[gpui::test]
+    fn test_word_left_at_start(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.word_left(&WordLeft, window, cx);
+                assert_eq!(input.selected_range, 0..0);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_word_left_stops_at_boundary(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world test", 11..11);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.word_left(&WordLeft, window, cx);
+                assert_eq!(input.selected_range, 6..6);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_word_right_at_end(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 11..11);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.word_right(&WordRight, window, cx);
+                assert_eq!(input.selected_range, 11..11);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_word_right_stops_at_boundary(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world test", 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.word_right(&WordRight, window, cx);
+                assert_eq!(input.selected_range, 5..5);
+            });
+        })
+        .unwrap();
+    }
+
+    // ============================================================
+    // SELECTION
+    // ============================================================
+
+    #[gpui::test]
+    fn test_select_left_extends_selection(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 3..3);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.select_left(&SelectLeft, window, cx);
+                assert_eq!(input.selected_range, 2..3);
+                assert!(input.selection_reversed);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_select_right_extends_selection(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 2..2);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.select_right(&SelectRight, window, cx);
+                assert_eq!(input.selected_range, 2..3);
+                assert!(!input.selection_reversed);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_select_all(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello\nworld", 3..3);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.select_all(&SelectAll, window, cx);
+                assert_eq!(input.selected_range, 0..11);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_select_to_beginning(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 6..6);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.select_to_beginning(&SelectToBeginning, window, cx);
+                assert_eq!(input.selected_range, 0..6);
+                assert!(input.selection_reversed);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_select_to_end(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 6..6);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.select_to_end(&SelectToEnd, window, cx);
+                assert_eq!(input.selected_range, 6..11);
+                assert!(!input.selection_reversed);
+            });
+        })
+        .unwrap();
+    }
+
+    // ============================================================
+    // EDITING - BACKSPACE
+    // ============================================================
+
+    #[gpui::test]
+    fn test_backspace_deletes_selection(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 6..11);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.backspace(&Backspace, window, cx);
+                assert_eq!(input.content(), "hello ");
+                assert_eq!(input.selected_range, 6..6);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_backspace_deletes_previous_grapheme(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 5..5);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.backspace(&Backspace, window, cx);
+                assert_eq!(input.content(), "hell");
+                assert_eq!(input.selected_range, 4..4);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_backspace_at_start_does_nothing(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.backspace(&Backspace, window, cx);
+                assert_eq!(input.content(), "hello");
+                assert_eq!(input.selected_range, 0..0);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_backspace_deletes_entire_emoji(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "Hi 👋", 7..7);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.backspace(&Backspace, window, cx);
+                assert_eq!(input.content(), "Hi ");
+                assert_eq!(input.selected_range, 3..3);
+            });
+        })
+        .unwrap();
+    }
+
+    // ============================================================
+    // EDITING - DELETE
+    // ============================================================
+
+    #[gpui::test]
+    fn test_delete_deletes_selection(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 0..5);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.delete(&Delete, window, cx);
+                assert_eq!(input.content(), " world");
+                assert_eq!(input.selected_range, 0..0);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_delete_deletes_next_grapheme(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.delete(&Delete, window, cx);
+                assert_eq!(input.content(), "ello");
+                assert_eq!(input.selected_range, 0..0);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_delete_at_end_does_nothing(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 5..5);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.delete(&Delete, window, cx);
+                assert_eq!(input.content(), "hello");
+                assert_eq!(input.selected_range, 5..5);
+            });
+        })
+        .unwrap();
+    }
+
+    // ============================================================
+    // EDITING - ENTER
+    // ============================================================
+
+    #[gpui::test]
+    fn test_enter_inserts_newline(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 5..5);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.enter(&Enter, window, cx);
+                assert_eq!(input.content(), "hello\n world");
+                assert_eq!(input.selected_range, 6..6);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_enter_replaces_selection(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 5..6);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.enter(&Enter, window, cx);
+                assert_eq!(input.content(), "hello\nworld");
+                assert_eq!(input.selected_range, 6..6);
+            });
+        })
+        .unwrap();
+    }
+
+    // ============================================================
+    // CLIPBOARD
+    // ============================================================
+
+    #[gpui::test]
+    fn test_copy_with_selection(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 6..11);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.copy(&Copy, window, cx);
+            });
+        })
+        .unwrap();
+
+        let clipboard = cx.read_from_clipboard();
+        assert!(clipboard.is_some());
+        assert_eq!(clipboard.unwrap().text().as_deref(), Some("world"));
+    }
+
+    /// Propagating must not also clobber the clipboard: an input with nothing
+    /// selected has to leave whatever is there alone.
+    #[gpui::test]
+    fn test_copy_without_selection_leaves_the_clipboard_alone(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 3..3);
+        cx.write_to_clipboard(ClipboardItem::new_string("untouched".to_string()));
+
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.copy(&Copy, window, cx);
+            });
+        })
+        .unwrap();
+
+        let clipboard = cx.read_from_clipboard();
+        assert_eq!(clipboard.unwrap().text().as_deref(), Some("untouched"));
+    }
+
+    /// The regression test. It has to go through a real dispatch — calling
+    /// `copy` directly sets `propagate_event` for nobody to read — so it
+    /// renders an actual `Input` inside a `div` that handles `Copy` and
+    /// dispatches at the focused input.
+    #[gpui::test]
+    fn test_copy_without_selection_reaches_the_outer_handler(cx: &mut TestAppContext) {
+        let outer_ran = dispatch_copy_at_focused_input(cx, 3..3);
+        assert!(
+            outer_ran,
+            "an input with no selection swallowed Copy instead of propagating it"
+        );
+    }
+
+    /// The other half: an input that does have a selection still consumes the
+    /// action, exactly as before.
+    #[gpui::test]
+    fn test_copy_with_selection_stops_at_the_input(cx: &mut TestAppContext) {
+        let outer_ran = dispatch_copy_at_focused_input(cx, 6..11);
+
+        assert!(
+            !outer_ran,
+            "an input with a selection let Copy escape to the outer handler"
+        );
+        let clipboard = cx.read_from_clipboard();
+        assert_eq!(clipboard.unwrap().text().as_deref(), Some("world"));
+    }
+
+    #[gpui::test]
+    fn test_cut_with_selection(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 0..5);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.cut(&Cut, window, cx);
+                assert_eq!(input.content(), " world");
+                assert_eq!(input.selected_range, 0..0);
+            });
+        })
+        .unwrap();
+
+        let clipboard = cx.read_from_clipboard();
+        assert_eq!(clipboard.unwrap().text().as_deref(), Some("hello"));
+    }
+
+    #[gpui::test]
+    fn test_paste_inserts_text(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 5..5);
+        cx.write_to_clipboard(ClipboardItem::new_string(" there".to_string()));
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.paste(&Paste, window, cx);
+                assert_eq!(input.content(), "hello there world");
+                assert_eq!(input.selected_range, 11..11);
+            });
+        })
+        .unwrap();
+    }
+
+    // ============================================================
+    // UNICODE / GRAPHEME HANDLING
+    // ============================================================
+
+    #[gpui::test]
+    fn test_movement_with_multibyte_utf8(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "café", 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range, 1..1);
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range, 2..2);
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range, 3..3);
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range, 5..5);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_movement_with_emoji(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "a👋b", 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range, 1..1);
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range, 5..5);
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range, 6..6);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_selection_with_multibyte_characters(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "日本語", 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.select_right(&SelectRight, window, cx);
+                assert_eq!(input.selected_range, 0..3);
+                input.select_right(&SelectRight, window, cx);
+                assert_eq!(input.selected_range, 0..6);
+                input.select_right(&SelectRight, window, cx);
+                assert_eq!(input.selected_range, 0..9);
+            });
+        })
+        .unwrap();
+    }
+
+    // ============================================================
+    // NEWLINE HANDLING
+    // ============================================================
+
+    #[gpui::test]
+    fn test_find_line_start_and_end(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "first\nsecond\nthird", 0..0);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, _cx| {
+                assert_eq!(input.find_line_start(0), 0);
+                assert_eq!(input.find_line_start(3), 0);
+                assert_eq!(input.find_line_start(6), 6);
+                assert_eq!(input.find_line_start(13), 13);
+
+                assert_eq!(input.find_line_end(0), 5);
+                assert_eq!(input.find_line_end(6), 12);
+                assert_eq!(input.find_line_end(13), 18);
+            });
+        })
+        .unwrap();
+    }
+
+    // ============================================================
+    // EDGE CASES
+    // ============================================================
+
+    #[gpui::test]
+    fn test_operations_on_empty_content(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "", 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.left(&Left, window, cx);
+                assert_eq!(input.selected_range, 0..0);
+
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range, 0..0);
+
+                input.backspace(&Backspace, window, cx);
+                assert_eq!(input.content(), "");
+
+                input.delete(&Delete, window, cx);
+                assert_eq!(input.content(), "");
+
+                input.select_all(&SelectAll, window, cx);
+                assert_eq!(input.selected_range, 0..0);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_set_content_resets_selection(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 3..8);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.selection_reversed = true;
+                input.marked_range = Some(5..7);
+                input.set_content("new content", cx);
+                assert_eq!(input.content(), "new content");
+                assert_eq!(input.selected_range, 0..0);
+                assert!(!input.selection_reversed);
+                assert_eq!(input.marked_range, None);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_cursor_clamped_to_content_length(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 100..100);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.move_to(1000, cx);
+                assert_eq!(input.selected_range, 5..5);
+
+                input.selected_range = 0..0;
+                input.select_to(1000, cx);
+                assert_eq!(input.selected_range, 0..5);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_previous_boundary_at_start(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 0..0);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, _cx| {
+                assert_eq!(input.previous_boundary(0), 0);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_next_boundary_at_end(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 0..0);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, _cx| {
+                assert_eq!(input.next_boundary(5), 5);
+                assert_eq!(input.next_boundary(100), 5);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_word_range_at_boundary(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 0..0);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, _cx| {
+                let (start, end) = input.word_range_at(5);
+                assert_eq!(start, 0);
+                assert_eq!(end, 5);
+
+                let (start, end) = input.word_range_at(8);
+                assert_eq!(start, 6);
+                assert_eq!(end, 11);
+            });
+        })
+        .unwrap();
+    }
+
+    // ============================================================
+    // EMOJI & GRAPHEME CLUSTERS
+    // ============================================================
+
+    #[gpui::test]
+    fn test_simple_emoji_navigation(cx: &mut TestAppContext) {
+        // 😀 is 4 bytes in UTF-8
+        let view = create_test_input(cx, "a😀b", 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                // Move right through: a -> 😀 -> b
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range.start, 1); // after 'a'
+
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range.start, 5); // after 😀 (1 + 4 bytes)
+
+                input.right(&Right, window, cx);
+                assert_eq!(input.selected_range.start, 6); // after 'b'
+
+                // Move left back
+                input.left(&Left, window, cx);
+                assert_eq!(input.selected_range.start, 5); // before 'b'
+
+                input.left(&Left, window, cx);
+                assert_eq!(input.selected_range.start, 1); // before 😀
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_emoji_with_skin_tone_modifier(cx: &mut TestAppContext) {
+        // 👋🏽 = 👋 (U+1F44B, 4 bytes) + 🏽 (U+1F3FD, 4 bytes) = 8 bytes total
+        let emoji = "👋🏽";
+        assert_eq!(emoji.len(), 8);
+
+        let view = create_test_input(cx, &format!("a{}b", emoji), 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx); // past 'a'
+                assert_eq!(input.selected_range.start, 1);
+
+                input.right(&Right, window, cx); // past entire emoji with modifier
+                assert_eq!(input.selected_range.start, 9); // 1 + 8
+
+                input.left(&Left, window, cx); // back before emoji
+                assert_eq!(input.selected_range.start, 1);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_zwj_family_emoji(cx: &mut TestAppContext) {
+        // 👨‍👩‍👧 = man + ZWJ + woman + ZWJ + girl
+        // Each person emoji is 4 bytes, ZWJ is 3 bytes
+        // Total: 4 + 3 + 4 + 3 + 4 = 18 bytes
+        let family = "👨‍👩‍👧";
+        assert_eq!(family.len(), 18);
+
+        let view = create_test_input(cx, &format!("x{}y", family), 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx); // past 'x'
+                assert_eq!(input.selected_range.start, 1);
+
+                input.right(&Right, window, cx); // past entire ZWJ sequence
+                assert_eq!(input.selected_range.start, 19); // 1 + 18
+
+                input.right(&Right, window, cx); // past 'y'
+                assert_eq!(input.selected_range.start, 20);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_backspace_deletes_emoji_between_ascii(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "a😀b", 5..5); // cursor after emoji
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.backspace(&Backspace, window, cx);
+                assert_eq!(input.content(), "ab");
+                assert_eq!(input.selected_range.start, 1);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_backspace_deletes_zwj_sequence(cx: &mut TestAppContext) {
+        let family = "👨‍👩‍👧";
+        let content = format!("a{}b", family);
+        let cursor_pos = 1 + family.len(); // after the family emoji
+
+        let view = create_test_input(cx, &content, cursor_pos..cursor_pos);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.backspace(&Backspace, window, cx);
+                assert_eq!(input.content(), "ab");
+                assert_eq!(input.selected_range.start, 1);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_delete_removes_entire_emoji(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "a😀b", 1..1); // cursor before emoji
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.delete(&Delete, window, cx);
+                assert_eq!(input.content(), "ab");
+                assert_eq!(input.selected_range.start, 1);
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_flag_emoji_navigation(cx: &mut TestAppContext) {
+        // 🇯🇵 = Regional Indicator J (4 bytes) + Regional Indicator P (4 bytes)
+        let flag = "🇯🇵";
+        assert_eq!(flag.len(), 8);
+
+        let view = create_test_input(cx, &format!("x{}y", flag), 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx); // past 'x'
+                input.right(&Right, window, cx); // past flag (should be single grapheme)
+                assert_eq!(input.selected_range.start, 9); // 1 + 8
+            });
+        })
+        .unwrap();
     }
 
-    fn character_index_for_point(
-        &mut self,
-        point: Point<Pixels>,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
-    ) -> Option<usize> {
-        let index = self.index_for_position(point);
-        Some(self.offset_to_utf16(index))
-    }
-}
+    #[gpui::test]
+    fn test_combining_diacritical_marks(cx: &mut TestAppContext) {
+        // é as e + combining acute accent (U+0301)
+        let combining = "e\u{0301}"; // 1 + 2 = 3 bytes
+        assert_eq!(combining.len(), 3);
 
-impl Focusable for InputState {
-    fn focus_handle(&self, _: &App) -> FocusHandle {
-        self.focus_handle.clone()
-    }
-}
+        let view = create_test_input(cx, &format!("a{}b", combining), 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx); // past 'a'
+                assert_eq!(input.selected_range.start, 1);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use gpui::{
-        div, AppContext, Entity, InteractiveElement, IntoElement, ParentElement, Render,
-        TestAppContext, TextStyle, WindowHandle,
-    };
-    use std::cell::Cell;
-    use std::rc::Rc;
+                input.right(&Right, window, cx); // past e + combining mark (single grapheme)
+                assert_eq!(input.selected_range.start, 4); // 1 + 3
 
-    struct TestView {
-        input: Entity<InputState>,
+                input.left(&Left, window, cx);
+                assert_eq!(input.selected_range.start, 1);
+            });
+        })
+        .unwrap();
     }
 
-    impl Render for TestView {
-        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
-            div()
-        }
-    }
+    #[gpui::test]
+    fn test_multiple_combining_marks(cx: &mut TestAppContext) {
+        // ë́ = e + combining diaeresis (U+0308) + combining acute (U+0301)
+        let multi_combining = "e\u{0308}\u{0301}"; // 1 + 2 + 2 = 5 bytes
+        assert_eq!(multi_combining.len(), 5);
 
-    fn create_test_input(
-        cx: &mut TestAppContext,
-        content: &str,
-        range: std::ops::Range<usize>,
-    ) -> WindowHandle<TestView> {
-        cx.add_window(|_window, cx| {
-            let input = cx.new(|cx| {
-                let mut input = InputState::new_multiline(cx);
-                input.content = content.to_string();
-                input.selected_range = range;
-                input
+        let view = create_test_input(cx, &format!("x{}y", multi_combining), 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx); // past 'x'
+                input.right(&Right, window, cx); // past entire combined character
+                assert_eq!(input.selected_range.start, 6); // 1 + 5
             });
-            TestView { input }
         })
+        .unwrap();
     }
 
-    /// A real `Input` under a `div` that handles `Copy`, so the action travels
-    /// the actual dispatch tree. `TestView` cannot serve here: it renders a
-    /// bare `div()`, so no input element is ever in the tree.
-    struct OuterCopyView {
-        input: Entity<InputState>,
-        outer_ran: Rc<Cell<bool>>,
+    #[gpui::test]
+    fn test_select_emoji_with_shift(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "a😀b", 1..1); // cursor before emoji
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.select_right(&SelectRight, window, cx);
+                assert_eq!(input.selected_range, 1..5); // selected the entire emoji
+            });
+        })
+        .unwrap();
     }
 
-    impl Render for OuterCopyView {
-        fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-            let outer_ran = self.outer_ran.clone();
-            div()
-                .on_action::<Copy>(move |_, _, _| outer_ran.set(true))
-                .child(crate::elements::input::text_area(&self.input, cx))
-        }
+    #[gpui::test]
+    fn test_cjk_characters(cx: &mut TestAppContext) {
+        // 你好 - each character is 3 bytes in UTF-8
+        let view = create_test_input(cx, "a你好b", 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx); // past 'a'
+                assert_eq!(input.selected_range.start, 1);
+
+                input.right(&Right, window, cx); // past 你
+                assert_eq!(input.selected_range.start, 4); // 1 + 3
+
+                input.right(&Right, window, cx); // past 好
+                assert_eq!(input.selected_range.start, 7); // 4 + 3
+
+                input.right(&Right, window, cx); // past 'b'
+                assert_eq!(input.selected_range.start, 8);
+            });
+        })
+        .unwrap();
     }
 
-    /// Focuses a rendered input holding `"hello world"` with `range` selected,
-    /// dispatches `Copy` at it, and reports whether the handler *outside* the
-    /// input ran.
-    fn dispatch_copy_at_focused_input(
-        cx: &mut TestAppContext,
-        range: std::ops::Range<usize>,
-    ) -> bool {
-        // Rendering any real element needs the theme bound, or `cx.theme()`
-        // panics on the unbound global. Only the theme, not all of
-        // `gpuikit::init`, which would bind keys these tests do not want.
-        cx.update(crate::theme::init);
+    #[gpui::test]
+    fn test_mixed_script_text(cx: &mut TestAppContext) {
+        // Mix of ASCII, CJK, and emoji
+        let view = create_test_input(cx, "Hi你😀", 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx); // past 'H'
+                assert_eq!(input.selected_range.start, 1);
 
-        let outer_ran = Rc::new(Cell::new(false));
-        let (view, cx) = cx.add_window_view({
-            let outer_ran = outer_ran.clone();
-            move |_window, cx| {
-                let input = cx.new(|cx| {
-                    let mut input = InputState::new_multiline(cx);
-                    input.content = "hello world".to_string();
-                    input.selected_range = range;
-                    input
-                });
-                OuterCopyView { input, outer_ran }
-            }
-        });
+                input.right(&Right, window, cx); // past 'i'
+                assert_eq!(input.selected_range.start, 2);
 
-        cx.update(|window, cx| {
-            let handle = view.read(cx).input.focus_handle(cx);
-            window.focus(&handle, cx);
-        });
-        cx.run_until_parked();
+                input.right(&Right, window, cx); // past 你 (3 bytes)
+                assert_eq!(input.selected_range.start, 5);
 
-        cx.dispatch_action(Copy);
+                input.right(&Right, window, cx); // past 😀 (4 bytes)
+                assert_eq!(input.selected_range.start, 9);
 
-        outer_ran.get()
+                // Now go back
+                input.left(&Left, window, cx);
+                assert_eq!(input.selected_range.start, 5);
+
+                input.left(&Left, window, cx);
+                assert_eq!(input.selected_range.start, 2);
+            });
+        })
+        .unwrap();
     }
 
-    fn create_test_input_with_layout(
+    #[gpui::test]
+    fn test_variation_selector_emoji(cx: &mut TestAppContext) {
+        // ☺️ = ☺ (U+263A, 3 bytes) + variation selector-16 (U+FE0F, 3 bytes)
+        let emoji_presentation = "☺\u{FE0F}";
+        assert_eq!(emoji_presentation.len(), 6);
+
+        let view = create_test_input(cx, &format!("a{}b", emoji_presentation), 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx); // past 'a'
+                input.right(&Right, window, cx); // past emoji with variation selector
+                assert_eq!(input.selected_range.start, 7); // 1 + 6
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_keycap_emoji(cx: &mut TestAppContext) {
+        // 1️⃣ = 1 + variation selector + combining enclosing keycap
+        let keycap = "1\u{FE0F}\u{20E3}";
+
+        let view = create_test_input(cx, &format!("x{}y", keycap), 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.right(&Right, window, cx); // past 'x'
+                input.right(&Right, window, cx); // past keycap sequence
+                let expected_pos = 1 + keycap.len();
+                assert_eq!(input.selected_range.start, expected_pos);
+            });
+        })
+        .unwrap();
+    }
+
+    // Single-line input tests
+
+    fn create_single_line_input(
         cx: &mut TestAppContext,
         content: &str,
-        range: std::ops::Range<usize>,
+        selected_range: Range<usize>,
     ) -> WindowHandle<TestView> {
-        let view = cx.add_window(|window, cx| {
+        cx.add_window(|_window, cx| {
             let input = cx.new(|cx| {
-                let mut input = InputState::new_multiline(cx);
+                let mut input = InputState::new_singleline(cx);
                 input.content = content.to_string();
-                input.selected_range = range;
-                input.update_line_layouts(px(500.), px(20.), &TextStyle::default(), window);
+                input.selected_range = selected_range;
                 input
             });
             TestView { input }
-        });
-        view
+        })
     }
 
-    // ============================================================
-    // BASIC MOVEMENT
-    // ============================================================
-
     #[gpui::test]
-    fn test_left_at_start_of_content(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 0..0);
+    fn test_single_line_enter_does_nothing(cx: &mut TestAppContext) {
+        let view = create_single_line_input(cx, "hello", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.left(&Left, window, cx);
-                assert_eq!(input.selected_range, 0..0);
+                input.enter(&Enter, window, cx);
+                assert_eq!(input.content(), "hello");
+                assert_eq!(input.selected_range, 5..5);
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_left_moves_by_grapheme(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 3..3);
-        view.update(cx, |view, window, cx| {
+    fn test_single_line_set_content_strips_newlines(cx: &mut TestAppContext) {
+        let view = create_single_line_input(cx, "", 0..0);
+        view.update(cx, |view, _window, cx| {
             view.input.update(cx, |input, cx| {
-                input.left(&Left, window, cx);
-                assert_eq!(input.selected_range, 2..2);
+                input.set_content("hello\nworld\r\nfoo", cx);
+                assert_eq!(input.content(), "hello world foo");
+            });
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_single_line_up_moves_to_start(cx: &mut TestAppContext) {
+        let view = create_single_line_input(cx, "hello world", 5..5);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.up(&Up, window, cx);
+                assert_eq!(input.selected_range, 0..0);
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_left_collapses_selection_to_start(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 1..4);
+    fn test_single_line_down_moves_to_end(cx: &mut TestAppContext) {
+        let view = create_single_line_input(cx, "hello world", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.left(&Left, window, cx);
-                assert_eq!(input.selected_range, 1..1);
+                input.down(&Down, window, cx);
+                assert_eq!(input.selected_range, 11..11); // "hello world".len() == 11
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_left_stops_at_end_of_line(cx: &mut TestAppContext) {
-        // "ab\ncd" - cursor at position 3 (start of "cd", after newline)
-        // Pressing left should move to position 2 (end of "ab", before newline)
-        let view = create_test_input(cx, "ab\ncd", 3..3);
+    fn test_single_line_select_up_selects_to_start(cx: &mut TestAppContext) {
+        let view = create_single_line_input(cx, "hello world", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.left(&Left, window, cx);
-                assert_eq!(input.selected_range, 2..2); // cursor at end of line 1
+                input.select_up(&SelectUp, window, cx);
+                assert_eq!(input.selected_range, 0..5);
+                assert!(input.selection_reversed);
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_right_at_end_of_content(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 5..5);
+    fn test_single_line_select_down_selects_to_end(cx: &mut TestAppContext) {
+        let view = create_single_line_input(cx, "hello world", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range, 5..5);
+                input.select_down(&SelectDown, window, cx);
+                assert_eq!(input.selected_range, 5..11); // "hello world".len() == 11
+                assert!(!input.selection_reversed);
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_right_moves_by_grapheme(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 2..2);
-        view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range, 3..3);
+    fn test_single_line_multiline_getter(cx: &mut TestAppContext) {
+        let view = create_single_line_input(cx, "hello", 0..0);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, _cx| {
+                assert!(!input.is_multiline());
             });
         })
         .unwrap();
+
+        let multiline_view = create_test_input(cx, "hello", 0..0);
+        multiline_view
+            .update(cx, |view, _window, cx| {
+                view.input.update(cx, |input, _cx| {
+                    assert!(input.is_multiline());
+                });
+            })
+            .unwrap();
     }
 
     #[gpui::test]
-    fn test_right_collapses_selection_to_end(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 1..4);
-        view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range, 4..4);
+    fn test_line_direction_ltr(cx: &mut TestAppContext) {
+        let view = create_test_input_with_layout(cx, "Hello world", 0..0);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, _cx| {
+                assert!(input.line_direction(0).is_ltr());
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_right_stops_at_end_of_line(cx: &mut TestAppContext) {
-        // "ab\ncd" - cursor at position 1 (after 'a')
-        // Pressing right should move to position 2 (end of "ab", before newline)
-        let view = create_test_input(cx, "ab\ncd", 1..1);
-        view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range, 2..2); // cursor at end of line 1
+    fn test_line_direction_rtl_arabic(cx: &mut TestAppContext) {
+        let view = create_test_input_with_layout(cx, "مرحبا بالعالم", 0..0);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, _cx| {
+                assert!(input.line_direction(0).is_rtl());
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_right_crosses_newline(cx: &mut TestAppContext) {
-        // "ab\ncd" - cursor at position 2 (end of "ab", before newline)
-        // Pressing right should move to position 3 (after newline, start of "cd")
-        let view = create_test_input(cx, "ab\ncd", 2..2);
-        view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range, 3..3); // cursor at start of line 2
+    fn test_line_direction_rtl_hebrew(cx: &mut TestAppContext) {
+        let view = create_test_input_with_layout(cx, "שלום עולם", 0..0);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, _cx| {
+                assert!(input.line_direction(0).is_rtl());
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_left_crosses_newline(cx: &mut TestAppContext) {
-        // "ab\ncd" - cursor at position 2 (end of "ab", before newline)
-        // Pressing left should move to position 1 (after 'a')
-        let view = create_test_input(cx, "ab\ncd", 2..2);
-        view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.left(&Left, window, cx);
-                assert_eq!(input.selected_range, 1..1);
+    fn test_line_direction_mixed_lines(cx: &mut TestAppContext) {
+        let view = create_test_input_with_layout(cx, "Hello\nمرحبا\nWorld", 0..0);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, _cx| {
+                assert!(input.line_direction(0).is_ltr());
+                assert!(input.line_direction(1).is_rtl());
+                assert!(input.line_direction(2).is_ltr());
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_home_moves_to_line_start(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "first\nsecond", 9..9);
-        view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.home(&Home, window, cx);
-                assert_eq!(input.selected_range, 6..6);
+    fn test_direction_at_offset_ltr(cx: &mut TestAppContext) {
+        let view = create_test_input_with_layout(cx, "Hello world", 0..0);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, _cx| {
+                assert!(input.direction_at_offset(0).is_ltr());
+                assert!(input.direction_at_offset(5).is_ltr());
+                assert!(input.direction_at_offset(11).is_ltr());
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_end_moves_to_line_end(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "first\nsecond", 8..8);
-        view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.end(&End, window, cx);
-                assert_eq!(input.selected_range, 12..12);
+    fn test_direction_at_offset_rtl(cx: &mut TestAppContext) {
+        let view = create_test_input_with_layout(cx, "مرحبا", 0..0);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, _cx| {
+                assert!(input.direction_at_offset(0).is_rtl());
+                assert!(input.direction_at_offset(5).is_rtl());
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_move_to_beginning(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "first\nsecond\nthird", 9..9);
-        view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.move_to_beginning(&MoveToBeginning, window, cx);
-                assert_eq!(input.selected_range, 0..0);
+    fn test_direction_at_offset_multiline(cx: &mut TestAppContext) {
+        let content = "Hello\nמילים";
+        let view = create_test_input_with_layout(cx, content, 0..0);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, _cx| {
+                assert!(input.direction_at_offset(0).is_ltr());
+                assert!(input.direction_at_offset(5).is_ltr());
+                assert!(input.direction_at_offset(6).is_rtl());
+                assert!(input.direction_at_offset(content.len()).is_rtl());
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_move_to_end(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "first\nsecond\nthird", 0..0);
-        view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.move_to_end(&MoveToEnd, window, cx);
-                assert_eq!(input.selected_range, 18..18);
+    fn test_empty_line_inherits_direction(cx: &mut TestAppContext) {
+        let view = create_test_input_with_layout(cx, "مرحبا\n\nWorld", 0..0);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, _cx| {
+                assert!(input.line_direction(0).is_rtl());
+                assert!(input.line_direction(1).is_rtl());
+                assert!(input.line_direction(2).is_ltr());
             });
         })
         .unwrap();
     }
 
-    // ============================================================
-    // WORD MOVEMENT
-    // ============================================================
-
     #[gpui::test]
-    fn test_word_left_at_start(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 0..0);
-        view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.word_left(&WordLeft, window, cx);
-                assert_eq!(input.selected_range, 0..0);
+    fn test_numbers_before_rtl(cx: &mut TestAppContext) {
+        let view = create_test_input_with_layout(cx, "123 مرحبا", 0..0);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, _cx| {
+                assert!(input.line_direction(0).is_rtl());
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_word_left_stops_at_boundary(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world test", 11..11);
-        view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.word_left(&WordLeft, window, cx);
-                assert_eq!(input.selected_range, 6..6);
+    fn test_line_direction_out_of_bounds(cx: &mut TestAppContext) {
+        let view = create_test_input_with_layout(cx, "Hello", 0..0);
+        view.update(cx, |view, _window, cx| {
+            view.input.update(cx, |input, _cx| {
+                assert!(input.line_direction(100).is_ltr());
             });
         })
         .unwrap();
     }
 
+    // ============================================================
+    // UNDO / REDO
+    // ============================================================
+
     #[gpui::test]
-    fn test_word_right_at_end(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 11..11);
+    fn test_undo_restores_content(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.word_right(&WordRight, window, cx);
-                assert_eq!(input.selected_range, 11..11);
+                // Disable grouping for predictable test behavior
+                input.set_group_interval(Duration::from_secs(0));
+
+                // Make an edit
+                input.replace_text_in_range(None, " world", window, cx);
+                assert_eq!(input.content(), "hello world");
+
+                // Undo should restore original content
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hello");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_word_right_stops_at_boundary(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world test", 0..0);
+    fn test_redo_restores_undone_content(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.word_right(&WordRight, window, cx);
-                assert_eq!(input.selected_range, 5..5);
+                input.set_group_interval(Duration::from_secs(0));
+
+                input.replace_text_in_range(None, " world", window, cx);
+                assert_eq!(input.content(), "hello world");
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hello");
+
+                input.redo(&Redo, window, cx);
+                assert_eq!(input.content(), "hello world");
             });
         })
         .unwrap();
     }
 
-    // ============================================================
-    // SELECTION
-    // ============================================================
-
     #[gpui::test]
-    fn test_select_left_extends_selection(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 3..3);
+    fn test_undo_with_no_history_does_nothing(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.select_left(&SelectLeft, window, cx);
-                assert_eq!(input.selected_range, 2..3);
-                assert!(input.selection_reversed);
+                assert!(!input.can_undo());
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hello");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_select_right_extends_selection(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 2..2);
+    fn test_redo_with_no_history_does_nothing(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.select_right(&SelectRight, window, cx);
-                assert_eq!(input.selected_range, 2..3);
-                assert!(!input.selection_reversed);
+                assert!(!input.can_redo());
+                input.redo(&Redo, window, cx);
+                assert_eq!(input.content(), "hello");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_select_all(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello\nworld", 3..3);
+    fn test_undo_restores_selection(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 0..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.select_all(&SelectAll, window, cx);
-                assert_eq!(input.selected_range, 0..11);
+                input.set_group_interval(Duration::from_secs(0));
+
+                // Delete selection
+                input.replace_text_in_range(None, "", window, cx);
+                assert_eq!(input.content(), " world");
+                assert_eq!(input.selected_range, 0..0);
+
+                // Undo should restore content and selection
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hello world");
+                assert_eq!(input.selected_range, 0..5);
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_select_to_beginning(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 6..6);
+    fn test_multiple_undo_redo(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.select_to_beginning(&SelectToBeginning, window, cx);
-                assert_eq!(input.selected_range, 0..6);
-                assert!(input.selection_reversed);
+                input.set_group_interval(Duration::from_secs(0));
+
+                input.replace_text_in_range(None, "a", window, cx);
+                input.replace_text_in_range(None, "b", window, cx);
+                input.replace_text_in_range(None, "c", window, cx);
+                assert_eq!(input.content(), "abc");
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "ab");
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "a");
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "");
+
+                input.redo(&Redo, window, cx);
+                assert_eq!(input.content(), "a");
+
+                input.redo(&Redo, window, cx);
+                assert_eq!(input.content(), "ab");
+
+                input.redo(&Redo, window, cx);
+                assert_eq!(input.content(), "abc");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_select_to_end(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 6..6);
+    fn test_new_edit_clears_redo_stack(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.select_to_end(&SelectToEnd, window, cx);
-                assert_eq!(input.selected_range, 6..11);
-                assert!(!input.selection_reversed);
+                input.set_group_interval(Duration::from_secs(0));
+
+                input.replace_text_in_range(None, " world", window, cx);
+                assert_eq!(input.content(), "hello world");
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hello");
+                assert!(input.can_redo());
+
+                // New edit should clear redo stack
+                input.replace_text_in_range(None, "!", window, cx);
+                assert_eq!(input.content(), "hello!");
+                assert!(!input.can_redo());
             });
         })
         .unwrap();
     }
 
-    // ============================================================
-    // EDITING - BACKSPACE
-    // ============================================================
-
     #[gpui::test]
-    fn test_backspace_deletes_selection(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 6..11);
+    fn test_set_content_clears_history(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.backspace(&Backspace, window, cx);
-                assert_eq!(input.content(), "hello ");
-                assert_eq!(input.selected_range, 6..6);
+                input.set_group_interval(Duration::from_secs(0));
+
+                input.replace_text_in_range(None, " world", window, cx);
+                assert!(input.can_undo());
+
+                input.set_content("new content", cx);
+                assert!(!input.can_undo());
+                assert!(!input.can_redo());
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_backspace_deletes_previous_grapheme(cx: &mut TestAppContext) {
+    fn test_can_undo_can_redo(cx: &mut TestAppContext) {
         let view = create_test_input(cx, "hello", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.backspace(&Backspace, window, cx);
-                assert_eq!(input.content(), "hell");
-                assert_eq!(input.selected_range, 4..4);
+                input.set_group_interval(Duration::from_secs(0));
+
+                assert!(!input.can_undo());
+                assert!(!input.can_redo());
+
+                input.replace_text_in_range(None, "!", window, cx);
+                assert!(input.can_undo());
+                assert!(!input.can_redo());
+
+                input.undo(&Undo, window, cx);
+                assert!(!input.can_undo());
+                assert!(input.can_redo());
+
+                input.redo(&Redo, window, cx);
+                assert!(input.can_undo());
+                assert!(!input.can_redo());
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_backspace_at_start_does_nothing(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 0..0);
+    fn test_backspace_is_undoable(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
+                input.set_group_interval(Duration::from_secs(0));
+
                 input.backspace(&Backspace, window, cx);
+                assert_eq!(input.content(), "hell");
+
+                input.undo(&Undo, window, cx);
                 assert_eq!(input.content(), "hello");
-                assert_eq!(input.selected_range, 0..0);
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_backspace_deletes_entire_emoji(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "Hi 👋", 7..7);
+    fn test_delete_is_undoable(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.backspace(&Backspace, window, cx);
-                assert_eq!(input.content(), "Hi ");
-                assert_eq!(input.selected_range, 3..3);
+                input.set_group_interval(Duration::from_secs(0));
+
+                input.delete(&Delete, window, cx);
+                assert_eq!(input.content(), "ello");
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hello");
             });
         })
         .unwrap();
     }
 
-    // ============================================================
-    // EDITING - DELETE
-    // ============================================================
-
     #[gpui::test]
-    fn test_delete_deletes_selection(cx: &mut TestAppContext) {
+    fn test_cut_is_undoable(cx: &mut TestAppContext) {
         let view = create_test_input(cx, "hello world", 0..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.delete(&Delete, window, cx);
+                input.set_group_interval(Duration::from_secs(0));
+
+                input.cut(&Cut, window, cx);
                 assert_eq!(input.content(), " world");
-                assert_eq!(input.selected_range, 0..0);
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hello world");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_delete_deletes_next_grapheme(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 0..0);
+    fn test_cut_line_with_no_selection(cx: &mut TestAppContext) {
+        // Cursor in middle line, no selection - should cut entire line including newline
+        let view = create_test_input(cx, "line1\nline2\nline3", 8..8); // cursor in "line2"
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.delete(&Delete, window, cx);
-                assert_eq!(input.content(), "ello");
-                assert_eq!(input.selected_range, 0..0);
+                input.cut(&Cut, window, cx);
+                assert_eq!(input.content(), "line1\nline3");
             });
         })
         .unwrap();
+
+        let clipboard = cx.read_from_clipboard();
+        assert_eq!(clipboard.unwrap().text().as_deref(), Some("line2\n"));
     }
 
     #[gpui::test]
-    fn test_delete_at_end_does_nothing(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 5..5);
+    fn test_cut_first_line_with_no_selection(cx: &mut TestAppContext) {
+        // Cursor on first line, no selection
+        let view = create_test_input(cx, "line1\nline2\nline3", 2..2); // cursor in "line1"
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.delete(&Delete, window, cx);
-                assert_eq!(input.content(), "hello");
-                assert_eq!(input.selected_range, 5..5);
+                input.cut(&Cut, window, cx);
+                assert_eq!(input.content(), "line2\nline3");
             });
         })
         .unwrap();
-    }
 
-    // ============================================================
-    // EDITING - ENTER
-    // ============================================================
+        let clipboard = cx.read_from_clipboard();
+        assert_eq!(clipboard.unwrap().text().as_deref(), Some("line1\n"));
+    }
 
     #[gpui::test]
-    fn test_enter_inserts_newline(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 5..5);
+    fn test_cut_last_line_with_no_selection(cx: &mut TestAppContext) {
+        // Cursor on last line, no selection - should include preceding newline
+        let view = create_test_input(cx, "line1\nline2\nline3", 14..14); // cursor in "line3"
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.enter(&Enter, window, cx);
-                assert_eq!(input.content(), "hello\n world");
-                assert_eq!(input.selected_range, 6..6);
+                input.cut(&Cut, window, cx);
+                assert_eq!(input.content(), "line1\nline2");
             });
         })
         .unwrap();
+
+        let clipboard = cx.read_from_clipboard();
+        assert_eq!(clipboard.unwrap().text().as_deref(), Some("\nline3"));
     }
 
     #[gpui::test]
-    fn test_enter_replaces_selection(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 5..6);
+    fn test_cut_empty_line(cx: &mut TestAppContext) {
+        // Cursor on empty line - should remove that line
+        let view = create_test_input(cx, "line1\n\nline3", 6..6); // cursor on empty line
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.enter(&Enter, window, cx);
-                assert_eq!(input.content(), "hello\nworld");
-                assert_eq!(input.selected_range, 6..6);
+                input.cut(&Cut, window, cx);
+                assert_eq!(input.content(), "line1\nline3");
             });
         })
         .unwrap();
-    }
 
-    // ============================================================
-    // CLIPBOARD
-    // ============================================================
+        let clipboard = cx.read_from_clipboard();
+        assert_eq!(clipboard.unwrap().text().as_deref(), Some("\n"));
+    }
 
     #[gpui::test]
-    fn test_copy_with_selection(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 6..11);
+    fn test_cut_only_line_with_no_selection(cx: &mut TestAppContext) {
+        // Single line content, no selection - should cut entire content
+        let view = create_test_input(cx, "hello", 2..2);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.copy(&Copy, window, cx);
+                input.cut(&Cut, window, cx);
+                assert_eq!(input.content(), "");
             });
         })
         .unwrap();
 
         let clipboard = cx.read_from_clipboard();
-        assert!(clipboard.is_some());
-        assert_eq!(clipboard.unwrap().text().as_deref(), Some("world"));
+        assert_eq!(clipboard.unwrap().text().as_deref(), Some("hello"));
     }
 
-    /// Propagating must not also clobber the clipboard: an input with nothing
-    /// selected has to leave whatever is there alone.
     #[gpui::test]
-    fn test_copy_without_selection_leaves_the_clipboard_alone(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 3..3);
-        cx.write_to_clipboard(ClipboardItem::new_string("untouched".to_string()));
-
+    fn test_cut_line_is_undoable(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "line1\nline2\nline3", 8..8);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.copy(&Copy, window, cx);
+                input.set_group_interval(Duration::from_secs(0));
+
+                input.cut(&Cut, window, cx);
+                assert_eq!(input.content(), "line1\nline3");
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "line1\nline2\nline3");
             });
         })
         .unwrap();
-
-        let clipboard = cx.read_from_clipboard();
-        assert_eq!(clipboard.unwrap().text().as_deref(), Some("untouched"));
     }
 
-    /// The regression test. It has to go through a real dispatch — calling
-    /// `copy` directly sets `propagate_event` for nobody to read — so it
-    /// renders an actual `Input` inside a `div` that handles `Copy` and
-    /// dispatches at the focused input.
     #[gpui::test]
-    fn test_copy_without_selection_reaches_the_outer_handler(cx: &mut TestAppContext) {
-        let outer_ran = dispatch_copy_at_focused_input(cx, 3..3);
-        assert!(
-            outer_ran,
-            "an input with no selection swallowed Copy instead of propagating it"
-        );
+    fn test_paste_is_undoable(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 5..5);
+        cx.write_to_clipboard(ClipboardItem::new_string(" world".to_string()));
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.set_group_interval(Duration::from_secs(0));
+
+                input.paste(&Paste, window, cx);
+                assert_eq!(input.content(), "hello world");
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hello");
+            });
+        })
+        .unwrap();
     }
 
-    /// The other half: an input that does have a selection still consumes the
-    /// action, exactly as before.
     #[gpui::test]
-    fn test_copy_with_selection_stops_at_the_input(cx: &mut TestAppContext) {
-        let outer_ran = dispatch_copy_at_focused_input(cx, 6..11);
+    fn test_enter_is_undoable(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 5..5);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.set_group_interval(Duration::from_secs(0));
 
-        assert!(
-            !outer_ran,
-            "an input with a selection let Copy escape to the outer handler"
-        );
-        let clipboard = cx.read_from_clipboard();
-        assert_eq!(clipboard.unwrap().text().as_deref(), Some("world"));
+                input.enter(&Enter, window, cx);
+                assert_eq!(input.content(), "hello\n world");
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hello world");
+            });
+        })
+        .unwrap();
     }
 
     #[gpui::test]
-    fn test_cut_with_selection(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 0..5);
+    fn test_delete_word_left(cx: &mut TestAppContext) {
+        // Cursor at end of "hello" in "hello world"
+        let view = create_test_input(cx, "hello world", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.cut(&Cut, window, cx);
+                input.delete_word_left(&DeleteWordLeft, window, cx);
                 assert_eq!(input.content(), " world");
                 assert_eq!(input.selected_range, 0..0);
             });
         })
         .unwrap();
-
-        let clipboard = cx.read_from_clipboard();
-        assert_eq!(clipboard.unwrap().text().as_deref(), Some("hello"));
     }
 
     #[gpui::test]
-    fn test_paste_inserts_text(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 5..5);
-        cx.write_to_clipboard(ClipboardItem::new_string(" there".to_string()));
+    fn test_delete_word_left_with_selection(cx: &mut TestAppContext) {
+        // Selection from 0 to 5 ("hello")
+        let view = create_test_input(cx, "hello world", 0..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.paste(&Paste, window, cx);
-                assert_eq!(input.content(), "hello there world");
-                assert_eq!(input.selected_range, 11..11);
+                input.delete_word_left(&DeleteWordLeft, window, cx);
+                assert_eq!(input.content(), " world");
             });
         })
         .unwrap();
     }
 
-    // ============================================================
-    // UNICODE / GRAPHEME HANDLING
-    // ============================================================
-
     #[gpui::test]
-    fn test_movement_with_multibyte_utf8(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "café", 0..0);
+    fn test_delete_word_left_at_start(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range, 1..1);
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range, 2..2);
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range, 3..3);
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range, 5..5);
+                input.delete_word_left(&DeleteWordLeft, window, cx);
+                assert_eq!(input.content(), "hello world");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_movement_with_emoji(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "a👋b", 0..0);
+    fn test_delete_word_right(cx: &mut TestAppContext) {
+        // Cursor at start
+        let view = create_test_input(cx, "hello world", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range, 1..1);
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range, 5..5);
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range, 6..6);
+                input.delete_word_right(&DeleteWordRight, window, cx);
+                assert_eq!(input.content(), " world");
+                assert_eq!(input.selected_range, 0..0);
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_selection_with_multibyte_characters(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "日本語", 0..0);
+    fn test_delete_word_right_with_selection(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 0..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.select_right(&SelectRight, window, cx);
-                assert_eq!(input.selected_range, 0..3);
-                input.select_right(&SelectRight, window, cx);
-                assert_eq!(input.selected_range, 0..6);
-                input.select_right(&SelectRight, window, cx);
-                assert_eq!(input.selected_range, 0..9);
+                input.delete_word_right(&DeleteWordRight, window, cx);
+                assert_eq!(input.content(), " world");
             });
         })
         .unwrap();
     }
 
-    // ============================================================
-    // NEWLINE HANDLING
-    // ============================================================
-
     #[gpui::test]
-    fn test_find_line_start_and_end(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "first\nsecond\nthird", 0..0);
-        view.update(cx, |view, _window, cx| {
-            view.input.update(cx, |input, _cx| {
-                assert_eq!(input.find_line_start(0), 0);
-                assert_eq!(input.find_line_start(3), 0);
-                assert_eq!(input.find_line_start(6), 6);
-                assert_eq!(input.find_line_start(13), 13);
-
-                assert_eq!(input.find_line_end(0), 5);
-                assert_eq!(input.find_line_end(6), 12);
-                assert_eq!(input.find_line_end(13), 18);
+    fn test_delete_word_right_at_end(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 11..11);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.delete_word_right(&DeleteWordRight, window, cx);
+                assert_eq!(input.content(), "hello world");
             });
         })
         .unwrap();
     }
 
-    // ============================================================
-    // EDGE CASES
-    // ============================================================
-
     #[gpui::test]
-    fn test_operations_on_empty_content(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "", 0..0);
+    fn test_delete_to_beginning_of_line(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.left(&Left, window, cx);
-                assert_eq!(input.selected_range, 0..0);
-
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range, 0..0);
-
-                input.backspace(&Backspace, window, cx);
-                assert_eq!(input.content(), "");
-
-                input.delete(&Delete, window, cx);
-                assert_eq!(input.content(), "");
-
-                input.select_all(&SelectAll, window, cx);
+                input.delete_to_beginning_of_line(&DeleteToBeginningOfLine, window, cx);
+                assert_eq!(input.content(), " world");
                 assert_eq!(input.selected_range, 0..0);
             });
         })
@@ -2679,1337 +6088,1884 @@ mod tests {
     }
 
     #[gpui::test]
-    fn test_set_content_resets_selection(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 3..8);
-        view.update(cx, |view, _window, cx| {
+    fn test_delete_to_beginning_of_line_multiline(cx: &mut TestAppContext) {
+        // Cursor at position 8 (middle of "line2")
+        let view = create_test_input(cx, "line1\nline2\nline3", 8..8);
+        view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.selection_reversed = true;
-                input.marked_range = Some(5..7);
-                input.set_content("new content", cx);
-                assert_eq!(input.content(), "new content");
-                assert_eq!(input.selected_range, 0..0);
-                assert!(!input.selection_reversed);
-                assert_eq!(input.marked_range, None);
+                input.delete_to_beginning_of_line(&DeleteToBeginningOfLine, window, cx);
+                assert_eq!(input.content(), "line1\nne2\nline3");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_cursor_clamped_to_content_length(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 100..100);
-        view.update(cx, |view, _window, cx| {
+    fn test_delete_to_beginning_of_line_at_start(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 0..0);
+        view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.move_to(1000, cx);
-                assert_eq!(input.selected_range, 5..5);
-
-                input.selected_range = 0..0;
-                input.select_to(1000, cx);
-                assert_eq!(input.selected_range, 0..5);
+                input.delete_to_beginning_of_line(&DeleteToBeginningOfLine, window, cx);
+                assert_eq!(input.content(), "hello world");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_previous_boundary_at_start(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 0..0);
-        view.update(cx, |view, _window, cx| {
-            view.input.update(cx, |input, _cx| {
-                assert_eq!(input.previous_boundary(0), 0);
+    fn test_delete_to_end_of_line(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 5..5);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.delete_to_end_of_line(&DeleteToEndOfLine, window, cx);
+                assert_eq!(input.content(), "hello");
+                assert_eq!(input.selected_range, 5..5);
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_next_boundary_at_end(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 0..0);
-        view.update(cx, |view, _window, cx| {
-            view.input.update(cx, |input, _cx| {
-                assert_eq!(input.next_boundary(5), 5);
-                assert_eq!(input.next_boundary(100), 5);
+    fn test_delete_to_end_of_line_multiline(cx: &mut TestAppContext) {
+        // Cursor at position 8 (middle of "line2")
+        let view = create_test_input(cx, "line1\nline2\nline3", 8..8);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.delete_to_end_of_line(&DeleteToEndOfLine, window, cx);
+                assert_eq!(input.content(), "line1\nli\nline3");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_word_range_at_boundary(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 0..0);
-        view.update(cx, |view, _window, cx| {
-            view.input.update(cx, |input, _cx| {
-                let (start, end) = input.word_range_at(5);
-                assert_eq!(start, 0);
-                assert_eq!(end, 5);
-
-                let (start, end) = input.word_range_at(8);
-                assert_eq!(start, 6);
-                assert_eq!(end, 11);
+    fn test_delete_to_end_of_line_at_end(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 11..11);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.delete_to_end_of_line(&DeleteToEndOfLine, window, cx);
+                assert_eq!(input.content(), "hello world");
             });
         })
         .unwrap();
     }
 
-    // ============================================================
-    // EMOJI & GRAPHEME CLUSTERS
-    // ============================================================
-
     #[gpui::test]
-    fn test_simple_emoji_navigation(cx: &mut TestAppContext) {
-        // 😀 is 4 bytes in UTF-8
-        let view = create_test_input(cx, "a😀b", 0..0);
+    fn test_delete_word_left_is_undoable(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                // Move right through: a -> 😀 -> b
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range.start, 1); // after 'a'
-
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range.start, 5); // after 😀 (1 + 4 bytes)
-
-                input.right(&Right, window, cx);
-                assert_eq!(input.selected_range.start, 6); // after 'b'
+                input.set_group_interval(Duration::from_secs(0));
 
-                // Move left back
-                input.left(&Left, window, cx);
-                assert_eq!(input.selected_range.start, 5); // before 'b'
+                input.delete_word_left(&DeleteWordLeft, window, cx);
+                assert_eq!(input.content(), " world");
 
-                input.left(&Left, window, cx);
-                assert_eq!(input.selected_range.start, 1); // before 😀
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hello world");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_emoji_with_skin_tone_modifier(cx: &mut TestAppContext) {
-        // 👋🏽 = 👋 (U+1F44B, 4 bytes) + 🏽 (U+1F3FD, 4 bytes) = 8 bytes total
-        let emoji = "👋🏽";
-        assert_eq!(emoji.len(), 8);
-
-        let view = create_test_input(cx, &format!("a{}b", emoji), 0..0);
+    fn test_delete_word_right_is_undoable(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 6..6);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx); // past 'a'
-                assert_eq!(input.selected_range.start, 1);
+                input.set_group_interval(Duration::from_secs(0));
 
-                input.right(&Right, window, cx); // past entire emoji with modifier
-                assert_eq!(input.selected_range.start, 9); // 1 + 8
+                input.delete_word_right(&DeleteWordRight, window, cx);
+                assert_eq!(input.content(), "hello ");
 
-                input.left(&Left, window, cx); // back before emoji
-                assert_eq!(input.selected_range.start, 1);
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hello world");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_zwj_family_emoji(cx: &mut TestAppContext) {
-        // 👨‍👩‍👧 = man + ZWJ + woman + ZWJ + girl
-        // Each person emoji is 4 bytes, ZWJ is 3 bytes
-        // Total: 4 + 3 + 4 + 3 + 4 = 18 bytes
-        let family = "👨‍👩‍👧";
-        assert_eq!(family.len(), 18);
-
-        let view = create_test_input(cx, &format!("x{}y", family), 0..0);
+    fn test_delete_to_beginning_of_line_is_undoable(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 5..5);
         view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx); // past 'x'
-                assert_eq!(input.selected_range.start, 1);
+            view.input.update(cx, |input, cx| {
+                input.set_group_interval(Duration::from_secs(0));
 
-                input.right(&Right, window, cx); // past entire ZWJ sequence
-                assert_eq!(input.selected_range.start, 19); // 1 + 18
+                input.delete_to_beginning_of_line(&DeleteToBeginningOfLine, window, cx);
+                assert_eq!(input.content(), " world");
 
-                input.right(&Right, window, cx); // past 'y'
-                assert_eq!(input.selected_range.start, 20);
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hello world");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_backspace_deletes_emoji_between_ascii(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "a😀b", 5..5); // cursor after emoji
+    fn test_delete_to_end_of_line_is_undoable(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello world", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.backspace(&Backspace, window, cx);
-                assert_eq!(input.content(), "ab");
-                assert_eq!(input.selected_range.start, 1);
+                input.set_group_interval(Duration::from_secs(0));
+
+                input.delete_to_end_of_line(&DeleteToEndOfLine, window, cx);
+                assert_eq!(input.content(), "hello");
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hello world");
             });
         })
         .unwrap();
     }
 
-    #[gpui::test]
-    fn test_backspace_deletes_zwj_sequence(cx: &mut TestAppContext) {
-        let family = "👨‍👩‍👧";
-        let content = format!("a{}b", family);
-        let cursor_pos = 1 + family.len(); // after the family emoji
+    // ============================================================
+    // READ-ONLY
+    // ============================================================
+    //
+    // Read-only closes every *user* path into the content while leaving
+    // focus, movement, selection, copy, scrolling and the programmatic
+    // setters alone.
 
-        let view = create_test_input(cx, &content, cursor_pos..cursor_pos);
-        view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.backspace(&Backspace, window, cx);
-                assert_eq!(input.content(), "ab");
-                assert_eq!(input.selected_range.start, 1);
+    fn create_read_only_input(
+        cx: &mut TestAppContext,
+        content: &str,
+        range: std::ops::Range<usize>,
+    ) -> WindowHandle<TestView> {
+        cx.add_window(|_window, cx| {
+            let input = cx.new(|cx| {
+                let mut input = InputState::new_multiline(cx).read_only(true);
+                input.content = content.to_string();
+                input.selected_range = range;
+                input
             });
+            TestView { input }
         })
-        .unwrap();
     }
 
+    /// Where plain typing actually arrives: gpui hands a character to the
+    /// focused element's IME handler, not through an action.
     #[gpui::test]
-    fn test_delete_removes_entire_emoji(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "a😀b", 1..1); // cursor before emoji
+    fn test_read_only_refuses_typed_text(cx: &mut TestAppContext) {
+        let view = create_read_only_input(cx, "hello", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.delete(&Delete, window, cx);
-                assert_eq!(input.content(), "ab");
-                assert_eq!(input.selected_range.start, 1);
+                input.replace_text_in_range(None, "x", window, cx);
+                assert_eq!(input.content(), "hello");
+                assert_eq!(input.selected_range, 5..5);
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_flag_emoji_navigation(cx: &mut TestAppContext) {
-        // 🇯🇵 = Regional Indicator J (4 bytes) + Regional Indicator P (4 bytes)
-        let flag = "🇯🇵";
-        assert_eq!(flag.len(), 8);
-
-        let view = create_test_input(cx, &format!("x{}y", flag), 0..0);
+    fn test_read_only_refuses_marked_text(cx: &mut TestAppContext) {
+        let view = create_read_only_input(cx, "hello", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx); // past 'x'
-                input.right(&Right, window, cx); // past flag (should be single grapheme)
-                assert_eq!(input.selected_range.start, 9); // 1 + 8
+                input.replace_and_mark_text_in_range(None, "\u{3053}", None, window, cx);
+                assert_eq!(input.content(), "hello");
+                assert!(input.marked_range().is_none());
             });
         })
         .unwrap();
     }
 
+    /// Each of these *moves the selection* before deleting through it, so
+    /// they need their own guard: leaning on the funnel would have a
+    /// read-only field answer backspace by silently eating a grapheme of the
+    /// user's selection.
     #[gpui::test]
-    fn test_combining_diacritical_marks(cx: &mut TestAppContext) {
-        // é as e + combining acute accent (U+0301)
-        let combining = "e\u{0301}"; // 1 + 2 = 3 bytes
-        assert_eq!(combining.len(), 3);
-
-        let view = create_test_input(cx, &format!("a{}b", combining), 0..0);
+    fn test_read_only_refuses_the_delete_family_without_moving_the_selection(
+        cx: &mut TestAppContext,
+    ) {
+        let view = create_read_only_input(cx, "hello world", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx); // past 'a'
-                assert_eq!(input.selected_range.start, 1);
-
-                input.right(&Right, window, cx); // past e + combining mark (single grapheme)
-                assert_eq!(input.selected_range.start, 4); // 1 + 3
+                input.backspace(&Backspace, window, cx);
+                input.delete(&Delete, window, cx);
+                input.delete_word_left(&DeleteWordLeft, window, cx);
+                input.delete_word_right(&DeleteWordRight, window, cx);
+                input.delete_to_beginning_of_line(&DeleteToBeginningOfLine, window, cx);
+                input.delete_to_end_of_line(&DeleteToEndOfLine, window, cx);
+                input.tab(&Tab, window, cx);
+                input.enter(&Enter, window, cx);
+                input.insert_newline(&InsertNewline, window, cx);
 
-                input.left(&Left, window, cx);
-                assert_eq!(input.selected_range.start, 1);
+                assert_eq!(input.content(), "hello world");
+                assert_eq!(
+                    input.selected_range,
+                    5..5,
+                    "a refused delete must not move the selection either"
+                );
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_multiple_combining_marks(cx: &mut TestAppContext) {
-        // ë́ = e + combining diaeresis (U+0308) + combining acute (U+0301)
-        let multi_combining = "e\u{0308}\u{0301}"; // 1 + 2 + 2 = 5 bytes
-        assert_eq!(multi_combining.len(), 5);
-
-        let view = create_test_input(cx, &format!("x{}y", multi_combining), 0..0);
+    fn test_read_only_refuses_paste_and_undo(cx: &mut TestAppContext) {
+        let view = create_read_only_input(cx, "hello", 5..5);
         view.update(cx, |view, window, cx| {
+            cx.write_to_clipboard(ClipboardItem::new_string(" there".to_string()));
             view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx); // past 'x'
-                input.right(&Right, window, cx); // past entire combined character
-                assert_eq!(input.selected_range.start, 6); // 1 + 5
+                input.paste(&Paste, window, cx);
+                assert_eq!(input.content(), "hello");
+
+                input.undo(&Undo, window, cx);
+                input.redo(&Redo, window, cx);
+                assert_eq!(input.content(), "hello");
             });
         })
         .unwrap();
     }
 
+    /// Taking text is not an edit, so cut keeps the copy and drops only the
+    /// removal.
     #[gpui::test]
-    fn test_select_emoji_with_shift(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "a😀b", 1..1); // cursor before emoji
+    fn test_read_only_cut_copies_without_removing(cx: &mut TestAppContext) {
+        let view = create_read_only_input(cx, "hello world", 0..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.select_right(&SelectRight, window, cx);
-                assert_eq!(input.selected_range, 1..5); // selected the entire emoji
+                input.cut(&Cut, window, cx);
+                assert_eq!(input.content(), "hello world");
             });
+
+            assert_eq!(
+                cx.read_from_clipboard().and_then(|item| item.text()),
+                Some("hello".to_string()),
+                "cut should still have copied"
+            );
         })
         .unwrap();
     }
 
+    /// Everything read-only is *not* about: moving, selecting, and the
+    /// owner's programmatic API.
     #[gpui::test]
-    fn test_cjk_characters(cx: &mut TestAppContext) {
-        // 你好 - each character is 3 bytes in UTF-8
-        let view = create_test_input(cx, "a你好b", 0..0);
+    fn test_read_only_still_moves_selects_and_takes_set_content(cx: &mut TestAppContext) {
+        let view = create_read_only_input(cx, "hello world", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx); // past 'a'
-                assert_eq!(input.selected_range.start, 1);
+                input.left(&Left, window, cx);
+                assert_eq!(input.selected_range, 4..4);
 
-                input.right(&Right, window, cx); // past 你
-                assert_eq!(input.selected_range.start, 4); // 1 + 3
+                input.select_all(&SelectAll, window, cx);
+                assert_eq!(input.selected_range, 0..11);
 
-                input.right(&Right, window, cx); // past 好
-                assert_eq!(input.selected_range.start, 7); // 4 + 3
+                input.set_content("replaced", cx);
+                assert_eq!(input.content(), "replaced");
 
-                input.right(&Right, window, cx); // past 'b'
-                assert_eq!(input.selected_range.start, 8);
+                input.insert_text("!", cx);
+                assert_eq!(input.content(), "!replaced");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_mixed_script_text(cx: &mut TestAppContext) {
-        // Mix of ASCII, CJK, and emoji
-        let view = create_test_input(cx, "Hi你😀", 0..0);
+    fn test_read_only_can_be_lifted(cx: &mut TestAppContext) {
+        let view = create_read_only_input(cx, "hello", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx); // past 'H'
-                assert_eq!(input.selected_range.start, 1);
-
-                input.right(&Right, window, cx); // past 'i'
-                assert_eq!(input.selected_range.start, 2);
-
-                input.right(&Right, window, cx); // past 你 (3 bytes)
-                assert_eq!(input.selected_range.start, 5);
-
-                input.right(&Right, window, cx); // past 😀 (4 bytes)
-                assert_eq!(input.selected_range.start, 9);
+                assert!(input.is_read_only());
 
-                // Now go back
-                input.left(&Left, window, cx);
-                assert_eq!(input.selected_range.start, 5);
+                input.set_read_only(false, cx);
+                assert!(!input.is_read_only());
 
-                input.left(&Left, window, cx);
-                assert_eq!(input.selected_range.start, 2);
+                input.replace_text_in_range(None, "!", window, cx);
+                assert_eq!(input.content(), "hello!");
             });
         })
         .unwrap();
     }
 
-    #[gpui::test]
-    fn test_variation_selector_emoji(cx: &mut TestAppContext) {
-        // ☺️ = ☺ (U+263A, 3 bytes) + variation selector-16 (U+FE0F, 3 bytes)
-        let emoji_presentation = "☺\u{FE0F}";
-        assert_eq!(emoji_presentation.len(), 6);
+    // ============================================================
+    // MASKS
+    // ============================================================
 
-        let view = create_test_input(cx, &format!("a{}b", emoji_presentation), 0..0);
+    fn create_masked_input(
+        cx: &mut TestAppContext,
+        mask: impl InputMask,
+    ) -> WindowHandle<TestView> {
+        cx.add_window(|_window, cx| {
+            let input = cx.new(|cx| InputState::new_singleline(cx).mask(mask));
+            TestView { input }
+        })
+    }
+
+    /// Types `text` a character at a time, as the IME handler would.
+    fn type_text(
+        input: &mut InputState,
+        text: &str,
+        window: &mut Window,
+        cx: &mut Context<InputState>,
+    ) {
+        for c in text.chars() {
+            input.replace_text_in_range(None, &c.to_string(), window, cx);
+        }
+    }
+
+    #[gpui::test]
+    fn test_mask_formats_as_you_type(cx: &mut TestAppContext) {
+        let view = create_masked_input(cx, PatternMask::phone());
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx); // past 'a'
-                input.right(&Right, window, cx); // past emoji with variation selector
-                assert_eq!(input.selected_range.start, 7); // 1 + 6
+                type_text(input, "555", window, cx);
+                assert_eq!(input.content(), "(555) ");
+                assert_eq!(input.cursor_offset(), 6);
+
+                type_text(input, "1234567", window, cx);
+                assert_eq!(input.content(), "(555) 123-4567");
+                assert_eq!(input.raw_value(), "5551234567");
+
+                type_text(input, "8", window, cx);
+                assert_eq!(input.content(), "(555) 123-4567", "past the last slot");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_keycap_emoji(cx: &mut TestAppContext) {
-        // 1️⃣ = 1 + variation selector + combining enclosing keycap
-        let keycap = "1\u{FE0F}\u{20E3}";
-
-        let view = create_test_input(cx, &format!("x{}y", keycap), 0..0);
+    fn test_mask_refuses_what_fits_no_slot(cx: &mut TestAppContext) {
+        let view = create_masked_input(cx, PatternMask::otp(6));
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.right(&Right, window, cx); // past 'x'
-                input.right(&Right, window, cx); // past keycap sequence
-                let expected_pos = 1 + keycap.len();
-                assert_eq!(input.selected_range.start, expected_pos);
+                type_text(input, "12x3", window, cx);
+                assert_eq!(input.content(), "123");
+                assert_eq!(input.cursor_offset(), 3);
             });
         })
         .unwrap();
     }
 
-    // Single-line input tests
+    /// Backspace just after a separator deletes the digit before it, and the
+    /// separators that trailed it go with it.
+    #[gpui::test]
+    fn test_mask_backspace_skips_separators(cx: &mut TestAppContext) {
+        let view = create_masked_input(cx, PatternMask::phone());
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                type_text(input, "5551", window, cx);
+                assert_eq!(input.content(), "(555) 1");
 
-    fn create_single_line_input(
-        cx: &mut TestAppContext,
-        content: &str,
-        selected_range: Range<usize>,
-    ) -> WindowHandle<TestView> {
-        cx.add_window(|_window, cx| {
-            let input = cx.new(|cx| {
-                let mut input = InputState::new_singleline(cx);
-                input.content = content.to_string();
-                input.selected_range = selected_range;
-                input
+                input.backspace(&Backspace, window, cx);
+                assert_eq!(input.content(), "(555) ");
+                input.backspace(&Backspace, window, cx);
+                assert_eq!(input.content(), "(55");
+                assert_eq!(input.cursor_offset(), 3);
             });
-            TestView { input }
         })
+        .unwrap();
     }
 
     #[gpui::test]
-    fn test_single_line_enter_does_nothing(cx: &mut TestAppContext) {
-        let view = create_single_line_input(cx, "hello", 5..5);
+    fn test_mask_edits_in_the_middle(cx: &mut TestAppContext) {
+        let view = create_masked_input(cx, PatternMask::phone());
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.enter(&Enter, window, cx);
-                assert_eq!(input.content(), "hello");
-                assert_eq!(input.selected_range, 5..5);
+                input.set_content("5551234567", cx);
+                assert_eq!(input.content(), "(555) 123-4567");
+
+                // Delete forward from before the `)`: takes the `1`.
+                input.selected_range = 4..4;
+                input.delete(&Delete, window, cx);
+                assert_eq!(input.raw_value(), "555234567");
+                assert_eq!(input.content(), "(555) 234-567");
+
+                input.selected_range = 4..4;
+                type_text(input, "9", window, cx);
+                assert_eq!(input.content(), "(555) 923-4567");
+                assert_eq!(input.cursor_offset(), 7, "after the typed 9");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_single_line_set_content_strips_newlines(cx: &mut TestAppContext) {
-        let view = create_single_line_input(cx, "", 0..0);
-        view.update(cx, |view, _window, cx| {
+    fn test_mask_paste_over_everything_reads_formatted_text(cx: &mut TestAppContext) {
+        let view = create_masked_input(cx, PatternMask::new("+1 (999) 999-9999"));
+        cx.write_to_clipboard(ClipboardItem::new_string("+1 (555) 123-4567".to_string()));
+        view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_content("hello\nworld\r\nfoo", cx);
-                assert_eq!(input.content(), "hello world foo");
+                input.paste(&Paste, window, cx);
+                assert_eq!(input.raw_value(), "5551234567");
+                assert_eq!(input.content(), "+1 (555) 123-4567");
+
+                input.undo_action(cx);
+                assert_eq!(input.content(), "");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_single_line_up_moves_to_start(cx: &mut TestAppContext) {
-        let view = create_single_line_input(cx, "hello world", 5..5);
+    fn test_mask_undo_restores_the_formatted_text(cx: &mut TestAppContext) {
+        let view = create_masked_input(cx, CreditCardMask);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.up(&Up, window, cx);
-                assert_eq!(input.selected_range, 0..0);
+                input.set_group_interval(Duration::ZERO);
+                type_text(input, "42424", window, cx);
+                assert_eq!(input.content(), "4242 4");
+
+                input.undo_action(cx);
+                assert_eq!(input.content(), "4242 ");
+                assert_eq!(input.raw_value(), "4242");
             });
         })
         .unwrap();
     }
 
+    // ============================================================
+    // SECURE ENTRY
+    // ============================================================
+
     #[gpui::test]
-    fn test_single_line_down_moves_to_end(cx: &mut TestAppContext) {
-        let view = create_single_line_input(cx, "hello world", 5..5);
+    fn test_secure_input_refuses_copy_and_cut(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hunter2", 0..7);
+        cx.write_to_clipboard(ClipboardItem::new_string("before".to_string()));
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.down(&Down, window, cx);
-                assert_eq!(input.selected_range, 11..11); // "hello world".len() == 11
+                input.set_secure(true, cx);
+                input.copy(&Copy, window, cx);
+                input.cut(&Cut, window, cx);
+                assert_eq!(input.content(), "hunter2", "cut removes nothing either");
             });
         })
         .unwrap();
+
+        let clipboard = cx.read_from_clipboard();
+        assert_eq!(clipboard.unwrap().text().as_deref(), Some("before"));
     }
 
+    /// Every entry on an undo stack holds a piece of what was typed, so a
+    /// secure input keeps none — and turning secure on drops what was kept.
     #[gpui::test]
-    fn test_single_line_select_up_selects_to_start(cx: &mut TestAppContext) {
-        let view = create_single_line_input(cx, "hello world", 5..5);
+    fn test_secure_input_keeps_no_history(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.select_up(&SelectUp, window, cx);
-                assert_eq!(input.selected_range, 0..5);
-                assert!(input.selection_reversed);
+                input.set_group_interval(Duration::ZERO);
+                input.replace_text_in_range(None, "plain", window, cx);
+                assert!(input.can_undo());
+
+                input.set_secure(true, cx);
+                assert!(!input.can_undo());
+
+                input.replace_text_in_range(None, "secret", window, cx);
+                input.backspace(&Backspace, window, cx);
+                assert!(!input.can_undo());
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "plainsecre");
             });
         })
         .unwrap();
     }
 
+    /// Word movement would show where the spaces in a passphrase are.
     #[gpui::test]
-    fn test_single_line_select_down_selects_to_end(cx: &mut TestAppContext) {
-        let view = create_single_line_input(cx, "hello world", 5..5);
+    fn test_secure_input_moves_by_word_over_the_whole_content(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "correct horse battery", 10..10);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.select_down(&SelectDown, window, cx);
-                assert_eq!(input.selected_range, 5..11); // "hello world".len() == 11
-                assert!(!input.selection_reversed);
+                input.set_secure(true, cx);
+                input.word_right(&WordRight, window, cx);
+                assert_eq!(input.cursor_offset(), 21);
+                input.word_left(&WordLeft, window, cx);
+                assert_eq!(input.cursor_offset(), 0);
             });
         })
         .unwrap();
     }
 
+    /// The shaped text is bullets, and offsets still come back as offsets into
+    /// the content — including for a character wider than one byte.
     #[gpui::test]
-    fn test_single_line_multiline_getter(cx: &mut TestAppContext) {
-        let view = create_single_line_input(cx, "hello", 0..0);
-        view.update(cx, |view, _window, cx| {
-            view.input.update(cx, |input, _cx| {
-                assert!(!input.is_multiline());
+    fn test_secure_input_maps_positions_through_its_bullets(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "pässwörd", 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.set_secure(true, cx);
+                input.update_line_layouts(px(500.), px(20.), &TextStyle::default(), window);
+                assert_eq!(input.display_text(), "••••••••");
+
+                let line = &input.line_layouts[0];
+                let shaped = line.wrapped_line.as_ref().unwrap();
+                assert_eq!(shaped.text.as_ref(), "••••••••");
+
+                // After "pä": two bullets in, three bytes into the content.
+                let x = line.position_for_index(3, px(20.)).unwrap().x;
+                let two_bullets = shaped.position_for_index(6, px(20.)).unwrap().x;
+                assert_eq!(x, two_bullets);
+                assert_eq!(input.index_for_position(point(x, px(5.))), 3);
+
+                input.set_revealed(true, cx);
+                input.update_line_layouts(px(500.), px(20.), &TextStyle::default(), window);
+                assert_eq!(
+                    input.line_layouts[0]
+                        .wrapped_line
+                        .as_ref()
+                        .unwrap()
+                        .text
+                        .as_ref(),
+                    "pässwörd"
+                );
             });
         })
         .unwrap();
+    }
 
-        let multiline_view = create_test_input(cx, "hello", 0..0);
-        multiline_view
-            .update(cx, |view, _window, cx| {
-                view.input.update(cx, |input, _cx| {
-                    assert!(input.is_multiline());
-                });
+    // ============================================================
+    // VALIDATION
+    // ============================================================
+
+    /// The input in `view`, and every `Rejected` it emits from now on.
+    fn rejections(
+        cx: &mut TestAppContext,
+        view: WindowHandle<TestView>,
+    ) -> Rc<std::cell::RefCell<Vec<Rejection>>> {
+        let input = view.update(cx, |view, _, _| view.input.clone()).unwrap();
+        let rejected = Rc::new(std::cell::RefCell::new(Vec::new()));
+        let sink = rejected.clone();
+        cx.update(|cx| {
+            cx.subscribe(&input, move |_, event, _| {
+                if let InputStateEvent::Rejected(rejection) = event {
+                    sink.borrow_mut().push(*rejection);
+                }
             })
-            .unwrap();
+            .detach()
+        });
+        rejected
     }
 
     #[gpui::test]
-    fn test_line_direction_ltr(cx: &mut TestAppContext) {
-        let view = create_test_input_with_layout(cx, "Hello world", 0..0);
-        view.update(cx, |view, _window, cx| {
-            view.input.update(cx, |input, _cx| {
-                assert!(input.line_direction(0).is_ltr());
+    fn test_max_len_cuts_a_paste_short(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "abc", 3..3);
+        let rejected = rejections(cx, view);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.set_max_len(Some(5));
+                input.replace_text_in_range(None, "défg", window, cx);
+                assert_eq!(input.content(), "abcdé");
+                assert_eq!(input.cursor_offset(), "abcdé".len());
+
+                input.replace_text_in_range(None, "x", window, cx);
+                assert_eq!(input.content(), "abcdé");
+
+                // Replacing a selection makes room for what replaces it.
+                input.selected_range = 0..3;
+                input.replace_text_in_range(None, "xyz", window, cx);
+                assert_eq!(input.content(), "xyzdé");
             });
         })
         .unwrap();
+        assert_eq!(*rejected.borrow(), [Rejection::TooLong, Rejection::TooLong]);
     }
 
     #[gpui::test]
-    fn test_line_direction_rtl_arabic(cx: &mut TestAppContext) {
-        let view = create_test_input_with_layout(cx, "مرحبا بالعالم", 0..0);
-        view.update(cx, |view, _window, cx| {
-            view.input.update(cx, |input, _cx| {
-                assert!(input.line_direction(0).is_rtl());
+    fn test_char_filter_drops_what_it_refuses(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "1", 1..1);
+        let rejected = rejections(cx, view);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.set_char_filter(Some(Rc::new(|c: char| c.is_ascii_digit())));
+                input.replace_text_in_range(None, "2a3b", window, cx);
+                assert_eq!(input.content(), "123");
+
+                // Nothing left of a refused edit: the selection is not
+                // replaced by nothing.
+                input.selected_range = 0..3;
+                input.replace_text_in_range(None, "x", window, cx);
+                assert_eq!(input.content(), "123");
             });
         })
         .unwrap();
+        assert_eq!(
+            *rejected.borrow(),
+            [Rejection::Filtered, Rejection::Filtered]
+        );
     }
 
     #[gpui::test]
-    fn test_line_direction_rtl_hebrew(cx: &mut TestAppContext) {
-        let view = create_test_input_with_layout(cx, "שלום עולם", 0..0);
-        view.update(cx, |view, _window, cx| {
-            view.input.update(cx, |input, _cx| {
-                assert!(input.line_direction(0).is_rtl());
+    fn test_validate_can_replace_or_reject_an_edit(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "", 0..0);
+        let rejected = rejections(cx, view);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.set_validate(Some(Rc::new(|edit: &Edit| {
+                    if edit.result().len() > 4 {
+                        Validation::Reject
+                    } else {
+                        Validation::Replace(edit.text.to_uppercase())
+                    }
+                })));
+                input.replace_text_in_range(None, "ab", window, cx);
+                assert_eq!(input.content(), "AB");
+                input.replace_text_in_range(None, "cde", window, cx);
+                assert_eq!(input.content(), "AB");
+
+                // Deletions go through it too.
+                input.backspace(&Backspace, window, cx);
+                assert_eq!(input.content(), "A");
             });
         })
         .unwrap();
+        assert_eq!(*rejected.borrow(), [Rejection::Invalid]);
     }
 
+    /// A composition is left alone while it is composed; what it commits is
+    /// checked, and a refused commit still ends it.
     #[gpui::test]
-    fn test_line_direction_mixed_lines(cx: &mut TestAppContext) {
-        let view = create_test_input_with_layout(cx, "Hello\nمرحبا\nWorld", 0..0);
-        view.update(cx, |view, _window, cx| {
-            view.input.update(cx, |input, _cx| {
-                assert!(input.line_direction(0).is_ltr());
-                assert!(input.line_direction(1).is_rtl());
-                assert!(input.line_direction(2).is_ltr());
+    fn test_an_ime_commit_is_checked(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "", 0..0);
+        let rejected = rejections(cx, view);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.set_char_filter(Some(Rc::new(|c: char| c.is_ascii_digit())));
+                input.replace_and_mark_text_in_range(None, "ichi", None, window, cx);
+                assert_eq!(input.content(), "ichi");
+
+                input.replace_text_in_range(None, "\u{4e00}", window, cx);
+                assert_eq!(input.content(), "");
+                assert!(input.marked_range().is_none());
             });
         })
         .unwrap();
+        assert_eq!(*rejected.borrow(), [Rejection::Filtered]);
     }
 
-    #[gpui::test]
-    fn test_direction_at_offset_ltr(cx: &mut TestAppContext) {
-        let view = create_test_input_with_layout(cx, "Hello world", 0..0);
-        view.update(cx, |view, _window, cx| {
-            view.input.update(cx, |input, _cx| {
-                assert!(input.direction_at_offset(0).is_ltr());
-                assert!(input.direction_at_offset(5).is_ltr());
-                assert!(input.direction_at_offset(11).is_ltr());
+    // ============================================================
+    // COMPLETION
+    // ============================================================
+
+    fn create_completing_input(cx: &mut TestAppContext) -> WindowHandle<TestView> {
+        cx.add_window(|_window, cx| {
+            let input = cx.new(|cx| {
+                InputState::new_singleline(cx).completions(PrefixCompletions::new([
+                    "git status",
+                    "git stash",
+                    "git switch",
+                ]))
             });
+            TestView { input }
         })
-        .unwrap();
     }
 
     #[gpui::test]
-    fn test_direction_at_offset_rtl(cx: &mut TestAppContext) {
-        let view = create_test_input_with_layout(cx, "مرحبا", 0..0);
-        view.update(cx, |view, _window, cx| {
-            view.input.update(cx, |input, _cx| {
-                assert!(input.direction_at_offset(0).is_rtl());
-                assert!(input.direction_at_offset(5).is_rtl());
+    fn test_typing_asks_for_a_completion_and_tab_takes_it(cx: &mut TestAppContext) {
+        let view = create_completing_input(cx);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.set_group_interval(Duration::ZERO);
+                assert_eq!(input.completion(), None);
+                input.replace_text_in_range(None, "git st", window, cx);
+                assert_eq!(input.completion(), Some("atus"));
+
+                input.tab(&Tab, window, cx);
+                assert_eq!(input.content(), "git status");
+                assert_eq!(input.cursor_offset(), "git status".len());
+                assert_eq!(input.completion(), None);
+
+                // Taken as one edit.
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "git st");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_direction_at_offset_multiline(cx: &mut TestAppContext) {
-        let content = "Hello\nמילים";
-        let view = create_test_input_with_layout(cx, content, 0..0);
-        view.update(cx, |view, _window, cx| {
-            view.input.update(cx, |input, _cx| {
-                assert!(input.direction_at_offset(0).is_ltr());
-                assert!(input.direction_at_offset(5).is_ltr());
-                assert!(input.direction_at_offset(6).is_rtl());
-                assert!(input.direction_at_offset(content.len()).is_rtl());
+    fn test_right_takes_a_completion_only_at_the_end(cx: &mut TestAppContext) {
+        let view = create_completing_input(cx);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.replace_text_in_range(None, "git sw", window, cx);
+                input.left(&Left, window, cx);
+                assert_eq!(input.completion(), None);
+
+                // Back at the end, the suggestion stays hidden until the
+                // text changes again.
+                input.right(&Right, window, cx);
+                assert_eq!(input.content(), "git sw");
+                assert_eq!(input.completion(), None);
+
+                input.replace_text_in_range(None, "i", window, cx);
+                input.right(&Right, window, cx);
+                assert_eq!(input.content(), "git switch");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_empty_line_inherits_direction(cx: &mut TestAppContext) {
-        let view = create_test_input_with_layout(cx, "مرحبا\n\nWorld", 0..0);
-        view.update(cx, |view, _window, cx| {
-            view.input.update(cx, |input, _cx| {
-                assert!(input.line_direction(0).is_rtl());
-                assert!(input.line_direction(1).is_rtl());
-                assert!(input.line_direction(2).is_ltr());
+    fn test_completions_cycle_both_ways(cx: &mut TestAppContext) {
+        let view = create_completing_input(cx);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.replace_text_in_range(None, "git s", window, cx);
+                assert_eq!(input.completion(), Some("tatus"));
+                input.next_completion(&NextCompletion, window, cx);
+                assert_eq!(input.completion(), Some("tash"));
+                input.previous_completion(&PreviousCompletion, window, cx);
+                input.previous_completion(&PreviousCompletion, window, cx);
+                assert_eq!(input.completion(), Some("witch"));
             });
         })
         .unwrap();
     }
 
+    /// With nothing to take, Tab is what it always was.
     #[gpui::test]
-    fn test_numbers_before_rtl(cx: &mut TestAppContext) {
-        let view = create_test_input_with_layout(cx, "123 مرحبا", 0..0);
-        view.update(cx, |view, _window, cx| {
-            view.input.update(cx, |input, _cx| {
-                assert!(input.line_direction(0).is_rtl());
+    fn test_tab_without_a_completion_inserts_a_tab(cx: &mut TestAppContext) {
+        let view = create_completing_input(cx);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.replace_text_in_range(None, "ls", window, cx);
+                assert_eq!(input.completion(), None);
+                input.tab(&Tab, window, cx);
+                assert_eq!(input.content(), "ls\t");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_line_direction_out_of_bounds(cx: &mut TestAppContext) {
-        let view = create_test_input_with_layout(cx, "Hello", 0..0);
-        view.update(cx, |view, _window, cx| {
-            view.input.update(cx, |input, _cx| {
-                assert!(input.line_direction(100).is_ltr());
+    fn test_secure_and_multiline_inputs_are_never_completed(cx: &mut TestAppContext) {
+        let view = create_completing_input(cx);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.replace_text_in_range(None, "git st", window, cx);
+                input.set_secure(true, cx);
+                assert_eq!(input.completion(), None);
+                input.replace_text_in_range(None, "a", window, cx);
+                assert_eq!(input.completion(), None);
+            });
+        })
+        .unwrap();
+
+        let view = create_test_input(cx, "", 0..0);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.set_completion_provider(
+                    Some(Rc::new(PrefixCompletions::new(["git status"]))),
+                    cx,
+                );
+                input.replace_text_in_range(None, "git", window, cx);
+                assert_eq!(input.completion(), None);
             });
         })
         .unwrap();
     }
 
     // ============================================================
-    // UNDO / REDO
+    // MULTIPLE SELECTIONS
     // ============================================================
 
+    fn ranges(input: &InputState) -> Vec<Range<usize>> {
+        input
+            .selections()
+            .into_iter()
+            .map(|selection| selection.range)
+            .collect()
+    }
+
     #[gpui::test]
-    fn test_undo_restores_content(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 5..5);
+    fn test_typing_lands_at_every_caret_as_one_undo_step(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "ab\ncd\nef", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                // Disable grouping for predictable test behavior
-                input.set_group_interval(Duration::from_secs(0));
-
-                // Make an edit
-                input.replace_text_in_range(None, " world", window, cx);
-                assert_eq!(input.content(), "hello world");
+                input.set_group_interval(Duration::ZERO);
+                input.add_selection(3..3, cx);
+                input.add_selection(6..6, cx);
+                input.replace_text_in_range(None, "x", window, cx);
+                assert_eq!(input.content(), "xab\nxcd\nxef");
+                assert_eq!(ranges(input), [1..1, 5..5, 9..9]);
+                assert_eq!(input.cursor_offset(), 9, "the newest is the primary");
 
-                // Undo should restore original content
                 input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "hello");
+                assert_eq!(input.content(), "ab\ncd\nef");
+                assert_eq!(ranges(input), [0..0, 3..3, 6..6]);
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_redo_restores_undone_content(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 5..5);
+    fn test_a_length_limit_goes_to_the_first_caret(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "ab\ncd", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
-
-                input.replace_text_in_range(None, " world", window, cx);
-                assert_eq!(input.content(), "hello world");
+                input.set_max_len(Some(6));
+                input.add_selection(3..3, cx);
+                input.replace_text_in_range(None, "x", window, cx);
+                assert_eq!(input.content(), "xab\ncd");
+                assert_eq!(ranges(input), [1..1, 4..4]);
+            });
+        })
+        .unwrap();
+    }
 
-                input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "hello");
+    #[gpui::test]
+    fn test_deletes_act_at_every_selection(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "one\ntwo", 3..3);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.add_selection(7..7, cx);
+                input.backspace(&Backspace, window, cx);
+                assert_eq!(input.content(), "on\ntw");
+                input.delete_word_left(&DeleteWordLeft, window, cx);
+                assert_eq!(input.content(), "\n");
+                assert_eq!(ranges(input), [0..0, 1..1]);
+            });
+        })
+        .unwrap();
+    }
 
-                input.redo(&Redo, window, cx);
-                assert_eq!(input.content(), "hello world");
+    #[gpui::test]
+    fn test_selections_that_meet_become_one(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "abc", 1..1);
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.add_selection(2..2, cx);
+                input.select_left(&SelectLeft, window, cx);
+                assert_eq!(ranges(input), [0..1, 1..2]);
+                input.left(&Left, window, cx);
+                input.left(&Left, window, cx);
+                assert!(!input.has_multiple_selections());
+                assert_eq!(input.selected_range(), &(0..0));
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_undo_with_no_history_does_nothing(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 0..0);
+    fn test_select_next_occurrence_then_copy_and_paste(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "foo bar foo", 1..1);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                assert!(!input.can_undo());
-                input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "hello");
+                input.select_next_occurrence(&SelectNextOccurrence, window, cx);
+                assert!(!input.has_multiple_selections());
+                assert_eq!(input.selected_range(), &(0..3));
+                input.select_next_occurrence(&SelectNextOccurrence, window, cx);
+                assert_eq!(ranges(input), [0..3, 8..11]);
+                assert_eq!(input.selected_range(), &(8..11));
+                // Nothing left to find: every occurrence is selected.
+                input.select_next_occurrence(&SelectNextOccurrence, window, cx);
+                assert_eq!(ranges(input), [0..3, 8..11]);
+
+                input.copy(&Copy, window, cx);
+            });
+        })
+        .unwrap();
+        let clipboard = cx.read_from_clipboard();
+        assert_eq!(clipboard.unwrap().text().as_deref(), Some("foo\nfoo"));
+
+        cx.write_to_clipboard(ClipboardItem::new_string("one\ntwo".to_string()));
+        view.update(cx, |view, window, cx| {
+            view.input.update(cx, |input, cx| {
+                input.paste(&Paste, window, cx);
+                assert_eq!(input.content(), "one bar two");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_redo_with_no_history_does_nothing(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 0..0);
+    fn test_a_single_line_input_keeps_one_selection(cx: &mut TestAppContext) {
+        let view = create_single_line_input(cx, "foo foo", 0..3);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                assert!(!input.can_redo());
-                input.redo(&Redo, window, cx);
-                assert_eq!(input.content(), "hello");
+                input.add_selection(4..7, cx);
+                assert!(!input.has_multiple_selections());
+                assert_eq!(input.selected_range(), &(4..7));
+                input.select_next_occurrence(&SelectNextOccurrence, window, cx);
+                assert!(!input.has_multiple_selections());
+                assert_eq!(input.selected_range(), &(4..7));
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_undo_restores_selection(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 0..5);
+    fn test_composition_and_select_all_go_back_to_one_selection(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "ab\ncd", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
-
-                // Delete selection
-                input.replace_text_in_range(None, "", window, cx);
-                assert_eq!(input.content(), " world");
-                assert_eq!(input.selected_range, 0..0);
+                input.add_selection(3..3, cx);
+                input.replace_and_mark_text_in_range(None, "k", None, window, cx);
+                assert_eq!(input.content(), "ab\nkcd");
+                assert!(!input.has_multiple_selections());
 
-                // Undo should restore content and selection
-                input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "hello world");
-                assert_eq!(input.selected_range, 0..5);
+                input.unmark_text(window, cx);
+                input.add_selection(0..0, cx);
+                input.select_all(&SelectAll, window, cx);
+                assert!(!input.has_multiple_selections());
+                assert_eq!(input.selected_range(), &(0..6));
             });
         })
         .unwrap();
     }
 
+    // UNDO TREE / PERSISTENCE
+
     #[gpui::test]
-    fn test_multiple_undo_redo(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "", 0..0);
+    fn test_saved_history_round_trips_through_json(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
-
-                input.replace_text_in_range(None, "a", window, cx);
-                input.replace_text_in_range(None, "b", window, cx);
-                input.replace_text_in_range(None, "c", window, cx);
-                assert_eq!(input.content(), "abc");
+                input.set_group_interval(Duration::ZERO);
+                input.replace_text_in_range(None, " world", window, cx);
+                input.replace_text_in_range(None, "!", window, cx);
 
-                input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "ab");
+                let saved = input.save_history().unwrap();
+                let json = serde_json::to_string(&saved).unwrap();
+                input.set_content("something else", cx);
+                assert!(!input.can_undo());
 
+                let saved: SavedHistory = serde_json::from_str(&json).unwrap();
+                input.restore_history(saved, cx);
+                assert_eq!(input.content(), "hello world!");
+                assert_eq!(input.selected_range(), &(12..12));
                 input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "a");
-
+                assert_eq!(input.content(), "hello world");
                 input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "");
-
-                input.redo(&Redo, window, cx);
-                assert_eq!(input.content(), "a");
-
-                input.redo(&Redo, window, cx);
-                assert_eq!(input.content(), "ab");
-
-                input.redo(&Redo, window, cx);
-                assert_eq!(input.content(), "abc");
+                assert_eq!(input.content(), "hello");
+                assert!(!input.can_undo());
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_new_edit_clears_redo_stack(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 5..5);
-        view.update(cx, |view, window, cx| {
+    fn test_secure_input_saves_no_history(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hunter2", 7..7);
+        view.update(cx, |view, _, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
-
-                input.replace_text_in_range(None, " world", window, cx);
-                assert_eq!(input.content(), "hello world");
-
-                input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "hello");
-                assert!(input.can_redo());
-
-                // New edit should clear redo stack
-                input.replace_text_in_range(None, "!", window, cx);
-                assert_eq!(input.content(), "hello!");
-                assert!(!input.can_redo());
+                input.set_secure(true, cx);
+                assert!(input.save_history().is_none());
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_set_content_clears_history(cx: &mut TestAppContext) {
+    fn test_restored_history_that_does_not_fit_is_dropped(cx: &mut TestAppContext) {
         let view = create_test_input(cx, "hello", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
+                input.set_group_interval(Duration::ZERO);
+                input.replace_text_in_range(None, " wörld", window, cx);
 
-                input.replace_text_in_range(None, " world", window, cx);
+                let mut saved = input.save_history().unwrap();
+                saved.content = "hi".to_string();
+                input.restore_history(saved, cx);
+                assert_eq!(input.selected_range(), &(2..2));
                 assert!(input.can_undo());
 
-                input.set_content("new content", cx);
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hi");
                 assert!(!input.can_undo());
-                assert!(!input.can_redo());
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_can_undo_can_redo(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 5..5);
+    fn test_restored_selections_that_do_not_fit_are_clamped(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hé", 3..3);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
+                input.replace_text_in_range(None, "llo", window, cx);
 
-                assert!(!input.can_undo());
-                assert!(!input.can_redo());
-
-                input.replace_text_in_range(None, "!", window, cx);
-                assert!(input.can_undo());
-                assert!(!input.can_redo());
+                // Edited by hand: past the content, and inside the `é`.
+                let mut json = serde_json::to_value(input.save_history().unwrap()).unwrap();
+                let patch = &mut json["history"]["nodes"][1]["patch"];
+                patch["selected_range"] = serde_json::json!({ "start": 2, "end": 40 });
+                patch["extra_selections"] =
+                    serde_json::json!([{ "range": { "start": 50, "end": 60 }, "reversed": false }]);
+                input.restore_history(serde_json::from_value(json).unwrap(), cx);
 
                 input.undo(&Undo, window, cx);
-                assert!(!input.can_undo());
-                assert!(input.can_redo());
-
-                input.redo(&Redo, window, cx);
-                assert!(input.can_undo());
-                assert!(!input.can_redo());
+                assert_eq!(input.content(), "hé");
+                assert_eq!(input.selected_range(), &(1..3));
+                assert!(!input.has_multiple_selections());
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_backspace_is_undoable(cx: &mut TestAppContext) {
+    fn test_undo_tree_keeps_the_undone_branch(cx: &mut TestAppContext) {
         let view = create_test_input(cx, "hello", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
+                input.set_group_interval(Duration::ZERO);
+                input.set_undo_tree(true);
 
-                input.backspace(&Backspace, window, cx);
-                assert_eq!(input.content(), "hell");
+                input.replace_text_in_range(None, " world", window, cx);
+                let world = input.current_history_state();
+                input.undo(&Undo, window, cx);
+                input.replace_text_in_range(None, "!", window, cx);
+                assert_eq!(input.content(), "hello!");
+                assert!(!input.can_redo());
+
+                let states = input.history_states();
+                assert_eq!(states.len(), 3);
+                assert_eq!(states[1].parent, states[2].parent);
+                assert!(states[2].is_current);
 
+                assert!(input.jump_to_history_state(world, cx));
+                assert_eq!(input.content(), "hello world");
+                assert_eq!(input.current_history_state(), world);
                 input.undo(&Undo, window, cx);
                 assert_eq!(input.content(), "hello");
+                input.redo(&Redo, window, cx);
+                assert_eq!(input.content(), "hello world");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_delete_is_undoable(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 0..0);
+    fn test_turning_the_undo_tree_off_keeps_the_current_line(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hello", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
+                input.set_group_interval(Duration::ZERO);
+                input.set_undo_tree(true);
 
-                input.delete(&Delete, window, cx);
-                assert_eq!(input.content(), "ello");
+                input.replace_text_in_range(None, " world", window, cx);
+                input.undo(&Undo, window, cx);
+                input.replace_text_in_range(None, "!", window, cx);
+                input.set_undo_tree(false);
 
+                assert_eq!(input.history_states().len(), 2);
                 input.undo(&Undo, window, cx);
                 assert_eq!(input.content(), "hello");
+                input.redo(&Redo, window, cx);
+                assert_eq!(input.content(), "hello!");
             });
         })
         .unwrap();
     }
 
+    // ============================================================
+    // FIND AND REPLACE
+    // ============================================================
+
     #[gpui::test]
-    fn test_cut_is_undoable(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 0..5);
-        view.update(cx, |view, window, cx| {
+    fn test_find_next_and_previous_select_matches_and_wrap(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "one two one\nthree one", 0..0);
+        view.update(cx, |view, _window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
+                input.set_search(Some(SearchQuery::literal("one")), cx);
+                assert_eq!(input.search_matches(), [0..3, 8..11, 18..21]);
+                assert_eq!(input.selected_range, 0..0);
+                assert_eq!(input.active_search_match(), None);
 
-                input.cut(&Cut, window, cx);
-                assert_eq!(input.content(), " world");
+                assert!(input.find_next(cx));
+                assert_eq!(input.selected_range, 0..3);
+                assert_eq!(input.active_search_match(), Some(0));
+                assert!(input.find_next(cx));
+                assert!(input.find_next(cx));
+                assert_eq!(input.selected_range, 18..21);
+                assert!(input.find_next(cx));
+                assert_eq!(input.selected_range, 0..3);
 
-                input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "hello world");
+                assert!(input.find_previous(cx));
+                assert_eq!(input.selected_range, 18..21);
+                assert!(input.find_previous(cx));
+                assert_eq!(input.selected_range, 8..11);
+
+                input.set_search(None, cx);
+                assert!(input.search_matches().is_empty());
+                assert!(!input.find_next(cx));
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_cut_line_with_no_selection(cx: &mut TestAppContext) {
-        // Cursor in middle line, no selection - should cut entire line including newline
-        let view = create_test_input(cx, "line1\nline2\nline3", 8..8); // cursor in "line2"
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_search_matches_follow_edits_and_undo(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "cat", 3..3);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.cut(&Cut, window, cx);
-                assert_eq!(input.content(), "line1\nline3");
+                input.set_group_interval(Duration::ZERO);
+                input.set_search(Some(SearchQuery::case_insensitive("CAT")), cx);
+                assert_eq!(input.search_matches(), [0..3]);
+
+                input.replace_text_in_range(None, " Cat", window, cx);
+                assert_eq!(input.search_matches(), [0..3, 4..7]);
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.search_matches(), [0..3]);
+                input.redo(&Redo, window, cx);
+                assert_eq!(input.search_matches(), [0..3, 4..7]);
             });
         })
         .unwrap();
-
-        let clipboard = cx.read_from_clipboard();
-        assert_eq!(clipboard.unwrap().text().as_deref(), Some("line2\n"));
     }
 
     #[gpui::test]
-    fn test_cut_first_line_with_no_selection(cx: &mut TestAppContext) {
-        // Cursor on first line, no selection
-        let view = create_test_input(cx, "line1\nline2\nline3", 2..2); // cursor in "line1"
+    fn test_replace_next_is_one_undo_step_per_match(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "a-a-a", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.cut(&Cut, window, cx);
-                assert_eq!(input.content(), "line2\nline3");
-            });
-        })
-        .unwrap();
+                input.set_search(Some(SearchQuery::literal("a")), cx);
 
-        let clipboard = cx.read_from_clipboard();
-        assert_eq!(clipboard.unwrap().text().as_deref(), Some("line1\n"));
-    }
+                // Nothing selected yet: the first call only finds.
+                assert!(!input.replace_next("bb", cx));
+                assert_eq!(input.selected_range, 0..1);
 
-    #[gpui::test]
-    fn test_cut_last_line_with_no_selection(cx: &mut TestAppContext) {
-        // Cursor on last line, no selection - should include preceding newline
-        let view = create_test_input(cx, "line1\nline2\nline3", 14..14); // cursor in "line3"
-        view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.cut(&Cut, window, cx);
-                assert_eq!(input.content(), "line1\nline2");
+                assert!(input.replace_next("bb", cx));
+                assert!(input.replace_next("bb", cx));
+                assert_eq!(input.content(), "bb-bb-a");
+                assert_eq!(input.selected_range, 6..7);
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "bb-a-a");
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "a-a-a");
             });
         })
         .unwrap();
-
-        let clipboard = cx.read_from_clipboard();
-        assert_eq!(clipboard.unwrap().text().as_deref(), Some("\nline3"));
     }
 
     #[gpui::test]
-    fn test_cut_empty_line(cx: &mut TestAppContext) {
-        // Cursor on empty line - should remove that line
-        let view = create_test_input(cx, "line1\n\nline3", 6..6); // cursor on empty line
+    fn test_replace_all_is_one_undo_step_and_expands_groups(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "x = 1\ny = 22\nz", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.cut(&Cut, window, cx);
-                assert_eq!(input.content(), "line1\nline3");
+                input.set_search(Some(SearchQuery::regex(r"^(\w) = (\d+)$").unwrap()), cx);
+                assert_eq!(input.replace_all("$2 => $1", cx), 2);
+                assert_eq!(input.content(), "1 => x\n22 => y\nz");
+                assert_eq!(input.cursor_offset(), 14);
+                assert!(input.search_matches().is_empty());
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "x = 1\ny = 22\nz");
+                input.redo(&Redo, window, cx);
+                assert_eq!(input.content(), "1 => x\n22 => y\nz");
             });
         })
         .unwrap();
-
-        let clipboard = cx.read_from_clipboard();
-        assert_eq!(clipboard.unwrap().text().as_deref(), Some("\n"));
     }
 
     #[gpui::test]
-    fn test_cut_only_line_with_no_selection(cx: &mut TestAppContext) {
-        // Single line content, no selection - should cut entire content
-        let view = create_test_input(cx, "hello", 2..2);
-        view.update(cx, |view, window, cx| {
+    fn test_replace_all_gives_a_length_limit_to_the_first_matches(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "a-a-a", 0..0);
+        view.update(cx, |view, _window, cx| {
             view.input.update(cx, |input, cx| {
-                input.cut(&Cut, window, cx);
-                assert_eq!(input.content(), "");
+                input.set_max_len(Some(7));
+                input.set_search(Some(SearchQuery::literal("a")), cx);
+                assert_eq!(input.replace_all("bb", cx), 3);
+                assert_eq!(input.content(), "bb-bb-b");
+                assert_eq!(input.cursor_offset(), 7);
             });
         })
         .unwrap();
-
-        let clipboard = cx.read_from_clipboard();
-        assert_eq!(clipboard.unwrap().text().as_deref(), Some("hello"));
     }
 
     #[gpui::test]
-    fn test_cut_line_is_undoable(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "line1\nline2\nline3", 8..8);
+    fn test_replace_all_keeps_typing_in_its_own_undo_step(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "ab", 2..2);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
-
-                input.cut(&Cut, window, cx);
-                assert_eq!(input.content(), "line1\nline3");
+                // Inside the default group interval, so typing alone would
+                // merge.
+                input.replace_text_in_range(None, "a", window, cx);
+                input.set_search(Some(SearchQuery::literal("a")), cx);
+                assert_eq!(input.replace_all("A", cx), 2);
+                input.replace_text_in_range(None, "!", window, cx);
+                assert_eq!(input.content(), "AbA!");
 
                 input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "line1\nline2\nline3");
+                assert_eq!(input.content(), "AbA");
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "aba");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_paste_is_undoable(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello", 5..5);
-        cx.write_to_clipboard(ClipboardItem::new_string(" world".to_string()));
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_secure_and_masked_inputs_limit_search(cx: &mut TestAppContext) {
+        let view = create_masked_input(cx, PatternMask::phone());
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
-
-                input.paste(&Paste, window, cx);
-                assert_eq!(input.content(), "hello world");
-
-                input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "hello");
+                type_text(input, "5551234567", window, cx);
+                input.set_search(Some(SearchQuery::literal("555")), cx);
+                assert_eq!(input.search_matches(), [1..4]);
+                assert!(input.find_next(cx));
+                assert!(!input.replace_next("999", cx));
+                assert_eq!(input.replace_all("999", cx), 0);
+                assert_eq!(input.content(), "(555) 123-4567");
+
+                input.set_mask(None, cx);
+                input.set_secure(true, cx);
+                assert!(input.search_matches().is_empty());
+                input.set_secure(false, cx);
+                assert_eq!(input.search_matches(), [1..4]);
             });
         })
         .unwrap();
     }
 
+    // ============================================================
+    // DECORATIONS
+    // ============================================================
+
+    fn decorated_ranges(input: &InputState) -> Vec<Range<usize>> {
+        input
+            .decorations()
+            .map(|decoration| decoration.range.clone())
+            .collect()
+    }
+
     #[gpui::test]
-    fn test_enter_is_undoable(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 5..5);
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_decorations_follow_edits(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "one two three", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
-
-                input.enter(&Enter, window, cx);
-                assert_eq!(input.content(), "hello\n world");
+                input.set_group_interval(Duration::ZERO);
+                input.set_decorations(
+                    vec![
+                        Decoration::new(4..7).style(DecorationStyle::Dotted),
+                        Decoration::new(8..13),
+                        Decoration::new(20..30),
+                    ],
+                    cx,
+                );
+                assert_eq!(decorated_ranges(input), [4..7, 8..13]);
 
+                // Before them: both move along.
+                input.replace_text_in_range(None, ">> ", window, cx);
+                assert_eq!(decorated_ranges(input), [7..10, 11..16]);
                 input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "hello world");
+                assert_eq!(decorated_ranges(input), [4..7, 8..13]);
+                input.redo(&Redo, window, cx);
+                assert_eq!(decorated_ranges(input), [7..10, 11..16]);
+
+                // Inside one: that one goes.
+                input.set_selected_range(8..8);
+                input.replace_text_in_range(None, "x", window, cx);
+                assert_eq!(input.content(), ">> one txwo three");
+                assert_eq!(decorated_ranges(input), [12..17]);
+                assert_eq!(
+                    input.decoration_at(14).map(|decoration| decoration.style),
+                    Some(DecorationStyle::Squiggly)
+                );
+                assert!(input.decoration_at(5).is_none());
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_delete_word_left(cx: &mut TestAppContext) {
-        // Cursor at end of "hello" in "hello world"
-        let view = create_test_input(cx, "hello world", 5..5);
-        view.update(cx, |view, window, cx| {
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_set_content_keeps_decorations_outside_the_change(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "alpha beta gamma", 0..0);
+        view.update(cx, |view, _window, cx| {
             view.input.update(cx, |input, cx| {
-                input.delete_word_left(&DeleteWordLeft, window, cx);
-                assert_eq!(input.content(), " world");
-                assert_eq!(input.selected_range, 0..0);
+                input.set_decorations(vec![Decoration::new(0..5), Decoration::new(11..16)], cx);
+                input.set_content("alpha BETA! gamma", cx);
+                assert_eq!(decorated_ranges(input), [0..5, 12..17]);
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_delete_word_left_with_selection(cx: &mut TestAppContext) {
-        // Selection from 0 to 5 ("hello")
-        let view = create_test_input(cx, "hello world", 0..5);
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_checker_is_asked_on_every_change(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.delete_word_left(&DeleteWordLeft, window, cx);
-                assert_eq!(input.content(), " world");
+                input.set_group_interval(Duration::ZERO);
+                let checker = WordListChecker::new(["hello", "world"]);
+                input.set_checker(Some(Rc::new(checker)), cx);
+
+                input.replace_text_in_range(None, "hello wrold", window, cx);
+                assert_eq!(decorated_ranges(input), [6..11]);
+                let message = input.decoration_at(8).and_then(|d| d.message.clone());
+                assert_eq!(message.as_deref(), Some("Unknown word “wrold”"));
+
+                input.undo(&Undo, window, cx);
+                assert!(decorated_ranges(input).is_empty());
+
+                // The owner's come first, and are kept alongside.
+                input.set_content("wrold", cx);
+                input.set_decorations(vec![Decoration::new(0..2)], cx);
+                assert_eq!(decorated_ranges(input), [0..2, 0..5]);
+
+                input.set_secure(true, cx);
+                assert!(decorated_ranges(input).is_empty());
+                input.set_secure(false, cx);
+                assert_eq!(decorated_ranges(input), [0..2, 0..5]);
+
+                input.set_checker(None, cx);
+                assert_eq!(decorated_ranges(input), [0..2]);
             });
         })
         .unwrap();
     }
 
-    #[gpui::test]
-    fn test_delete_word_left_at_start(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 0..0);
-        view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.delete_word_left(&DeleteWordLeft, window, cx);
-                assert_eq!(input.content(), "hello world");
-            });
-        })
-        .unwrap();
+    // ============================================================
+    // TOKENS
+    // ============================================================
+
+    fn token_ranges(input: &InputState) -> Vec<Range<usize>> {
+        input
+            .tokens()
+            .iter()
+            .map(|token| token.range.clone())
+            .collect()
     }
 
     #[gpui::test]
-    fn test_delete_word_right(cx: &mut TestAppContext) {
-        // Cursor at start
-        let view = create_test_input(cx, "hello world", 0..0);
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_tokens_are_stepped_over_and_deleted_whole(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hi @al", 6..6);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.delete_word_right(&DeleteWordRight, window, cx);
-                assert_eq!(input.content(), " world");
-                assert_eq!(input.selected_range, 0..0);
+                input.set_token_triggers(['@']);
+                let query = input.trigger_query().expect("the caret ends `@al`");
+                assert_eq!(query.range, 3..6);
+                assert!(input.insert_token(query.range, "@alice", "u1", cx));
+                assert_eq!(input.content(), "hi @alice ");
+                assert_eq!(token_ranges(input), [3..9]);
+                assert_eq!(input.trigger_query(), None);
+
+                // Left from after the token lands before it, and Right back.
+                input.set_selected_range(9..9);
+                input.left(&Left, window, cx);
+                assert_eq!(input.cursor_offset(), 3);
+                input.right(&Right, window, cx);
+                assert_eq!(input.cursor_offset(), 9);
+                input.select_left(&SelectLeft, window, cx);
+                assert_eq!(input.selected_range(), &(3..9));
+
+                // Backspace takes all of it.
+                input.set_selected_range(9..9);
+                input.backspace(&Backspace, window, cx);
+                assert_eq!(input.content(), "hi  ");
+                assert!(input.tokens().is_empty());
+
+                // And undo brings it back as a token.
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hi @alice ");
+                assert_eq!(token_ranges(input), [3..9]);
+                input.redo(&Redo, window, cx);
+                assert!(input.tokens().is_empty());
+                input.undo(&Undo, window, cx);
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "hi @al");
+                assert!(input.tokens().is_empty());
+                input.redo(&Redo, window, cx);
+                assert_eq!(token_ranges(input), [3..9]);
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_delete_word_right_with_selection(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 0..5);
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_tokens_follow_edits_and_dissolve_when_cut_into(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.delete_word_right(&DeleteWordRight, window, cx);
-                assert_eq!(input.content(), " world");
+                input.set_tokenized_text(
+                    TokenizedText {
+                        text: "ask #ops now".into(),
+                        tokens: vec![Token::new(4..8, "c1"), Token::new(6..10, "overlaps")],
+                    },
+                    cx,
+                );
+                assert_eq!(token_ranges(input), [4..8]);
+
+                input.set_selected_range(0..0);
+                input.replace_text_in_range(None, "do ", window, cx);
+                assert_eq!(token_ranges(input), [7..11]);
+                assert_eq!(input.token_at(11).map(|t| t.payload.as_str()), Some("c1"));
+
+                input.set_selected_range(9..9);
+                input.replace_text_in_range(None, "x", window, cx);
+                assert!(input.tokens().is_empty());
+
+                // Saved with the content, and put back.
+                input.set_tokenized_text(
+                    TokenizedText {
+                        text: "@a b".into(),
+                        tokens: vec![Token::new(0..2, "u1")],
+                    },
+                    cx,
+                );
+                let saved = serde_json::to_string(&input.tokenized_text()).unwrap();
+                let restored: TokenizedText = serde_json::from_str(&saved).unwrap();
+                assert_eq!(restored, input.tokenized_text());
             });
         })
         .unwrap();
     }
 
+    fn attribute_spans(input: &InputState) -> Vec<(std::ops::Range<usize>, Attribute)> {
+        input
+            .attributes()
+            .iter()
+            .map(|span| (span.range.clone(), span.attribute.clone()))
+            .collect()
+    }
+
     #[gpui::test]
-    fn test_delete_word_right_at_end(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 11..11);
+    fn test_toggles_style_the_selection_one_undo_step_each(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "make it bold", 8..12);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.delete_word_right(&DeleteWordRight, window, cx);
-                assert_eq!(input.content(), "hello world");
+                input.set_attributed(true, cx);
+                input.toggle_bold(&ToggleBold, window, cx);
+                assert_eq!(attribute_spans(input), [(8..12, Attribute::Bold)]);
+
+                input.set_selected_range(5..12);
+                input.toggle_italic(&ToggleItalic, window, cx);
+                assert_eq!(
+                    attribute_spans(input),
+                    [(5..12, Attribute::Italic), (8..12, Attribute::Bold)]
+                );
+                // Italic everywhere already, so off it comes.
+                input.set_selected_range(8..12);
+                input.toggle_italic(&ToggleItalic, window, cx);
+                assert_eq!(
+                    attribute_spans(input),
+                    [(5..8, Attribute::Italic), (8..12, Attribute::Bold)]
+                );
+
+                input.undo(&Undo, window, cx);
+                input.undo(&Undo, window, cx);
+                assert_eq!(attribute_spans(input), [(8..12, Attribute::Bold)]);
+                input.undo(&Undo, window, cx);
+                assert!(input.attributes().is_empty());
+                assert_eq!(input.content(), "make it bold");
+                assert_eq!(input.selected_range(), &(8..12));
+
+                input.redo(&Redo, window, cx);
+                assert_eq!(attribute_spans(input), [(8..12, Attribute::Bold)]);
+                assert_eq!(input.selected_range(), &(8..12));
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_delete_to_beginning_of_line(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 5..5);
+    fn test_a_toggle_at_the_caret_styles_what_is_typed_next(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "ab", 2..2);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.delete_to_beginning_of_line(&DeleteToBeginningOfLine, window, cx);
-                assert_eq!(input.content(), " world");
-                assert_eq!(input.selected_range, 0..0);
+                input.set_attributed(true, cx);
+                input.toggle_bold(&ToggleBold, window, cx);
+                assert!(input.attributes().is_empty());
+                input.replace_text_in_range(None, "c", window, cx);
+                input.replace_text_in_range(None, "d", window, cx);
+                assert_eq!(attribute_spans(input), [(2..4, Attribute::Bold)]);
+
+                input.toggle_bold(&ToggleBold, window, cx);
+                input.replace_text_in_range(None, "e", window, cx);
+                assert_eq!(input.content(), "abcde");
+                assert_eq!(attribute_spans(input), [(2..4, Attribute::Bold)]);
+
+                // Without a toggle, typing at the end of bold text extends it.
+                input.set_selected_range(4..4);
+                input.replace_text_in_range(None, "x", window, cx);
+                assert_eq!(attribute_spans(input), [(2..5, Attribute::Bold)]);
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_delete_to_beginning_of_line_multiline(cx: &mut TestAppContext) {
-        // Cursor at position 8 (middle of "line2")
-        let view = create_test_input(cx, "line1\nline2\nline3", 8..8);
+    fn test_undo_puts_back_the_spans_of_deleted_text(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.delete_to_beginning_of_line(&DeleteToBeginningOfLine, window, cx);
-                assert_eq!(input.content(), "line1\nne2\nline3");
+                input.set_attributed(true, cx);
+                input.set_markdown("**bold** and `code`", cx);
+                input.set_selected_range(0..5);
+                input.backspace(&Backspace, window, cx);
+                assert_eq!(input.content(), "and code");
+                assert_eq!(attribute_spans(input), [(4..8, Attribute::Code)]);
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "bold and code");
+                assert_eq!(
+                    attribute_spans(input),
+                    [(0..4, Attribute::Bold), (9..13, Attribute::Code)]
+                );
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_delete_to_beginning_of_line_at_start(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 0..0);
+    fn test_markdown_round_trips_through_an_attributed_input(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.delete_to_beginning_of_line(&DeleteToBeginningOfLine, window, cx);
-                assert_eq!(input.content(), "hello world");
+                let source = "**a** *b* `c` [d](https://e.example)\\\nnext";
+                input.set_markdown(source, cx);
+                // Not attributed: the text only.
+                assert_eq!(input.content(), "a b c d\nnext");
+                assert!(input.attributes().is_empty());
+
+                input.set_attributed(true, cx);
+                input.set_markdown(source, cx);
+                assert_eq!(input.content(), "a b c d\nnext");
+                assert_eq!(
+                    attribute_spans(input),
+                    [
+                        (0..1, Attribute::Bold),
+                        (2..3, Attribute::Italic),
+                        (4..5, Attribute::Code),
+                        (6..7, Attribute::Link("https://e.example".into())),
+                    ]
+                );
+                assert_eq!(input.markdown(), source);
+                let rich = input.rich_text();
+                assert_eq!(rich.to_plain_text(), "a b c d\nnext");
+
+                // A secure input takes no attributes.
+                input.set_secure(true, cx);
+                input.set_selected_range(0..1);
+                assert!(!input.toggle_attribute(Attribute::Italic, cx));
+                input.toggle_bold(&ToggleBold, window, cx);
+                assert_eq!(attribute_spans(input)[0], (0..1, Attribute::Bold));
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_delete_to_end_of_line(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 5..5);
-        view.update(cx, |view, window, cx| {
+    fn test_tokens_are_refused_in_secure_inputs(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "@x", 2..2);
+        view.update(cx, |view, _window, cx| {
             view.input.update(cx, |input, cx| {
-                input.delete_to_end_of_line(&DeleteToEndOfLine, window, cx);
-                assert_eq!(input.content(), "hello");
-                assert_eq!(input.selected_range, 5..5);
+                input.set_token_triggers(['@']);
+                input.set_secure(true, cx);
+                assert_eq!(input.trigger_query(), None);
+                assert!(!input.insert_token(0..2, "@xavier", "u9", cx));
+                assert_eq!(input.content(), "@x");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_delete_to_end_of_line_multiline(cx: &mut TestAppContext) {
-        // Cursor at position 8 (middle of "line2")
-        let view = create_test_input(cx, "line1\nline2\nline3", 8..8);
+    fn test_kills_join_and_yank_pop_cycles_the_ring(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "one two\nthree", 4..4);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.delete_to_end_of_line(&DeleteToEndOfLine, window, cx);
-                assert_eq!(input.content(), "line1\nli\nline3");
+                // To the end of the line, then the newline: one entry.
+                input.kill_to_end_of_line(&KillToEndOfLine, window, cx);
+                input.kill_to_end_of_line(&KillToEndOfLine, window, cx);
+                assert_eq!(input.content(), "one three");
+                input.kill_word_left(&KillWordLeft, window, cx);
+                assert_eq!(input.content(), "three");
+
+                input.set_selected_range(5..5);
+                input.yank(&Yank, window, cx);
+                assert_eq!(input.content(), "threeone two\n");
+                assert_eq!(input.selected_range(), &(13..13));
+
+                // Moving away ends the chance to pop.
+                input.set_selected_range(0..0);
+                input.kill_word_right(&KillWordRight, window, cx);
+                input.yank(&Yank, window, cx);
+                assert_eq!(input.content(), "threeone two\n");
+                input.yank_pop(&YankPop, window, cx);
+                assert_eq!(input.content(), "one two\n two\n");
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "threeone two\n");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_delete_to_end_of_line_at_end(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 11..11);
+    fn test_secure_kills_stay_out_of_the_ring(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "hunter2", 7..7);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.delete_to_end_of_line(&DeleteToEndOfLine, window, cx);
-                assert_eq!(input.content(), "hello world");
+                input.set_secure(true, cx);
+                input.kill_to_beginning_of_line(&KillToBeginningOfLine, window, cx);
+                assert_eq!(input.content(), "");
+                input.yank(&Yank, window, cx);
+                assert_eq!(input.content(), "");
             });
+            assert!(cx.try_global::<KillRing>().is_none());
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_delete_word_left_is_undoable(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 5..5);
+    fn test_transpose_swaps_around_the_caret(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "abcd", 1..1);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
-
-                input.delete_word_left(&DeleteWordLeft, window, cx);
-                assert_eq!(input.content(), " world");
+                input.transpose(&Transpose, window, cx);
+                assert_eq!(input.content(), "bacd");
+                assert_eq!(input.selected_range(), &(2..2));
+                // At the end of the line, the last two swap.
+                input.set_selected_range(4..4);
+                input.transpose(&Transpose, window, cx);
+                assert_eq!(input.content(), "badc");
+                // Nothing before the caret to swap with.
+                input.set_selected_range(0..0);
+                input.transpose(&Transpose, window, cx);
+                assert_eq!(input.content(), "badc");
 
                 input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "hello world");
+                assert_eq!(input.content(), "bacd");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_delete_word_right_is_undoable(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 6..6);
+    fn test_vim_modes_move_the_caret_as_vim_does(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "abc", 3..3);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
-
-                input.delete_word_right(&DeleteWordRight, window, cx);
-                assert_eq!(input.content(), "hello ");
-
-                input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "hello world");
+                assert_eq!(input.vim_mode(), VimMode::Insert);
+                input.enter_normal_mode(&EnterNormalMode, window, cx);
+                assert_eq!(input.vim_mode(), VimMode::Normal);
+                assert_eq!(input.selected_range(), &(2..2));
+
+                input.enter_insert_mode_after(&EnterInsertModeAfter, window, cx);
+                assert_eq!(input.vim_mode(), VimMode::Insert);
+                assert_eq!(input.selected_range(), &(3..3));
+
+                input.set_selected_range(0..0);
+                input.enter_normal_mode(&EnterNormalMode, window, cx);
+                assert_eq!(input.selected_range(), &(0..0));
+                input.enter_insert_mode(&EnterInsertMode, window, cx);
+                assert_eq!(input.selected_range(), &(0..0));
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_delete_to_beginning_of_line_is_undoable(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 5..5);
+    fn test_submissions_are_recalled_with_up_and_down(cx: &mut TestAppContext) {
+        let view = create_single_line_input(cx, "", 0..0);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
+                input.submit_on = Some(SubmitOn::Enter);
+                input.set_recall(Some(RecallHistory::new()));
+                for command in ["git status", "ls", "git push"] {
+                    input.set_content(command, cx);
+                    input.enter(&Enter, window, cx);
+                }
+                assert_eq!(
+                    input.recall_history().unwrap().entries(),
+                    ["git status", "ls", "git push"]
+                );
 
-                input.delete_to_beginning_of_line(&DeleteToBeginningOfLine, window, cx);
-                assert_eq!(input.content(), " world");
+                input.set_content("git", cx);
+                input.up(&Up, window, cx);
+                assert_eq!(input.content(), "git push");
+                assert_eq!(input.selected_range(), &(8..8));
+                input.up(&Up, window, cx);
+                assert_eq!(input.content(), "git status");
+                // Nothing older starts with the draft: the caret moves instead.
+                input.up(&Up, window, cx);
+                assert_eq!(input.content(), "git status");
+                assert_eq!(input.selected_range(), &(0..0));
 
-                input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "hello world");
+                input.down(&Down, window, cx);
+                input.down(&Down, window, cx);
+                assert_eq!(input.content(), "git");
+                input.down(&Down, window, cx);
+                assert_eq!(input.selected_range(), &(3..3));
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_delete_to_end_of_line_is_undoable(cx: &mut TestAppContext) {
-        let view = create_test_input(cx, "hello world", 5..5);
+    fn test_recall_leaves_multiline_and_secure_inputs_alone(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "one\ntwo", 5..5);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.set_group_interval(Duration::from_secs(0));
-
-                input.delete_to_end_of_line(&DeleteToEndOfLine, window, cx);
-                assert_eq!(input.content(), "hello");
-
-                input.undo(&Undo, window, cx);
-                assert_eq!(input.content(), "hello world");
+                input.set_recall(Some(RecallHistory::new()));
+                input.recall.as_mut().unwrap().push("earlier");
+                input.up(&Up, window, cx);
+                assert_eq!(input.content(), "one\ntwo");
             });
         })
         .unwrap();
-    }
-
-    // ============================================================
-    // READ-ONLY
-    // ============================================================
-    //
-    // Read-only closes every *user* path into the content while leaving
-    // focus, movement, selection, copy, scrolling and the programmatic
-    // setters alone.
-
-    fn create_read_only_input(
-        cx: &mut TestAppContext,
-        content: &str,
-        range: std::ops::Range<usize>,
-    ) -> WindowHandle<TestView> {
-        cx.add_window(|_window, cx| {
-            let input = cx.new(|cx| {
-                let mut input = InputState::new_multiline(cx).read_only(true);
-                input.content = content.to_string();
-                input.selected_range = range;
-                input
-            });
-            TestView { input }
-        })
-    }
 
-    /// Where plain typing actually arrives: gpui hands a character to the
-    /// focused element's IME handler, not through an action.
-    #[gpui::test]
-    fn test_read_only_refuses_typed_text(cx: &mut TestAppContext) {
-        let view = create_read_only_input(cx, "hello", 5..5);
+        let view = create_single_line_input(cx, "hunter2", 7..7);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.replace_text_in_range(None, "x", window, cx);
-                assert_eq!(input.content(), "hello");
-                assert_eq!(input.selected_range, 5..5);
+                input.submit_on = Some(SubmitOn::Enter);
+                input.set_recall(Some(RecallHistory::new()));
+                input.set_secure(true, cx);
+                input.enter(&Enter, window, cx);
+                assert!(input.recall_history().unwrap().entries().is_empty());
             });
         })
         .unwrap();
     }
 
+    /// Presses inside the selection at `at` and drags it away, returning
+    /// what was picked up.
+    fn pick_up(
+        input: &mut InputState,
+        at: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<InputState>,
+    ) -> TextDrag {
+        input.on_mouse_down(at, 1, false, false, window, cx);
+        input.on_mouse_move(at + point(px(20.), px(0.)), cx);
+        cx.remove_global::<TextDrag>()
+    }
+
     #[gpui::test]
-    fn test_read_only_refuses_marked_text(cx: &mut TestAppContext) {
-        let view = create_read_only_input(cx, "hello", 5..5);
+    fn test_dragging_a_selection_moves_it_in_one_undo_step(cx: &mut TestAppContext) {
+        let view = create_test_input_with_layout(cx, "one two\nthree", 0..4);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.replace_and_mark_text_in_range(None, "\u{3053}", None, window, cx);
-                assert_eq!(input.content(), "hello");
-                assert!(input.marked_range().is_none());
+                let drag = pick_up(input, point(px(0.), px(5.)), window, cx);
+                assert_eq!(drag.text, "one ");
+                // Onto the start of the second line.
+                let remove = input.drop_text_drag(&drag, point(px(0.), px(25.)), false, window, cx);
+                assert!(!remove);
+                assert_eq!(input.content(), "two\none three");
+                assert_eq!(input.selected_range, 4..8);
+
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "one two\nthree");
             });
         })
         .unwrap();
     }
 
-    /// Each of these *moves the selection* before deleting through it, so
-    /// they need their own guard: leaning on the funnel would have a
-    /// read-only field answer backspace by silently eating a grapheme of the
-    /// user's selection.
     #[gpui::test]
-    fn test_read_only_refuses_the_delete_family_without_moving_the_selection(
-        cx: &mut TestAppContext,
-    ) {
-        let view = create_read_only_input(cx, "hello world", 5..5);
+    fn test_alt_drops_a_copy(cx: &mut TestAppContext) {
+        let view = create_test_input_with_layout(cx, "one two\nthree", 0..4);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                input.backspace(&Backspace, window, cx);
-                input.delete(&Delete, window, cx);
-                input.delete_word_left(&DeleteWordLeft, window, cx);
-                input.delete_word_right(&DeleteWordRight, window, cx);
-                input.delete_to_beginning_of_line(&DeleteToBeginningOfLine, window, cx);
-                input.delete_to_end_of_line(&DeleteToEndOfLine, window, cx);
-                input.tab(&Tab, window, cx);
-                input.enter(&Enter, window, cx);
-                input.insert_newline(&InsertNewline, window, cx);
+                let drag = pick_up(input, point(px(0.), px(5.)), window, cx);
+                input.drop_text_drag(&drag, point(px(0.), px(25.)), true, window, cx);
+                assert_eq!(input.content(), "one two\none three");
+                assert_eq!(input.selected_range, 8..12);
 
-                assert_eq!(input.content(), "hello world");
-                assert_eq!(
-                    input.selected_range,
-                    5..5,
-                    "a refused delete must not move the selection either"
-                );
+                input.undo(&Undo, window, cx);
+                assert_eq!(input.content(), "one two\nthree");
             });
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_read_only_refuses_paste_and_undo(cx: &mut TestAppContext) {
-        let view = create_read_only_input(cx, "hello", 5..5);
+    fn test_a_press_in_the_selection_without_a_drag_is_a_click(cx: &mut TestAppContext) {
+        let view = create_test_input_with_layout(cx, "one two\nthree", 0..4);
         view.update(cx, |view, window, cx| {
-            cx.write_to_clipboard(ClipboardItem::new_string(" there".to_string()));
             view.input.update(cx, |input, cx| {
-                input.paste(&Paste, window, cx);
-                assert_eq!(input.content(), "hello");
-
-                input.undo(&Undo, window, cx);
-                input.redo(&Redo, window, cx);
-                assert_eq!(input.content(), "hello");
+                input.on_mouse_down(point(px(0.), px(5.)), 1, false, false, window, cx);
+                assert_eq!(input.selected_range, 0..4);
+                input.on_mouse_move(point(px(1.), px(5.)), cx);
+                input.on_mouse_up(cx);
+                assert_eq!(input.selected_range, 0..0);
+                assert!(!cx.has_global::<TextDrag>());
             });
         })
         .unwrap();
     }
 
-    /// Taking text is not an edit, so cut keeps the copy and drops only the
-    /// removal.
     #[gpui::test]
-    fn test_read_only_cut_copies_without_removing(cx: &mut TestAppContext) {
-        let view = create_read_only_input(cx, "hello world", 0..5);
+    fn test_dragging_into_another_input_moves_it_across(cx: &mut TestAppContext) {
+        let view = create_test_input_with_layout(cx, "one two\nthree", 4..8);
         view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.cut(&Cut, window, cx);
-                assert_eq!(input.content(), "hello world");
+            let other = cx.new(InputState::new_singleline);
+            let drag = view.input.update(cx, |input, cx| {
+                pick_up(input, point(px(0.), px(25.)), window, cx)
             });
+            assert_eq!(drag.text, "two\n");
 
-            assert_eq!(
-                cx.read_from_clipboard().and_then(|item| item.text()),
-                Some("hello".to_string()),
-                "cut should still have copied"
-            );
+            let remove = other.update(cx, |other, cx| {
+                other.drop_text_drag(&drag, point(px(0.), px(0.)), false, window, cx)
+            });
+            assert!(remove);
+            view.input
+                .update(cx, |input, cx| input.remove_dragged(&drag, window, cx));
+            // A newline has no place in a single line.
+            assert_eq!(other.read(cx).content(), "two ");
+            assert_eq!(view.input.read(cx).content(), "one three");
+
+            // Each input undoes its own half.
+            view.input
+                .update(cx, |input, cx| input.undo(&Undo, window, cx));
+            assert_eq!(view.input.read(cx).content(), "one two\nthree");
+            assert_eq!(other.read(cx).content(), "two ");
+            other.update(cx, |other, cx| other.undo(&Undo, window, cx));
+            assert_eq!(other.read(cx).content(), "");
         })
         .unwrap();
     }
 
-    /// Everything read-only is *not* about: moving, selecting, and the
-    /// owner's programmatic API.
     #[gpui::test]
-    fn test_read_only_still_moves_selects_and_takes_set_content(cx: &mut TestAppContext) {
-        let view = create_read_only_input(cx, "hello world", 5..5);
+    fn test_text_a_restricted_input_keeps_out_stays_in_the_source(cx: &mut TestAppContext) {
+        let view = create_test_input(cx, "call 555 1234\n", 5..13);
         view.update(cx, |view, window, cx| {
-            view.input.update(cx, |input, cx| {
-                input.left(&Left, window, cx);
-                assert_eq!(input.selected_range, 4..4);
-
-                input.select_all(&SelectAll, window, cx);
-                assert_eq!(input.selected_range, 0..11);
-
-                input.set_content("replaced", cx);
-                assert_eq!(input.content(), "replaced");
+            let digits =
+                cx.new(|cx| InputState::new_singleline(cx).char_filter(|ch| ch.is_ascii_digit()));
+            let short = cx.new(|cx| InputState::new_singleline(cx).max_len(3));
+            let drag = TextDrag {
+                source: view.input.downgrade(),
+                range: 5..13,
+                text: "555 1234".into(),
+            };
 
-                input.insert_text("!", cx);
-                assert_eq!(input.content(), "!replaced");
-            });
+            for target in [&digits, &short] {
+                let remove = target.update(cx, |target, cx| {
+                    target.drop_text_drag(&drag, point(px(0.), px(0.)), false, window, cx)
+                });
+                // As the element does on release.
+                if remove {
+                    view.input
+                        .update(cx, |input, cx| input.remove_dragged(&drag, window, cx));
+                }
+            }
+            assert_eq!(digits.read(cx).content(), "5551234");
+            assert_eq!(short.read(cx).content(), "555");
+            assert_eq!(view.input.read(cx).content(), "call 555 1234\n");
         })
         .unwrap();
     }
 
     #[gpui::test]
-    fn test_read_only_can_be_lifted(cx: &mut TestAppContext) {
-        let view = create_read_only_input(cx, "hello", 5..5);
+    fn test_secure_and_read_only_inputs_hold_on_to_their_text(cx: &mut TestAppContext) {
+        let view = create_test_input_with_layout(cx, "hunter2", 0..7);
         view.update(cx, |view, window, cx| {
             view.input.update(cx, |input, cx| {
-                assert!(input.is_read_only());
-
-                input.set_read_only(false, cx);
-                assert!(!input.is_read_only());
+                input.set_secure(true, cx);
+                input.on_mouse_down(point(px(0.), px(5.)), 1, false, false, window, cx);
+                input.on_mouse_move(point(px(40.), px(5.)), cx);
+                assert!(!cx.has_global::<TextDrag>());
+            });
 
-                input.replace_text_in_range(None, "!", window, cx);
-                assert_eq!(input.content(), "hello!");
+            let drag = TextDrag {
+                source: view.input.downgrade(),
+                range: 0..0,
+                text: "dropped".into(),
+            };
+            view.input.update(cx, |input, cx| {
+                input.set_secure(false, cx);
+                input.set_read_only(true, cx);
+                input.drop_text_drag(&drag, point(px(0.), px(5.)), false, window, cx);
+                input.drop_text("dropped", point(px(0.), px(5.)), window, cx);
+                assert_eq!(input.content(), "hunter2");
             });
         })
         .unwrap();
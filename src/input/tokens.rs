@@ -0,0 +1,283 @@
+//! Tokens: ranges of an input's text that behave as one character — the
+//! `@alice` and `#general` of a chat composer.
+//!
+//! A [`Token`] is a range of the content with a payload of the owner's — a
+//! user id, a channel id, whatever the text stands for. While it is in an
+//! [`InputState`](super::InputState):
+//!
+//! - the caret never lands inside it. Left and Right step over it whole, a
+//!   click inside it goes to whichever end is nearer, and a selection grows
+//!   over all of it or none of it.
+//! - Backspace and Delete remove it whole, since what they would have taken
+//!   one character of is now selected entire.
+//! - an edit that does reach inside it — a paste over half of it, made with
+//!   [`set_selected_range`](super::InputState::set_selected_range) — turns it
+//!   back into plain text, since what the payload named is no longer there.
+//! - undo and redo bring it back as it was.
+//!
+//! `elements::input` paints each token as a pill behind its text.
+//!
+//! # Triggers
+//!
+//! With [`token_triggers`](super::InputState::token_triggers) set, say to
+//! `['@', '#']`, [`trigger_query`](super::InputState::trigger_query) is the
+//! word being typed after one of them: a trigger at the start of a line or
+//! after a space, with no space between it and the caret. That is the query a
+//! suggestion popup filters on, and its [`range`](TriggerQuery::range) is what
+//! [`insert_token`](super::InputState::insert_token) replaces with the chosen
+//! token. `elements::mentions` puts the two together.
+//!
+//! # Saving
+//!
+//! [`tokenized_text`](super::InputState::tokenized_text) is the content and
+//! its tokens as one serde value, and
+//! [`set_tokenized_text`](super::InputState::set_tokenized_text) puts it back.
+//! Tokens are saved with the undo history too.
+
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+/// A range of an input's text that is edited as a unit. See the
+/// [module docs](self).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Token {
+    /// The byte range of the content it covers. Never empty once given to an
+    /// input.
+    pub range: Range<usize>,
+    /// What the text stands for, as its owner spells it.
+    pub payload: String,
+}
+
+impl Token {
+    /// A token over `range`, standing for `payload`.
+    pub fn new(range: Range<usize>, payload: impl Into<String>) -> Self {
+        Self {
+            range,
+            payload: payload.into(),
+        }
+    }
+}
+
+/// An input's content with its tokens, as
+/// [`tokenized_text`](super::InputState::tokenized_text) returns it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenizedText {
+    /// The content, tokens' text included.
+    pub text: String,
+    /// The tokens, in order, with ranges into `text`.
+    #[serde(default)]
+    pub tokens: Vec<Token>,
+}
+
+/// The word being typed after a trigger character. See
+/// [`trigger_query`](super::InputState::trigger_query).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriggerQuery {
+    /// The trigger typed.
+    pub trigger: char,
+    /// From the trigger to the caret: what a chosen token replaces.
+    pub range: Range<usize>,
+    /// What has been typed since the trigger.
+    pub query: String,
+}
+
+/// Keeps the tokens that fit `content` — non-empty, inside it, on character
+/// boundaries, and clear of the ones before them — in order.
+pub(crate) fn sanitize(mut tokens: Vec<Token>, content: &str) -> Vec<Token> {
+    tokens.retain(|token| {
+        let range = &token.range;
+        range.start < range.end
+            && range.end <= content.len()
+            && content.is_char_boundary(range.start)
+            && content.is_char_boundary(range.end)
+    });
+    tokens.sort_by_key(|token| token.range.start);
+    let mut end = 0;
+    tokens.retain(|token| {
+        let clear = token.range.start >= end;
+        if clear {
+            end = token.range.end;
+        }
+        clear
+    });
+    tokens
+}
+
+/// Moves `tokens` for `range` of the text having been replaced by `new_len`
+/// bytes: those after it along, those inside or across it out.
+pub(crate) fn shift(tokens: &mut Vec<Token>, range: &Range<usize>, new_len: usize) {
+    tokens.retain_mut(|token| {
+        let own = &mut token.range;
+        if own.end <= range.start {
+            true
+        } else if own.start >= range.end {
+            own.start = own.start - range.len() + new_len;
+            own.end = own.end - range.len() + new_len;
+            true
+        } else {
+            false
+        }
+    });
+}
+
+/// The tokens wholly inside `range`, with ranges relative to its start — what
+/// an edit of `range` is about to remove.
+pub(crate) fn within(tokens: &[Token], range: &Range<usize>) -> Vec<Token> {
+    tokens
+        .iter()
+        .filter(|token| token.range.start >= range.start && token.range.end <= range.end)
+        .map(|token| {
+            Token::new(
+                token.range.start - range.start..token.range.end - range.start,
+                token.payload.clone(),
+            )
+        })
+        .collect()
+}
+
+/// Puts back tokens taken with [`within`], for text put back at `at`.
+pub(crate) fn restore(tokens: &mut Vec<Token>, taken: &[Token], at: usize) {
+    tokens.extend(taken.iter().map(|token| {
+        Token::new(
+            at + token.range.start..at + token.range.end,
+            token.payload.clone(),
+        )
+    }));
+    tokens.sort_by_key(|token| token.range.start);
+}
+
+/// The token `offset` is strictly inside, if any.
+pub(crate) fn containing(tokens: &[Token], offset: usize) -> Option<&Token> {
+    tokens
+        .iter()
+        .find(|token| token.range.start < offset && offset < token.range.end)
+}
+
+/// `offset`, moved out of any token it is inside: to the end `forward` says,
+/// or with `forward` unset to the nearer one.
+pub(crate) fn snap(tokens: &[Token], offset: usize, forward: Option<bool>) -> usize {
+    let Some(token) = containing(tokens, offset) else {
+        return offset;
+    };
+    let forward = forward.unwrap_or(token.range.end - offset < offset - token.range.start);
+    if forward {
+        token.range.end
+    } else {
+        token.range.start
+    }
+}
+
+/// The word typed after one of `triggers` up to `caret`, if the caret is in
+/// one: the trigger starts the line or follows whitespace, nothing between it
+/// and the caret is whitespace, and none of it is a token.
+pub(crate) fn trigger_query(
+    content: &str,
+    caret: usize,
+    triggers: &[char],
+    tokens: &[Token],
+) -> Option<TriggerQuery> {
+    if triggers.is_empty() || caret > content.len() || !content.is_char_boundary(caret) {
+        return None;
+    }
+    let before = &content[..caret];
+    let mut chars = before.char_indices().rev();
+    let (start, trigger) = loop {
+        let (at, ch) = chars.next()?;
+        if ch.is_whitespace() {
+            return None;
+        }
+        if triggers.contains(&ch) {
+            break (at, ch);
+        }
+    };
+    if before[..start]
+        .chars()
+        .next_back()
+        .is_some_and(|ch| !ch.is_whitespace())
+    {
+        return None;
+    }
+    if tokens
+        .iter()
+        .any(|token| token.range.start < caret && token.range.end > start)
+    {
+        return None;
+    }
+    Some(TriggerQuery {
+        trigger,
+        range: start..caret,
+        query: before[start + trigger.len_utf8()..].to_string(),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)] // a slice of one token
+mod tests {
+    use super::*;
+
+    fn ranges(tokens: &[Token]) -> Vec<Range<usize>> {
+        tokens.iter().map(|token| token.range.clone()).collect()
+    }
+
+    #[test]
+    fn sanitize_drops_what_does_not_fit_or_overlaps() {
+        let tokens = vec![
+            Token::new(6..9, "c"),
+            Token::new(0..4, "a"),
+            Token::new(2..5, "b"),
+            Token::new(4..4, "empty"),
+            Token::new(8..20, "long"),
+        ];
+        assert_eq!(ranges(&sanitize(tokens, "abcdefghij")), [0..4, 6..9]);
+    }
+
+    #[test]
+    fn edits_move_what_follows_and_dissolve_what_they_touch() {
+        let mut tokens = vec![Token::new(0..6, "alice"), Token::new(7..11, "bob")];
+        shift(&mut tokens, &(6..6), 3);
+        assert_eq!(ranges(&tokens), [0..6, 10..14]);
+        shift(&mut tokens, &(12..13), 0);
+        assert_eq!(ranges(&tokens), [0..6]);
+    }
+
+    #[test]
+    fn taken_tokens_come_back_where_their_text_does() {
+        let tokens = vec![Token::new(2..5, "a"), Token::new(6..8, "b")];
+        let taken = within(&tokens, &(1..6));
+        assert_eq!(ranges(&taken), [1..4]);
+
+        let mut after = Vec::new();
+        restore(&mut after, &taken, 10);
+        assert_eq!(after, [Token::new(11..14, "a")]);
+    }
+
+    #[test]
+    fn snapping_leaves_a_token_by_the_end_asked_for() {
+        let tokens = vec![Token::new(2..8, "alice")];
+        assert_eq!(snap(&tokens, 3, None), 2);
+        assert_eq!(snap(&tokens, 6, None), 8);
+        assert_eq!(snap(&tokens, 3, Some(true)), 8);
+        assert_eq!(snap(&tokens, 6, Some(false)), 2);
+        assert_eq!(snap(&tokens, 2, Some(true)), 2);
+        assert_eq!(snap(&tokens, 8, None), 8);
+    }
+
+    #[test]
+    fn a_trigger_starts_a_word() {
+        let triggers = ['@', '#'];
+        let query = trigger_query("hi @al", 6, &triggers, &[]).unwrap();
+        assert_eq!(query.trigger, '@');
+        assert_eq!(query.range, 3..6);
+        assert_eq!(query.query, "al");
+
+        assert_eq!(trigger_query("#", 1, &triggers, &[]).unwrap().query, "");
+        // Mid-word, past a space, or not a trigger at all.
+        assert_eq!(trigger_query("me@host", 7, &triggers, &[]), None);
+        assert_eq!(trigger_query("@al ex", 6, &triggers, &[]), None);
+        assert_eq!(trigger_query("hi al", 5, &triggers, &[]), None);
+        // Inside what is already a token.
+        let tokens = [Token::new(0..6, "alice")];
+        assert_eq!(trigger_query("@alice", 6, &triggers, &tokens), None);
+    }
+}
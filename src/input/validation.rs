@@ -0,0 +1,137 @@
+//! Limits on what an edit may put into an input: a maximum length, a filter
+//! on characters, and a hook that sees each edit before it is applied.
+//!
+//! Each used to be a `TextChanged` subscriber that read the content back and
+//! wrote a corrected one with `set_content` — which clears undo, moves the
+//! cursor to the start, and lands in the middle of an IME composition. Here
+//! the edit is corrected *before* it reaches the content, so what is applied
+//! is an ordinary edit: one undo step, the cursor after it, and no second
+//! `TextChanged`.
+//!
+//! An edit passes through, in order:
+//!
+//! 1. [`InputState::char_filter`](super::InputState::char_filter) — characters
+//!    it refuses are dropped from what was typed or pasted;
+//! 2. [`InputState::max_len`](super::InputState::max_len) — what would take
+//!    the content past the limit is cut off the end;
+//! 3. [`InputState::validate`](super::InputState::validate) — which sees the
+//!    edit as it now stands and accepts it, replaces its text, or refuses it.
+//!
+//! Whenever one of them takes something out, the input emits
+//! [`InputStateEvent::Rejected`](super::InputStateEvent::Rejected) with the
+//! first [`Rejection`] that applied, so a field can shake or flash. Pasting
+//! `12ab` into a digits-only field inserts `12` *and* emits it.
+//!
+//! An IME composition is not checked while it is being composed — a filter for
+//! digits would otherwise refuse the romaji for 一 — but the committed text is.
+//! Programmatic [`set_content`](super::InputState::set_content) is not checked
+//! at all: it is the owner's API, as it is for read-only.
+
+use std::ops::Range;
+use std::rc::Rc;
+
+/// A [`validate`](super::InputState::validate) hook, as
+/// [`set_validate`](super::InputState::set_validate) takes it.
+pub type Validator = Rc<dyn Fn(&Edit) -> Validation>;
+
+/// One edit, before it is applied: `range` of `content` is to become `text`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edit<'a> {
+    /// The content as it is now.
+    pub content: &'a str,
+    /// The byte range of `content` being replaced. Empty for an insertion.
+    pub range: &'a Range<usize>,
+    /// What replaces it. Empty for a deletion.
+    pub text: &'a str,
+}
+
+impl Edit<'_> {
+    /// The content as it would be after this edit.
+    pub fn result(&self) -> String {
+        let mut result = self.content.to_string();
+        result.replace_range(self.range.clone(), self.text);
+        result
+    }
+
+    /// Whether this edit only removes text.
+    pub fn is_deletion(&self) -> bool {
+        self.text.is_empty()
+    }
+}
+
+/// What a [`validate`](super::InputState::validate) hook decides about an
+/// [`Edit`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Validation {
+    /// Apply the edit as it is.
+    Accept,
+    /// Apply the edit with this text in place of its own — upper-casing a hex
+    /// digit, say. Not checked again.
+    Replace(String),
+    /// Leave the content as it is.
+    Reject,
+}
+
+/// Why an edit was cut short or refused. Carried by
+/// [`InputStateEvent::Rejected`](super::InputStateEvent::Rejected).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// The [`char_filter`](super::InputState::char_filter) dropped a
+    /// character.
+    Filtered,
+    /// The edit would have taken the content past
+    /// [`max_len`](super::InputState::max_len).
+    TooLong,
+    /// The [`validate`](super::InputState::validate) hook refused it.
+    Invalid,
+}
+
+/// `text` with every character `allow` refuses dropped, and whether any was.
+pub(crate) fn filter_chars(text: &str, allow: impl Fn(char) -> bool) -> (String, bool) {
+    let kept: String = text.chars().filter(|c| allow(*c)).collect();
+    let dropped = kept.len() != text.len();
+    (kept, dropped)
+}
+
+/// The longest prefix of `text` that is at most `room` characters.
+pub(crate) fn truncate_chars(text: &str, room: usize) -> &str {
+    match text.char_indices().nth(room) {
+        Some((offset, _)) => &text[..offset],
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_edit_knows_its_result() {
+        let edit = Edit {
+            content: "hello world",
+            range: &(6..11),
+            text: "there",
+        };
+        assert_eq!(edit.result(), "hello there");
+        assert!(!edit.is_deletion());
+    }
+
+    #[test]
+    fn filtering_reports_a_drop() {
+        assert_eq!(
+            filter_chars("12ab3", |c| c.is_ascii_digit()),
+            ("123".to_string(), true)
+        );
+        assert_eq!(
+            filter_chars("123", |c| c.is_ascii_digit()),
+            ("123".to_string(), false)
+        );
+    }
+
+    #[test]
+    fn truncation_counts_characters_not_bytes() {
+        assert_eq!(truncate_chars("héllo", 2), "hé");
+        assert_eq!(truncate_chars("hi", 5), "hi");
+        assert_eq!(truncate_chars("hi", 0), "");
+    }
+}
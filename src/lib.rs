@@ -146,6 +146,24 @@ pub fn assets() -> resource::ResourceSource<Assets> {
 /// # Panics
 /// Calling a gpuikit component before initialization will panic.
 pub fn init(cx: &mut App) {
+    init_with_input_bindings(cx, None);
+}
+
+/// [`init`], with the text input keybindings the app chooses — a preset such
+/// as [`InputBindings::readline()`](input::InputBindings::readline), or any
+/// other [`InputBindings`](input::InputBindings) — in place of the platform
+/// defaults.
+///
+/// Choose the bindings here rather than calling
+/// [`bind_input_keys`](input::bind_input_keys) after [`init`]: that second
+/// call registers the plain `Input` arrows, Enter and Escape again, after the
+/// combobox, command palette, mentions and number field bindings, and so
+/// takes those keys back from all four.
+///
+/// ```ignore
+/// gpuikit::init_with_input_bindings(cx, InputBindings::readline());
+/// ```
+pub fn init_with_input_bindings(cx: &mut App, bindings: impl Into<Option<input::InputBindings>>) {
     theme::init(cx);
     utils::element_manager::init(cx);
     // Before `bind_input_keys`, and the order is load-bearing: both bind Tab,
@@ -153,7 +171,7 @@ pub fn init(cx: &mut App) {
     // that is what keeps Tab inside a focused text input rather than moving
     // focus out of it. See `a11y`'s module docs, section 4.
     a11y::bind_focus_keys(cx);
-    input::bind_input_keys(cx, None);
+    input::bind_input_keys(cx, bindings);
     elements::dialog::bind_dialog_keys(cx);
     // After `bind_focus_keys`, and after `bind_dialog_keys`. Binding
     // precedence is by key-context depth with ties broken by registration
@@ -184,5 +202,11 @@ pub fn init(cx: &mut App) {
     // `elements::combobox`'s and `elements::command`'s `# The keyboard`.
     elements::combobox::bind_combobox_keys(cx);
     elements::command::bind_command_keys(cx);
+    // Likewise after `bind_input_keys`: `up`, `down`, `enter` and `escape` in
+    // `"Mentions > Input"`. See `elements::mentions`' `# The keyboard`.
+    elements::mentions::bind_mentions_keys(cx);
+    // After `bind_input_keys` for the same reason: `up` and `down` in
+    // `"NumberField > Input"`. See `elements::number_field`'s `# Stepping`.
+    elements::number_field::bind_number_field_keys(cx);
     elements::toast::init(cx);
 }
@@ -27,7 +27,7 @@ pub struct InlineStyle {
 /// Theme-resolved colors for the inline styles that need them. Resolved by
 /// the element (which has the theme) and passed into
 /// [`RichText::to_highlights_with`]; `None` leaves that aspect unstyled.
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct InlinePalette {
     /// Background wash behind inline code spans.
     pub code_background: Option<Hsla>,
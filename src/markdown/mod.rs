@@ -374,7 +374,9 @@ impl Markdown {
 #[derive(IntoElement)]
 pub struct MarkdownElement {
     markdown: Entity<Markdown>,
-    style: MarkdownStyle,
+    /// `None` until [`MarkdownElement::style`] is called: the style is then
+    /// built from the active theme's type scale at render time.
+    style: Option<MarkdownStyle>,
     element_id: Option<ElementId>,
 }
 
@@ -393,18 +395,21 @@ pub fn markdown(source: impl Into<SharedString>, cx: &mut App) -> MarkdownElemen
 }
 
 impl MarkdownElement {
-    /// Create a new markdown element with default styling.
+    /// Create a new markdown element, sized from the active theme's
+    /// [`TypeScale`](crate::theme::TypeScale).
     pub fn new(markdown: Entity<Markdown>) -> Self {
         Self {
             markdown,
-            style: MarkdownStyle::default(),
+            style: None,
             element_id: None,
         }
     }
 
-    /// Set a custom style for the markdown.
+    /// Set a custom style for the markdown. Its sizes are used as given, not
+    /// the theme's — start from [`MarkdownStyle::from_type_scale`] to adjust
+    /// the theme's rather than the crate's defaults.
     pub fn style(mut self, style: MarkdownStyle) -> Self {
-        self.style = style;
+        self.style = Some(style);
         self
     }
 
@@ -436,7 +441,9 @@ impl RenderOnce for MarkdownElement {
         let events = markdown.events.clone();
         let unclosed_code_block = markdown.unclosed_code_block;
         let selection = markdown.selection.clone();
-        let style = self.style.clone();
+        let style = self
+            .style
+            .unwrap_or_else(|| MarkdownStyle::from_type_scale(&cx.theme().type_scale()));
 
         // New frame: the previous frame's run layouts are about to be
         // dropped and must not be hit-tested.
@@ -593,14 +600,18 @@ impl MarkdownRenderer {
         // hit-testing and link clicks are untouched. A document is named by
         // its contents, so it takes no accessible name — see
         // `crate::a11y::role_requires_a_name`.
-        div()
+        let document = div()
             .id(document_id)
             .announce(A11y::new(Role::Document))
             .w_full()
             .flex()
             .flex_col()
-            .gap(rems(self.style.block_spacing))
-            .children(self.elements)
+            .gap(rems(self.style.block_spacing));
+        match self.style.font_family {
+            Some(family) => document.font_family(family),
+            None => document,
+        }
+        .children(self.elements)
     }
 
     fn handle_event(&mut self, event: &Event<'static>, cx: &App) {
@@ -1,13 +1,17 @@
 //! Typography and styling for markdown rendering.
 //!
-//! Uses a minor third (1.2) typescale for balanced visual hierarchy.
+//! Sizes come from a [`TypeScale`]: the active theme's unless a style is set
+//! explicitly, and the crate's default — a minor third (1.2) — for the
+//! constructors here that take none.
 
 use gpui::{FontWeight, Hsla, SharedString};
 
-/// The typescale ratio (minor third).
+use crate::theme::{TextMetrics, TextRole, TypeScale};
+
+/// The default scale's ratio (minor third).
 pub const TYPESCALE_RATIO: f32 = 1.2;
 
-/// Base font size in rems.
+/// The default scale's body size in rems.
 pub const BASE_SIZE: f32 = 1.0;
 
 /// Style configuration for a text element.
@@ -27,60 +31,37 @@ pub struct TextStyle {
 
 impl Default for TextStyle {
     fn default() -> Self {
+        Self::body()
+    }
+}
+
+impl TextStyle {
+    /// The style for one rung of a type scale.
+    pub fn from_metrics(metrics: &TextMetrics) -> Self {
         Self {
-            size: BASE_SIZE,
-            line_height: 1.5,
-            weight: FontWeight::NORMAL,
+            size: metrics.size.0,
+            line_height: metrics.line_height.0 / metrics.size.0,
+            weight: metrics.weight,
             color: None,
             margin_top: 0.0,
         }
     }
-}
 
-impl TextStyle {
-    /// Create a heading style at the given scale level.
+    /// Create a heading style at the given scale level, on the default scale.
     ///
     /// Level 1 is the largest (h1), level 6 is the smallest (h6).
     pub fn heading(level: u8) -> Self {
-        let scale_power = match level {
-            1 => 4,
-            2 => 3,
-            3 => 2,
-            4 => 1,
-            _ => 0, // H5, H6 are base size
-        };
-
-        let size = BASE_SIZE * TYPESCALE_RATIO.powi(scale_power);
-
-        Self {
-            size,
-            line_height: 1.2,
-            weight: FontWeight::BOLD,
-            color: None,
-            margin_top: 0.0,
-        }
+        Self::from_metrics(&TypeScale::default().heading(level))
     }
 
-    /// Create body text style.
+    /// Create body text style, on the default scale.
     pub fn body() -> Self {
-        Self {
-            size: BASE_SIZE,
-            line_height: 1.5,
-            weight: FontWeight::NORMAL,
-            color: None,
-            margin_top: 0.0,
-        }
+        Self::from_metrics(&TypeScale::default().metrics(TextRole::Body))
     }
 
-    /// Create code/monospace text style.
+    /// Create code/monospace text style, on the default scale.
     pub fn code() -> Self {
-        Self {
-            size: BASE_SIZE * 0.875,
-            line_height: 1.5,
-            weight: FontWeight::NORMAL,
-            color: None,
-            margin_top: 0.0,
-        }
+        Self::from_metrics(&TypeScale::default().metrics(TextRole::Code))
     }
 }
 
@@ -106,6 +87,8 @@ pub struct MarkdownStyle {
     pub code: TextStyle,
 
     // Font families
+    /// Font family for everything but code. `None` inherits the window's.
+    pub font_family: Option<SharedString>,
     /// Font family for code blocks and inline code.
     pub code_font_family: SharedString,
 
@@ -141,18 +124,29 @@ pub struct MarkdownStyle {
 }
 
 impl Default for MarkdownStyle {
+    /// The style for the crate's default [`TypeScale`].
     fn default() -> Self {
-        Self {
-            body: TextStyle::body(),
-            h1: TextStyle::heading(1),
-            h2: TextStyle::heading(2),
-            h3: TextStyle::heading(3),
-            h4: TextStyle::heading(4),
-            h5: TextStyle::heading(5),
-            h6: TextStyle::heading(6),
-            code: TextStyle::code(),
+        Self::from_type_scale(&TypeScale::default())
+    }
+}
 
-            code_font_family: SharedString::from("monospace"),
+impl MarkdownStyle {
+    /// A style sized and set from `scale`, with default spacing and colours.
+    /// This is what a `MarkdownElement` without an explicit style renders
+    /// with, from the active theme's scale.
+    pub fn from_type_scale(scale: &TypeScale) -> Self {
+        Self {
+            body: TextStyle::from_metrics(&scale.metrics(TextRole::Body)),
+            h1: TextStyle::from_metrics(&scale.heading(1)),
+            h2: TextStyle::from_metrics(&scale.heading(2)),
+            h3: TextStyle::from_metrics(&scale.heading(3)),
+            h4: TextStyle::from_metrics(&scale.heading(4)),
+            h5: TextStyle::from_metrics(&scale.heading(5)),
+            h6: TextStyle::from_metrics(&scale.heading(6)),
+            code: TextStyle::from_metrics(&scale.metrics(TextRole::Code)),
+
+            font_family: scale.font_family.clone(),
+            code_font_family: scale.code_font_family.clone(),
 
             block_spacing: 0.5,
 
@@ -168,9 +162,7 @@ impl Default for MarkdownStyle {
             selection_background: None,
         }
     }
-}
 
-impl MarkdownStyle {
     /// Create a new style with default typography.
     pub fn new() -> Self {
         Self::default()
@@ -275,6 +267,22 @@ mod tests {
         );
     }
 
+    /// A scale's body size reaches every heading and the code style.
+    #[test]
+    fn test_from_type_scale_follows_body_size() {
+        let scale = TypeScale {
+            body: gpui::Rems(1.25),
+            ..Default::default()
+        };
+        let style = MarkdownStyle::from_type_scale(&scale);
+        let default = MarkdownStyle::default();
+
+        assert!((style.body.size - 1.25).abs() < 0.01);
+        assert!((style.h1.size / default.h1.size - 1.25).abs() < 0.01);
+        assert!((style.code.size / default.code.size - 1.25).abs() < 0.01);
+        assert!((style.h1.line_height - default.h1.line_height).abs() < 0.01);
+    }
+
     #[test]
     fn test_line_heights() {
         let body = TextStyle::body();
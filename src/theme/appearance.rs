@@ -0,0 +1,221 @@
+//! Following the system's light/dark setting.
+//!
+//! An app that wants this hands [`init_with_appearance`] a light theme and a
+//! dark one instead of calling [`theme::init`](super::init). From then on the
+//! active theme is whichever of the two matches the appearance gpui reports,
+//! and flipping the system setting swaps it through
+//! [`set_theme`](super::set_theme) — so every window re-renders, exactly as a
+//! switch from the registry does.
+//!
+//! # One call per window
+//!
+//! gpui reports appearance changes per window, and has no hook for "a window
+//! was opened" that a library could attach to. So each window that should
+//! follow the setting calls [`observe_window_appearance`] once, typically in
+//! its root view's constructor:
+//!
+//! ```ignore
+//! cx.open_window(options, |window, cx| {
+//!     gpuikit::theme::observe_window_appearance(window, cx);
+//!     cx.new(|cx| Workspace::new(window, cx))
+//! })
+//! ```
+//!
+//! One window is enough for the whole app to follow — the theme is global —
+//! and more than one is harmless: a change every window reports is applied
+//! once and then found to be a no-op.
+//!
+//! # Testing
+//!
+//! [`apply_appearance`] is the whole reaction to a change, separated from the
+//! observer so a test can feed it an appearance gpui's test platform would
+//! never produce.
+
+use std::sync::Arc;
+
+use gpui::{App, Global, Window, WindowAppearance};
+
+use super::{set_theme, GlobalTheme, Theme};
+
+/// The theme to use for each appearance, while the app follows the system.
+///
+/// Its presence as a global is what "following" means: remove it with
+/// [`stop_following_appearance`] and appearance changes are ignored.
+#[derive(Debug, Clone)]
+pub struct AppearanceThemes {
+    /// The theme for `Light` and `VibrantLight`.
+    pub light: Arc<Theme>,
+    /// The theme for `Dark` and `VibrantDark`.
+    pub dark: Arc<Theme>,
+}
+
+impl Global for AppearanceThemes {}
+
+impl AppearanceThemes {
+    /// A light/dark pair.
+    pub fn new(light: impl Into<Arc<Theme>>, dark: impl Into<Arc<Theme>>) -> Self {
+        Self {
+            light: light.into(),
+            dark: dark.into(),
+        }
+    }
+
+    /// The theme for `appearance`. The vibrant appearances are macOS's
+    /// translucent variants of the same two, and get the same themes.
+    pub fn for_appearance(&self, appearance: WindowAppearance) -> &Arc<Theme> {
+        match appearance {
+            WindowAppearance::Light | WindowAppearance::VibrantLight => &self.light,
+            WindowAppearance::Dark | WindowAppearance::VibrantDark => &self.dark,
+        }
+    }
+}
+
+impl Default for AppearanceThemes {
+    /// Gruvbox, both ways round.
+    fn default() -> Self {
+        Self::new(Theme::gruvbox_light(), Theme::gruvbox_dark())
+    }
+}
+
+/// [`theme::init`](super::init), then follow the system appearance with
+/// `themes`.
+///
+/// The theme is chosen from the app-wide appearance immediately, so the first
+/// frame is already right; call [`observe_window_appearance`] for each window
+/// to keep it right.
+pub fn init_with_appearance(themes: AppearanceThemes, cx: &mut App) {
+    super::init(cx);
+    follow_appearance(themes, cx);
+}
+
+/// Start following the system appearance with `themes`, or replace the pair
+/// already being followed.
+pub fn follow_appearance(themes: AppearanceThemes, cx: &mut App) {
+    cx.set_global(themes);
+    apply_appearance(cx.window_appearance(), cx);
+}
+
+/// Stop following the system appearance, leaving the current theme in place.
+///
+/// A picker that offers "System" beside named themes calls this when the user
+/// picks a name; otherwise the next flip of the system setting would undo
+/// their choice.
+pub fn stop_following_appearance(cx: &mut App) {
+    if cx.has_global::<AppearanceThemes>() {
+        cx.remove_global::<AppearanceThemes>();
+    }
+}
+
+/// Whether the app is currently following the system appearance.
+pub fn is_following_appearance(cx: &App) -> bool {
+    cx.has_global::<AppearanceThemes>()
+}
+
+/// Swap the theme when `window`'s appearance changes, for as long as the
+/// window lives.
+///
+/// Also applies the window's current appearance, which can differ from the
+/// app-wide one [`follow_appearance`] started from.
+pub fn observe_window_appearance(window: &mut Window, cx: &mut App) {
+    apply_appearance(window.appearance(), cx);
+    window
+        .observe_window_appearance(|window, cx| apply_appearance(window.appearance(), cx))
+        .detach();
+}
+
+/// React to the appearance becoming `appearance`.
+///
+/// Does nothing when the app is not following the system, or when the theme
+/// for `appearance` is already the active one — the second case is what makes
+/// several windows reporting one change cost one re-render.
+pub fn apply_appearance(appearance: WindowAppearance, cx: &mut App) {
+    let Some(themes) = cx.try_global::<AppearanceThemes>() else {
+        return;
+    };
+    let theme = themes.for_appearance(appearance).clone();
+    if Arc::ptr_eq(&theme, &cx.global::<GlobalTheme>().0) {
+        return;
+    }
+    set_theme(theme, cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::ActiveTheme;
+    use gpui::{div, px, size, Context, IntoElement, Render, Styled, TestAppContext};
+
+    fn latte_and_mocha() -> AppearanceThemes {
+        AppearanceThemes::new(Theme::catppuccin_latte(), Theme::catppuccin_mocha())
+    }
+
+    fn active_name(cx: &mut TestAppContext) -> String {
+        cx.update(|cx| cx.theme().name.to_string())
+    }
+
+    struct Counter {
+        renders: usize,
+    }
+
+    impl Render for Counter {
+        fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            self.renders += 1;
+            div().bg(cx.theme().bg_color)
+        }
+    }
+
+    /// gpui's test platform reports `Light`, so the pair's light half is the
+    /// theme from the first frame.
+    #[gpui::test]
+    fn init_picks_the_theme_for_the_current_appearance(cx: &mut TestAppContext) {
+        cx.update(|cx| init_with_appearance(latte_and_mocha(), cx));
+        assert_eq!(active_name(cx), "Catppuccin Latte");
+    }
+
+    #[gpui::test]
+    fn a_flip_to_dark_swaps_the_theme_and_re_renders(cx: &mut TestAppContext) {
+        cx.update(|cx| init_with_appearance(latte_and_mocha(), cx));
+        let window = cx.open_window(size(px(200.), px(100.)), |window, cx| {
+            observe_window_appearance(window, cx);
+            Counter { renders: 0 }
+        });
+        cx.run_until_parked();
+        let view = window.root(cx).unwrap();
+        let renders_before = view.read_with(cx, |counter, _| counter.renders);
+
+        cx.update(|cx| apply_appearance(WindowAppearance::VibrantDark, cx));
+        cx.run_until_parked();
+
+        assert_eq!(active_name(cx), "Catppuccin Mocha");
+        assert!(view.read_with(cx, |counter, _| counter.renders) > renders_before);
+
+        cx.update(|cx| apply_appearance(WindowAppearance::Light, cx));
+        assert_eq!(active_name(cx), "Catppuccin Latte");
+    }
+
+    /// A user who picked a named theme keeps it when the system flips.
+    #[gpui::test]
+    fn after_stopping_a_flip_changes_nothing(cx: &mut TestAppContext) {
+        cx.update(|cx| init_with_appearance(latte_and_mocha(), cx));
+        cx.update(|cx| {
+            stop_following_appearance(cx);
+            crate::theme::ThemeRegistry::set_active("Gruvbox Dark", cx).unwrap();
+            apply_appearance(WindowAppearance::Light, cx);
+        });
+
+        assert_eq!(active_name(cx), "Gruvbox Dark");
+        assert!(!cx.update(|cx| is_following_appearance(cx)));
+    }
+
+    /// A change reported by every window is one swap, not one per window.
+    #[gpui::test]
+    fn the_same_appearance_twice_is_a_no_op(cx: &mut TestAppContext) {
+        cx.update(|cx| init_with_appearance(latte_and_mocha(), cx));
+        cx.update(|cx| apply_appearance(WindowAppearance::Dark, cx));
+        let first = cx.update(|cx| cx.theme().clone());
+
+        cx.update(|cx| apply_appearance(WindowAppearance::VibrantDark, cx));
+
+        assert!(Arc::ptr_eq(&first, &cx.update(|cx| cx.theme().clone())));
+    }
+}
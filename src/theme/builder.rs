@@ -0,0 +1,408 @@
+//! Deriving a whole theme from its five primitives.
+//!
+//! [`Themeable`](super::Themeable)'s defaults are the fallback for a theme that
+//! sets nothing, and they are arithmetic on alpha: `fg_muted` is the
+//! foreground at 70%, the placeholder at 50%. Over the page they were tuned
+//! against that is fine; over a mid-tone background the same fractions land
+//! below the contrast text needs, and nothing says so.
+//!
+//! [`ThemeBuilder`] derives every secondary token instead, and fills in every
+//! override, so a built theme does not depend on the trait's defaults at all.
+//!
+//! # Why OKLab
+//!
+//! Each token is a step in *perceptual* lightness — OKLab's `L`, in which equal
+//! steps look equal — between or beyond the primitives. HSL lightness is not
+//! perceptual: a step of 0.1 moves a yellow far less than a blue, which is how
+//! a derived palette ends up with one hue that cannot be read.
+//!
+//! # Why a floor as well
+//!
+//! Steps alone cannot promise a ratio — that depends on where the primitives
+//! sit. So every token text is drawn in is stepped first and then, if it still
+//! falls short of [`MIN_TEXT_CONTRAST`] against what it is drawn on, pushed
+//! further toward the foreground until it does not. The status colours get the
+//! same treatment at [`MIN_LARGE_TEXT_CONTRAST`], the WCAG minimum for icons
+//! and indicators. The primitives are taken as given: a foreground the builder
+//! quietly changed would be a surprise, and
+//! [`Theme::contrast_report`](super::Theme::contrast_report) says when one is
+//! the problem.
+
+use gpui::{hsla, Hsla, Rgba};
+
+use super::contrast::{contrast_ratio, MIN_LARGE_TEXT_CONTRAST, MIN_TEXT_CONTRAST};
+use super::{ControlScale, Shape, Theme, ThemeVariant, TypeScale};
+
+/// Builds a [`Theme`] with every token derived from five primitives.
+///
+/// ```ignore
+/// let theme = ThemeBuilder::new(
+///     "Harbour",
+///     parse_hex("#d8dee9"), // fg
+///     parse_hex("#2e3440"), // bg
+///     parse_hex("#3b4252"), // surface
+///     parse_hex("#4c566a"), // border
+///     parse_hex("#88c0d0"), // accent
+/// )
+/// .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ThemeBuilder {
+    name: String,
+    variant: Option<ThemeVariant>,
+    fg: Hsla,
+    bg: Hsla,
+    surface: Hsla,
+    border: Hsla,
+    accent: Hsla,
+    controls: ControlScale,
+    typography: TypeScale,
+    shape: Shape,
+}
+
+impl ThemeBuilder {
+    /// A builder from the five primitives, in `Theme::new`'s order.
+    pub fn new(
+        name: impl Into<String>,
+        fg: Hsla,
+        bg: Hsla,
+        surface: Hsla,
+        border: Hsla,
+        accent: Hsla,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            variant: None,
+            fg,
+            bg,
+            surface,
+            border,
+            accent,
+            controls: ControlScale::default(),
+            typography: TypeScale::default(),
+            shape: Shape::default(),
+        }
+    }
+
+    /// Whether the theme is light or dark. By default this is read off the
+    /// background: dark when its perceptual lightness is below half.
+    pub fn variant(mut self, variant: ThemeVariant) -> Self {
+        self.variant = Some(variant);
+        self
+    }
+
+    /// The control size scale. Defaults to the crate's.
+    pub fn controls(mut self, controls: ControlScale) -> Self {
+        self.controls = controls;
+        self
+    }
+
+    /// The type scale. Defaults to the crate's.
+    pub fn typography(mut self, typography: TypeScale) -> Self {
+        self.typography = typography;
+        self
+    }
+
+    /// Radii, spacing and shadows. Defaults to the crate's.
+    pub fn shape(mut self, shape: Shape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Derive every token.
+    pub fn build(self) -> Theme {
+        let variant = self.variant.unwrap_or(if Oklab::from(self.bg).l < 0.5 {
+            ThemeVariant::Dark
+        } else {
+            ThemeVariant::Light
+        });
+        // Which way "further from the page" is: lighter on a dark theme.
+        let away = match variant {
+            ThemeVariant::Dark => 1.0,
+            ThemeVariant::Light => -1.0,
+        };
+        let Self {
+            name,
+            fg,
+            bg,
+            surface,
+            border,
+            accent,
+            controls,
+            typography,
+            shape,
+            ..
+        } = self;
+
+        let mut theme = Theme::new(name, variant, fg, bg, surface, border, accent);
+        theme.controls = controls;
+        theme.typography = typography;
+        theme.shape = shape;
+
+        let fg_muted = legible(mix(bg, fg, 0.72), bg, fg, MIN_TEXT_CONTRAST);
+        theme.fg_muted_color = Some(fg_muted);
+        // Disabled text is exempt from the WCAG minimum, and reads as disabled
+        // because it is fainter than everything else.
+        theme.fg_disabled_color = Some(mix(bg, fg, 0.45));
+
+        let surface_secondary = step(surface, 0.04 * away);
+        let surface_tertiary = step(surface, 0.08 * away);
+        theme.surface_secondary_color = Some(surface_secondary);
+        theme.surface_tertiary_color = Some(surface_tertiary);
+        let border_secondary = step(border, 0.08 * away);
+        theme.border_secondary_color = Some(border_secondary);
+        theme.border_subtle_color = Some(mix(surface, border, 0.5));
+
+        theme.outline_color = Some(accent);
+        theme.accent_bg_color = Some(mix(bg, accent, 0.15));
+        theme.accent_bg_hover_color = Some(mix(bg, accent, 0.25));
+        // Translucent on purpose: a selection is painted over text it must
+        // not hide.
+        theme.selection_color = Some(accent.opacity(0.3));
+
+        let status = |hue: f32| {
+            let start = match variant {
+                ThemeVariant::Dark => 0.72,
+                ThemeVariant::Light => 0.55,
+            };
+            legible(
+                Oklab::from_lch(start, 0.14, hue).into(),
+                bg,
+                fg,
+                MIN_LARGE_TEXT_CONTRAST,
+            )
+        };
+        let info = status(250.0);
+        let success = status(145.0);
+        let warning = status(75.0);
+        // A destructive badge puts the page colour on this fill as text, so it
+        // is held to the text minimum rather than the indicator one.
+        let danger = legible(status(27.0), bg, fg, MIN_TEXT_CONTRAST);
+        theme.info_color = Some(info);
+        theme.success_color = Some(success);
+        theme.warning_color = Some(warning);
+        theme.danger_color = Some(danger);
+
+        theme.button_bg_color = Some(surface);
+        theme.button_bg_hover_color = Some(surface_secondary);
+        theme.button_bg_active_color = Some(surface_tertiary);
+        theme.button_border_color = Some(border);
+
+        theme.input_bg_color = Some(surface);
+        theme.input_border_color = Some(border);
+        theme.input_border_hover_color = Some(border_secondary);
+        theme.input_border_focused_color = Some(accent);
+        theme.input_text_color = Some(fg);
+        theme.input_placeholder_color = Some(legible(
+            mix(surface, fg, 0.6),
+            surface,
+            fg,
+            MIN_TEXT_CONTRAST,
+        ));
+        theme.input_selection_color = Some(accent.opacity(0.3));
+        theme.input_cursor_color = Some(accent);
+        theme.overlay_color = Some(hsla(0.0, 0.0, 0.0, 0.6));
+
+        theme.badge_blue_color = Some(info);
+        theme.badge_gold_color = Some(warning);
+        theme.badge_red_color = Some(danger);
+        theme.badge_green_color = Some(success);
+        theme.badge_teal_color = Some(status(195.0));
+        theme.badge_amber_color = Some(status(60.0));
+        theme.badge_gray_color = Some(fg_muted);
+
+        theme
+    }
+}
+
+/// `color`, pushed toward `ink` in perceptual steps until it reaches `minimum`
+/// against `background` — or `ink` itself, if even that cannot.
+fn legible(color: Hsla, background: Hsla, ink: Hsla, minimum: f32) -> Hsla {
+    let mut candidate = color;
+    let mut t = 0.0;
+    while contrast_ratio(candidate, background) < minimum && t < 1.0 {
+        t += 0.05;
+        candidate = mix(color, ink, t);
+    }
+    candidate
+}
+
+/// `color` with its perceptual lightness moved by `delta`, hue and chroma kept.
+fn step(color: Hsla, delta: f32) -> Hsla {
+    let mut lab = Oklab::from(color);
+    lab.l = (lab.l + delta).clamp(0.0, 1.0);
+    lab.into()
+}
+
+/// The colour `t` of the way from `from` to `to`, in OKLab.
+fn mix(from: Hsla, to: Hsla, t: f32) -> Hsla {
+    let (a, b) = (Oklab::from(from), Oklab::from(to));
+    Oklab {
+        l: a.l + (b.l - a.l) * t,
+        a: a.a + (b.a - a.a) * t,
+        b: a.b + (b.b - a.b) * t,
+        alpha: a.alpha + (b.alpha - a.alpha) * t,
+    }
+    .into()
+}
+
+/// A colour in Björn Ottosson's OKLab space.
+#[derive(Debug, Clone, Copy)]
+struct Oklab {
+    l: f32,
+    a: f32,
+    b: f32,
+    alpha: f32,
+}
+
+impl Oklab {
+    /// From the cylindrical form: lightness, chroma and hue in degrees.
+    fn from_lch(l: f32, c: f32, hue: f32) -> Self {
+        let hue = hue.to_radians();
+        Self {
+            l,
+            a: c * hue.cos(),
+            b: c * hue.sin(),
+            alpha: 1.0,
+        }
+    }
+}
+
+impl From<Hsla> for Oklab {
+    fn from(color: Hsla) -> Self {
+        let rgb = Rgba::from(color);
+        let linear = |c: f32| {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let (r, g, b) = (linear(rgb.r), linear(rgb.g), linear(rgb.b));
+        let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+        Self {
+            l: 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            a: 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            b: 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+            alpha: rgb.a,
+        }
+    }
+}
+
+impl From<Oklab> for Hsla {
+    /// Out-of-gamut colours are clipped per channel.
+    fn from(lab: Oklab) -> Self {
+        let l = (lab.l + 0.396_337_78 * lab.a + 0.215_803_76 * lab.b).powi(3);
+        let m = (lab.l - 0.105_561_346 * lab.a - 0.063_854_17 * lab.b).powi(3);
+        let s = (lab.l - 0.089_484_18 * lab.a - 1.291_485_5 * lab.b).powi(3);
+        let encode = |c: f32| {
+            let c = c.clamp(0.0, 1.0);
+            if c <= 0.003_130_8 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            }
+        };
+        Rgba {
+            r: encode(4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s),
+            g: encode(-1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s),
+            b: encode(-0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s),
+            a: lab.alpha,
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::{file, parse_hex, Themeable};
+
+    fn murky() -> ThemeBuilder {
+        ThemeBuilder::new(
+            "Murky",
+            parse_hex("#d0d0d0"),
+            parse_hex("#4a4a4a"),
+            parse_hex("#555555"),
+            parse_hex("#6a6a6a"),
+            parse_hex("#e0c060"),
+        )
+    }
+
+    #[test]
+    fn oklab_round_trips() {
+        for hex in ["#000000", "#ffffff", "#fb4934", "#458588", "#7aa2f7"] {
+            let color = parse_hex(hex);
+            let back = Rgba::from(Hsla::from(Oklab::from(color)));
+            let rgb = Rgba::from(color);
+            for (x, y) in [(back.r, rgb.r), (back.g, rgb.g), (back.b, rgb.b)] {
+                assert!((x - y).abs() < 0.002, "{hex}: {back:?} vs {rgb:?}");
+            }
+        }
+    }
+
+    /// Nothing is left to the trait's defaults.
+    #[test]
+    fn every_token_is_set() {
+        let theme = murky().build();
+        assert_eq!(file::overrides(&theme).count(), 34);
+    }
+
+    /// The motivating case: on a mid-grey page the trait's alpha-derived muted
+    /// text and placeholder fail, and the builder's do not.
+    #[test]
+    fn derived_text_passes_where_the_defaults_fail() {
+        let ThemeBuilder {
+            fg,
+            bg,
+            surface,
+            border,
+            accent,
+            ..
+        } = murky();
+        let defaults = Theme::new(
+            "Defaults",
+            ThemeVariant::Dark,
+            fg,
+            bg,
+            surface,
+            border,
+            accent,
+        );
+        assert!(!defaults.contrast_report().passes());
+
+        let report = murky().build().contrast_report();
+        for pair in [
+            "fg_muted / bg",
+            "input_placeholder / input_bg",
+            "badge destructive: bg / danger",
+            "destructive_fg / destructive_bg",
+        ] {
+            assert!(report.get(pair).unwrap().passes(), "{report}");
+        }
+    }
+
+    #[test]
+    fn the_variant_is_read_off_the_background() {
+        assert_eq!(murky().build().variant, ThemeVariant::Dark);
+        let light = ThemeBuilder::new(
+            "Paper",
+            parse_hex("#1a1a1a"),
+            parse_hex("#fafafa"),
+            parse_hex("#f0f0f0"),
+            parse_hex("#d0d0d0"),
+            parse_hex("#1d4ed8"),
+        )
+        .build();
+        assert_eq!(light.variant, ThemeVariant::Light);
+        assert!(
+            light.contrast_report().passes(),
+            "{}",
+            light.contrast_report()
+        );
+        // Nested surfaces step away from the page: darker on a light theme.
+        assert!(Oklab::from(light.surface_tertiary()).l < Oklab::from(light.surface_secondary()).l);
+    }
+}
@@ -0,0 +1,247 @@
+//! WCAG contrast ratios for the pairs of colours a theme actually puts text on.
+//!
+//! [`Theme::contrast_report`](super::Theme::contrast_report) checks each pair
+//! of colours that gpuikit draws text with, one over the other, against the
+//! WCAG 2.x minimum for normal-size text. A custom theme can be validated in
+//! an ordinary unit test:
+//!
+//! ```ignore
+//! #[test]
+//! fn our_theme_is_readable() {
+//!     let report = our_theme().contrast_report();
+//!     assert!(report.passes(), "{report}");
+//! }
+//! ```
+//!
+//! The `Display` impl is a table of every pair with failures marked, so the
+//! assertion message says which pair failed and by how much.
+//!
+//! # Translucent colours
+//!
+//! Several derived tokens are translucent — the trait's `fg_muted` is the
+//! foreground at 70% — and a ratio between a translucent colour and anything
+//! is meaningless. Each pair is composited first: the background over the
+//! theme's `bg`, then the foreground over that, which is what the pixels on
+//! screen are.
+
+use std::fmt;
+
+use gpui::{Hsla, Rgba};
+
+use super::Themeable;
+
+/// The WCAG 2.x minimum for normal-size text (success criterion 1.4.3, AA).
+pub const MIN_TEXT_CONTRAST: f32 = 4.5;
+
+/// The WCAG 2.x minimum for large text and for non-text UI such as borders
+/// and icons (1.4.3 and 1.4.11, AA).
+pub const MIN_LARGE_TEXT_CONTRAST: f32 = 3.0;
+
+/// The WCAG relative luminance of `color`, ignoring its alpha.
+pub fn relative_luminance(color: Hsla) -> f32 {
+    let rgb = Rgba::from(color);
+    let linear = |channel: f32| {
+        if channel <= 0.04045 {
+            channel / 12.92
+        } else {
+            ((channel + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(rgb.r) + 0.7152 * linear(rgb.g) + 0.0722 * linear(rgb.b)
+}
+
+/// The WCAG contrast ratio between two opaque colours, from 1 to 21.
+///
+/// Symmetric: which is the text and which the background does not matter.
+pub fn contrast_ratio(a: Hsla, b: Hsla) -> f32 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// One foreground/background pair and how it measured.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastCheck {
+    /// What the pair is, in the `Themeable` method names that produce it —
+    /// `"input_placeholder / input_bg"`.
+    pub pair: &'static str,
+    /// The text colour, composited as drawn.
+    pub foreground: Hsla,
+    /// The colour behind it, composited as drawn.
+    pub background: Hsla,
+    /// The measured ratio.
+    pub ratio: f32,
+    /// The ratio the pair needs.
+    pub minimum: f32,
+}
+
+impl ContrastCheck {
+    /// Whether the pair meets its minimum.
+    pub fn passes(&self) -> bool {
+        self.ratio >= self.minimum
+    }
+}
+
+/// Every text pair a theme draws, measured. Built by
+/// [`Theme::contrast_report`](super::Theme::contrast_report).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastReport {
+    /// The theme's name, for the table heading.
+    pub theme: String,
+    /// One entry per pair, in a fixed order.
+    pub checks: Vec<ContrastCheck>,
+}
+
+impl ContrastReport {
+    /// Measure every pair `theme` draws text with.
+    pub fn for_theme(name: impl Into<String>, theme: &impl Themeable) -> Self {
+        let page = theme.bg();
+        let pairs: [(&'static str, Hsla, Hsla); 9] = [
+            ("fg / bg", theme.fg(), page),
+            ("fg_muted / bg", theme.fg_muted(), page),
+            (
+                "input_text / input_bg",
+                theme.input_text(),
+                theme.input_bg(),
+            ),
+            (
+                "input_placeholder / input_bg",
+                theme.input_placeholder(),
+                theme.input_bg(),
+            ),
+            // The four badge variants, as `Badge` draws them.
+            ("badge: bg / accent", page, theme.accent()),
+            (
+                "badge secondary: fg / surface_secondary",
+                theme.fg(),
+                theme.surface_secondary(),
+            ),
+            ("badge outline: fg / bg", theme.fg(), page),
+            ("badge destructive: bg / danger", page, theme.danger()),
+            (
+                "destructive_fg / destructive_bg",
+                theme.destructive_fg(),
+                theme.destructive_bg(),
+            ),
+        ];
+        let checks = pairs
+            .into_iter()
+            .map(|(pair, foreground, background)| {
+                let background = page.blend(background);
+                let foreground = background.blend(foreground);
+                ContrastCheck {
+                    pair,
+                    foreground,
+                    background,
+                    ratio: contrast_ratio(foreground, background),
+                    minimum: MIN_TEXT_CONTRAST,
+                }
+            })
+            .collect();
+        Self {
+            theme: name.into(),
+            checks,
+        }
+    }
+
+    /// Whether every pair meets its minimum.
+    pub fn passes(&self) -> bool {
+        self.checks.iter().all(ContrastCheck::passes)
+    }
+
+    /// The pairs that fall short.
+    pub fn failures(&self) -> impl Iterator<Item = &ContrastCheck> {
+        self.checks.iter().filter(|check| !check.passes())
+    }
+
+    /// The check for one pair, by its [`pair`](ContrastCheck::pair) label.
+    pub fn get(&self, pair: &str) -> Option<&ContrastCheck> {
+        self.checks.iter().find(|check| check.pair == pair)
+    }
+}
+
+impl fmt::Display for ContrastReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "contrast for {}:", self.theme)?;
+        for check in &self.checks {
+            writeln!(
+                f,
+                "  {:<42} {:>5.2}:1  {}",
+                check.pair,
+                check.ratio,
+                if check.passes() {
+                    "ok".to_string()
+                } else {
+                    format!("FAIL (needs {}:1)", check.minimum)
+                }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::{parse_hex, Theme, ThemeVariant};
+    use gpui::hsla;
+
+    #[test]
+    fn black_on_white_is_the_maximum() {
+        let ratio = contrast_ratio(hsla(0., 0., 0., 1.), hsla(0., 0., 1., 1.));
+        assert!((ratio - 21.0).abs() < 0.01);
+        assert!((contrast_ratio(gpui::red(), gpui::red()) - 1.0).abs() < 0.001);
+    }
+
+    /// A reference value: #767676 on white is the classic just-passing grey.
+    #[test]
+    fn matches_the_wcag_reference_grey() {
+        let ratio = contrast_ratio(parse_hex("#767676"), parse_hex("#ffffff"));
+        assert!((ratio - 4.54).abs() < 0.01, "{ratio}");
+    }
+
+    /// The trait's `fg_muted` is the foreground at 70%, and is measured as the
+    /// colour it actually comes out as over the page.
+    #[test]
+    fn translucent_text_is_measured_as_drawn() {
+        let theme = Theme::new(
+            "Grey",
+            ThemeVariant::Light,
+            parse_hex("#000000"),
+            parse_hex("#ffffff"),
+            parse_hex("#ffffff"),
+            parse_hex("#cccccc"),
+            parse_hex("#0000ff"),
+        );
+        let check = theme
+            .contrast_report()
+            .get("fg_muted / bg")
+            .cloned()
+            .unwrap();
+        let opaque = contrast_ratio(check.foreground, parse_hex("#ffffff"));
+        assert!(check.ratio < 21.0);
+        assert!((check.ratio - opaque).abs() < 0.001);
+    }
+
+    /// The failure this exists to catch: a mid-grey page under the trait's
+    /// default placeholder.
+    #[test]
+    fn a_low_contrast_theme_fails_and_says_where() {
+        let theme = Theme::new(
+            "Murky",
+            ThemeVariant::Dark,
+            parse_hex("#9a9a9a"),
+            parse_hex("#5a5a5a"),
+            parse_hex("#5a5a5a"),
+            parse_hex("#707070"),
+            parse_hex("#7aa2f7"),
+        );
+        let report = theme.contrast_report();
+
+        assert!(!report.passes());
+        assert!(report
+            .failures()
+            .any(|check| check.pair == "input_placeholder / input_bg"));
+        assert!(report.to_string().contains("FAIL"));
+    }
+}
@@ -20,6 +20,7 @@
 //! to different shapes with nothing holding them together.
 
 use gpui::Rems;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// The 1px border a bordered control draws, in rems at the 16px root this
 /// scale's values are stated at.
@@ -63,26 +64,34 @@ impl ControlSize {
 /// All lengths are rems, so a consumer that changes the root font size
 /// rescales the whole set. Resolve one with
 /// [`Themeable::control`](crate::theme::Themeable::control).
-#[derive(Debug, Clone, Copy, PartialEq)]
+///
+/// Serialized as plain numbers of rems, which is how a theme file states it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct ControlMetrics {
     /// The control's outer height. This is the number the whole scale exists
     /// to make equal across a row.
+    #[serde(with = "rems")]
     pub height: Rems,
     /// Horizontal padding inside the control.
+    #[serde(with = "rems")]
     pub padding_x: Rems,
     /// Spacing between an icon and a label *inside* one control. A trailing
     /// label that belongs to the control but sits outside its box — a
     /// checkbox's or a switch's — wants more room than this; those use
     /// `gap * 2.0`.
+    #[serde(with = "rems")]
     pub gap: Rems,
     /// Corner radius.
+    #[serde(with = "rems")]
     pub radius: Rems,
     /// Font size for text inside the control.
+    #[serde(with = "rems")]
     pub text_size: Rems,
     /// The line box that text sits in. Declared rather than inherited: set
     /// only a height and a large enough font pushes text out of the box it was
     /// supposed to be centred in — the same "emergent size" failure one level
     /// down. Every rung satisfies `line_height + 2px border <= height`.
+    #[serde(with = "rems")]
     pub line_height: Rems,
     /// How much of its box a control's graphic fills — a checkbox's box, an
     /// icon button's glyph, a switch's track height.
@@ -91,6 +100,7 @@ pub struct ControlMetrics {
     /// its rung and still read heavy next to its neighbours. `ink` is the knob
     /// for that, and it is a judgement rather than a derivation — an element
     /// with a genuine reason may use its own ratio.
+    #[serde(with = "rems")]
     pub ink: Rems,
 }
 
@@ -152,7 +162,11 @@ pub struct TrackMetrics {
 /// A theme overrides
 /// [`Themeable::control_scale`](crate::theme::Themeable::control_scale) to
 /// rescale every control at once.
-#[derive(Debug, Clone, Copy, PartialEq)]
+///
+/// A rung missing from a deserialized scale is the crate's default rung, so a
+/// theme file can restate only the one it changes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ControlScale {
     /// The `Small` rung.
     pub small: ControlMetrics,
@@ -215,6 +229,19 @@ impl Default for ControlScale {
     }
 }
 
+/// `Rems` has no serde impls of its own; a theme file writes the bare number.
+pub(crate) mod rems {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Rems, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f32(value.0)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Rems, D::Error> {
+        f32::deserialize(deserializer).map(Rems)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,580 @@
+//! Theme files: a [`Theme`] as JSON or TOML, so a theme can ship without a
+//! recompile.
+//!
+//! The format is one flat table of colours keyed by the [`Themeable`] method
+//! that reads them, plus the theme's name, its variant and, optionally, its
+//! [`ControlScale`]:
+//!
+//! ```json
+//! {
+//!   "name": "Paper",
+//!   "variant": "light",
+//!   "colors": {
+//!     "fg": "#1f1f1f",
+//!     "bg": "#fafafa",
+//!     "surface": "#f0f0f0",
+//!     "border": "#d0d0d0",
+//!     "accent": "#3367d6",
+//!     "fg_muted": "#5f5f5f",
+//!     "selection": "#3367d64d"
+//!   }
+//! }
+//! ```
+//!
+//! The five primitives are required, exactly as they are for [`Theme::new`].
+//! Every other key is one of `Theme`'s `Option` overrides, and leaving it out
+//! means what `None` means: the trait's derived default.
+//!
+//! # Why the colours are a map
+//!
+//! A struct with one `String` field per token would have been derived in one
+//! line, and every error it produced would have been serde's — a line and a
+//! column, never the key. A designer with a typo in a hex string wants to be
+//! told *which token*, so the colours are read as strings first and converted
+//! one key at a time against [`COLOR_KEYS`], and every failure names the key
+//! and the string it could not read. An unknown key is an error rather than
+//! ignored for the same reason: `fg_mutted` silently doing nothing is the
+//! hardest possible typo to find.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use gpui::{Hsla, Rgba};
+use serde::{Deserialize, Serialize};
+
+use super::{ControlScale, Shape, Theme, ThemeVariant, TypeScale};
+
+/// Declares one optional colour token: its key in a theme file, and the
+/// `Theme` field it reads and writes.
+macro_rules! token {
+    ($key:literal, $field:ident) => {
+        Token {
+            key: $key,
+            get: |theme| theme.$field,
+            slot: |theme| &mut theme.$field,
+        }
+    };
+}
+
+/// One optional colour token.
+struct Token {
+    key: &'static str,
+    get: fn(&Theme) -> Option<Hsla>,
+    slot: fn(&mut Theme) -> &mut Option<Hsla>,
+}
+
+/// The required keys, in the order [`Theme::new`] takes them.
+pub const PRIMITIVE_KEYS: [&str; 5] = ["fg", "bg", "surface", "border", "accent"];
+
+/// Every optional key, in `Theme`'s field order.
+const TOKENS: [Token; 34] = [
+    token!("fg_muted", fg_muted_color),
+    token!("fg_disabled", fg_disabled_color),
+    token!("surface_secondary", surface_secondary_color),
+    token!("surface_tertiary", surface_tertiary_color),
+    token!("border_secondary", border_secondary_color),
+    token!("border_subtle", border_subtle_color),
+    token!("outline", outline_color),
+    token!("accent_bg", accent_bg_color),
+    token!("accent_bg_hover", accent_bg_hover_color),
+    token!("info", info_color),
+    token!("success", success_color),
+    token!("warning", warning_color),
+    token!("danger", danger_color),
+    token!("selection", selection_color),
+    token!("button_bg", button_bg_color),
+    token!("button_bg_hover", button_bg_hover_color),
+    token!("button_bg_active", button_bg_active_color),
+    token!("button_border", button_border_color),
+    token!("input_bg", input_bg_color),
+    token!("input_border", input_border_color),
+    token!("input_border_hover", input_border_hover_color),
+    token!("input_border_focused", input_border_focused_color),
+    token!("input_text", input_text_color),
+    token!("input_placeholder", input_placeholder_color),
+    token!("input_selection", input_selection_color),
+    token!("input_cursor", input_cursor_color),
+    token!("overlay", overlay_color),
+    token!("badge_blue", badge_blue_color),
+    token!("badge_gold", badge_gold_color),
+    token!("badge_red", badge_red_color),
+    token!("badge_green", badge_green_color),
+    token!("badge_teal", badge_teal_color),
+    token!("badge_amber", badge_amber_color),
+    token!("badge_gray", badge_gray_color),
+];
+
+/// Every key a theme file's `colors` table may contain: the five primitives,
+/// then every optional override.
+pub const COLOR_KEYS: [&str; 39] = {
+    let mut keys = [""; 39];
+    let mut i = 0;
+    while i < PRIMITIVE_KEYS.len() {
+        keys[i] = PRIMITIVE_KEYS[i];
+        i += 1;
+    }
+    let mut j = 0;
+    while j < TOKENS.len() {
+        keys[i + j] = TOKENS[j].key;
+        j += 1;
+    }
+    keys
+};
+
+/// The primitive a key names, read or written.
+pub(crate) fn primitive<'a>(theme: &'a mut Theme, key: &str) -> Option<&'a mut Hsla> {
+    match key {
+        "fg" => Some(&mut theme.fg_color),
+        "bg" => Some(&mut theme.bg_color),
+        "surface" => Some(&mut theme.surface_color),
+        "border" => Some(&mut theme.border_color),
+        "accent" => Some(&mut theme.accent_color),
+        _ => None,
+    }
+}
+
+/// The optional override slot a key names.
+pub(crate) fn override_slot<'a>(theme: &'a mut Theme, key: &str) -> Option<&'a mut Option<Hsla>> {
+    TOKENS
+        .iter()
+        .find(|token| token.key == key)
+        .map(|token| (token.slot)(theme))
+}
+
+/// Every override a theme sets, by key.
+pub(crate) fn overrides(theme: &Theme) -> impl Iterator<Item = (&'static str, Hsla)> + '_ {
+    TOKENS
+        .iter()
+        .filter_map(|token| (token.get)(theme).map(|color| (token.key, color)))
+}
+
+/// Why a theme file could not be read or written.
+///
+/// Every variant about a colour names the key it was found under, and the
+/// ones about a value carry the string as written — the two things a designer
+/// needs to find the line.
+#[derive(Debug)]
+pub enum ThemeFileError {
+    /// The document is not valid JSON or TOML, or is missing `name` or
+    /// `colors`. The message is the parser's own, with its line and column.
+    Syntax(String),
+    /// One of the five primitives is absent.
+    MissingColor {
+        /// The primitive's key.
+        key: &'static str,
+    },
+    /// A key under `colors` that no token answers to.
+    UnknownColor {
+        /// The key as written.
+        key: String,
+    },
+    /// A colour string that is not `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`.
+    InvalidColor {
+        /// The token the string was given for.
+        key: String,
+        /// The string as written.
+        value: String,
+    },
+    /// Reading or writing the file itself failed.
+    Io {
+        /// The file.
+        path: PathBuf,
+        /// What the filesystem said.
+        source: std::io::Error,
+    },
+}
+
+impl fmt::Display for ThemeFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeFileError::Syntax(message) => write!(f, "invalid theme file: {message}"),
+            ThemeFileError::MissingColor { key } => {
+                write!(f, "theme file is missing the required colour `{key}`")
+            }
+            ThemeFileError::UnknownColor { key } => {
+                write!(f, "theme file sets `{key}`, which is not a theme colour")
+            }
+            ThemeFileError::InvalidColor { key, value } => write!(
+                f,
+                "theme colour `{key}` is `{value}`, which is not #rgb, #rgba, #rrggbb or #rrggbbaa"
+            ),
+            ThemeFileError::Io { path, source } => {
+                write!(f, "theme file {}: {source}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ThemeFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ThemeFileError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// A theme as it is written to disk.
+///
+/// The serde-facing half of the format: [`ThemeFile::into_theme`] is where a
+/// colour string becomes an `Hsla` and where the errors that name a key come
+/// from. Public so that an app can embed a theme inside a settings document of
+/// its own and still get the same validation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeFile {
+    /// The theme's display name.
+    pub name: String,
+    /// `"dark"` or `"light"`. Defaults to dark, as [`ThemeVariant`] does.
+    #[serde(default)]
+    pub variant: ThemeVariant,
+    /// Colour strings keyed by [`COLOR_KEYS`].
+    pub colors: BTreeMap<String, String>,
+    /// The control size scale. A theme file that leaves it out gets the
+    /// crate's scale, and one that gives only some rungs gets the crate's
+    /// value for the others.
+    #[serde(default)]
+    pub controls: ControlScale,
+    /// The type scale. Left out, the crate's; partly given, the crate's value
+    /// for every field not stated.
+    #[serde(default)]
+    pub typography: TypeScale,
+    /// Radii, spacing and shadows, defaulted field by field like `typography`.
+    #[serde(default)]
+    pub shape: Shape,
+}
+
+impl ThemeFile {
+    /// The file form of `theme`. Only the overrides the theme actually sets
+    /// are written, so a file saved from a minimal theme stays minimal.
+    pub fn from_theme(theme: &Theme) -> Self {
+        let mut colors = BTreeMap::new();
+        colors.insert("fg".to_string(), to_hex(theme.fg_color));
+        colors.insert("bg".to_string(), to_hex(theme.bg_color));
+        colors.insert("surface".to_string(), to_hex(theme.surface_color));
+        colors.insert("border".to_string(), to_hex(theme.border_color));
+        colors.insert("accent".to_string(), to_hex(theme.accent_color));
+        for (key, color) in overrides(theme) {
+            colors.insert(key.to_string(), to_hex(color));
+        }
+
+        ThemeFile {
+            name: theme.name.to_string(),
+            variant: theme.variant,
+            colors,
+            controls: theme.controls,
+            typography: theme.typography.clone(),
+            shape: theme.shape.clone(),
+        }
+    }
+
+    /// The theme this file describes.
+    ///
+    /// Fails on the first unknown key or unreadable colour in key order, and
+    /// then on the first missing primitive.
+    pub fn into_theme(self) -> Result<Theme, ThemeFileError> {
+        let black = gpui::black();
+        let mut theme = Theme::new(self.name, self.variant, black, black, black, black, black);
+        theme.controls = self.controls;
+        theme.typography = self.typography;
+        theme.shape = self.shape;
+
+        for (key, value) in &self.colors {
+            let color = parse_color(key, value)?;
+            if let Some(slot) = primitive(&mut theme, key) {
+                *slot = color;
+            } else if let Some(slot) = override_slot(&mut theme, key) {
+                *slot = Some(color);
+            } else {
+                return Err(ThemeFileError::UnknownColor { key: key.clone() });
+            }
+        }
+
+        if let Some(key) = PRIMITIVE_KEYS
+            .into_iter()
+            .find(|key| !self.colors.contains_key(*key))
+        {
+            return Err(ThemeFileError::MissingColor { key });
+        }
+
+        Ok(theme)
+    }
+}
+
+/// Read one colour string, naming `key` if it cannot be read.
+///
+/// gpui's `Rgba` parser rather than [`parse_hex`](super::parse_hex): it
+/// accepts the short and alpha forms, and it says no instead of falling back
+/// to black.
+pub(crate) fn parse_color(key: &str, value: &str) -> Result<Hsla, ThemeFileError> {
+    Rgba::try_from(value)
+        .map(Hsla::from)
+        .map_err(|_| ThemeFileError::InvalidColor {
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+}
+
+/// `#rrggbb`, or `#rrggbbaa` when the colour is not opaque.
+///
+/// gpui's own `Serialize` for `Hsla` always writes the alpha byte; a theme
+/// file is read by people, and `ff` on every line is noise.
+pub fn to_hex(color: Hsla) -> String {
+    let rgba = Rgba::from(color);
+    let byte = |channel: f32| (channel * 255.0).round() as u8;
+    let (r, g, b, a) = (byte(rgba.r), byte(rgba.g), byte(rgba.b), byte(rgba.a));
+    if a == u8::MAX {
+        format!("#{r:02x}{g:02x}{b:02x}")
+    } else {
+        format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+    }
+}
+
+impl Theme {
+    /// Read a theme from a JSON theme file's contents.
+    pub fn from_json(json: &str) -> Result<Theme, ThemeFileError> {
+        serde_json::from_str::<ThemeFile>(json)
+            .map_err(|error| ThemeFileError::Syntax(error.to_string()))?
+            .into_theme()
+    }
+
+    /// This theme as a JSON theme file, pretty-printed.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&ThemeFile::from_theme(self))
+            .expect("a theme file is strings, a map and numbers, all representable as JSON")
+    }
+
+    /// Read a theme from a TOML theme file's contents.
+    pub fn from_toml(toml: &str) -> Result<Theme, ThemeFileError> {
+        toml::from_str::<ThemeFile>(toml)
+            .map_err(|error| ThemeFileError::Syntax(error.to_string()))?
+            .into_theme()
+    }
+
+    /// This theme as a TOML theme file.
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(&ThemeFile::from_theme(self))
+            .expect("a theme file is strings, a map and numbers, all representable as TOML")
+    }
+
+    /// Read a theme file from disk: TOML if the path ends in `.toml`, JSON
+    /// otherwise.
+    pub fn load(path: impl AsRef<Path>) -> Result<Theme, ThemeFileError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|source| ThemeFileError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        if is_toml(path) {
+            Theme::from_toml(&contents)
+        } else {
+            Theme::from_json(&contents)
+        }
+    }
+
+    /// Write this theme to disk, in the format [`Theme::load`] would read
+    /// back from the same path.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ThemeFileError> {
+        let path = path.as_ref();
+        let contents = if is_toml(path) {
+            self.to_toml()
+        } else {
+            self.to_json()
+        };
+        std::fs::write(path, contents).map_err(|source| ThemeFileError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+pub(crate) fn is_toml(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::{ControlSize, Themeable};
+
+    const MINIMAL: &str = r##"{
+        "name": "Paper",
+        "variant": "light",
+        "colors": {
+            "fg": "#1f1f1f",
+            "bg": "#fafafa",
+            "surface": "#f0f0f0",
+            "border": "#d0d0d0",
+            "accent": "#3367d6"
+        }
+    }"##;
+
+    /// `Hsla` goes through float conversions both ways, so themes are compared
+    /// as the hex strings a file would hold.
+    fn hex_colors(theme: &Theme) -> BTreeMap<String, String> {
+        ThemeFile::from_theme(theme).colors
+    }
+
+    #[test]
+    fn a_file_with_only_primitives_derives_the_rest() {
+        let theme = Theme::from_json(MINIMAL).unwrap();
+
+        assert_eq!(theme.name.as_ref(), "Paper");
+        assert_eq!(theme.variant, ThemeVariant::Light);
+        assert_eq!(to_hex(theme.fg()), "#1f1f1f");
+        assert_eq!(theme.fg_muted_color, None);
+        assert_eq!(theme.button_bg(), theme.surface());
+        assert_eq!(theme.controls, ControlScale::default());
+    }
+
+    #[test]
+    fn every_built_in_theme_round_trips_through_json_and_toml() {
+        for theme in [
+            Theme::gruvbox_dark(),
+            Theme::gruvbox_light(),
+            Theme::catppuccin_latte(),
+            Theme::catppuccin_frappe(),
+            Theme::catppuccin_macchiato(),
+            Theme::catppuccin_mocha(),
+        ] {
+            let from_json = Theme::from_json(&theme.to_json()).unwrap();
+            let from_toml = Theme::from_toml(&theme.to_toml()).unwrap();
+
+            for read in [&from_json, &from_toml] {
+                assert_eq!(read.name, theme.name);
+                assert_eq!(read.variant, theme.variant);
+                assert_eq!(hex_colors(read), hex_colors(&theme), "{}", theme.name);
+                assert_eq!(read.controls, theme.controls);
+            }
+        }
+    }
+
+    /// The alpha survives: gruvbox's selection is a translucent yellow.
+    #[test]
+    fn a_translucent_colour_keeps_its_alpha() {
+        let theme = Theme::from_json(&Theme::gruvbox_dark().to_json()).unwrap();
+        let selection = theme.selection_color.unwrap();
+        assert!((selection.a - 0.25).abs() < 0.01);
+
+        assert_eq!(to_hex(gpui::hsla(0.0, 1.0, 0.5, 1.0)), "#ff0000");
+        assert_eq!(to_hex(gpui::hsla(0.0, 1.0, 0.5, 0.5)), "#ff000080");
+    }
+
+    #[test]
+    fn an_invalid_colour_names_its_key_and_its_string() {
+        let json = MINIMAL.replace(r##""accent": "#3367d6""##, r##""accent": "#33zz""##);
+        let error = Theme::from_json(&json).unwrap_err();
+
+        match &error {
+            ThemeFileError::InvalidColor { key, value } => {
+                assert_eq!(key, "accent");
+                assert_eq!(value, "#33zz");
+            }
+            other => panic!("expected InvalidColor, got {other:?}"),
+        }
+        let message = error.to_string();
+        assert!(
+            message.contains("accent") && message.contains("#33zz"),
+            "{message}"
+        );
+    }
+
+    /// The short string that made `parse_hex` slice out of bounds.
+    #[test]
+    fn a_too_short_colour_is_an_error_rather_than_a_panic() {
+        let json = MINIMAL.replace(r##""fg": "#1f1f1f""##, r##""fg": "#1""##);
+        assert!(matches!(
+            Theme::from_json(&json),
+            Err(ThemeFileError::InvalidColor { key, .. }) if key == "fg"
+        ));
+    }
+
+    #[test]
+    fn a_missing_primitive_is_named() {
+        let json = MINIMAL.replace(r##""border": "#d0d0d0","##, "");
+        assert!(matches!(
+            Theme::from_json(&json),
+            Err(ThemeFileError::MissingColor { key: "border" })
+        ));
+    }
+
+    #[test]
+    fn an_unknown_key_is_an_error_not_a_silent_no_op() {
+        let json = MINIMAL.replace(
+            r##""fg": "#1f1f1f","##,
+            r##""fg": "#1f1f1f", "fg_mutted": "#5f5f5f","##,
+        );
+        assert!(matches!(
+            Theme::from_json(&json),
+            Err(ThemeFileError::UnknownColor { key }) if key == "fg_mutted"
+        ));
+    }
+
+    #[test]
+    fn a_syntax_error_reports_the_parsers_position() {
+        let error = Theme::from_json("{ \"name\": ").unwrap_err();
+        assert!(matches!(error, ThemeFileError::Syntax(_)));
+        assert!(error.to_string().contains("line 1"), "{error}");
+    }
+
+    #[test]
+    fn a_partial_control_scale_keeps_the_crates_other_rungs() {
+        let toml = r##"
+            name = "Roomy"
+
+            [colors]
+            fg = "#ffffff"
+            bg = "#000000"
+            surface = "#111111"
+            border = "#333333"
+            accent = "#0066cc"
+
+            [controls.large]
+            height = 2.0
+            padding_x = 0.75
+            gap = 0.5
+            radius = 0.5
+            text_size = 1.0
+            line_height = 1.25
+            ink = 1.25
+        "##;
+        let theme = Theme::from_toml(toml).unwrap();
+
+        assert_eq!(theme.variant, ThemeVariant::Dark);
+        assert_eq!(theme.control(ControlSize::Large).height.0, 2.0);
+        assert_eq!(theme.controls.small, ControlScale::default().small);
+    }
+
+    #[test]
+    fn every_key_is_listed_once() {
+        let mut keys = COLOR_KEYS.to_vec();
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), COLOR_KEYS.len());
+        assert!(!keys.contains(&""));
+    }
+
+    #[test]
+    fn load_and_save_pick_the_format_from_the_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let theme = Theme::catppuccin_mocha();
+
+        for name in ["mocha.json", "mocha.toml"] {
+            let path = dir.path().join(name);
+            theme.save(&path).unwrap();
+            let written = std::fs::read_to_string(&path).unwrap();
+            assert_eq!(
+                written.trim_start().starts_with('{'),
+                name.ends_with(".json")
+            );
+            assert_eq!(hex_colors(&Theme::load(&path).unwrap()), hex_colors(&theme));
+        }
+
+        assert!(matches!(
+            Theme::load(dir.path().join("absent.json")),
+            Err(ThemeFileError::Io { .. })
+        ));
+    }
+}
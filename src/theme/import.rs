@@ -0,0 +1,715 @@
+//! Importing editor themes: VS Code's `colors` JSON and Zed's theme schema.
+//!
+//! People already have an editor theme they like, and it already says what
+//! their background, borders, inputs and error colour are. [`from_vscode`] and
+//! [`from_zed`] map those keys onto [`Theme`]'s tokens — `editor.background`
+//! to `bg`, `focusBorder` to `accent`, `input.*` to the `input_*` overrides and
+//! so on — and say what they could not do:
+//!
+//! - [`ImportedTheme::unmapped`] lists the gpuikit tokens the source had
+//!   nothing for. An unmapped override is left `None` and derives as usual;
+//!   an unmapped *primitive* is taken from the built-in Gruvbox theme of the
+//!   same variant, since a theme cannot be built without one.
+//! - [`ImportedTheme::ignored`] lists the source keys nothing read — the
+//!   terminal palette beyond the badge colours, the minimap, the diff editor.
+//!   It is long for any real VS Code theme, and that is expected.
+//!
+//! Each token lists the source keys that can supply it in order of
+//! preference, so a theme that sets `sideBar.background` and one that only
+//! sets `editorWidget.background` both get a surface.
+//!
+//! # JSON with comments
+//!
+//! VS Code's theme files are JSONC: comments and trailing commas are allowed,
+//! and many published themes use both. They are stripped before parsing.
+//!
+//! # Syntax colours
+//!
+//! With the `editor` feature, [`ImportedTheme::syntax`] is a syntect theme
+//! built from the source's own syntax colours — VS Code's `tokenColors`, Zed's
+//! `syntax` — so code is highlighted the way the editor highlights it. Hand the
+//! import to `SyntaxHighlighter::use_imported_theme`, which falls back to the
+//! nearest of syntect's bundled themes when the source has no syntax colours.
+
+use std::collections::BTreeSet;
+
+use gpui::Hsla;
+use serde_json::{Map, Value};
+
+use super::contrast::relative_luminance;
+use super::file::{self, parse_color, ThemeFileError};
+use super::{Theme, ThemeVariant};
+
+/// A theme read from another editor's format, and what did not carry over.
+#[derive(Debug, Clone)]
+pub struct ImportedTheme {
+    /// The theme, named as the source named it.
+    pub theme: Theme,
+    /// gpuikit colour keys, as in [`file::COLOR_KEYS`], that no source key
+    /// supplied.
+    pub unmapped: Vec<&'static str>,
+    /// Source keys that no gpuikit token read, sorted.
+    pub ignored: Vec<String>,
+    /// The source's syntax colours as a syntect theme, when it has any.
+    #[cfg(feature = "editor")]
+    pub syntax: Option<syntect::highlighting::Theme>,
+}
+
+/// VS Code `colors` keys for each gpuikit token, most preferred first.
+const VSCODE: &[(&str, &[&str])] = &[
+    ("fg", &["editor.foreground", "foreground"]),
+    ("bg", &["editor.background"]),
+    (
+        "surface",
+        &[
+            "sideBar.background",
+            "panel.background",
+            "editorWidget.background",
+        ],
+    ),
+    (
+        "border",
+        &[
+            "panel.border",
+            "editorGroup.border",
+            "sideBar.border",
+            "contrastBorder",
+        ],
+    ),
+    (
+        "accent",
+        &["focusBorder", "button.background", "textLink.foreground"],
+    ),
+    ("fg_muted", &["descriptionForeground"]),
+    ("fg_disabled", &["disabledForeground"]),
+    (
+        "surface_secondary",
+        &["editorWidget.background", "dropdown.background"],
+    ),
+    ("surface_tertiary", &["list.hoverBackground"]),
+    ("border_secondary", &["widget.border"]),
+    (
+        "border_subtle",
+        &[
+            "editorIndentGuide.background1",
+            "editorIndentGuide.background",
+        ],
+    ),
+    ("outline", &["focusBorder"]),
+    ("accent_bg", &["list.activeSelectionBackground"]),
+    ("accent_bg_hover", &["list.hoverBackground"]),
+    ("info", &["editorInfo.foreground"]),
+    (
+        "success",
+        &[
+            "gitDecoration.addedResourceForeground",
+            "terminal.ansiGreen",
+        ],
+    ),
+    ("warning", &["editorWarning.foreground"]),
+    ("danger", &["editorError.foreground", "errorForeground"]),
+    ("selection", &["editor.selectionBackground"]),
+    ("button_bg", &["button.secondaryBackground"]),
+    ("button_bg_hover", &["button.secondaryHoverBackground"]),
+    ("button_border", &["button.border"]),
+    ("input_bg", &["input.background"]),
+    ("input_border", &["input.border"]),
+    (
+        "input_border_focused",
+        &["inputOption.activeBorder", "focusBorder"],
+    ),
+    ("input_text", &["input.foreground"]),
+    ("input_placeholder", &["input.placeholderForeground"]),
+    ("input_selection", &["editor.selectionBackground"]),
+    ("input_cursor", &["editorCursor.foreground"]),
+    ("badge_blue", &["terminal.ansiBlue"]),
+    ("badge_gold", &["terminal.ansiYellow"]),
+    ("badge_red", &["terminal.ansiRed"]),
+    ("badge_green", &["terminal.ansiGreen"]),
+    ("badge_teal", &["terminal.ansiCyan"]),
+    ("badge_amber", &["terminal.ansiBrightYellow"]),
+    ("badge_gray", &["terminal.ansiBrightBlack"]),
+];
+
+/// Zed `style` keys for each gpuikit token, most preferred first. The first
+/// player's colours are flattened to `players.cursor` and `players.selection`.
+const ZED: &[(&str, &[&str])] = &[
+    ("fg", &["text", "editor.foreground"]),
+    ("bg", &["editor.background", "background"]),
+    (
+        "surface",
+        &["surface.background", "elevated_surface.background"],
+    ),
+    ("border", &["border"]),
+    ("accent", &["text.accent", "border.focused"]),
+    ("fg_muted", &["text.muted"]),
+    ("fg_disabled", &["text.disabled"]),
+    ("surface_secondary", &["elevated_surface.background"]),
+    ("surface_tertiary", &["element.selected"]),
+    ("border_secondary", &["border.selected"]),
+    ("border_subtle", &["border.variant"]),
+    ("outline", &["border.focused"]),
+    ("accent_bg", &["element.selected"]),
+    ("accent_bg_hover", &["ghost_element.hover"]),
+    ("info", &["info"]),
+    ("success", &["success", "created"]),
+    ("warning", &["warning"]),
+    ("danger", &["error"]),
+    ("selection", &["players.selection"]),
+    ("button_bg", &["element.background"]),
+    ("button_bg_hover", &["element.hover"]),
+    ("button_bg_active", &["element.active"]),
+    ("button_border", &["border"]),
+    ("input_bg", &["editor.background"]),
+    ("input_border", &["border"]),
+    ("input_border_hover", &["border.selected"]),
+    ("input_border_focused", &["border.focused"]),
+    ("input_text", &["text", "editor.foreground"]),
+    ("input_placeholder", &["text.placeholder"]),
+    ("input_selection", &["players.selection"]),
+    ("input_cursor", &["players.cursor"]),
+    ("badge_blue", &["terminal.ansi.blue"]),
+    ("badge_gold", &["terminal.ansi.yellow"]),
+    ("badge_red", &["terminal.ansi.red"]),
+    ("badge_green", &["terminal.ansi.green"]),
+    ("badge_teal", &["terminal.ansi.cyan"]),
+    ("badge_amber", &["terminal.ansi.bright_yellow"]),
+    ("badge_gray", &["terminal.ansi.bright_black"]),
+];
+
+/// Import a VS Code colour theme — the JSON a theme extension ships, with
+/// `name`, `type`, `colors` and optionally `tokenColors`.
+pub fn from_vscode(json: &str) -> Result<ImportedTheme, ThemeFileError> {
+    let document = parse(json)?;
+    let name = document
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or("Imported");
+    let colors = document
+        .get("colors")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    let variant = match document.get("type").and_then(Value::as_str) {
+        Some("light" | "hcLight") => Some(ThemeVariant::Light),
+        Some("dark" | "hc" | "hcDark") => Some(ThemeVariant::Dark),
+        _ => None,
+    };
+
+    #[allow(unused_mut)]
+    let mut imported = map(name, variant, &colors, VSCODE)?;
+    #[cfg(feature = "editor")]
+    {
+        imported.syntax = document
+            .get("tokenColors")
+            .and_then(Value::as_array)
+            .map(|rules| syntax::from_token_colors(name, rules));
+    }
+    Ok(imported)
+}
+
+/// Import a Zed theme family — `{ "name", "themes": [{ "name", "appearance",
+/// "style" }] }` — as one theme per member, in the family's order.
+pub fn from_zed(json: &str) -> Result<Vec<ImportedTheme>, ThemeFileError> {
+    let document = parse(json)?;
+    let themes = document
+        .get("themes")
+        .and_then(Value::as_array)
+        .ok_or_else(|| ThemeFileError::Syntax("a Zed theme family has a `themes` array".into()))?;
+
+    themes
+        .iter()
+        .map(|member| {
+            let name = member
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or("Imported");
+            let variant = match member.get("appearance").and_then(Value::as_str) {
+                Some("light") => Some(ThemeVariant::Light),
+                Some("dark") => Some(ThemeVariant::Dark),
+                _ => None,
+            };
+            let style = member
+                .get("style")
+                .and_then(Value::as_object)
+                .cloned()
+                .unwrap_or_default();
+
+            let mut colors = Map::new();
+            for (key, value) in &style {
+                match (key.as_str(), value) {
+                    ("players", Value::Array(players)) => {
+                        // The local user is the first player.
+                        if let Some(Value::Object(player)) = players.first() {
+                            for (field, color) in player {
+                                colors.insert(format!("players.{field}"), color.clone());
+                            }
+                        }
+                    }
+                    // Syntax colours are the `editor` feature's business, and
+                    // not a `style` colour left unread.
+                    ("syntax", _) => {}
+                    _ => {
+                        colors.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+
+            #[allow(unused_mut)]
+            let mut imported = map(name, variant, &colors, ZED)?;
+            #[cfg(feature = "editor")]
+            {
+                imported.syntax = style
+                    .get("syntax")
+                    .and_then(Value::as_object)
+                    .map(|syntax| syntax::from_zed_syntax(name, syntax));
+            }
+            Ok(imported)
+        })
+        .collect()
+}
+
+/// Parse JSON, tolerating the comments and trailing commas JSONC allows.
+fn parse(json: &str) -> Result<Map<String, Value>, ThemeFileError> {
+    serde_json::from_str::<Map<String, Value>>(&strip_jsonc(json))
+        .map_err(|error| ThemeFileError::Syntax(error.to_string()))
+}
+
+/// `json` without `//` and `/* */` comments or trailing commas, outside
+/// strings. Newlines are kept, so the parser's line numbers still match the
+/// file.
+fn strip_jsonc(json: &str) -> String {
+    // Comments first, so a comma followed by one is still seen as trailing.
+    let mut uncommented = String::with_capacity(json.len());
+    let mut chars = json.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            uncommented.push(c);
+            match c {
+                '\\' => uncommented.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        uncommented.push(c);
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut last = '\0';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        uncommented.push(c);
+                    }
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            _ => {
+                in_string = c == '"';
+                uncommented.push(c);
+            }
+        }
+    }
+
+    let mut out = String::with_capacity(uncommented.len());
+    let mut chars = uncommented.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        let trailing =
+            c == ',' && matches!(chars.clone().find(|c| !c.is_whitespace()), Some('}' | ']'));
+        if !trailing {
+            in_string = c == '"';
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Build a theme from `colors` through one format's table.
+fn map(
+    name: &str,
+    variant: Option<ThemeVariant>,
+    colors: &Map<String, Value>,
+    table: &[(&'static str, &[&str])],
+) -> Result<ImportedTheme, ThemeFileError> {
+    let mut used = BTreeSet::new();
+    let mut found = Vec::new();
+    for &(token, sources) in table {
+        let hit = sources.iter().find_map(|source| {
+            colors
+                .get(*source)
+                .and_then(Value::as_str)
+                .map(|value| (*source, value))
+        });
+        if let Some((source, value)) = hit {
+            found.push((token, parse_color(source, value)?));
+            used.insert(source);
+        }
+    }
+
+    let lookup = |key: &str| {
+        found
+            .iter()
+            .find(|(token, _)| *token == key)
+            .map(|(_, color)| *color)
+    };
+    // The variant the source declares, else the one its background implies,
+    // else dark.
+    let variant = variant
+        .or_else(|| {
+            lookup("bg").map(|bg| {
+                if relative_luminance(bg) < 0.18 {
+                    ThemeVariant::Dark
+                } else {
+                    ThemeVariant::Light
+                }
+            })
+        })
+        .unwrap_or_default();
+    let base = match variant {
+        ThemeVariant::Dark => Theme::gruvbox_dark(),
+        ThemeVariant::Light => Theme::gruvbox_light(),
+    };
+
+    let mut theme = Theme::new(
+        name.to_string(),
+        variant,
+        base.fg_color,
+        base.bg_color,
+        base.surface_color,
+        base.border_color,
+        base.accent_color,
+    );
+    for &(token, color) in &found {
+        set(&mut theme, token, color);
+    }
+
+    let unmapped = file::COLOR_KEYS
+        .into_iter()
+        .filter(|key| lookup(key).is_none())
+        .collect();
+    let ignored = colors
+        .iter()
+        .filter(|(key, value)| !value.is_null() && !used.contains(key.as_str()))
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    Ok(ImportedTheme {
+        theme,
+        unmapped,
+        ignored,
+        #[cfg(feature = "editor")]
+        syntax: None,
+    })
+}
+
+/// Write one token by its key.
+fn set(theme: &mut Theme, key: &str, color: Hsla) {
+    if let Some(primitive) = file::primitive(theme, key) {
+        *primitive = color;
+    } else if let Some(slot) = file::override_slot(theme, key) {
+        *slot = Some(color);
+    }
+}
+
+/// Syntax colours as a syntect theme.
+#[cfg(feature = "editor")]
+mod syntax {
+    use std::str::FromStr;
+
+    use gpui::Rgba;
+    use serde_json::{Map, Value};
+    use syntect::highlighting::{
+        Color, FontStyle, ScopeSelectors, StyleModifier, Theme, ThemeItem, ThemeSettings,
+    };
+
+    /// TextMate scopes for each of Zed's syntax keys.
+    const ZED_SCOPES: &[(&str, &str)] = &[
+        ("attribute", "entity.other.attribute-name"),
+        ("boolean", "constant.language.boolean"),
+        ("comment", "comment"),
+        ("comment.doc", "comment.block.documentation"),
+        ("constant", "constant"),
+        ("constructor", "entity.name.function.constructor"),
+        ("emphasis", "markup.italic"),
+        ("emphasis.strong", "markup.bold"),
+        ("enum", "entity.name.enum"),
+        ("function", "entity.name.function, support.function"),
+        ("keyword", "keyword, storage.modifier"),
+        ("label", "entity.name.label"),
+        ("link_uri", "markup.underline.link"),
+        ("number", "constant.numeric"),
+        ("operator", "keyword.operator"),
+        ("property", "variable.other.property, variable.other.member"),
+        ("punctuation", "punctuation"),
+        ("string", "string"),
+        ("string.escape", "constant.character.escape"),
+        ("string.regex", "string.regexp"),
+        ("tag", "entity.name.tag"),
+        ("title", "markup.heading"),
+        ("type", "entity.name.type, support.type, storage.type"),
+        ("variable", "variable"),
+        ("variable.special", "variable.language"),
+    ];
+
+    /// A syntect theme from VS Code's `tokenColors`, which are TextMate rules
+    /// already: a `scope` string or list, and `settings` with `foreground`,
+    /// `background` and `fontStyle`. A rule with no scope is the defaults.
+    pub(super) fn from_token_colors(name: &str, rules: &[Value]) -> Theme {
+        let mut theme = Theme {
+            name: Some(name.to_string()),
+            ..Default::default()
+        };
+        for rule in rules {
+            let Some(settings) = rule.get("settings") else {
+                continue;
+            };
+            let style = StyleModifier {
+                foreground: color(settings.get("foreground")),
+                background: color(settings.get("background")),
+                font_style: settings
+                    .get("fontStyle")
+                    .and_then(Value::as_str)
+                    .map(font_style),
+            };
+            let scope = match rule.get("scope") {
+                Some(Value::String(scope)) => Some(scope.clone()),
+                Some(Value::Array(scopes)) => Some(
+                    scopes
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+                _ => None,
+            };
+            match scope {
+                Some(scope) => push(&mut theme, &scope, style),
+                None => {
+                    theme.settings = ThemeSettings {
+                        foreground: style.foreground,
+                        background: style.background,
+                        ..theme.settings
+                    }
+                }
+            }
+        }
+        theme
+    }
+
+    /// A syntect theme from Zed's `syntax` map of highlight names to
+    /// `{ color, font_style, font_weight }`.
+    pub(super) fn from_zed_syntax(name: &str, syntax: &Map<String, Value>) -> Theme {
+        let mut theme = Theme {
+            name: Some(name.to_string()),
+            ..Default::default()
+        };
+        for (key, scope) in ZED_SCOPES {
+            let Some(entry) = syntax.get(*key) else {
+                continue;
+            };
+            let mut font = FontStyle::empty();
+            if entry.get("font_style").and_then(Value::as_str) == Some("italic") {
+                font |= FontStyle::ITALIC;
+            }
+            if entry
+                .get("font_weight")
+                .and_then(Value::as_f64)
+                .is_some_and(|weight| weight >= 600.0)
+            {
+                font |= FontStyle::BOLD;
+            }
+            let style = StyleModifier {
+                foreground: color(entry.get("color")),
+                background: None,
+                font_style: (!font.is_empty()).then_some(font),
+            };
+            push(&mut theme, scope, style);
+        }
+        theme
+    }
+
+    /// Add a rule, skipping a selector syntect cannot parse rather than
+    /// failing the import over one rule.
+    fn push(theme: &mut Theme, scope: &str, style: StyleModifier) {
+        if let Ok(scope) = ScopeSelectors::from_str(scope) {
+            theme.scopes.push(ThemeItem { scope, style });
+        }
+    }
+
+    fn color(value: Option<&Value>) -> Option<Color> {
+        let rgba = Rgba::try_from(value?.as_str()?).ok()?;
+        let byte = |channel: f32| (channel * 255.0).round() as u8;
+        Some(Color {
+            r: byte(rgba.r),
+            g: byte(rgba.g),
+            b: byte(rgba.b),
+            a: byte(rgba.a),
+        })
+    }
+
+    fn font_style(style: &str) -> FontStyle {
+        let mut font = FontStyle::empty();
+        for word in style.split_whitespace() {
+            match word {
+                "bold" => font |= FontStyle::BOLD,
+                "italic" => font |= FontStyle::ITALIC,
+                "underline" => font |= FontStyle::UNDERLINE,
+                _ => {}
+            }
+        }
+        font
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::file::to_hex;
+    use crate::theme::Themeable;
+
+    const VSCODE_THEME: &str = r##"{
+        // Comments and trailing commas, as published themes have them.
+        "name": "Harbour",
+        "type": "dark",
+        "colors": {
+            "editor.background": "#1e2127",
+            "editor.foreground": "#abb2bf",
+            "sideBar.background": "#21252b",
+            "focusBorder": "#528bff",
+            "input.background": "#1b1d23",
+            "input.placeholderForeground": "#5c6370aa",
+            "editorError.foreground": "#e06c75",
+            "minimap.background": "#1e2127", /* unused by gpuikit */
+        },
+        "tokenColors": [
+            { "settings": { "foreground": "#abb2bf" } },
+            { "scope": ["keyword", "storage"], "settings": { "foreground": "#c678dd", "fontStyle": "italic" } },
+        ],
+    }"##;
+
+    #[test]
+    fn vscode_keys_map_onto_tokens() {
+        let imported = from_vscode(VSCODE_THEME).unwrap();
+        let theme = &imported.theme;
+
+        assert_eq!(theme.name.as_ref(), "Harbour");
+        assert_eq!(theme.variant, ThemeVariant::Dark);
+        assert_eq!(to_hex(theme.bg()), "#1e2127");
+        assert_eq!(to_hex(theme.fg()), "#abb2bf");
+        assert_eq!(to_hex(theme.surface()), "#21252b");
+        assert_eq!(to_hex(theme.accent()), "#528bff");
+        assert_eq!(to_hex(theme.outline()), "#528bff");
+        assert_eq!(to_hex(theme.input_bg()), "#1b1d23");
+        assert_eq!(to_hex(theme.danger()), "#e06c75");
+        assert!((theme.input_placeholder().a - 0xaa as f32 / 255.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn what_did_not_carry_over_is_reported() {
+        let imported = from_vscode(VSCODE_THEME).unwrap();
+
+        // `border` had no source, so it came from the dark built-in.
+        assert!(imported.unmapped.contains(&"border"));
+        assert_eq!(imported.theme.border(), Theme::gruvbox_dark().border());
+        assert!(!imported.unmapped.contains(&"bg"));
+        assert_eq!(imported.ignored, ["minimap.background"]);
+    }
+
+    #[test]
+    fn a_bad_colour_names_its_source_key() {
+        let error = from_vscode(r##"{"colors": {"editor.background": "navy"}}"##).unwrap_err();
+        assert!(
+            matches!(&error, ThemeFileError::InvalidColor { key, value }
+                if key == "editor.background" && value == "navy"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn without_a_type_the_variant_follows_the_background() {
+        let imported = from_vscode(r##"{"colors": {"editor.background": "#fafafa"}}"##).unwrap();
+        assert_eq!(imported.theme.variant, ThemeVariant::Light);
+    }
+
+    #[test]
+    fn a_zed_family_imports_every_member() {
+        let family = r##"{
+            "name": "One",
+            "themes": [
+                {
+                    "name": "One Dark",
+                    "appearance": "dark",
+                    "style": {
+                        "background": "#3b414d",
+                        "editor.background": "#282c33",
+                        "text": "#dce0e5",
+                        "text.placeholder": "#878a98",
+                        "border.focused": "#47679e",
+                        "background.appearance": "opaque",
+                        "players": [{ "cursor": "#74ade8", "selection": "#74ade83d" }],
+                        "syntax": { "keyword": { "color": "#b477cf", "font_style": null, "font_weight": null } }
+                    }
+                },
+                { "name": "One Light", "appearance": "light", "style": { "editor.background": "#fafafa" } }
+            ]
+        }"##;
+
+        let themes = from_zed(family).unwrap();
+
+        assert_eq!(themes.len(), 2);
+        let dark = &themes[0].theme;
+        assert_eq!(to_hex(dark.bg()), "#282c33");
+        assert_eq!(to_hex(dark.input_placeholder()), "#878a98");
+        assert_eq!(to_hex(dark.input_cursor()), "#74ade8");
+        assert_eq!(to_hex(dark.accent()), "#47679e");
+        assert!(themes[0].ignored.contains(&"background".to_string()));
+        assert!(themes[0]
+            .ignored
+            .contains(&"background.appearance".to_string()));
+        assert_eq!(themes[1].theme.variant, ThemeVariant::Light);
+    }
+
+    #[test]
+    fn jsonc_is_stripped_without_touching_strings() {
+        let stripped = strip_jsonc(r#"{"a": "// not a comment", "b": [1, 2,], /* c */ }"#);
+        let value: Value = serde_json::from_str(&stripped).unwrap();
+        assert_eq!(value["a"], "// not a comment");
+        assert_eq!(value["b"], serde_json::json!([1, 2]));
+    }
+
+    #[cfg(feature = "editor")]
+    #[test]
+    fn token_colors_become_a_syntect_theme() {
+        let syntax = from_vscode(VSCODE_THEME).unwrap().syntax.unwrap();
+
+        assert_eq!(syntax.settings.foreground.unwrap().r, 0xab);
+        assert_eq!(syntax.scopes.len(), 1);
+        let rule = &syntax.scopes[0].style;
+        assert_eq!(rule.foreground.unwrap().r, 0xc6);
+        assert_eq!(
+            rule.font_style,
+            Some(syntect::highlighting::FontStyle::ITALIC)
+        );
+    }
+}
@@ -6,11 +6,34 @@
 //!
 //! Consumers can implement this trait for their own theme types.
 
+pub mod appearance;
+pub mod builder;
+pub mod contrast;
 pub mod control;
-
+pub mod file;
+pub mod import;
+pub mod refine;
+pub mod registry;
+pub mod scope;
+pub mod shape;
+pub mod type_scale;
+pub mod watcher;
+
+pub use appearance::{init_with_appearance, observe_window_appearance, AppearanceThemes};
+pub use builder::ThemeBuilder;
+pub use contrast::{contrast_ratio, ContrastCheck, ContrastReport};
 pub use control::{ControlMetrics, ControlScale, ControlSize, TrackMetrics};
-
-use gpui::{hsla, px, App, BoxShadow, Global, Hsla, Pixels, SharedString};
+pub use file::{ThemeFile, ThemeFileError};
+pub use import::{from_vscode, from_zed, ImportedTheme};
+pub use refine::ThemeOverride;
+pub use registry::{set_theme, ThemeRegistry};
+pub use scope::{with_theme, with_theme_override};
+pub use shape::{Elevation, Radius, Shape};
+pub use type_scale::{TextMetrics, TextRole, TypeScale};
+pub use watcher::ThemeWatcher;
+
+use gpui::{hsla, px, App, BoxShadow, Global, Hsla, Pixels, Rems, SharedString};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 /// How thick the ring around a keyboard-focused control is.
@@ -183,15 +206,18 @@ pub trait Themeable {
 
     /// Text on a destructive button.
     ///
-    /// Black or white off the fill's own lightness, not
+    /// Black or white, whichever contrasts more with the fill, not
     /// [`fg`](Themeable::fg): a light theme's foreground is dark, and dark
     /// text on a saturated red is the one combination this has to avoid.
+    /// Measured rather than read off HSL lightness, which says a bright red
+    /// and a bright yellow are equally light; one of them wants black text.
     fn destructive_fg(&self) -> Hsla {
         let bg = self.destructive_bg();
-        if bg.l > 0.6 {
-            hsla(0.0, 0.0, 0.0, 1.0)
+        let (black, white) = (hsla(0.0, 0.0, 0.0, 1.0), hsla(0.0, 0.0, 1.0, 1.0));
+        if contrast_ratio(black, bg) > contrast_ratio(white, bg) {
+            black
         } else {
-            hsla(0.0, 0.0, 1.0, 1.0)
+            white
         }
     }
 
@@ -284,13 +310,55 @@ pub trait Themeable {
     fn control(&self, size: ControlSize) -> ControlMetrics {
         self.control_scale().metrics(size)
     }
+
+    /// The type scale every piece of text in the crate sizes itself from.
+    ///
+    /// Override this to restyle all text at once.
+    fn type_scale(&self) -> TypeScale {
+        TypeScale::default()
+    }
+
+    /// The size, line box, weight and family for one kind of text.
+    ///
+    /// This is what an element calls; `type_scale` is what a theme overrides.
+    fn text(&self, role: TextRole) -> TextMetrics {
+        self.type_scale().metrics(role)
+    }
+
+    // === Shape ===
+
+    /// Corner radii, the spacing unit and the elevation shadows.
+    ///
+    /// Override this to reshape every surface at once — [`Shape::flat`] for
+    /// square corners and no shadows.
+    fn shape(&self) -> Shape {
+        Shape::default()
+    }
+
+    /// The corner radius of one rung, for anything that is not a control.
+    fn radius(&self, radius: Radius) -> Rems {
+        self.shape().radius(radius)
+    }
+
+    /// `units` of spacing, for padding and gaps. One unit is 4px by default.
+    fn space(&self, units: f32) -> Rems {
+        self.shape().space(units)
+    }
+
+    /// The shadow a surface at `elevation` casts. Empty for no shadow.
+    fn elevation(&self, elevation: Elevation) -> Vec<BoxShadow> {
+        self.shape().elevation(elevation)
+    }
 }
 
+/// Install the default theme and the [`ThemeRegistry`] of built-ins.
 pub fn init(cx: &mut App) {
     cx.set_global(GlobalTheme::default());
+    cx.set_global(ThemeRegistry::default());
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ThemeVariant {
     #[default]
     Dark,
@@ -350,6 +418,12 @@ pub struct Theme {
     /// and `ControlScale::default()` is the crate's scale rather than an
     /// absence of one.
     pub controls: ControlScale,
+
+    /// The type scale. Not an `Option`, for the same reason as `controls`.
+    pub typography: TypeScale,
+
+    /// Radii, spacing and elevation. Not an `Option` either.
+    pub shape: Shape,
 }
 
 impl Themeable for Theme {
@@ -498,6 +572,21 @@ impl Themeable for Theme {
     fn control_scale(&self) -> ControlScale {
         self.controls
     }
+    fn type_scale(&self) -> TypeScale {
+        self.typography.clone()
+    }
+    fn shape(&self) -> Shape {
+        self.shape.clone()
+    }
+    fn radius(&self, radius: Radius) -> Rems {
+        self.shape.radius(radius)
+    }
+    fn space(&self, units: f32) -> Rems {
+        self.shape.space(units)
+    }
+    fn elevation(&self, elevation: Elevation) -> Vec<BoxShadow> {
+        self.shape.elevation(elevation)
+    }
 }
 
 impl Theme {
@@ -555,9 +644,17 @@ impl Theme {
             badge_amber_color: None,
             badge_gray_color: None,
             controls: ControlScale::default(),
+            typography: TypeScale::default(),
+            shape: Shape::default(),
         }
     }
 
+    /// The WCAG contrast of every pair of colours this theme draws text with.
+    /// See [`contrast`] for what is measured and how to use it in a test.
+    pub fn contrast_report(&self) -> ContrastReport {
+        ContrastReport::for_theme(self.name.to_string(), self)
+    }
+
     /// Create a Gruvbox Dark theme
     pub fn gruvbox_dark() -> Self {
         let mut theme = Theme::new(
@@ -792,11 +889,19 @@ pub trait ActiveTheme {
 }
 
 impl ActiveTheme for App {
+    /// The innermost [`scope`]'s theme while a scoped subtree is being drawn,
+    /// and [`GlobalTheme`] otherwise.
     fn theme(&self) -> &Arc<Theme> {
-        &self.global::<GlobalTheme>().0
+        scope::innermost(self).unwrap_or_else(|| &self.global::<GlobalTheme>().0)
     }
 }
 
+/// Parse a `#rrggbb` string, for the built-in themes' literals.
+///
+/// Lenient on purpose, and only fit for strings written in this source: an
+/// unreadable channel becomes `0` and a string shorter than six digits panics.
+/// Anything a user typed goes through a theme file instead, which reports the
+/// key and the string it could not read — see [`file`].
 pub fn parse_hex(hex: &str) -> Hsla {
     let hex = hex.trim_start_matches('#');
 
@@ -884,6 +989,18 @@ mod tests {
         );
     }
 
+    /// The same wire for the type scale: `Theme::typography` is what
+    /// `Themeable::text` reads.
+    #[test]
+    fn a_theme_can_resize_all_text_at_once() {
+        let mut theme = Theme::default();
+        theme.typography.body = gpui::Rems(1.125);
+        assert_eq!(theme.text(TextRole::Body).size.0, 1.125);
+        assert!(
+            theme.text(TextRole::H1).size.0 > TypeScale::default().metrics(TextRole::H1).size.0
+        );
+    }
+
     #[test]
     fn test_hex_parsing() {
         let color = parse_hex("#ffffff");
@@ -0,0 +1,384 @@
+//! Partial themes, layered over a whole one.
+//!
+//! Changing one token used to mean cloning a [`Theme`] and setting a field,
+//! which ties the change to one base: a sidebar that wants "whatever the app
+//! uses, but with a darker background" had to be rebuilt every time the app's
+//! theme changed. A [`ThemeOverride`] is the change on its own — every token
+//! optional — and is applied to whichever theme is underneath at the time.
+//!
+//! Overrides stack: [`ThemeOverride::then`] puts one over another, and
+//! [`with_theme_override`](super::scope::with_theme_override) applies one to a
+//! subtree over whatever theme is in effect where it is built, another
+//! override's included.
+//!
+//! # What overriding a primitive changes
+//!
+//! Setting `bg` changes every token *derived* from it in the base — one the
+//! base left as `None` — because derived tokens are computed when read. A token
+//! the base set explicitly stays as the base set it. So a refinement of a
+//! built-in theme, all of whose surfaces are explicit, needs to say which
+//! surfaces it means; a refinement of a [`Theme::new`] theme does not.
+//!
+//! # Serialised form
+//!
+//! The theme file's, without the name and with everything optional:
+//!
+//! ```toml
+//! variant = "dark"
+//!
+//! [colors]
+//! bg = "#1d2021"
+//! surface = "#282828"
+//! ```
+//!
+//! Keys and errors are exactly a theme file's — an unknown key or an unreadable
+//! colour is a [`ThemeFileError`] naming the key.
+
+use std::collections::BTreeMap;
+
+use gpui::Hsla;
+use serde::{Deserialize, Serialize};
+
+use super::file::{self, parse_color, to_hex, ThemeFileError};
+use super::{ControlScale, Shape, Theme, ThemeVariant, TypeScale};
+
+/// Declares [`ThemeOverride`] with one optional field per colour key, and the
+/// by-key access the serialised form and [`ThemeOverride::apply_to`] go
+/// through.
+macro_rules! theme_override {
+    ($($key:ident),* $(,)?) => {
+        /// Any subset of a theme's tokens, to be layered over a base theme.
+        ///
+        /// Colour fields are named by the [`Themeable`](super::Themeable)
+        /// method that reads them, as a theme file's keys are. `None`
+        /// everywhere — the [`Default`] — changes nothing.
+        ///
+        /// ```ignore
+        /// let sidebar = ThemeOverride {
+        ///     bg: Some(parse_hex("#1d2021")),
+        ///     fg: Some(parse_hex("#ebdbb2")),
+        ///     variant: Some(ThemeVariant::Dark),
+        ///     ..Default::default()
+        /// };
+        /// ```
+        #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+        #[serde(try_from = "OverrideFile", into = "OverrideFile")]
+        pub struct ThemeOverride {
+            $(pub $key: Option<Hsla>,)*
+            /// Whether the refined theme is light or dark.
+            pub variant: Option<ThemeVariant>,
+            /// A whole replacement control scale. Whole rather than per rung:
+            /// a scale is designed as a set.
+            pub controls: Option<ControlScale>,
+            /// A whole replacement type scale, whole for the same reason.
+            pub typography: Option<TypeScale>,
+            /// A whole replacement shape: radii, spacing and shadows.
+            pub shape: Option<Shape>,
+        }
+
+        impl ThemeOverride {
+            /// The field a colour key names.
+            fn slot(&mut self, key: &str) -> Option<&mut Option<Hsla>> {
+                match key {
+                    $(stringify!($key) => Some(&mut self.$key),)*
+                    _ => None,
+                }
+            }
+
+            /// Every colour this override sets, by key, in
+            /// [`COLOR_KEYS`](file::COLOR_KEYS) order.
+            pub fn colors(&self) -> impl Iterator<Item = (&'static str, Hsla)> {
+                [$((stringify!($key), self.$key)),*]
+                    .into_iter()
+                    .filter_map(|(key, color)| color.map(|color| (key, color)))
+            }
+        }
+    };
+}
+
+theme_override!(
+    fg,
+    bg,
+    surface,
+    border,
+    accent,
+    fg_muted,
+    fg_disabled,
+    surface_secondary,
+    surface_tertiary,
+    border_secondary,
+    border_subtle,
+    outline,
+    accent_bg,
+    accent_bg_hover,
+    info,
+    success,
+    warning,
+    danger,
+    selection,
+    button_bg,
+    button_bg_hover,
+    button_bg_active,
+    button_border,
+    input_bg,
+    input_border,
+    input_border_hover,
+    input_border_focused,
+    input_text,
+    input_placeholder,
+    input_selection,
+    input_cursor,
+    overlay,
+    badge_blue,
+    badge_gold,
+    badge_red,
+    badge_green,
+    badge_teal,
+    badge_amber,
+    badge_gray,
+);
+
+impl ThemeOverride {
+    /// An override that changes nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether this changes nothing.
+    pub fn is_empty(&self) -> bool {
+        self.colors().next().is_none()
+            && self.variant.is_none()
+            && self.controls.is_none()
+            && self.typography.is_none()
+            && self.shape.is_none()
+    }
+
+    /// `top` layered over this: every token `top` sets wins, and every token
+    /// it leaves out is this one's.
+    pub fn then(mut self, top: &ThemeOverride) -> Self {
+        for (key, color) in top.colors() {
+            if let Some(slot) = self.slot(key) {
+                *slot = Some(color);
+            }
+        }
+        self.variant = top.variant.or(self.variant);
+        self.controls = top.controls.or(self.controls);
+        self.typography = top.typography.clone().or(self.typography);
+        self.shape = top.shape.clone().or(self.shape);
+        self
+    }
+
+    /// `base` with this override's tokens in place of its own. The name is
+    /// the base's.
+    pub fn apply_to(&self, base: &Theme) -> Theme {
+        let mut theme = base.clone();
+        for (key, color) in self.colors() {
+            if let Some(primitive) = file::primitive(&mut theme, key) {
+                *primitive = color;
+            } else if let Some(slot) = file::override_slot(&mut theme, key) {
+                *slot = Some(color);
+            }
+        }
+        if let Some(variant) = self.variant {
+            theme.variant = variant;
+        }
+        if let Some(controls) = self.controls {
+            theme.controls = controls;
+        }
+        if let Some(typography) = &self.typography {
+            theme.typography = typography.clone();
+        }
+        if let Some(shape) = &self.shape {
+            theme.shape = shape.clone();
+        }
+        theme
+    }
+}
+
+impl Theme {
+    /// This theme with `overrides` layered over it.
+    pub fn refined(&self, overrides: &ThemeOverride) -> Theme {
+        overrides.apply_to(self)
+    }
+}
+
+/// The serde-facing form of a [`ThemeOverride`]: a theme file with every part
+/// optional and no name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OverrideFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    variant: Option<ThemeVariant>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    colors: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    controls: Option<ControlScale>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    typography: Option<TypeScale>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    shape: Option<Shape>,
+}
+
+impl TryFrom<OverrideFile> for ThemeOverride {
+    type Error = ThemeFileError;
+
+    fn try_from(file: OverrideFile) -> Result<Self, Self::Error> {
+        let mut overrides = ThemeOverride {
+            variant: file.variant,
+            controls: file.controls,
+            typography: file.typography,
+            shape: file.shape,
+            ..Default::default()
+        };
+        for (key, value) in &file.colors {
+            let color = parse_color(key, value)?;
+            let slot = overrides
+                .slot(key)
+                .ok_or_else(|| ThemeFileError::UnknownColor { key: key.clone() })?;
+            *slot = Some(color);
+        }
+        Ok(overrides)
+    }
+}
+
+impl From<ThemeOverride> for OverrideFile {
+    fn from(overrides: ThemeOverride) -> Self {
+        OverrideFile {
+            variant: overrides.variant,
+            colors: overrides
+                .colors()
+                .map(|(key, color)| (key.to_string(), to_hex(color)))
+                .collect(),
+            controls: overrides.controls,
+            typography: overrides.typography,
+            shape: overrides.shape,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::{parse_hex, ControlSize, Themeable};
+
+    /// The struct and the theme file agree on what the keys are.
+    #[test]
+    fn every_theme_file_key_is_a_field() {
+        let mut overrides = ThemeOverride::new();
+        for key in file::COLOR_KEYS {
+            assert!(overrides.slot(key).is_some(), "no field for `{key}`");
+        }
+        assert_eq!(
+            overrides.colors().count(),
+            0,
+            "a default override sets nothing"
+        );
+    }
+
+    #[test]
+    fn applying_changes_only_what_it_sets() {
+        let base = Theme::gruvbox_light();
+        let overrides = ThemeOverride {
+            bg: Some(parse_hex("#1d2021")),
+            fg_muted: Some(parse_hex("#a89984")),
+            variant: Some(ThemeVariant::Dark),
+            ..Default::default()
+        };
+
+        let refined = base.refined(&overrides);
+
+        assert_eq!(refined.bg(), parse_hex("#1d2021"));
+        assert_eq!(refined.fg_muted(), parse_hex("#a89984"));
+        assert_eq!(refined.variant, ThemeVariant::Dark);
+        assert_eq!(refined.fg(), base.fg());
+        assert_eq!(refined.surface_secondary(), base.surface_secondary());
+        assert_eq!(refined.name, base.name);
+    }
+
+    /// A derived token follows an overridden primitive; an explicit one does
+    /// not.
+    #[test]
+    fn derived_tokens_follow_an_overridden_primitive() {
+        let base = Theme::new(
+            "Plain",
+            ThemeVariant::Light,
+            parse_hex("#000000"),
+            parse_hex("#ffffff"),
+            parse_hex("#eeeeee"),
+            parse_hex("#cccccc"),
+            parse_hex("#0000ff"),
+        );
+        let refined = base.refined(&ThemeOverride {
+            accent: Some(parse_hex("#ff0000")),
+            ..Default::default()
+        });
+        assert_eq!(refined.input_border_focused(), parse_hex("#ff0000"));
+    }
+
+    #[test]
+    fn the_top_layer_wins() {
+        let bottom = ThemeOverride {
+            bg: Some(parse_hex("#111111")),
+            fg: Some(parse_hex("#eeeeee")),
+            ..Default::default()
+        };
+        let top = ThemeOverride {
+            bg: Some(parse_hex("#222222")),
+            ..Default::default()
+        };
+
+        let layered = bottom.then(&top);
+
+        assert_eq!(layered.bg, Some(parse_hex("#222222")));
+        assert_eq!(layered.fg, Some(parse_hex("#eeeeee")));
+    }
+
+    #[test]
+    fn round_trips_through_json_and_toml() {
+        let mut controls = ControlScale::default();
+        controls.medium.height = gpui::Rems(2.0);
+        let overrides = ThemeOverride {
+            bg: Some(parse_hex("#1d2021")),
+            selection: Some(parse_hex("#458588").opacity(0.5)),
+            variant: Some(ThemeVariant::Dark),
+            controls: Some(controls),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&overrides).unwrap();
+        let from_json: ThemeOverride = serde_json::from_str(&json).unwrap();
+        let toml = toml::to_string(&overrides).unwrap();
+        let from_toml: ThemeOverride = toml::from_str(&toml).unwrap();
+
+        for back in [from_json, from_toml] {
+            assert_eq!(back.variant, overrides.variant);
+            assert_eq!(
+                back.colors()
+                    .map(|(key, color)| (key, to_hex(color)))
+                    .collect::<Vec<_>>(),
+                overrides
+                    .colors()
+                    .map(|(key, color)| (key, to_hex(color)))
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                back.controls.unwrap().metrics(ControlSize::Medium).height.0,
+                2.0
+            );
+        }
+    }
+
+    /// Only what is set is written.
+    #[test]
+    fn an_empty_override_serialises_to_an_empty_table() {
+        assert_eq!(serde_json::to_string(&ThemeOverride::new()).unwrap(), "{}");
+    }
+
+    #[test]
+    fn an_unknown_key_is_named() {
+        let error = serde_json::from_str::<ThemeOverride>(r##"{"colors":{"bgg":"#000"}}"##)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("bgg"), "{error}");
+    }
+}
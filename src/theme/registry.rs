@@ -0,0 +1,223 @@
+//! Named themes, and switching between them at runtime.
+//!
+//! [`GlobalTheme`] is still the one theme everything draws with — `cx.theme()`
+//! reads it and nothing else. The registry is the list of themes an app can
+//! switch *to*, so that a theme picker is a list of names rather than a
+//! `match` every app writes for itself.
+//!
+//! # What a switch re-renders
+//!
+//! [`set_theme`] is the one way the active theme changes, and the registry goes
+//! through it. It does two things:
+//!
+//! - **Refreshes every window.** A refresh bypasses gpui's view cache, so every
+//!   gpuikit element is rebuilt against the new theme on the next frame. An
+//!   element reads the theme in `render`, so that is all an element needs.
+//! - **Notifies `GlobalTheme`'s observers**, which `cx.set_global` does on its
+//!   own. A view that *stores* something derived from the theme — a computed
+//!   style, a cached layout — subscribes with
+//!   `cx.observe_global::<GlobalTheme>(…)` and recomputes it there.
+
+use std::sync::Arc;
+
+use gpui::{App, Global, SharedString};
+
+use super::{GlobalTheme, Theme};
+use crate::error::{Error, Result};
+
+/// Make `theme` the active theme, and re-render every window with it.
+///
+/// The registry's [`set_active`](ThemeRegistry::set_active) calls this; so can
+/// an app with a theme that was never registered, such as one being previewed
+/// from a file.
+pub fn set_theme(theme: impl Into<Arc<Theme>>, cx: &mut App) {
+    cx.set_global(GlobalTheme(theme.into()));
+    cx.refresh_windows();
+}
+
+/// The themes an app can switch between, by name.
+///
+/// Installed by [`theme::init`](super::init), pre-populated with the crate's
+/// six built-in themes. Order is registration order, which is the order a
+/// picker should list them in.
+#[derive(Debug, Clone)]
+pub struct ThemeRegistry {
+    themes: Vec<Arc<Theme>>,
+}
+
+impl Global for ThemeRegistry {}
+
+impl Default for ThemeRegistry {
+    /// Gruvbox dark and light, then the four Catppuccin flavours from
+    /// lightest to darkest.
+    fn default() -> Self {
+        let mut registry = Self::new();
+        for theme in [
+            Theme::gruvbox_dark(),
+            Theme::gruvbox_light(),
+            Theme::catppuccin_latte(),
+            Theme::catppuccin_frappe(),
+            Theme::catppuccin_macchiato(),
+            Theme::catppuccin_mocha(),
+        ] {
+            registry.insert(theme);
+        }
+        registry
+    }
+}
+
+impl ThemeRegistry {
+    /// An empty registry, for an app that ships none of the built-ins.
+    pub fn new() -> Self {
+        Self { themes: Vec::new() }
+    }
+
+    /// The app's registry.
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    /// Mutable access to the app's registry, to add or replace themes.
+    pub fn global_mut(cx: &mut App) -> &mut Self {
+        cx.global_mut::<Self>()
+    }
+
+    /// Add `theme` under its own name.
+    ///
+    /// A theme with the same name is replaced in place, keeping its position.
+    /// Replacing the *active* theme here does not re-render anything — call
+    /// [`set_active`](Self::set_active) with the name afterwards for that.
+    pub fn insert(&mut self, theme: impl Into<Arc<Theme>>) {
+        let theme = theme.into();
+        match self.themes.iter_mut().find(|t| t.name == theme.name) {
+            Some(existing) => *existing = theme,
+            None => self.themes.push(theme),
+        }
+    }
+
+    /// Remove the theme with this name, returning it.
+    ///
+    /// Removing the active theme does not change what is drawn: `GlobalTheme`
+    /// holds its own reference.
+    pub fn remove(&mut self, name: &str) -> Option<Arc<Theme>> {
+        let index = self.themes.iter().position(|t| t.name.as_ref() == name)?;
+        Some(self.themes.remove(index))
+    }
+
+    /// The theme with this name.
+    pub fn get(&self, name: &str) -> Option<&Arc<Theme>> {
+        self.themes.iter().find(|t| t.name.as_ref() == name)
+    }
+
+    /// Every registered theme, in registration order.
+    pub fn themes(&self) -> &[Arc<Theme>] {
+        &self.themes
+    }
+
+    /// Every registered name, in registration order.
+    pub fn names(&self) -> impl Iterator<Item = &SharedString> {
+        self.themes.iter().map(|t| &t.name)
+    }
+
+    /// Switch the app to the registered theme called `name`.
+    ///
+    /// Fails with [`Error::ThemeNotFound`], and leaves the active theme alone,
+    /// when there is no such theme.
+    pub fn set_active(name: &str, cx: &mut App) -> Result<()> {
+        let theme = Self::global(cx)
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Error::ThemeNotFound(name.to_string()))?;
+        set_theme(theme, cx);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::ActiveTheme;
+    use gpui::{div, px, size, Context, IntoElement, Render, Styled, TestAppContext, Window};
+
+    #[test]
+    fn the_built_ins_are_registered_in_picker_order() {
+        let registry = ThemeRegistry::default();
+        let names: Vec<&str> = registry.names().map(|name| name.as_ref()).collect();
+        assert_eq!(
+            names,
+            [
+                "Gruvbox Dark",
+                "Gruvbox Light",
+                "Catppuccin Latte",
+                "Catppuccin Frappé",
+                "Catppuccin Macchiato",
+                "Catppuccin Mocha",
+            ]
+        );
+    }
+
+    #[test]
+    fn inserting_a_known_name_replaces_it_in_place() {
+        let mut registry = ThemeRegistry::default();
+        let mut recoloured = Theme::catppuccin_latte();
+        recoloured.accent_color = gpui::red();
+        registry.insert(recoloured);
+
+        assert_eq!(registry.themes().len(), 6);
+        assert_eq!(registry.themes()[2].accent_color, gpui::red());
+    }
+
+    #[gpui::test]
+    fn an_unknown_name_is_an_error_and_changes_nothing(cx: &mut TestAppContext) {
+        cx.update(crate::theme::init);
+
+        let result = cx.update(|cx| ThemeRegistry::set_active("Solarized", cx));
+
+        assert!(matches!(result, Err(Error::ThemeNotFound(name)) if name == "Solarized"));
+        cx.update(|cx| assert_eq!(cx.theme().name.as_ref(), "Gruvbox Dark"));
+    }
+
+    /// Counts its renders, and the `GlobalTheme` notifications it observes.
+    struct Counter {
+        renders: usize,
+        notified: usize,
+    }
+
+    impl Render for Counter {
+        fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            self.renders += 1;
+            div().bg(cx.theme().bg_color)
+        }
+    }
+
+    /// The whole promise: one call, and a view that never asked to be notified
+    /// draws again with the new theme — and one that did ask hears about it.
+    #[gpui::test]
+    fn switching_re_renders_and_notifies(cx: &mut TestAppContext) {
+        cx.update(crate::theme::init);
+        let window = cx.open_window(size(px(200.), px(100.)), |_window, cx| {
+            cx.observe_global::<GlobalTheme>(|counter: &mut Counter, _cx| counter.notified += 1)
+                .detach();
+            Counter {
+                renders: 0,
+                notified: 0,
+            }
+        });
+        cx.run_until_parked();
+        let view = window.root(cx).unwrap();
+        let renders_before = view.read_with(cx, |counter, _| counter.renders);
+
+        cx.update(|cx| ThemeRegistry::set_active("Catppuccin Mocha", cx))
+            .unwrap();
+        cx.run_until_parked();
+
+        cx.update(|cx| assert_eq!(cx.theme().name.as_ref(), "Catppuccin Mocha"));
+        view.read_with(cx, |counter, _| {
+            assert!(
+                counter.renders > renders_before,
+                "the window was not redrawn"
+            );
+            assert_eq!(counter.notified, 1);
+        });
+    }
+}
@@ -0,0 +1,402 @@
+//! Themes scoped to a subtree.
+//!
+//! `cx.theme()` answers with the innermost open scope's theme, and with
+//! [`GlobalTheme`](super::GlobalTheme) when none is open. A scope is opened by
+//! a wrapper element around one child — [`with_theme`] for a whole theme,
+//! [`with_theme_override`] for a partial one — for exactly as long as that
+//! child is being laid out, prepainted and painted. So an inverted toolbar, a
+//! dark sidebar in a light app or a preview of a user-picked theme is one
+//! wrapper, and no `Card` or `Dialog` inside it knows.
+//!
+//! # Why a global, when the form context is thread-local
+//!
+//! The mechanism is [`form`](crate::elements::form)'s ambient stack — a push
+//! around the child's `request_layout`, `prepaint` and `paint`, relying on gpui
+//! building a subtree while its ancestor's call is on the Rust stack — with one
+//! difference. The form context rejected a `gpui::Global` because reading one
+//! needs a `cx`; reading the theme already does, and `cx.theme()` returns a
+//! *reference*, which a `RefCell` in a thread-local cannot hand out. So the
+//! stack is a global, and `cx.theme()` keeps its signature and every call site.
+//!
+//! Pushing is therefore a [`scope`] call around a closure rather than form's
+//! drop guard: a guard would have to hold the `&mut App` the child needs.
+//!
+//! # Out-of-line draws
+//!
+//! `deferred()` lays its child out in place but prepaints and paints it after
+//! the rest of the frame, when every scope has closed. Colours chosen in
+//! `render` are unaffected — they were read inside the scope and captured —
+//! but an element that reads `cx.theme()` in `paint`, as the text input does
+//! for its caret and selection, would fall back to the global theme inside a
+//! scoped dialog.
+//!
+//! So every overlay in `src/elements/` wraps what it defers in a `with_theme`
+//! of the theme it rendered with:
+//!
+//! ```ignore
+//! deferred(with_theme(cx.theme().clone(), anchored().child(popup)))
+//! ```
+//!
+//! which reopens the scope when the deferred paint happens. An overlay written
+//! outside the crate does the same. A tooltip is not a descendant at all —
+//! gpui builds it as a view of its own, on hover — and draws with the global
+//! theme.
+use std::panic::Location;
+use std::sync::Arc;
+
+use gpui::{
+    AnyElement, App, Bounds, ElementId, Global, GlobalElementId, InspectorElementId, IntoElement,
+    LayoutId, Pixels, Window,
+};
+
+use super::{ActiveTheme, Theme, ThemeOverride};
+
+/// The open scopes, innermost last.
+#[derive(Default)]
+struct ThemeScopes(Vec<Arc<Theme>>);
+
+impl Global for ThemeScopes {}
+
+/// Run `f` with `theme` as the answer to `cx.theme()`.
+///
+/// Public for the same reason form's `push` is: a hand-written `Element` may
+/// need to open a scope around its own children. A component never should.
+pub fn scope<R>(theme: Arc<Theme>, cx: &mut App, f: impl FnOnce(&mut App) -> R) -> R {
+    cx.default_global::<ThemeScopes>().0.push(theme);
+    let result = f(cx);
+    cx.global_mut::<ThemeScopes>().0.pop();
+    result
+}
+
+/// The innermost open scope's theme, if any scope is open.
+pub(crate) fn innermost(cx: &App) -> Option<&Arc<Theme>> {
+    cx.try_global::<ThemeScopes>()
+        .and_then(|scopes| scopes.0.last())
+}
+
+/// Draw `child`, and everything inside it, with `theme`.
+///
+/// ```ignore
+/// with_theme(ThemeRegistry::global(cx).get("Catppuccin Mocha").unwrap().clone(), preview)
+/// ```
+pub fn with_theme(theme: impl Into<Arc<Theme>>, child: impl IntoElement) -> WithTheme {
+    WithTheme {
+        theme: theme.into(),
+        child: child.into_any_element(),
+    }
+}
+
+/// Wraps one child so that it draws with a given theme. Built by
+/// [`with_theme`].
+///
+/// Like `WithFormContext`, it reports no id and no role, so wrapping a subtree
+/// in it changes neither the element-id path nor the accessibility tree — nor
+/// its layout, since the child's layout id is returned as its own.
+pub struct WithTheme {
+    theme: Arc<Theme>,
+    child: AnyElement,
+}
+
+impl IntoElement for WithTheme {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+impl gpui::Element for WithTheme {
+    type RequestLayoutState = ();
+    type PrepaintState = ();
+
+    fn id(&self) -> Option<ElementId> {
+        None
+    }
+
+    fn source_location(&self) -> Option<&'static Location<'static>> {
+        None
+    }
+
+    fn request_layout(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> (LayoutId, ()) {
+        let layout_id = scope(self.theme.clone(), cx, |cx| {
+            self.child.request_layout(window, cx)
+        });
+        (layout_id, ())
+    }
+
+    fn prepaint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        _bounds: Bounds<Pixels>,
+        _request_layout: &mut (),
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        scope(self.theme.clone(), cx, |cx| self.child.prepaint(window, cx));
+    }
+
+    fn paint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        _bounds: Bounds<Pixels>,
+        _request_layout: &mut (),
+        _prepaint: &mut (),
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        scope(self.theme.clone(), cx, |cx| self.child.paint(window, cx));
+    }
+}
+
+/// Draw `child` with `overrides` layered over the theme it would otherwise
+/// have drawn with.
+///
+/// "Otherwise" is `cx.theme()` where the wrapper is built: the enclosing
+/// scope's theme in a component rendered inside one, and the global theme if
+/// there is none. It is resolved then, once, into a [`with_theme`] — so a
+/// scoped override follows a runtime theme switch from the next render rather
+/// than pinning the theme it was written against. Two overrides built in the
+/// same `render` resolve against the same theme; stack them with
+/// [`ThemeOverride::then`] to layer one over the other.
+///
+/// ```ignore
+/// h_stack()
+///     .child(with_theme_override(dark_sidebar.clone(), sidebar, cx))
+///     .child(content)
+/// ```
+pub fn with_theme_override(
+    overrides: ThemeOverride,
+    child: impl IntoElement,
+    cx: &App,
+) -> WithTheme {
+    with_theme(cx.theme().refined(&overrides), child)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::dialog::{Dialog, DialogState};
+    use crate::theme::{parse_hex, Themeable};
+    use gpui::{
+        canvas, deferred, div, px, size, AppContext, Context, Entity, Hsla, ParentElement, Render,
+        RenderOnce, Styled, TestAppContext,
+    };
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Where a probe was, and the background `cx.theme()` gave it there.
+    type Seen = Rc<RefCell<Vec<(&'static str, &'static str, Hsla)>>>;
+
+    /// Records what `cx.theme()` says in `render`, `prepaint` and `paint`.
+    #[derive(gpui::IntoElement)]
+    struct Probe {
+        label: &'static str,
+        seen: Seen,
+    }
+
+    impl RenderOnce for Probe {
+        fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+            let Probe { label, seen } = self;
+            seen.borrow_mut().push((label, "render", cx.theme().bg()));
+            let (prepaint_seen, paint_seen) = (seen.clone(), seen);
+            canvas(
+                move |_, _, cx| {
+                    prepaint_seen
+                        .borrow_mut()
+                        .push((label, "prepaint", cx.theme().bg()))
+                },
+                move |_, _, _, cx| {
+                    paint_seen
+                        .borrow_mut()
+                        .push((label, "paint", cx.theme().bg()))
+                },
+            )
+            .size_full()
+        }
+    }
+
+    /// A window whose whole content is `tree`, rebuilt every render.
+    struct Panel {
+        seen: Seen,
+        tree: fn(&dyn Fn(&'static str) -> Probe, &App) -> AnyElement,
+    }
+
+    impl Render for Panel {
+        fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            let seen = self.seen.clone();
+            let probe = move |label| Probe {
+                label,
+                seen: seen.clone(),
+            };
+            (self.tree)(&probe, cx)
+        }
+    }
+
+    /// Draw `tree`, then what each probe saw, and the global background.
+    fn draw(
+        cx: &mut TestAppContext,
+        tree: fn(&dyn Fn(&'static str) -> Probe, &App) -> AnyElement,
+    ) -> (Seen, Hsla) {
+        cx.update(crate::theme::init);
+        let seen = Seen::default();
+        let view_seen = seen.clone();
+        cx.open_window(size(px(400.), px(300.)), move |_window, _cx| Panel {
+            seen: view_seen,
+            tree,
+        });
+        cx.run_until_parked();
+        assert!(cx.update(|cx| innermost(cx).is_none()), "a scope leaked");
+        (seen, cx.update(|cx| cx.theme().bg()))
+    }
+
+    /// `label` saw `expected` every time it looked, in every phase.
+    fn assert_saw(seen: &Seen, label: &str, expected: Hsla) {
+        for phase in ["render", "prepaint", "paint"] {
+            let found: Vec<_> = seen
+                .borrow()
+                .iter()
+                .filter(|(l, p, _)| *l == label && *p == phase)
+                .map(|(_, _, color)| *color)
+                .collect();
+            assert!(!found.is_empty(), "{label} was never seen in {phase}");
+            assert!(
+                found.iter().all(|color| *color == expected),
+                "{label} in {phase}: {found:?}"
+            );
+        }
+    }
+
+    fn bg(hex: &str) -> ThemeOverride {
+        ThemeOverride {
+            bg: Some(parse_hex(hex)),
+            ..Default::default()
+        }
+    }
+
+    #[gpui::test]
+    fn descendants_see_the_override_in_every_phase(cx: &mut TestAppContext) {
+        let (seen, global) = draw(cx, |probe, cx| {
+            div()
+                .size_full()
+                .child(probe("outside"))
+                .child(with_theme_override(
+                    bg("#111111"),
+                    div()
+                        .size_full()
+                        .child(probe("sidebar"))
+                        .child(with_theme_override(bg("#222222"), probe("nested"), cx)),
+                    cx,
+                ))
+                .child(probe("after"))
+                .into_any_element()
+        });
+
+        assert_saw(&seen, "outside", global);
+        assert_saw(&seen, "sidebar", parse_hex("#111111"));
+        assert_saw(&seen, "nested", parse_hex("#222222"));
+        assert_saw(&seen, "after", global);
+    }
+
+    /// A component that overrides nothing, to resolve its override wherever
+    /// it is rendered.
+    #[derive(gpui::IntoElement)]
+    struct Overridden(Probe);
+
+    impl RenderOnce for Overridden {
+        fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+            with_theme_override(ThemeOverride::default(), self.0, cx)
+        }
+    }
+
+    #[gpui::test]
+    fn an_override_rendered_inside_a_scope_refines_it(cx: &mut TestAppContext) {
+        let (seen, _global) = draw(cx, |probe, _cx| {
+            with_theme(Theme::catppuccin_latte(), Overridden(probe("inside"))).into_any_element()
+        });
+
+        assert_saw(&seen, "inside", Theme::catppuccin_latte().bg());
+    }
+
+    #[gpui::test]
+    fn a_whole_theme_reaches_every_descendant(cx: &mut TestAppContext) {
+        let (seen, global) = draw(cx, |probe, _cx| {
+            div()
+                .size_full()
+                .child(with_theme(
+                    Theme::catppuccin_latte(),
+                    div().size_full().child(probe("preview")),
+                ))
+                .child(probe("app"))
+                .into_any_element()
+        });
+
+        assert_saw(&seen, "preview", Theme::catppuccin_latte().bg());
+        assert_saw(&seen, "app", global);
+    }
+
+    /// The overlay pattern: a deferred child is painted after every scope has
+    /// closed, and a `with_theme` inside the `deferred` reopens its own.
+    #[gpui::test]
+    fn a_deferred_overlay_keeps_the_scope_it_rendered_in(cx: &mut TestAppContext) {
+        let (seen, _global) = draw(cx, |probe, _cx| {
+            let latte = Arc::new(Theme::catppuccin_latte());
+            with_theme(
+                latte.clone(),
+                div()
+                    .size_full()
+                    .child(deferred(with_theme(latte, probe("dialog")))),
+            )
+            .into_any_element()
+        });
+
+        assert_saw(&seen, "dialog", Theme::catppuccin_latte().bg());
+    }
+
+    /// A dialog whose state lives in the host, drawn inside a scope.
+    struct Host {
+        dialog: Entity<DialogState>,
+    }
+
+    impl Render for Host {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            with_theme(Theme::catppuccin_latte(), self.dialog.clone())
+        }
+    }
+
+    /// gpuikit's own overlays follow the pattern: a dialog opened inside a
+    /// scope paints its content with the scoped theme.
+    #[gpui::test]
+    fn a_dialog_inside_a_scope_paints_with_it(cx: &mut TestAppContext) {
+        cx.update(crate::theme::init);
+        let seen = Seen::default();
+        let content_seen = seen.clone();
+        let window = cx.open_window(size(px(400.), px(300.)), move |_window, cx| {
+            let content = move |_: &mut Window, _: &mut App| {
+                Probe {
+                    label: "body",
+                    seen: content_seen.clone(),
+                }
+                .into_any_element()
+            };
+            Host {
+                dialog: cx.new(|_| DialogState::new(Dialog::new("scoped").content(content))),
+            }
+        });
+        window
+            .update(cx, |host, window, cx| {
+                host.dialog.update(cx, |dialog, cx| dialog.open(window, cx))
+            })
+            .unwrap();
+        cx.run_until_parked();
+
+        assert_saw(&seen, "body", Theme::catppuccin_latte().bg());
+    }
+}
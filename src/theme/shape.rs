@@ -0,0 +1,292 @@
+//! Corner radii, spacing and elevation: how surfaces are shaped rather than
+//! coloured.
+//!
+//! Colours were themeable from the start, and controls and text have their
+//! scales, but every tooltip, card, toast and menu still chose its own corner
+//! radius, padding and shadow — `px(4.)` here, `rounded_md()` and
+//! `shadow_lg()` there. A theme that wanted sharp corners and no shadows had
+//! nothing to override; it had to fork the elements.
+//!
+//! A [`Shape`] carries the three:
+//!
+//! - **Radius**, in three [`Radius`] rungs. Controls keep their own radius in
+//!   [`ControlMetrics`](super::ControlMetrics), since it scales with the
+//!   control; these are for everything that is not a control.
+//! - **Spacing**, as one unit. Elements ask for a number of units —
+//!   [`Themeable::space`](super::Themeable::space)`(3.)` is the old `p_3()` —
+//!   so a denser theme shrinks the unit and every gap and padding follows.
+//! - **Elevation**, as a shadow for each of four [`Elevation`] levels. A flat
+//!   theme sets every level to no shadow at all.
+//!
+//! The defaults are the values the elements had hard-coded, so a theme that
+//! overrides nothing looks exactly as before.
+
+use gpui::{hsla, px, BoxShadow, Hsla, Rems, Rgba};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::control::rems;
+use super::file::to_hex;
+
+/// A rung of corner radius.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Radius {
+    /// 4px. Tooltips, tabs, list and menu items, small parts inside a larger
+    /// surface.
+    Small,
+    /// 6px. Popovers, menus, toasts, alerts. The default.
+    #[default]
+    Medium,
+    /// 8px. Cards, dialogs, accordions — the largest surfaces.
+    Large,
+}
+
+/// How far a surface sits above the page, and so how much shadow it casts.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Elevation {
+    /// Barely lifted: a key cap, a slider thumb, the selected item of a
+    /// toggle group.
+    Raised,
+    /// Clear of its surroundings: a tooltip, a switch's thumb.
+    Floating,
+    /// Over the page's content: popovers, menus, toasts, the command palette.
+    /// The default.
+    #[default]
+    Overlay,
+    /// Over everything, with the page dimmed behind it: a dialog.
+    Modal,
+}
+
+/// A theme's radii, spacing unit and shadows.
+///
+/// A field missing from a deserialized shape is the crate's default, so a
+/// theme file can restate only what it changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Shape {
+    /// The [`Radius::Small`] rung.
+    #[serde(with = "rems")]
+    pub radius_small: Rems,
+    /// The [`Radius::Medium`] rung.
+    #[serde(with = "rems")]
+    pub radius_medium: Rems,
+    /// The [`Radius::Large`] rung.
+    #[serde(with = "rems")]
+    pub radius_large: Rems,
+    /// One unit of spacing. Paddings and gaps are whole or half multiples.
+    #[serde(with = "rems")]
+    pub spacing: Rems,
+    /// The [`Elevation::Raised`] shadow.
+    #[serde(with = "shadow")]
+    pub raised: Vec<BoxShadow>,
+    /// The [`Elevation::Floating`] shadow.
+    #[serde(with = "shadow")]
+    pub floating: Vec<BoxShadow>,
+    /// The [`Elevation::Overlay`] shadow.
+    #[serde(with = "shadow")]
+    pub overlay: Vec<BoxShadow>,
+    /// The [`Elevation::Modal`] shadow.
+    #[serde(with = "shadow")]
+    pub modal: Vec<BoxShadow>,
+}
+
+impl Default for Shape {
+    /// 4 / 6 / 8px radii, a 4px spacing unit, and gpui's `shadow_sm` to
+    /// `shadow_xl` for the four elevations.
+    fn default() -> Self {
+        let layer = |y: f32, blur: f32, spread: f32| {
+            BoxShadow::new(px(0.), px(y), hsla(0., 0., 0., 0.1))
+                .blur_radius(px(blur))
+                .spread_radius(px(spread))
+        };
+        Self {
+            radius_small: Rems(0.25),
+            radius_medium: Rems(0.375),
+            radius_large: Rems(0.5),
+            spacing: Rems(0.25),
+            raised: vec![layer(1., 3., 0.), layer(1., 2., -1.)],
+            floating: vec![layer(4., 6., -1.), layer(2., 4., -2.)],
+            overlay: vec![layer(10., 15., -3.), layer(4., 6., -4.)],
+            modal: vec![layer(20., 25., -5.), layer(8., 10., -6.)],
+        }
+    }
+}
+
+impl Shape {
+    /// Square corners and no shadows, with the default spacing.
+    pub fn flat() -> Self {
+        Self {
+            radius_small: Rems(0.),
+            radius_medium: Rems(0.),
+            radius_large: Rems(0.),
+            raised: Vec::new(),
+            floating: Vec::new(),
+            overlay: Vec::new(),
+            modal: Vec::new(),
+            ..Self::default()
+        }
+    }
+
+    /// The radius of one rung.
+    pub fn radius(&self, radius: Radius) -> Rems {
+        match radius {
+            Radius::Small => self.radius_small,
+            Radius::Medium => self.radius_medium,
+            Radius::Large => self.radius_large,
+        }
+    }
+
+    /// `units` spacing units.
+    pub fn space(&self, units: f32) -> Rems {
+        self.spacing * units
+    }
+
+    /// The shadow for one level.
+    pub fn elevation(&self, elevation: Elevation) -> Vec<BoxShadow> {
+        match elevation {
+            Elevation::Raised => self.raised.clone(),
+            Elevation::Floating => self.floating.clone(),
+            Elevation::Overlay => self.overlay.clone(),
+            Elevation::Modal => self.modal.clone(),
+        }
+    }
+}
+
+/// A shadow as a theme file writes it: a list of layers, each with pixel
+/// offsets and a colour string.
+///
+/// ```toml
+/// [[shape.overlay]]
+/// y = 10.0
+/// blur = 15.0
+/// spread = -3.0
+/// color = "#0000001a"
+/// ```
+mod shadow {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Layer {
+        #[serde(default)]
+        x: f32,
+        #[serde(default)]
+        y: f32,
+        #[serde(default)]
+        blur: f32,
+        #[serde(default)]
+        spread: f32,
+        color: String,
+    }
+
+    pub fn serialize<S: Serializer>(value: &[BoxShadow], serializer: S) -> Result<S::Ok, S::Error> {
+        value
+            .iter()
+            .map(|shadow| Layer {
+                x: shadow.offset.x.into(),
+                y: shadow.offset.y.into(),
+                blur: shadow.blur_radius.into(),
+                spread: shadow.spread_radius.into(),
+                color: to_hex(shadow.color),
+            })
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<BoxShadow>, D::Error> {
+        Vec::<Layer>::deserialize(deserializer)?
+            .into_iter()
+            .map(|layer| {
+                let color = Rgba::try_from(layer.color.as_str())
+                    .map(Hsla::from)
+                    .map_err(|_| {
+                        serde::de::Error::custom(format!(
+                            "`{}` is not a #rgb, #rgba, #rrggbb or #rrggbbaa colour",
+                            layer.color
+                        ))
+                    })?;
+                Ok(BoxShadow::new(px(layer.x), px(layer.y), color)
+                    .blur_radius(px(layer.blur))
+                    .spread_radius(px(layer.spread)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_radii_grow_with_the_rung() {
+        let shape = Shape::default();
+        assert!(shape.radius(Radius::Small).0 < shape.radius(Radius::Medium).0);
+        assert!(shape.radius(Radius::Medium).0 < shape.radius(Radius::Large).0);
+    }
+
+    /// The defaults are gpui's utilities, so an unthemed element looks as it
+    /// did when it called them directly.
+    #[test]
+    fn the_default_shadows_are_gpuis() {
+        use gpui::{div, Styled};
+
+        let shadows = |el: gpui::Div| {
+            let mut el = el;
+            el.style().box_shadow.clone().unwrap()
+        };
+        let shape = Shape::default();
+        assert_eq!(
+            shape.elevation(Elevation::Raised),
+            shadows(div().shadow_sm())
+        );
+        assert_eq!(
+            shape.elevation(Elevation::Floating),
+            shadows(div().shadow_md())
+        );
+        assert_eq!(
+            shape.elevation(Elevation::Overlay),
+            shadows(div().shadow_lg())
+        );
+        assert_eq!(
+            shape.elevation(Elevation::Modal),
+            shadows(div().shadow_xl())
+        );
+    }
+
+    #[test]
+    fn flat_has_no_shadow_and_no_rounding() {
+        let flat = Shape::flat();
+        assert!(flat.elevation(Elevation::Modal).is_empty());
+        assert_eq!(flat.radius(Radius::Large).0, 0.);
+        assert_eq!(flat.space(3.).0, Shape::default().space(3.).0);
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let mut shape = Shape::flat();
+        shape.spacing = Rems(0.2);
+        shape.overlay =
+            vec![BoxShadow::new(px(0.), px(2.), hsla(0., 0., 0., 0.5)).blur_radius(px(8.))];
+
+        let written = toml::to_string(&shape).unwrap();
+        let read: Shape = toml::from_str(&written).unwrap();
+
+        assert_eq!(read.spacing.0, 0.2);
+        assert!(read.modal.is_empty());
+        assert_eq!(read.overlay.len(), 1);
+        assert_eq!(read.overlay[0].blur_radius, px(8.));
+        assert_eq!(
+            to_hex(read.overlay[0].color),
+            to_hex(shape.overlay[0].color)
+        );
+    }
+
+    #[test]
+    fn a_bad_shadow_colour_is_named() {
+        let error = toml::from_str::<Shape>("[[overlay]]\ncolor = \"grey\"")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("grey"), "{error}");
+    }
+}
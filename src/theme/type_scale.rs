@@ -0,0 +1,282 @@
+//! The shared type scale: what size, weight and family each kind of text is.
+//!
+//! [`control`](super::control) gave every control one height to agree on;
+//! text had no equivalent. `elements::typography` and the markdown renderer
+//! each carried a copy of the same minor-third scale as private constants, the
+//! editor had its own 14px Monaco, and a consumer that wanted larger body text
+//! had three places to change it and no way to reach two of them.
+//!
+//! A [`TextRole`] names a rung; the theme resolves it to [`TextMetrics`]. Every
+//! rung is derived from one body size and one ratio, so changing
+//! [`TypeScale::body`] reflows headings, small print and code together.
+//!
+//! # The derivation
+//!
+//! | role    | size               | line box | weight  |
+//! |---------|--------------------|----------|---------|
+//! | Display | body × ratio⁵      | heading  | heading |
+//! | H1–H4   | body × ratio⁴ … ¹  | heading  | heading |
+//! | Lead    | body × ratio       | body     | normal  |
+//! | Body    | body               | body     | normal  |
+//! | Small   | body × ⅞           | body     | normal  |
+//! | Code    | body × ⅞           | body     | normal  |
+//!
+//! Small and code are seven-eighths of body — 14px at a 16px root — rather
+//! than a step down the ratio, which at 1.2 is 13.3px and renders soft.
+
+use gpui::{FontWeight, Rems, SharedString};
+use serde::{Deserialize, Serialize};
+
+use super::control::rems;
+
+/// How much of body size small print and code are.
+const SMALL_FACTOR: f32 = 0.875;
+
+/// A rung on the type scale.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextRole {
+    /// Larger than any heading: a hero title, an empty state's headline.
+    Display,
+    /// The top heading level.
+    H1,
+    /// Second heading level.
+    H2,
+    /// Third heading level.
+    H3,
+    /// Fourth heading level, one step above body.
+    H4,
+    /// An introductory paragraph, one step above body.
+    Lead,
+    /// Running text. The default.
+    #[default]
+    Body,
+    /// Captions, labels, help text.
+    Small,
+    /// Monospaced text: inline code, code blocks, the editor.
+    Code,
+}
+
+impl TextRole {
+    /// Every rung, largest first.
+    pub const ALL: [TextRole; 9] = [
+        TextRole::Display,
+        TextRole::H1,
+        TextRole::H2,
+        TextRole::H3,
+        TextRole::H4,
+        TextRole::Lead,
+        TextRole::Body,
+        TextRole::Small,
+        TextRole::Code,
+    ];
+
+    /// The rung for a heading level. Levels past 4 have no rung of their own;
+    /// see [`TypeScale::heading`].
+    pub fn heading(level: u8) -> Self {
+        match level {
+            0 | 1 => TextRole::H1,
+            2 => TextRole::H2,
+            3 => TextRole::H3,
+            _ => TextRole::H4,
+        }
+    }
+
+    /// The rung's name, for showcases and debug output.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextRole::Display => "Display",
+            TextRole::H1 => "H1",
+            TextRole::H2 => "H2",
+            TextRole::H3 => "H3",
+            TextRole::H4 => "H4",
+            TextRole::Lead => "Lead",
+            TextRole::Body => "Body",
+            TextRole::Small => "Small",
+            TextRole::Code => "Code",
+        }
+    }
+
+    fn is_heading(&self) -> bool {
+        matches!(
+            self,
+            TextRole::Display | TextRole::H1 | TextRole::H2 | TextRole::H3 | TextRole::H4
+        )
+    }
+}
+
+/// Everything text on one rung needs. Resolve one with
+/// [`Themeable::text`](crate::theme::Themeable::text).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextMetrics {
+    /// Font size.
+    pub size: Rems,
+    /// The line box, already multiplied out.
+    pub line_height: Rems,
+    /// Font weight.
+    pub weight: FontWeight,
+    /// Font family, or `None` to inherit the surrounding text's.
+    pub family: Option<SharedString>,
+}
+
+/// The whole scale, as a theme-owned value.
+///
+/// A theme overrides
+/// [`Themeable::type_scale`](crate::theme::Themeable::type_scale) to restyle
+/// every piece of text at once. A field missing from a deserialized scale is
+/// the crate's default, so a theme file can restate only what it changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TypeScale {
+    /// Body text size. Every other rung is derived from it.
+    #[serde(with = "rems")]
+    pub body: Rems,
+    /// The step between heading levels.
+    pub ratio: f32,
+    /// Line height of running text, as a multiple of its size.
+    pub body_line_height: f32,
+    /// Line height of headings, as a multiple of their size. Tighter than
+    /// body: a heading rarely wraps, and when it does loose lines read as two
+    /// headings.
+    pub heading_line_height: f32,
+    /// Weight of the display and heading rungs.
+    pub heading_weight: FontWeight,
+    /// Family for everything but code. `None` inherits the window's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_family: Option<SharedString>,
+    /// Family for the code rung.
+    pub code_font_family: SharedString,
+}
+
+impl Default for TypeScale {
+    /// A 16px body on a minor-third (1.2) scale — the sizes
+    /// `elements::typography` and markdown headings have always had.
+    fn default() -> Self {
+        Self {
+            body: Rems(1.0),
+            ratio: 1.2,
+            body_line_height: 1.5,
+            heading_line_height: 1.2,
+            heading_weight: FontWeight::BOLD,
+            font_family: None,
+            code_font_family: "monospace".into(),
+        }
+    }
+}
+
+impl TypeScale {
+    /// The metrics for one rung.
+    pub fn metrics(&self, role: TextRole) -> TextMetrics {
+        let size = match role {
+            TextRole::Display => self.step(5),
+            TextRole::H1 => self.step(4),
+            TextRole::H2 => self.step(3),
+            TextRole::H3 => self.step(2),
+            TextRole::H4 | TextRole::Lead => self.step(1),
+            TextRole::Body => self.body,
+            TextRole::Small | TextRole::Code => self.body * SMALL_FACTOR,
+        };
+        let (line_height, weight) = if role.is_heading() {
+            (self.heading_line_height, self.heading_weight)
+        } else {
+            (self.body_line_height, FontWeight::NORMAL)
+        };
+        let family = match role {
+            TextRole::Code => Some(self.code_font_family.clone()),
+            _ => self.font_family.clone(),
+        };
+        TextMetrics {
+            size,
+            line_height: size * line_height,
+            weight,
+            family,
+        }
+    }
+
+    /// Metrics for any heading level. Levels 1–4 are their rungs; 5 and 6 are
+    /// body-sized, set as headings — the scale has no room below H4 that is
+    /// still larger than body.
+    pub fn heading(&self, level: u8) -> TextMetrics {
+        if level <= 4 {
+            return self.metrics(TextRole::heading(level));
+        }
+        TextMetrics {
+            size: self.body,
+            line_height: self.body * self.heading_line_height,
+            weight: self.heading_weight,
+            family: self.font_family.clone(),
+        }
+    }
+
+    fn step(&self, power: i32) -> Rems {
+        self.body * self.ratio.powi(power)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_rung_is_no_larger_than_the_one_above() {
+        let scale = TypeScale::default();
+        for pair in TextRole::ALL.windows(2) {
+            let (larger, smaller) = (scale.metrics(pair[0]), scale.metrics(pair[1]));
+            assert!(
+                larger.size.0 >= smaller.size.0,
+                "{} is smaller than {}",
+                pair[0].name(),
+                pair[1].name()
+            );
+        }
+    }
+
+    /// The sizes typography and markdown had before the scale existed.
+    #[test]
+    fn the_default_keeps_the_old_sizes() {
+        let scale = TypeScale::default();
+        assert!((scale.metrics(TextRole::H1).size.0 - 1.2f32.powi(4)).abs() < 1e-5);
+        assert_eq!(scale.metrics(TextRole::Body).size.0, 1.0);
+        assert_eq!(scale.metrics(TextRole::Small).size.0, 0.875);
+        assert_eq!(scale.metrics(TextRole::Body).line_height.0, 1.5);
+        assert_eq!(scale.heading(6).size.0, 1.0);
+        assert_eq!(scale.heading(6).weight, FontWeight::BOLD);
+    }
+
+    #[test]
+    fn changing_body_moves_every_rung() {
+        let mut scale = TypeScale::default();
+        let before = TextRole::ALL.map(|role| scale.metrics(role).size.0);
+
+        scale.body = Rems(1.25);
+
+        for (role, before) in TextRole::ALL.into_iter().zip(before) {
+            let after = scale.metrics(role).size.0;
+            assert!(
+                (after / before - 1.25).abs() < 1e-5,
+                "{} did not follow body",
+                role.name()
+            );
+        }
+    }
+
+    #[test]
+    fn only_code_is_monospaced() {
+        let scale = TypeScale {
+            font_family: Some("Inter".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            scale.metrics(TextRole::Code).family,
+            Some(scale.code_font_family.clone())
+        );
+        assert_eq!(scale.metrics(TextRole::H2).family.as_deref(), Some("Inter"));
+    }
+
+    #[test]
+    fn a_partial_scale_deserialises_over_the_default() {
+        let scale: TypeScale = toml::from_str("body = 1.125").unwrap();
+        assert_eq!(scale.body.0, 1.125);
+        assert_eq!(scale.ratio, TypeScale::default().ratio);
+        assert_eq!(scale.code_font_family.as_ref(), "monospace");
+    }
+}
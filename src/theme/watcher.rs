@@ -0,0 +1,287 @@
+//! Reloading a theme file while the app runs.
+//!
+//! A designer iterating on a theme file used to rebuild the app for every
+//! colour. A [`ThemeWatcher`] watches one file instead: each time its contents
+//! change it is read again with [`Theme::load`], and a theme that parses
+//! becomes the active one through [`set_theme`] — so every window re-renders,
+//! exactly as a switch from the registry does.
+//!
+//! ```ignore
+//! let watcher = cx.new(|cx| ThemeWatcher::new("themes/draft.toml", cx));
+//! // Keep `watcher` alive for as long as the file should be followed.
+//! ```
+//!
+//! # A file that does not parse
+//!
+//! The theme stays as it was, and the [`ThemeFileError`] — which names the key
+//! and the value, or the parser's line and column — is shown as a destructive
+//! toast in the active window, once per broken save rather than once per poll.
+//! Toasts need [`toast::init`](crate::elements::toast::init); without it, or
+//! with no window open, the error is logged instead. Either way it is kept in
+//! [`ThemeWatcher::error`] until the next good save.
+//!
+//! # Why polling
+//!
+//! The watcher stats nothing and subscribes to nothing: on each tick it reads
+//! the file and compares the text with what it read last. A theme file is a
+//! few kilobytes, and comparing contents catches a save that a modification
+//! time with one-second granularity would miss.
+//!
+//! The tick is gpui's [`BackgroundExecutor::timer`](gpui::BackgroundExecutor::timer),
+//! not a platform watcher and not a thread of our own. A watcher thread would
+//! be the kind of thread `undying_thread_guard` exists to keep out of this
+//! crate, and the executor's timer is also the deterministic clock under
+//! `#[gpui::test]`, so the watcher can be tested by advancing it.
+//!
+//! Polling stops when the watcher entity is dropped.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use gpui::{Context, SharedString, Task};
+
+use super::{set_theme, Theme, ThemeFileError};
+use crate::elements::toast::{GlobalToastManager, Toast};
+
+/// How often [`ThemeWatcher::new`] looks at the file.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Follows one theme file, making each good save of it the active theme.
+///
+/// An entity, so that dropping it is what stops the polling. See the
+/// [module docs](self).
+pub struct ThemeWatcher {
+    path: PathBuf,
+    /// The text last read, whether or not it parsed. `None` until the first
+    /// successful read, so the first poll always loads.
+    contents: Option<String>,
+    /// Why the last change was not applied, until a later one is.
+    error: Option<SharedString>,
+    reloads: usize,
+    _poll: Task<()>,
+}
+
+impl ThemeWatcher {
+    /// Load `path` now, then again whenever it changes, checking every
+    /// [`DEFAULT_POLL_INTERVAL`].
+    pub fn new(path: impl Into<PathBuf>, cx: &mut Context<Self>) -> Self {
+        Self::with_interval(path, DEFAULT_POLL_INTERVAL, cx)
+    }
+
+    /// [`ThemeWatcher::new`], checking every `interval`.
+    pub fn with_interval(
+        path: impl Into<PathBuf>,
+        interval: Duration,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let poll = cx.spawn(async move |this, cx| loop {
+            // Bound to a local first: awaiting the call directly would hold a
+            // borrow of `cx` across the await point.
+            let timer = cx.background_executor().timer(interval);
+            timer.await;
+            if this.update(cx, |watcher, cx| watcher.poll(cx)).is_err() {
+                break;
+            }
+        });
+
+        let mut watcher = Self {
+            path: path.into(),
+            contents: None,
+            error: None,
+            reloads: 0,
+            _poll: poll,
+        };
+        watcher.poll(cx);
+        watcher
+    }
+
+    /// The file being watched.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Why the file's current contents are not the active theme, if they
+    /// are not.
+    pub fn error(&self) -> Option<&SharedString> {
+        self.error.as_ref()
+    }
+
+    /// How many times the file has been applied, the initial load included.
+    pub fn reloads(&self) -> usize {
+        self.reloads
+    }
+
+    /// Read the file, and apply it if it changed since the last read.
+    fn poll(&mut self, cx: &mut Context<Self>) {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(source) => {
+                // Whatever is read next is news, even the same bytes again:
+                // the file was gone in between, as an atomic save leaves it.
+                self.contents = None;
+                let error = ThemeFileError::Io {
+                    path: self.path.clone(),
+                    source,
+                };
+                self.fail(error, cx);
+                return;
+            }
+        };
+        if self.contents.as_ref() == Some(&contents) {
+            return;
+        }
+
+        let parsed = if super::file::is_toml(&self.path) {
+            Theme::from_toml(&contents)
+        } else {
+            Theme::from_json(&contents)
+        };
+        self.contents = Some(contents);
+        match parsed {
+            Ok(theme) => {
+                self.error = None;
+                self.reloads += 1;
+                set_theme(theme, cx);
+                cx.notify();
+            }
+            Err(error) => self.fail(error, cx),
+        }
+    }
+
+    /// Record `error` and report it, unless it is the one already reported.
+    fn fail(&mut self, error: ThemeFileError, cx: &mut Context<Self>) {
+        let message = SharedString::from(error.to_string());
+        if self.error.as_ref() == Some(&message) {
+            return;
+        }
+        self.error = Some(message.clone());
+        cx.notify();
+
+        let window = cx.active_window().or_else(|| cx.windows().first().copied());
+        let shown = match window {
+            Some(window) if cx.has_global::<GlobalToastManager>() => window
+                .update(cx, |_, window, cx| {
+                    Toast::new()
+                        .title("Theme not reloaded")
+                        .description(message.clone())
+                        .destructive()
+                        .show(window, cx)
+                })
+                .is_ok(),
+            _ => false,
+        };
+        if !shown {
+            log::warn!("{message}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::toast::{self, ToastExt};
+    use crate::theme::file::to_hex;
+    use crate::theme::{ActiveTheme, Themeable};
+    use gpui::{px, size, AppContext, Entity, TestAppContext};
+
+    const INTERVAL: Duration = Duration::from_millis(100);
+
+    /// A theme file in a fresh directory, and a watcher on it.
+    fn watch(
+        cx: &mut TestAppContext,
+        theme: &Theme,
+    ) -> (tempfile::TempDir, PathBuf, Entity<ThemeWatcher>) {
+        cx.update(|cx| {
+            crate::theme::init(cx);
+            toast::init(cx);
+        });
+        cx.open_window(size(px(400.), px(300.)), |_window, _cx| gpui::Empty);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("draft.toml");
+        theme.save(&path).unwrap();
+        let watcher = cx.new(|cx| ThemeWatcher::with_interval(path.clone(), INTERVAL, cx));
+        (dir, path, watcher)
+    }
+
+    /// The active background, as hex: a theme read back from a file matches
+    /// the original to the digit, not to the last bit of an `f32`.
+    fn bg(cx: &mut TestAppContext) -> String {
+        cx.update(|cx| to_hex(cx.theme().bg()))
+    }
+
+    fn tick(cx: &mut TestAppContext) {
+        cx.executor().advance_clock(INTERVAL);
+        cx.run_until_parked();
+    }
+
+    #[gpui::test]
+    fn a_save_becomes_the_active_theme(cx: &mut TestAppContext) {
+        let (_dir, path, watcher) = watch(cx, &Theme::catppuccin_latte());
+        assert_eq!(bg(cx), to_hex(Theme::catppuccin_latte().bg()));
+
+        Theme::catppuccin_mocha().save(&path).unwrap();
+        tick(cx);
+
+        assert_eq!(bg(cx), to_hex(Theme::catppuccin_mocha().bg()));
+        assert_eq!(watcher.read_with(cx, |watcher, _| watcher.reloads()), 2);
+    }
+
+    #[gpui::test]
+    fn an_unchanged_file_is_not_reapplied(cx: &mut TestAppContext) {
+        let (_dir, _path, watcher) = watch(cx, &Theme::catppuccin_latte());
+        tick(cx);
+        tick(cx);
+        assert_eq!(watcher.read_with(cx, |watcher, _| watcher.reloads()), 1);
+    }
+
+    #[gpui::test]
+    fn a_broken_save_keeps_the_theme_and_toasts_once(cx: &mut TestAppContext) {
+        let (_dir, path, watcher) = watch(cx, &Theme::catppuccin_latte());
+        let toasts = |cx: &mut TestAppContext| cx.update(|cx| cx.toast_manager().read(cx).count());
+
+        let broken = Theme::catppuccin_mocha()
+            .to_toml()
+            .replacen("bg = \"#", "bg = \"#zz", 1);
+        std::fs::write(&path, broken).unwrap();
+        tick(cx);
+        tick(cx);
+
+        assert_eq!(bg(cx), to_hex(Theme::catppuccin_latte().bg()));
+        let error = watcher.read_with(cx, |watcher, _| watcher.error().cloned());
+        assert!(error.is_some_and(|error| error.contains("bg")));
+        assert_eq!(toasts(cx), 1);
+
+        Theme::catppuccin_mocha().save(&path).unwrap();
+        tick(cx);
+
+        assert_eq!(bg(cx), to_hex(Theme::catppuccin_mocha().bg()));
+        assert!(watcher.read_with(cx, |watcher, _| watcher.error().is_none()));
+    }
+
+    #[gpui::test]
+    fn the_same_file_back_after_a_read_error_clears_it(cx: &mut TestAppContext) {
+        let (_dir, path, watcher) = watch(cx, &Theme::catppuccin_latte());
+        let saved = std::fs::read(&path).unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+        tick(cx);
+        assert!(watcher.read_with(cx, |watcher, _| watcher.error().is_some()));
+
+        std::fs::write(&path, saved).unwrap();
+        tick(cx);
+        assert!(watcher.read_with(cx, |watcher, _| watcher.error().is_none()));
+        assert_eq!(bg(cx), to_hex(Theme::catppuccin_latte().bg()));
+    }
+
+    #[gpui::test]
+    fn dropping_the_watcher_stops_it(cx: &mut TestAppContext) {
+        let (_dir, path, watcher) = watch(cx, &Theme::catppuccin_latte());
+        drop(watcher);
+        cx.run_until_parked();
+
+        Theme::catppuccin_mocha().save(&path).unwrap();
+        tick(cx);
+
+        assert_eq!(bg(cx), to_hex(Theme::catppuccin_latte().bg()));
+    }
+}
@@ -34,8 +34,9 @@
 //! The load-bearing change is in `Cargo.toml`: with no `smol` dependency,
 //! `use smol::Timer` does not compile, so the invariant is a build error and
 //! not a convention. These tests cover what a build error cannot — a
-//! re-added manifest entry, and the two call sites still being scheduled on
-//! the executor rather than on something new.
+//! re-added manifest entry, and the call sites that wait — the two above, and
+//! the theme watcher's poll since — still being scheduled on the executor
+//! rather than on something new.
 //!
 //! Like `release_version_guard` and `build_profile_guard`, this lives in the
 //! lib rather than in `tests/`, because `cargo test --lib` is the command that
@@ -220,8 +221,12 @@ fn no_source_file_reaches_for_one() {
 }
 
 #[test]
-fn the_two_delays_are_scheduled_on_the_executor() {
-    for (relative, expected) in [("src/input/blink.rs", 2), ("src/elements/toast.rs", 1)] {
+fn every_delay_is_scheduled_on_the_executor() {
+    for (relative, expected) in [
+        ("src/input/blink.rs", 2),
+        ("src/elements/toast.rs", 1),
+        ("src/theme/watcher.rs", 1),
+    ] {
         let path = repo_root().join(relative);
         let code = code_only(&std::fs::read_to_string(&path).expect("source file is readable"));
         let found = code.matches("background_executor().timer(").count();